@@ -50,6 +50,7 @@ pub struct Files {
     use_guards: Option<Rc<dyn Guard>>,
     guards: Vec<Rc<dyn Guard>>,
     hidden_files: bool,
+    precompressed: bool,
 }
 
 impl fmt::Debug for Files {
@@ -74,6 +75,7 @@ impl Clone for Files {
             use_guards: self.use_guards.clone(),
             guards: self.guards.clone(),
             hidden_files: self.hidden_files,
+            precompressed: self.precompressed,
         }
     }
 }
@@ -120,6 +122,7 @@ impl Files {
             use_guards: None,
             guards: Vec::new(),
             hidden_files: false,
+            precompressed: false,
         }
     }
 
@@ -143,6 +146,32 @@ impl Files {
     }
 
     /// Set custom directory renderer.
+    ///
+    /// The renderer is called to produce the listing response for directories when
+    /// [`show_files_listing()`](Self::show_files_listing) is enabled; the built-in HTML listing is
+    /// used if no custom renderer is set. It can be used to, e.g., render listings in other
+    /// formats, sort entries, or apply a different visibility rule than
+    /// [`Directory::is_visible()`]. Errors are turned into error responses.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_files::Files;
+    /// use actix_web::{dev::ServiceResponse, HttpResponse};
+    ///
+    /// let files = Files::new("/", "./static")
+    ///     .show_files_listing()
+    ///     .files_listing_renderer(|dir, req| {
+    ///         let mut names = std::fs::read_dir(&dir.path)?
+    ///             .filter(|entry| dir.is_visible(entry))
+    ///             .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+    ///             .collect::<Vec<_>>();
+    ///
+    ///         names.sort_unstable();
+    ///
+    ///         let res = HttpResponse::Ok().body(names.join("\n"));
+    ///         Ok(ServiceResponse::new(req.clone(), res))
+    ///     });
+    /// ```
     pub fn files_listing_renderer<F>(mut self, f: F) -> Self
     where
         for<'r, 's> F:
@@ -321,6 +350,31 @@ impl Files {
         self.hidden_files = true;
         self
     }
+
+    /// Enables serving precompressed sibling files.
+    ///
+    /// When a file is requested, siblings with the extensions `.br`, `.gz` and `.zst` are
+    /// considered as Brotli-, Gzip- and Zstd-encoded variants. The variant whose encoding is most
+    /// preferred by the request's `Accept-Encoding` header is served, with the `Content-Encoding`
+    /// header set and the content type of the original file. The original file is served if no
+    /// variant exists or none is preferred over the unencoded file.
+    ///
+    /// Range requests are served from the chosen variant. Responses carry a
+    /// `Vary: Accept-Encoding` header.
+    ///
+    /// The original file must exist for its variants to be served.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_files::Files;
+    ///
+    /// // serves `./static/app.js.br` for `/app.js` to clients accepting Brotli
+    /// let files = Files::new("/", "./static").prefer_precompressed();
+    /// ```
+    pub fn prefer_precompressed(mut self) -> Self {
+        self.precompressed = true;
+        self
+    }
 }
 
 impl HttpServiceFactory for Files {
@@ -372,6 +426,7 @@ impl ServiceFactory<ServiceRequest> for Files {
             file_flags: self.file_flags,
             guards: self.use_guards.clone(),
             hidden_files: self.hidden_files,
+            precompressed: self.precompressed,
         };
 
         if let Some(ref default) = *self.default.borrow() {
@@ -238,6 +238,33 @@ impl NamedFile {
         Self::from_file(file, path)
     }
 
+    /// Opens the `encoding`-compressed variant, at `variant_path`, of the file at `path`.
+    ///
+    /// Content type and disposition are determined using `path`.
+    pub(crate) async fn open_variant_async(
+        path: &Path,
+        variant_path: PathBuf,
+        encoding: ContentEncoding,
+    ) -> io::Result<NamedFile> {
+        let file = {
+            #[cfg(not(feature = "experimental-io-uring"))]
+            {
+                File::open(&variant_path)?
+            }
+
+            #[cfg(feature = "experimental-io-uring")]
+            {
+                File::open(&variant_path).await?
+            }
+        };
+
+        let mut named_file = Self::from_file(file, path)?;
+        named_file.path = variant_path;
+        named_file.encoding = Some(encoding);
+
+        Ok(named_file)
+    }
+
     /// Returns reference to the underlying file object.
     #[inline]
     pub fn file(&self) -> &File {
@@ -538,10 +565,11 @@ impl NamedFile {
                     // Accept-Encoding header for this request and that downstream middleware will
                     // not attempt compression for requests without it.
                     //
-                    // TODO: Solve question around what to do if self.encoding is set and partial
-                    // range is requested. Reject request? Ignoring self.encoding seems wrong, too.
-                    // In practice, it should not come up.
-                    if req.headers().contains_key(&header::ACCEPT_ENCODING) {
+                    // If self.encoding is set, e.g. for precompressed files, the range refers to
+                    // the encoded file and the encoding is kept.
+                    if self.encoding.is_none()
+                        && req.headers().contains_key(&header::ACCEPT_ENCODING)
+                    {
                         // don't allow compression middleware to modify partial content
                         res.insert_header((
                             header::CONTENT_ENCODING,
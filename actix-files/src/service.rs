@@ -1,11 +1,19 @@
-use std::{fmt, io, ops::Deref, path::PathBuf, rc::Rc};
+use std::{
+    fmt, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use actix_web::{
     body::BoxBody,
     dev::{self, Service, ServiceRequest, ServiceResponse},
     error::Error,
     guard::Guard,
-    http::{header, Method},
+    http::{
+        header::{self, AcceptEncoding, ContentEncoding, Encoding, Header as _},
+        Method,
+    },
     HttpResponse,
 };
 use futures_core::future::LocalBoxFuture;
@@ -39,6 +47,7 @@ pub struct FilesServiceInner {
     pub(crate) file_flags: named::Flags,
     pub(crate) guards: Option<Rc<dyn Guard>>,
     pub(crate) hidden_files: bool,
+    pub(crate) precompressed: bool,
 }
 
 impl fmt::Debug for FilesServiceInner {
@@ -74,10 +83,30 @@ impl FilesService {
         named_file.flags = self.file_flags;
 
         let (req, _) = req.into_parts();
-        let res = named_file.into_response(&req);
+        let mut res = named_file.into_response(&req);
+
+        if self.precompressed {
+            res.headers_mut().append(
+                header::VARY,
+                header::HeaderValue::from_static("accept-encoding"),
+            );
+        }
+
         ServiceResponse::new(req, res)
     }
 
+    /// Opens the file at `path` or, if enabled, its precompressed variant that is most preferred
+    /// by the request.
+    async fn open_file(&self, req: &ServiceRequest, path: &Path) -> io::Result<NamedFile> {
+        if self.precompressed {
+            if let Some((variant, encoding)) = precompressed_variant(req, path) {
+                return NamedFile::open_variant_async(path, variant, encoding).await;
+            }
+        }
+
+        NamedFile::open_async(path).await
+    }
+
     fn show_index(&self, req: ServiceRequest, path: PathBuf) -> ServiceResponse {
         let dir = Directory::new(self.directory.clone(), path);
 
@@ -161,7 +190,7 @@ impl Service<ServiceRequest> for FilesService {
                 match this.index {
                     Some(ref index) => {
                         let named_path = path.join(index);
-                        match NamedFile::open_async(named_path).await {
+                        match this.open_file(&req, &named_path).await {
                             Ok(named_file) => Ok(this.serve_named_file(req, named_file)),
                             Err(_) if this.show_index => Ok(this.show_index(req, path)),
                             Err(err) => this.handle_err(err, req).await,
@@ -174,22 +203,64 @@ impl Service<ServiceRequest> for FilesService {
                     )),
                 }
             } else {
-                match NamedFile::open_async(&path).await {
-                    Ok(mut named_file) => {
-                        if let Some(ref mime_override) = this.mime_override {
-                            let new_disposition =
-                                mime_override(&named_file.content_type.type_());
-                            named_file.content_disposition.disposition = new_disposition;
-                        }
-                        named_file.flags = this.file_flags;
-
-                        let (req, _) = req.into_parts();
-                        let res = named_file.into_response(&req);
-                        Ok(ServiceResponse::new(req, res))
-                    }
+                match this.open_file(&req, &path).await {
+                    Ok(named_file) => Ok(this.serve_named_file(req, named_file)),
                     Err(err) => this.handle_err(err, req).await,
                 }
             }
         })
     }
 }
+
+/// Precompressed variant encodings and their file extensions.
+const PRECOMPRESSED_VARIANTS: &[(ContentEncoding, &str)] = &[
+    (ContentEncoding::Brotli, "br"),
+    (ContentEncoding::Zstd, "zst"),
+    (ContentEncoding::Gzip, "gz"),
+];
+
+/// Returns the path and encoding of the existing precompressed sibling of the file at `path` that
+/// is most preferred by the request's `Accept-Encoding` header, if it is preferred over the
+/// unencoded file.
+fn precompressed_variant(
+    req: &ServiceRequest,
+    path: &Path,
+) -> Option<(PathBuf, ContentEncoding)> {
+    let accept_encoding = AcceptEncoding::parse(req).ok()?;
+
+    if !path.is_file() {
+        return None;
+    }
+
+    let variants = PRECOMPRESSED_VARIANTS
+        .iter()
+        .filter_map(|&(encoding, ext)| {
+            let mut variant = path.as_os_str().to_owned();
+            variant.push(".");
+            variant.push(ext);
+
+            let variant = PathBuf::from(variant);
+            variant.is_file().then(|| (variant, encoding))
+        })
+        .collect::<Vec<_>>();
+
+    if variants.is_empty() {
+        return None;
+    }
+
+    let supported = variants
+        .iter()
+        .map(|&(_, encoding)| Encoding::Known(encoding))
+        .chain([Encoding::identity()])
+        .collect::<Vec<_>>();
+
+    match accept_encoding.negotiate(supported.iter())? {
+        Encoding::Known(ContentEncoding::Identity) => None,
+
+        Encoding::Known(preferred) => variants
+            .into_iter()
+            .find(|&(_, encoding)| encoding == preferred),
+
+        Encoding::Unknown(_) => None,
+    }
+}
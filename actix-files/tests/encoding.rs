@@ -64,3 +64,82 @@ async fn partial_range_response_encoding() {
         "identity"
     );
 }
+
+#[actix_web::test]
+async fn precompressed_variants() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("style.css"), "plain").unwrap();
+    std::fs::write(dir.path().join("style.css.gz"), "gzip").unwrap();
+    std::fs::write(dir.path().join("style.css.br"), "brotli").unwrap();
+    std::fs::write(dir.path().join("style.css.zst"), "zstd").unwrap();
+
+    let srv = test::init_service(
+        App::new().service(Files::new("/", dir.path()).prefer_precompressed()),
+    )
+    .await;
+
+    for (accept_encoding, body, encoding) in [
+        ("br, gzip", "brotli", Some("br")),
+        ("gzip, br", "gzip", Some("gzip")),
+        ("zstd;q=0.5, br;q=0.4", "zstd", Some("zstd")),
+        ("br;q=0, gzip;q=0.5", "gzip", Some("gzip")),
+        ("identity, br;q=0.5", "plain", None),
+        ("deflate", "plain", None),
+    ] {
+        let req = TestRequest::with_uri("/style.css")
+            .insert_header((header::ACCEPT_ENCODING, accept_encoding))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        let content_type = res.headers().get(header::CONTENT_TYPE).unwrap();
+        assert!(content_type.to_str().unwrap().starts_with("text/css"));
+        assert_eq!(res.headers().get(header::VARY).unwrap(), "accept-encoding");
+        assert_eq!(
+            res.headers()
+                .get(header::CONTENT_ENCODING)
+                .map(|enc| enc.to_str().unwrap()),
+            encoding,
+            "for Accept-Encoding: {}",
+            accept_encoding,
+        );
+        assert_eq!(test::read_body(res).await, body);
+    }
+
+    // no Accept-Encoding header
+    let req = TestRequest::with_uri("/style.css").to_request();
+    let res = test::call_service(&srv, req).await;
+    assert!(!res.headers().contains_key(header::CONTENT_ENCODING));
+    assert_eq!(test::read_body(res).await, "plain");
+
+    // ranges are served from the chosen variant
+    let req = TestRequest::with_uri("/style.css")
+        .insert_header((header::ACCEPT_ENCODING, "br"))
+        .insert_header((header::RANGE, "bytes=1-3"))
+        .to_request();
+    let res = test::call_service(&srv, req).await;
+    assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "br");
+    assert_eq!(
+        res.headers().get(header::CONTENT_RANGE).unwrap(),
+        "bytes 1-3/6"
+    );
+    assert_eq!(test::read_body(res).await, "rot");
+}
+
+#[actix_web::test]
+async fn precompressed_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("style.css"), "plain").unwrap();
+    std::fs::write(dir.path().join("style.css.br"), "brotli").unwrap();
+
+    let srv = test::init_service(App::new().service(Files::new("/", dir.path()))).await;
+
+    let req = TestRequest::with_uri("/style.css")
+        .insert_header((header::ACCEPT_ENCODING, "br"))
+        .to_request();
+    let res = test::call_service(&srv, req).await;
+    assert!(!res.headers().contains_key(header::CONTENT_ENCODING));
+    assert!(!res.headers().contains_key(header::VARY));
+    assert_eq!(test::read_body(res).await, "plain");
+}
@@ -1,4 +1,5 @@
 use std::{
+    any::Any,
     error::Error as StdError,
     fmt,
     pin::Pin,
@@ -17,7 +18,29 @@ pub struct BoxBody(BoxBodyInner);
 enum BoxBodyInner {
     None(body::None),
     Bytes(Bytes),
-    Stream(Pin<Box<dyn MessageBody<Error = Box<dyn StdError>>>>),
+    Stream(Pin<Box<dyn AnyMessageBody>>),
+}
+
+/// Object-safe extension of [`MessageBody`] that allows the original, type-erased body to be
+/// recovered from a [`BoxBody`].
+trait AnyMessageBody: MessageBody<Error = Box<dyn StdError>> {
+    fn inner_any(&self) -> &dyn Any;
+
+    fn inner_any_pin_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any>;
+}
+
+impl<B, F> AnyMessageBody for MessageBodyMapErr<B, F>
+where
+    B: MessageBody + 'static,
+    F: FnOnce(B::Error) -> Box<dyn StdError>,
+{
+    fn inner_any(&self) -> &dyn Any {
+        self.get_ref()
+    }
+
+    fn inner_any_pin_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any> {
+        self.get_pin_mut()
+    }
 }
 
 impl fmt::Debug for BoxBodyInner {
@@ -57,6 +80,46 @@ impl BoxBody {
     pub fn as_pin_mut(&mut self) -> Pin<&mut Self> {
         Pin::new(self)
     }
+
+    /// Returns a reference to the inner body if it is of type `B`.
+    ///
+    /// Bodies that can be trivially converted into [`Bytes`] (see [`MessageBody::try_into_bytes`])
+    /// are stored as `Bytes` when boxed, so they can only be recovered as `Bytes`. Similarly, bodies
+    /// with a size of [`BodySize::None`] can only be recovered as [`body::None`].
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::body::BoxBody;
+    /// use bytes::Bytes;
+    ///
+    /// let body = BoxBody::new("hello world");
+    /// assert_eq!(body.downcast_ref::<Bytes>().unwrap(), "hello world");
+    /// assert!(body.downcast_ref::<&'static str>().is_none());
+    /// ```
+    pub fn downcast_ref<B: MessageBody + 'static>(&self) -> Option<&B> {
+        match &self.0 {
+            BoxBodyInner::None(body) => (body as &dyn Any).downcast_ref(),
+            BoxBodyInner::Bytes(body) => (body as &dyn Any).downcast_ref(),
+            BoxBodyInner::Stream(body) => body.inner_any().downcast_ref(),
+        }
+    }
+
+    /// Returns a mutable reference to the inner body if it is of type `B`.
+    ///
+    /// See [`downcast_ref`](Self::downcast_ref) for details on which types can be recovered.
+    pub fn downcast_mut<B: MessageBody + Unpin + 'static>(&mut self) -> Option<&mut B> {
+        match &mut self.0 {
+            BoxBodyInner::None(body) => (body as &mut dyn Any).downcast_mut(),
+            BoxBodyInner::Bytes(body) => (body as &mut dyn Any).downcast_mut(),
+            BoxBodyInner::Stream(body) => {
+                let body = body.as_mut().inner_any_pin_mut();
+
+                // SAFETY: a reference is only returned when the inner body is of type `B`, which
+                // is `Unpin`, so it is never required to stay pinned
+                unsafe { body.get_unchecked_mut() }.downcast_mut()
+            }
+        }
+    }
 }
 
 impl MessageBody for BoxBody {
@@ -107,8 +170,13 @@ mod tests {
 
     use static_assertions::{assert_impl_all, assert_not_impl_any};
 
+    use futures_util::stream;
+
     use super::*;
-    use crate::body::to_bytes;
+    use crate::{
+        body::{to_bytes, SizedStream},
+        Error,
+    };
 
     assert_impl_all!(BoxBody: fmt::Debug, MessageBody, Unpin);
     assert_not_impl_any!(BoxBody: Send, Sync);
@@ -123,4 +191,42 @@ mod tests {
             Bytes::from(vec![1, 2, 3]),
         );
     }
+
+    #[actix_rt::test]
+    async fn downcast_complete_bodies() {
+        let mut body = BoxBody::new(Bytes::from_static(b"123"));
+        assert_eq!(body.downcast_ref::<Bytes>().unwrap(), "123");
+        assert!(body.downcast_ref::<body::None>().is_none());
+
+        *body.downcast_mut::<Bytes>().unwrap() = Bytes::from_static(b"abc");
+        assert_eq!(to_bytes(body).await.unwrap(), "abc");
+
+        // bytes-like bodies are recovered as `Bytes`
+        let body = BoxBody::new(String::from("123"));
+        assert!(body.downcast_ref::<String>().is_none());
+        assert_eq!(body.downcast_ref::<Bytes>().unwrap(), "123");
+
+        let body = BoxBody::new(body::None::new());
+        assert!(body.downcast_ref::<body::None>().is_some());
+        assert!(body.downcast_ref::<Bytes>().is_none());
+    }
+
+    #[actix_rt::test]
+    async fn downcast_stream_body() {
+        type TestStream = stream::Iter<std::array::IntoIter<Result<Bytes, Error>, 1>>;
+
+        let stream: TestStream = stream::iter([Ok(Bytes::from_static(b"123"))]);
+
+        let mut body = BoxBody::new(SizedStream::new(3, stream));
+        assert!(body.downcast_ref::<Bytes>().is_none());
+        assert_eq!(
+            body.downcast_ref::<SizedStream<TestStream>>()
+                .unwrap()
+                .size(),
+            BodySize::Sized(3),
+        );
+        assert!(body.downcast_mut::<SizedStream<TestStream>>().is_some());
+
+        assert_eq!(to_bytes(body).await.unwrap(), "123");
+    }
 }
@@ -108,6 +108,110 @@ where
     }
 }
 
+pin_project! {
+    /// A three-way "either" type specialized for body types.
+    ///
+    /// Like [`EitherBody`], but for middleware that can respond with one of three body types, such
+    /// as the inner service's body, a rewritten body, or an error body, without nesting
+    /// `EitherBody<EitherBody<..>>`. The third variant defaults to `BoxBody` since error responses
+    /// are the common case.
+    ///
+    /// For example, such middleware will often have
+    /// `type Response = ServiceResponse<EitherBody3<B, String>>`.
+    #[project = EitherBody3Proj]
+    #[derive(Debug, Clone)]
+    pub enum EitherBody3<B1, B2, B3 = BoxBody> {
+        /// A body of type `B1`.
+        First { #[pin] body: B1 },
+
+        /// A body of type `B2`.
+        Second { #[pin] body: B2 },
+
+        /// A body of type `B3`.
+        Third { #[pin] body: B3 },
+    }
+}
+
+impl<B1, B2, B3> EitherBody3<B1, B2, B3> {
+    /// Creates new `EitherBody3` using first variant.
+    #[inline]
+    pub fn first(body: B1) -> Self {
+        Self::First { body }
+    }
+
+    /// Creates new `EitherBody3` using second variant.
+    #[inline]
+    pub fn second(body: B2) -> Self {
+        Self::Second { body }
+    }
+
+    /// Creates new `EitherBody3` using third variant.
+    #[inline]
+    pub fn third(body: B3) -> Self {
+        Self::Third { body }
+    }
+}
+
+impl<B1, B2, B3> MessageBody for EitherBody3<B1, B2, B3>
+where
+    B1: MessageBody + 'static,
+    B2: MessageBody + 'static,
+    B3: MessageBody + 'static,
+{
+    type Error = Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        match self {
+            EitherBody3::First { body } => body.size(),
+            EitherBody3::Second { body } => body.size(),
+            EitherBody3::Third { body } => body.size(),
+        }
+    }
+
+    #[inline]
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        match self.project() {
+            EitherBody3Proj::First { body } => body
+                .poll_next(cx)
+                .map_err(|err| Error::new_body().with_cause(err)),
+            EitherBody3Proj::Second { body } => body
+                .poll_next(cx)
+                .map_err(|err| Error::new_body().with_cause(err)),
+            EitherBody3Proj::Third { body } => body
+                .poll_next(cx)
+                .map_err(|err| Error::new_body().with_cause(err)),
+        }
+    }
+
+    #[inline]
+    fn try_into_bytes(self) -> Result<Bytes, Self> {
+        match self {
+            EitherBody3::First { body } => body
+                .try_into_bytes()
+                .map_err(|body| EitherBody3::First { body }),
+            EitherBody3::Second { body } => body
+                .try_into_bytes()
+                .map_err(|body| EitherBody3::Second { body }),
+            EitherBody3::Third { body } => body
+                .try_into_bytes()
+                .map_err(|body| EitherBody3::Third { body }),
+        }
+    }
+
+    #[inline]
+    fn boxed(self) -> BoxBody {
+        match self {
+            EitherBody3::First { body } => body.boxed(),
+            EitherBody3::Second { body } => body.boxed(),
+            EitherBody3::Third { body } => body.boxed(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +223,17 @@ mod tests {
         let _body: EitherBody<_, ()> = EitherBody::left(());
         let _body: EitherBody<(), _> = EitherBody::right(());
     }
+
+    #[test]
+    fn three_variants() {
+        let body = EitherBody3::<_, (), ()>::first("hello");
+        assert_eq!(body.size(), BodySize::Sized(5));
+        assert_eq!(body.try_into_bytes().unwrap(), "hello");
+
+        let body = EitherBody3::<(), _, ()>::second(Bytes::from_static(b"world"));
+        assert_eq!(body.try_into_bytes().unwrap(), "world");
+
+        let body = EitherBody3::<(), (), BoxBody>::third(BoxBody::new(()));
+        assert_eq!(body.size(), BodySize::Sized(0));
+    }
 }
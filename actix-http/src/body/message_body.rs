@@ -94,6 +94,10 @@ pub trait MessageBody {
     /// Body types with [`BodySize::None`] are allowed to return empty `Bytes`. Although, if calling
     /// this method, it is recommended to check `size` first and return early.
     ///
+    /// This method never panics and is safe to call on any body, including streaming bodies that
+    /// declare a [`BodySize::Sized`] length; a body that cannot be trivially converted is returned
+    /// unchanged, so callers can optimistically try this before falling back to `poll_next`.
+    ///
     /// # Errors
     /// The default implementation will error and return the original type back to the caller for
     /// further use.
@@ -488,6 +492,16 @@ where
             mapper: Some(mapper),
         }
     }
+
+    /// Returns a reference to the wrapped body.
+    pub(crate) fn get_ref(&self) -> &B {
+        &self.body
+    }
+
+    /// Returns a pinned mutable reference to the wrapped body.
+    pub(crate) fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut B> {
+        self.project().body
+    }
 }
 
 impl<B, F, E> MessageBody for MessageBodyMapErr<B, F>
@@ -692,6 +706,31 @@ mod tests {
         assert_poll_next_none!(Pin::new(&mut body));
     }
 
+    #[actix_rt::test]
+    async fn sized_stream_body_combinators() {
+        fn sized_stream_body() -> EitherBody<(), EitherBody<BoxBody, ()>> {
+            let body = body::SizedStream::new(
+                4,
+                stream::iter([Ok::<_, std::io::Error>(Bytes::from_static(b"test"))]),
+            );
+            let body = BoxBody::new(body);
+            let body = EitherBody::<_, ()>::left(body);
+            EitherBody::<(), _>::right(body)
+        }
+
+        // sized, but not complete; conversion fails without consuming the body
+        let body = sized_stream_body();
+        assert_eq!(body.size(), BodySize::Sized(4));
+        let mut body = body.try_into_bytes().unwrap_err();
+        assert_eq!(body.size(), BodySize::Sized(4));
+        assert_poll_next!(Pin::new(&mut body), Bytes::from("test"));
+        assert_poll_next_none!(Pin::new(&mut body));
+
+        let body = MessageBodyMapErr::new(sized_stream_body(), |err| err);
+        let body = body.try_into_bytes().unwrap_err();
+        assert_eq!(body::to_bytes(body).await.unwrap(), Bytes::from("test"));
+    }
+
     #[actix_rt::test]
     async fn none_body_combinators() {
         fn none_body() -> BoxBody {
@@ -10,16 +10,20 @@ mod boxed;
 mod either;
 mod message_body;
 mod none;
+mod on_finish;
+mod progress;
 mod size;
 mod sized_stream;
 mod utils;
 
 pub use self::body_stream::BodyStream;
 pub use self::boxed::BoxBody;
-pub use self::either::EitherBody;
+pub use self::either::{EitherBody, EitherBody3};
 pub use self::message_body::MessageBody;
 pub(crate) use self::message_body::MessageBodyMapErr;
 pub use self::none::None;
+pub use self::on_finish::{BodyOutcome, OnFinishBody};
+pub use self::progress::ProgressBody;
 pub use self::size::BodySize;
 pub use self::sized_stream::SizedStream;
 pub use self::utils::{to_bytes, to_bytes_limited, BodyLimitExceeded};
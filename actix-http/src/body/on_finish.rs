@@ -0,0 +1,198 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use super::{BodySize, MessageBody};
+
+/// How a body wrapped in [`OnFinishBody`] finished.
+#[derive(Debug)]
+pub enum BodyOutcome<'a, E> {
+    /// The body yielded all of its chunks.
+    Completed,
+
+    /// The body yielded an error.
+    Errored(&'a E),
+
+    /// The body was dropped before it completed or yielded an error.
+    Dropped,
+}
+
+pin_project! {
+    /// Body wrapper that reports when the inner body has finished.
+    ///
+    /// The `callback` is invoked exactly once, with a [`BodyOutcome`]: `Completed` when the inner
+    /// body completes, `Errored` when it yields an error, or `Dropped` when the body is dropped
+    /// before either happens, e.g. because the client disconnected. This makes it possible to,
+    /// e.g., log responses that were truncated because the data source of a streaming body failed,
+    /// and to release resources as soon as the body is done.
+    ///
+    /// Bodies with a [size](MessageBody::size) of `BodySize::None` or `BodySize::Sized(0)` may
+    /// never be polled and so report `Dropped` even when they were sent successfully.
+    ///
+    /// The byte stream itself is passed through unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::body::{self, BodyOutcome, BodyStream, OnFinishBody};
+    /// use bytes::Bytes;
+    /// use futures_util::stream;
+    ///
+    /// # actix_rt::System::new().block_on(async {
+    /// let chunks = stream::iter([Ok(Bytes::from_static(b"123")), Err("source failed")]);
+    ///
+    /// let body = OnFinishBody::new(BodyStream::new(chunks), |outcome: BodyOutcome<'_, &str>| {
+    ///     match outcome {
+    ///         BodyOutcome::Completed => {}
+    ///         BodyOutcome::Errored(err) => tracing::error!("response truncated: {}", err),
+    ///         BodyOutcome::Dropped => tracing::warn!("response abandoned"),
+    ///     }
+    /// });
+    ///
+    /// assert!(body::to_bytes(body).await.is_err());
+    /// # });
+    /// ```
+    pub struct OnFinishBody<B: MessageBody, F: FnOnce(BodyOutcome<'_, B::Error>)> {
+        #[pin]
+        body: B,
+        callback: Option<F>,
+    }
+
+    impl<B: MessageBody, F: FnOnce(BodyOutcome<'_, B::Error>)> PinnedDrop for OnFinishBody<B, F> {
+        fn drop(this: Pin<&mut Self>) {
+            if let Some(callback) = this.project().callback.take() {
+                callback(BodyOutcome::Dropped);
+            }
+        }
+    }
+}
+
+impl<B, F> OnFinishBody<B, F>
+where
+    B: MessageBody,
+    F: FnOnce(BodyOutcome<'_, B::Error>),
+{
+    /// Wraps `body`, calling `callback` once it completes, errors, or is dropped.
+    #[inline]
+    pub fn new(body: B, callback: F) -> Self {
+        Self {
+            body,
+            callback: Some(callback),
+        }
+    }
+}
+
+impl<B, F> MessageBody for OnFinishBody<B, F>
+where
+    B: MessageBody,
+    F: FnOnce(BodyOutcome<'_, B::Error>),
+{
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        let chunk = ready!(this.body.poll_next(cx));
+
+        match chunk {
+            None => {
+                if let Some(callback) = this.callback.take() {
+                    callback(BodyOutcome::Completed);
+                }
+            }
+
+            Some(Err(ref err)) => {
+                if let Some(callback) = this.callback.take() {
+                    callback(BodyOutcome::Errored(err));
+                }
+            }
+
+            Some(Ok(_)) => {}
+        }
+
+        Poll::Ready(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use futures_util::stream;
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::body::{to_bytes, BodyStream};
+
+    assert_impl_all!(OnFinishBody<(), fn(BodyOutcome<'_, std::convert::Infallible>)>: MessageBody);
+
+    type Events = Rc<RefCell<Vec<String>>>;
+
+    fn recorder<E: ToString>() -> (Events, impl FnOnce(BodyOutcome<'_, E>)) {
+        let events = Events::default();
+        let events2 = Rc::clone(&events);
+        (events, move |outcome: BodyOutcome<'_, E>| {
+            events2.borrow_mut().push(match outcome {
+                BodyOutcome::Completed => "completed".to_owned(),
+                BodyOutcome::Errored(err) => err.to_string(),
+                BodyOutcome::Dropped => "dropped".to_owned(),
+            })
+        })
+    }
+
+    #[actix_rt::test]
+    async fn reports_completion() {
+        let (events, callback) = recorder();
+
+        let body = OnFinishBody::new("hello", callback);
+        assert_eq!(body.size(), BodySize::Sized(5));
+
+        assert_eq!(to_bytes(body).await.unwrap(), "hello");
+        assert_eq!(*events.borrow(), ["completed"]);
+    }
+
+    #[actix_rt::test]
+    async fn reports_error_once() {
+        let (events, callback) = recorder();
+
+        let chunks = stream::iter([
+            Ok(Bytes::from_static(b"12")),
+            Err("failed"),
+            Err("failed again"),
+        ]);
+        let body = OnFinishBody::new(BodyStream::new(chunks), callback);
+        actix_rt::pin!(body);
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) =
+            actix_utils::future::poll_fn(|cx| body.as_mut().poll_next(cx)).await
+        {
+            chunks.push(chunk);
+        }
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(*events.borrow(), ["failed"]);
+    }
+
+    #[actix_rt::test]
+    async fn dropped_early() {
+        let (events, callback) = recorder::<std::convert::Infallible>();
+
+        let body = OnFinishBody::new("hello", callback);
+        drop(body);
+
+        assert_eq!(*events.borrow(), ["dropped"]);
+    }
+}
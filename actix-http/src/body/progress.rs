@@ -0,0 +1,205 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use super::{BodySize, MessageBody};
+
+pin_project! {
+    /// Body wrapper that reports how many bytes have been yielded by the inner body.
+    ///
+    /// Each time the inner body produces a chunk, the `callback` is invoked with the cumulative
+    /// number of bytes emitted so far and the [size](MessageBody::size) the body declared when it
+    /// was wrapped. The callback is not invoked while the inner body is pending, nor when it errors
+    /// or completes. Empty chunks still invoke the callback, with an unchanged byte count.
+    ///
+    /// The byte stream itself is passed through unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::body::{self, BodySize, ProgressBody};
+    /// use bytes::Bytes;
+    ///
+    /// # actix_rt::System::new().block_on(async {
+    /// let mut progress = Vec::new();
+    ///
+    /// let body = ProgressBody::new(Bytes::from_static(b"123"), |sent, total| {
+    ///     progress.push((sent, total));
+    /// });
+    /// let bytes = body::to_bytes(body).await.unwrap();
+    ///
+    /// assert_eq!(bytes, "123");
+    /// assert_eq!(progress, [(3, BodySize::Sized(3))]);
+    /// # });
+    /// ```
+    pub struct ProgressBody<B, F> {
+        #[pin]
+        body: B,
+        callback: F,
+        emitted: u64,
+        total: BodySize,
+    }
+}
+
+impl<B, F> ProgressBody<B, F>
+where
+    B: MessageBody,
+    F: FnMut(u64, BodySize),
+{
+    /// Wraps `body`, calling `callback` each time a chunk is yielded.
+    #[inline]
+    pub fn new(body: B, callback: F) -> Self {
+        Self {
+            total: body.size(),
+            body,
+            callback,
+            emitted: 0,
+        }
+    }
+
+    /// Returns the number of bytes yielded by the inner body so far.
+    #[inline]
+    pub fn bytes_emitted(&self) -> u64 {
+        self.emitted
+    }
+}
+
+impl<B, F> MessageBody for ProgressBody<B, F>
+where
+    B: MessageBody,
+    F: FnMut(u64, BodySize),
+{
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        let chunk = ready!(this.body.poll_next(cx));
+
+        if let Some(Ok(ref bytes)) = chunk {
+            *this.emitted += bytes.len() as u64;
+            (this.callback)(*this.emitted, *this.total);
+        }
+
+        Poll::Ready(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque, convert::Infallible, rc::Rc};
+
+    use actix_rt::pin;
+    use actix_utils::future::poll_fn;
+    use futures_util::{stream, StreamExt as _};
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::{
+        body::{to_bytes, BodyStream, BoxBody},
+        Error,
+    };
+
+    assert_impl_all!(ProgressBody<(), fn(u64, BodySize)>: MessageBody);
+
+    type Events = Rc<RefCell<Vec<(u64, BodySize)>>>;
+
+    fn recorder() -> (Events, impl FnMut(u64, BodySize)) {
+        let events = Events::default();
+        let events2 = Rc::clone(&events);
+        (events, move |sent, total| {
+            events2.borrow_mut().push((sent, total))
+        })
+    }
+
+    /// Body that yields its chunks as-is, including empty ones.
+    struct Chunks(VecDeque<Bytes>);
+
+    impl MessageBody for Chunks {
+        type Error = Infallible;
+
+        fn size(&self) -> BodySize {
+            BodySize::Stream
+        }
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+            Poll::Ready(self.0.pop_front().map(Ok))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn reports_cumulative_bytes() {
+        let (events, callback) = recorder();
+
+        let chunks = Chunks(VecDeque::from([
+            Bytes::from_static(b"12"),
+            Bytes::new(),
+            Bytes::from_static(b"345"),
+        ]));
+        let body = ProgressBody::new(chunks, callback);
+        assert_eq!(body.size(), BodySize::Stream);
+
+        assert_eq!(to_bytes(body).await.unwrap(), "12345");
+        assert_eq!(
+            *events.borrow(),
+            [
+                (2, BodySize::Stream),
+                (2, BodySize::Stream),
+                (5, BodySize::Stream),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn pending_does_not_report() {
+        let (events, callback) = recorder();
+
+        let mut ready = false;
+        let stream = stream::poll_fn(move |cx| {
+            if ready {
+                Poll::Ready(None)
+            } else {
+                ready = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        });
+        let body = ProgressBody::new(BodyStream::new(stream.map(Ok::<Bytes, Error>)), callback);
+        pin!(body);
+
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(body.as_mut().poll_next(&mut cx).is_pending());
+        assert!(events.borrow().is_empty());
+
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx)).await.is_none());
+        assert!(events.borrow().is_empty());
+        assert_eq!(body.bytes_emitted(), 0);
+    }
+
+    #[actix_rt::test]
+    async fn composes_with_boxed_body() {
+        let (events, callback) = recorder();
+
+        let body = ProgressBody::new(BoxBody::new("hello"), callback).boxed();
+        assert_eq!(body.size(), BodySize::Sized(5));
+
+        assert_eq!(to_bytes(body).await.unwrap(), "hello");
+        assert_eq!(*events.borrow(), [(5, BodySize::Sized(5))]);
+    }
+}
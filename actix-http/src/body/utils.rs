@@ -188,6 +188,16 @@ mod tests {
         assert!(to_bytes_limited(body, 3).await.is_err());
     }
 
+    #[actix_rt::test]
+    async fn to_bytes_limited_sized_fails_fast() {
+        // declared size exceeds limit so the stream must never be polled
+        let stream = stream::poll_fn(|_| -> Poll<Option<Result<Bytes, Error>>> {
+            panic!("body should not be polled")
+        });
+        let body = SizedStream::new(8, stream);
+        assert!(to_bytes_limited(body, 7).await.is_err());
+    }
+
     #[actix_rt::test]
     async fn to_body_limit_error() {
         let err_stream = stream::once(async { Err(io::Error::new(io::ErrorKind::Other, "")) });
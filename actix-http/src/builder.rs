@@ -5,7 +5,7 @@ use actix_service::{IntoServiceFactory, Service, ServiceFactory};
 
 use crate::{
     body::{BoxBody, MessageBody},
-    h1::{self, ExpectHandler, H1Service, UpgradeHandler},
+    h1::{self, ExpectHandler, H1Service, HeadLimits, UpgradeHandler},
     service::HttpService,
     ConnectCallback, Extensions, KeepAlive, Request, Response, ServiceConfig,
 };
@@ -19,6 +19,7 @@ pub struct HttpServiceBuilder<T, S, X = ExpectHandler, U = UpgradeHandler> {
     client_disconnect_timeout: Duration,
     secure: bool,
     local_addr: Option<net::SocketAddr>,
+    h1_limits: HeadLimits,
     expect: X,
     upgrade: Option<U>,
     on_connect_ext: Option<Rc<ConnectCallback<T>>>,
@@ -40,6 +41,7 @@ where
             client_disconnect_timeout: Duration::ZERO,
             secure: false,
             local_addr: None,
+            h1_limits: HeadLimits::default(),
 
             // dispatcher parts
             expect: ExpectHandler,
@@ -124,6 +126,41 @@ where
         self.client_disconnect_timeout(dur)
     }
 
+    /// Set the maximum number of headers in HTTP/1 requests.
+    ///
+    /// Requests with more headers are rejected with a `431 Request Header Fields Too Large`
+    /// response.
+    ///
+    /// By default, the limit is [`DEFAULT_MAX_HEADER_COUNT`](crate::h1::DEFAULT_MAX_HEADER_COUNT).
+    pub fn h1_max_header_count(mut self, count: usize) -> Self {
+        self.h1_limits.max_header_count = count;
+        self
+    }
+
+    /// Set the maximum size, in bytes, of HTTP/1 request heads.
+    ///
+    /// The size includes the request line and all headers. Requests with larger heads are rejected
+    /// with a `431 Request Header Fields Too Large` response.
+    ///
+    /// By default, the limit is [`DEFAULT_MAX_HEADER_SIZE`](crate::h1::DEFAULT_MAX_HEADER_SIZE).
+    pub fn h1_max_header_size(mut self, size: usize) -> Self {
+        self.h1_limits.max_header_size = size;
+        self
+    }
+
+    /// Set the maximum size, in bytes, of HTTP/1 request lines, excluding the line ending.
+    ///
+    /// Requests with longer request lines (eg. because of a long URI) are rejected with a
+    /// `431 Request Header Fields Too Large` response. Request lines are also limited by
+    /// [`h1_max_header_size`](Self::h1_max_header_size).
+    ///
+    /// By default, the limit is
+    /// [`DEFAULT_MAX_REQUEST_LINE_SIZE`](crate::h1::DEFAULT_MAX_REQUEST_LINE_SIZE).
+    pub fn h1_max_request_line_size(mut self, size: usize) -> Self {
+        self.h1_limits.max_request_line_size = size;
+        self
+    }
+
     /// Provide service for `EXPECT: 100-Continue` support.
     ///
     /// Service get called with request that contains `EXPECT` header.
@@ -142,6 +179,7 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
             expect: expect.into_factory(),
             upgrade: self.upgrade,
             on_connect_ext: self.on_connect_ext,
@@ -166,6 +204,7 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
             expect: self.expect,
             upgrade: Some(upgrade.into_factory()),
             on_connect_ext: self.on_connect_ext,
@@ -195,12 +234,13 @@ where
         S::InitError: fmt::Debug,
         S::Response: Into<Response<B>>,
     {
-        let cfg = ServiceConfig::new(
+        let cfg = ServiceConfig::with_h1_limits(
             self.keep_alive,
             self.client_request_timeout,
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
+            self.h1_limits,
         );
 
         H1Service::with_config(cfg, service.into_factory())
@@ -220,12 +260,13 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
+        let cfg = ServiceConfig::with_h1_limits(
             self.keep_alive,
             self.client_request_timeout,
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
+            self.h1_limits,
         );
 
         crate::h2::H2Service::with_config(cfg, service.into_factory())
@@ -242,12 +283,13 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
+        let cfg = ServiceConfig::with_h1_limits(
             self.keep_alive,
             self.client_request_timeout,
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
+            self.h1_limits,
         );
 
         HttpService::with_config(cfg, service.into_factory())
@@ -6,7 +6,7 @@ use std::{
 
 use bytes::BytesMut;
 
-use crate::{date::DateService, KeepAlive};
+use crate::{date::DateService, h1::HeadLimits, KeepAlive};
 
 /// HTTP service configuration.
 #[derive(Debug, Clone)]
@@ -20,6 +20,7 @@ struct Inner {
     secure: bool,
     local_addr: Option<std::net::SocketAddr>,
     date_service: DateService,
+    h1_limits: HeadLimits,
 }
 
 impl Default for ServiceConfig {
@@ -42,6 +43,25 @@ impl ServiceConfig {
         client_disconnect_timeout: Duration,
         secure: bool,
         local_addr: Option<net::SocketAddr>,
+    ) -> ServiceConfig {
+        ServiceConfig::with_h1_limits(
+            keep_alive,
+            client_request_timeout,
+            client_disconnect_timeout,
+            secure,
+            local_addr,
+            HeadLimits::default(),
+        )
+    }
+
+    /// Create instance of `ServiceConfig` with limits for HTTP/1 request heads.
+    pub(crate) fn with_h1_limits(
+        keep_alive: KeepAlive,
+        client_request_timeout: Duration,
+        client_disconnect_timeout: Duration,
+        secure: bool,
+        local_addr: Option<net::SocketAddr>,
+        h1_limits: HeadLimits,
     ) -> ServiceConfig {
         ServiceConfig(Rc::new(Inner {
             keep_alive: keep_alive.normalize(),
@@ -50,6 +70,7 @@ impl ServiceConfig {
             secure,
             local_addr,
             date_service: DateService::new(),
+            h1_limits,
         }))
     }
 
@@ -73,6 +94,12 @@ impl ServiceConfig {
         self.0.keep_alive
     }
 
+    /// Limits for HTTP/1 request heads.
+    #[inline]
+    pub(crate) fn h1_limits(&self) -> HeadLimits {
+        self.0.h1_limits
+    }
+
     /// Creates a time object representing the deadline for this connection's keep-alive period, if
     /// enabled.
     ///
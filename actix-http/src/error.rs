@@ -272,6 +272,10 @@ pub enum PayloadError {
     #[display(fmt = "payload length is unknown")]
     UnknownLength,
 
+    /// Payload was not received in time.
+    #[display(fmt = "timed out while reading payload")]
+    Timeout,
+
     /// HTTP/2 payload error.
     #[cfg(feature = "http2")]
     #[display(fmt = "{}", _0)]
@@ -290,6 +294,7 @@ impl std::error::Error for PayloadError {
             PayloadError::EncodingCorrupted => None,
             PayloadError::Overflow => None,
             PayloadError::UnknownLength => None,
+            PayloadError::Timeout => None,
             #[cfg(feature = "http2")]
             PayloadError::Http2Payload(err) => Some(err),
             PayloadError::Io(err) => Some(err),
@@ -10,7 +10,8 @@ use super::{
     encoder, Message, MessageType,
 };
 use crate::{
-    body::BodySize, error::ParseError, ConnectionType, Request, Response, ServiceConfig,
+    body::BodySize, error::ParseError, header, responses::Trailers, ConnectionType, Request,
+    Response, ServiceConfig,
 };
 
 bitflags! {
@@ -58,11 +59,12 @@ impl Codec {
         } else {
             Flags::empty()
         };
+        let decoder = decoder::MessageDecoder::with_limits(config.h1_limits());
 
         Codec {
             config,
             flags,
-            decoder: decoder::MessageDecoder::default(),
+            decoder,
             payload: None,
             version: Version::HTTP_11,
             conn_type: ConnectionType::Close,
@@ -182,6 +184,11 @@ impl Encoder<Message<(Response<()>, BodySize)>> for Codec {
                     self.conn_type,
                     &self.config,
                 )?;
+
+                // trailers can only be sent when they are declared in advance
+                if res.headers().contains_key(header::TRAILER) {
+                    self.encoder.te.set_trailers(Trailers::take(&mut res));
+                }
             }
 
             Message::Chunk(Some(bytes)) => {
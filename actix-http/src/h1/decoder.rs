@@ -12,10 +12,46 @@ use super::chunked::ChunkedState;
 use crate::{error::ParseError, header::HeaderMap, ConnectionType, Request, ResponseHead};
 
 pub(crate) const MAX_BUFFER_SIZE: usize = 131_072;
-const MAX_HEADERS: usize = 96;
+pub(crate) const MAX_HEADERS: usize = 96;
+
+/// Limits applied to the heads of incoming messages.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HeadLimits {
+    /// Maximum number of headers.
+    pub(crate) max_header_count: usize,
+
+    /// Maximum size of the head, in bytes, including the request or status line.
+    pub(crate) max_header_size: usize,
+
+    /// Maximum size of the request line, in bytes, excluding its line ending.
+    pub(crate) max_request_line_size: usize,
+}
+
+impl Default for HeadLimits {
+    fn default() -> Self {
+        Self {
+            max_header_count: super::DEFAULT_MAX_HEADER_COUNT,
+            max_header_size: super::DEFAULT_MAX_HEADER_SIZE,
+            max_request_line_size: super::DEFAULT_MAX_REQUEST_LINE_SIZE,
+        }
+    }
+}
 
 /// Incoming message decoder
-pub(crate) struct MessageDecoder<T: MessageType>(PhantomData<T>);
+pub(crate) struct MessageDecoder<T: MessageType> {
+    limits: HeadLimits,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: MessageType> MessageDecoder<T> {
+    /// Constructs decoder that applies `limits` to message heads.
+    pub(crate) fn with_limits(limits: HeadLimits) -> Self {
+        MessageDecoder {
+            limits,
+            _phantom: PhantomData,
+        }
+    }
+}
 
 #[derive(Debug)]
 /// Incoming request type
@@ -27,7 +63,7 @@ pub(crate) enum PayloadType {
 
 impl<T: MessageType> Default for MessageDecoder<T> {
     fn default() -> Self {
-        MessageDecoder(PhantomData)
+        MessageDecoder::with_limits(HeadLimits::default())
     }
 }
 
@@ -36,7 +72,7 @@ impl<T: MessageType> Decoder for MessageDecoder<T> {
     type Error = ParseError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        T::decode(src)
+        T::decode(src, &self.limits)
     }
 }
 
@@ -70,7 +106,10 @@ pub(crate) trait MessageType: Sized {
 
     fn headers_mut(&mut self) -> &mut HeaderMap;
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError>;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &HeadLimits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError>;
 
     fn set_headers(
         &mut self,
@@ -230,22 +269,34 @@ impl MessageType for Request {
         &mut self.head_mut().headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
-        let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &HeadLimits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
+        // the request line can only be longer than the limit if it is lower than the head limit
+        if limits.max_request_line_size < limits.max_header_size {
+            check_request_line(src, limits.max_request_line_size)?;
+        }
+
+        let mut headers_buf: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+        let mut headers_heap = Vec::new();
+        let headers = header_index_buf(&mut headers_buf, &mut headers_heap, limits);
 
         let (len, method, uri, ver, h_len) = {
             // SAFETY:
             // Create an uninitialized array of `MaybeUninit`. The `assume_init` is safe because the
             // type we are claiming to have initialized here is a bunch of `MaybeUninit`s, which
             // do not require initialization.
-            let mut parsed = unsafe {
+            let mut parsed_buf = unsafe {
                 MaybeUninit::<[MaybeUninit<httparse::Header<'_>>; MAX_HEADERS]>::uninit()
                     .assume_init()
             };
+            let mut parsed_heap = Vec::new();
+            let parsed = parsed_header_buf(&mut parsed_buf, &mut parsed_heap, limits);
 
             let mut req = httparse::Request::new(&mut []);
 
-            match req.parse_with_uninit_headers(src, &mut parsed)? {
+            match req.parse_with_uninit_headers(src, parsed)? {
                 httparse::Status::Complete(len) => {
                     let method = Method::from_bytes(req.method.unwrap().as_bytes())
                         .map_err(|_| ParseError::Method)?;
@@ -255,14 +306,14 @@ impl MessageType for Request {
                     } else {
                         Version::HTTP_10
                     };
-                    HeaderIndex::record(src, req.headers, &mut headers);
+                    HeaderIndex::record(src, req.headers, headers);
 
                     (len, method, uri, version, req.headers.len())
                 }
 
                 httparse::Status::Partial => {
-                    return if src.len() >= MAX_BUFFER_SIZE {
-                        trace!("MAX_BUFFER_SIZE unprocessed data reached, closing");
+                    return if src.len() >= limits.max_header_size {
+                        trace!("max header size of unprocessed data reached, closing");
                         Err(ParseError::TooLarge)
                     } else {
                         // Return None to notify more read are needed for parsing request
@@ -272,6 +323,11 @@ impl MessageType for Request {
             }
         };
 
+        if len > limits.max_header_size {
+            trace!("request head exceeds max header size, closing");
+            return Err(ParseError::TooLarge);
+        }
+
         let mut msg = Request::new();
 
         // convert headers
@@ -330,25 +386,32 @@ impl MessageType for ResponseHead {
         &mut self.headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
-        let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &HeadLimits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
+        let mut headers_buf: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+        let mut headers_heap = Vec::new();
+        let headers = header_index_buf(&mut headers_buf, &mut headers_heap, limits);
 
         let (len, ver, status, h_len) = {
             // SAFETY:
             // Create an uninitialized array of `MaybeUninit`. The `assume_init` is safe because the
             // type we are claiming to have initialized here is a bunch of `MaybeUninit`s, which
             // do not require initialization.
-            let mut parsed = unsafe {
+            let mut parsed_buf = unsafe {
                 MaybeUninit::<[MaybeUninit<httparse::Header<'_>>; MAX_HEADERS]>::uninit()
                     .assume_init()
             };
+            let mut parsed_heap = Vec::new();
+            let parsed = parsed_header_buf(&mut parsed_buf, &mut parsed_heap, limits);
 
             let mut res = httparse::Response::new(&mut []);
 
             let mut config = httparse::ParserConfig::default();
             config.allow_spaces_after_header_name_in_responses(true);
 
-            match config.parse_response_with_uninit_headers(&mut res, src, &mut parsed)? {
+            match config.parse_response_with_uninit_headers(&mut res, src, parsed)? {
                 httparse::Status::Complete(len) => {
                     let version = if res.version.unwrap() == 1 {
                         Version::HTTP_11
@@ -358,14 +421,14 @@ impl MessageType for ResponseHead {
 
                     let status = StatusCode::from_u16(res.code.unwrap())
                         .map_err(|_| ParseError::Status)?;
-                    HeaderIndex::record(src, res.headers, &mut headers);
+                    HeaderIndex::record(src, res.headers, headers);
 
                     (len, version, status, res.headers.len())
                 }
 
                 httparse::Status::Partial => {
-                    return if src.len() >= MAX_BUFFER_SIZE {
-                        error!("MAX_BUFFER_SIZE unprocessed data reached, closing");
+                    return if src.len() >= limits.max_header_size {
+                        error!("max header size of unprocessed data reached, closing");
                         Err(ParseError::TooLarge)
                     } else {
                         Ok(None)
@@ -374,6 +437,11 @@ impl MessageType for ResponseHead {
             }
         };
 
+        if len > limits.max_header_size {
+            error!("response head exceeds max header size, closing");
+            return Err(ParseError::TooLarge);
+        }
+
         let mut msg = ResponseHead::new(status);
         msg.version = ver;
 
@@ -408,6 +476,64 @@ impl MessageType for ResponseHead {
     }
 }
 
+/// Returns an error if the request line at the start of `src` is longer than `max` bytes.
+fn check_request_line(src: &[u8], max: usize) -> Result<(), ParseError> {
+    match src.iter().take(max + 2).position(|&b| b == b'\n') {
+        Some(pos) => {
+            let len = if pos > 0 && src[pos - 1] == b'\r' {
+                pos - 1
+            } else {
+                pos
+            };
+
+            if len > max {
+                trace!("request line exceeds max request line size, closing");
+                return Err(ParseError::TooLarge);
+            }
+        }
+
+        // no line ending found within the limit
+        None if src.len() >= max + 2 => {
+            trace!("request line exceeds max request line size, closing");
+            return Err(ParseError::TooLarge);
+        }
+
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Returns a header index buffer sized to the header count limit, using the stack buffer unless
+/// the limit is higher than its size.
+fn header_index_buf<'a>(
+    buf: &'a mut [HeaderIndex; MAX_HEADERS],
+    heap: &'a mut Vec<HeaderIndex>,
+    limits: &HeadLimits,
+) -> &'a mut [HeaderIndex] {
+    if limits.max_header_count <= MAX_HEADERS {
+        &mut buf[..limits.max_header_count]
+    } else {
+        heap.resize(limits.max_header_count, EMPTY_HEADER_INDEX);
+        heap
+    }
+}
+
+/// Returns a parsed header buffer sized to the header count limit, using the stack buffer unless
+/// the limit is higher than its size.
+fn parsed_header_buf<'a, 'b>(
+    buf: &'a mut [MaybeUninit<httparse::Header<'b>>; MAX_HEADERS],
+    heap: &'a mut Vec<MaybeUninit<httparse::Header<'b>>>,
+    limits: &HeadLimits,
+) -> &'a mut [MaybeUninit<httparse::Header<'b>>] {
+    if limits.max_header_count <= MAX_HEADERS {
+        &mut buf[..limits.max_header_count]
+    } else {
+        heap.resize(limits.max_header_count, MaybeUninit::uninit());
+        heap
+    }
+}
+
 #[derive(Clone, Copy)]
 pub(crate) struct HeaderIndex {
     pub(crate) name: (usize, usize),
@@ -1193,4 +1319,74 @@ mod tests {
         let chunk = pl.decode(&mut buf).unwrap().unwrap();
         assert_eq!(chunk, PayloadItem::Chunk(Bytes::from_static(b"0\r\n")));
     }
+
+    #[test]
+    fn head_limits() {
+        fn decode(buf: &str, limits: HeadLimits) -> Result<Option<Request>, ParseError> {
+            let mut buf = BytesMut::from(buf);
+            MessageDecoder::<Request>::with_limits(limits)
+                .decode(&mut buf)
+                .map(|res| res.map(|(req, _)| req))
+        }
+
+        let headers = |count: usize| {
+            (0..count)
+                .map(|idx| format!("x-header-{}: {}\r\n", idx, idx))
+                .collect::<String>()
+        };
+
+        // header count
+        let limits = HeadLimits {
+            max_header_count: 2,
+            ..HeadLimits::default()
+        };
+        let req = format!("GET /test HTTP/1.1\r\n{}\r\n", headers(2));
+        assert!(decode(&req, limits).unwrap().is_some());
+        let req = format!("GET /test HTTP/1.1\r\n{}\r\n", headers(3));
+        assert!(matches!(decode(&req, limits), Err(ParseError::TooLarge)));
+
+        // header count larger than the default
+        let req = format!("GET /test HTTP/1.1\r\n{}\r\n", headers(150));
+        assert!(matches!(
+            decode(&req, HeadLimits::default()),
+            Err(ParseError::TooLarge)
+        ));
+        let limits = HeadLimits {
+            max_header_count: 200,
+            ..HeadLimits::default()
+        };
+        let req = decode(&req, limits).unwrap().unwrap();
+        assert_eq!(req.headers().len(), 150);
+
+        // header size, complete and partial
+        let limits = HeadLimits {
+            max_header_size: 64,
+            ..HeadLimits::default()
+        };
+        assert!(decode("GET /test HTTP/1.1\r\nx-header: 1\r\n\r\n", limits)
+            .unwrap()
+            .is_some());
+        let req = format!("GET /test HTTP/1.1\r\nx-header: {}\r\n\r\n", "a".repeat(64));
+        assert!(matches!(decode(&req, limits), Err(ParseError::TooLarge)));
+        let req = format!("GET /test HTTP/1.1\r\nx-header: {}", "a".repeat(64));
+        assert!(matches!(decode(&req, limits), Err(ParseError::TooLarge)));
+
+        // request line, complete and partial
+        let limits = HeadLimits {
+            max_request_line_size: 18,
+            ..HeadLimits::default()
+        };
+        assert!(decode("GET /test HTTP/1.1\r\n\r\n", limits)
+            .unwrap()
+            .is_some());
+        assert!(decode("GET /test", limits).unwrap().is_none());
+        assert!(matches!(
+            decode("GET /tests HTTP/1.1\r\n\r\n", limits),
+            Err(ParseError::TooLarge)
+        ));
+        assert!(matches!(
+            decode("GET /tests/long/path", limits),
+            Err(ParseError::TooLarge)
+        ));
+    }
 }
@@ -957,9 +957,17 @@ where
 
         let mut read_some = false;
 
+        // heads larger than the default buffer size must fit into the read buffer to be decoded
+        let max_buffer_size = this
+            .codec
+            .config()
+            .h1_limits()
+            .max_header_size
+            .max(MAX_BUFFER_SIZE);
+
         loop {
             // Return early when read buf exceed decoder's max buffer size.
-            if this.read_buf.len() >= MAX_BUFFER_SIZE {
+            if this.read_buf.len() >= max_buffer_size {
                 // At this point it's not known IO stream is still scheduled to be waked up so
                 // force wake up dispatcher just in case.
                 //
@@ -13,7 +13,9 @@ use crate::{
     header::{
         map::Value, HeaderMap, HeaderName, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING,
     },
-    helpers, ConnectionType, RequestHeadType, Response, ServiceConfig, StatusCode, Version,
+    helpers,
+    responses::Trailers,
+    ConnectionType, RequestHeadType, Response, ServiceConfig, StatusCode, Version,
 };
 
 const AVERAGE_HEADER_SIZE: usize = 30;
@@ -367,6 +369,7 @@ impl<T: MessageType> MessageEncoder<T> {
 #[derive(Debug)]
 pub(crate) struct TransferEncoding {
     kind: TransferEncodingKind,
+    trailers: Option<Trailers>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -390,6 +393,7 @@ impl TransferEncoding {
     pub fn empty() -> TransferEncoding {
         TransferEncoding {
             kind: TransferEncodingKind::Length(0),
+            trailers: None,
         }
     }
 
@@ -397,6 +401,7 @@ impl TransferEncoding {
     pub fn eof() -> TransferEncoding {
         TransferEncoding {
             kind: TransferEncodingKind::Eof,
+            trailers: None,
         }
     }
 
@@ -404,6 +409,7 @@ impl TransferEncoding {
     pub fn chunked() -> TransferEncoding {
         TransferEncoding {
             kind: TransferEncodingKind::Chunked(false),
+            trailers: None,
         }
     }
 
@@ -411,6 +417,14 @@ impl TransferEncoding {
     pub fn length(len: u64) -> TransferEncoding {
         TransferEncoding {
             kind: TransferEncodingKind::Length(len),
+            trailers: None,
+        }
+    }
+
+    /// Sets trailers to send after the last chunk. Ignored if encoding is not chunked.
+    pub fn set_trailers(&mut self, trailers: Option<Trailers>) {
+        if let TransferEncodingKind::Chunked(_) = self.kind {
+            self.trailers = trailers;
         }
     }
 
@@ -430,7 +444,7 @@ impl TransferEncoding {
 
                 if msg.is_empty() {
                     *eof = true;
-                    buf.extend_from_slice(b"0\r\n\r\n");
+                    write_last_chunk(self.trailers.take(), buf);
                 } else {
                     writeln!(helpers::MutWriter(buf), "{:X}\r", msg.len())
                         .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
@@ -474,7 +488,7 @@ impl TransferEncoding {
             TransferEncodingKind::Chunked(ref mut eof) => {
                 if !*eof {
                     *eof = true;
-                    buf.extend_from_slice(b"0\r\n\r\n");
+                    write_last_chunk(self.trailers.take(), buf);
                 }
                 Ok(())
             }
@@ -482,6 +496,23 @@ impl TransferEncoding {
     }
 }
 
+/// Writes the last chunk of a chunked body, followed by the trailer fields, if any.
+fn write_last_chunk(trailers: Option<Trailers>, buf: &mut BytesMut) {
+    buf.extend_from_slice(b"0\r\n");
+
+    if let Some(trailers) = trailers {
+        for (name, value) in trailers.into_headers() {
+            buf.reserve(name.as_str().len() + value.len() + 4);
+            buf.extend_from_slice(name.as_str().as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+    }
+
+    buf.extend_from_slice(b"\r\n");
+}
+
 /// # Safety
 /// Callers must ensure that the given `len` matches the given `value` length and that `buf` is
 /// valid for writes of at least `len` bytes.
@@ -551,6 +582,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_chunked_te_trailers() {
+        let trailers = || {
+            let mut res = Response::ok();
+            res.set_trailers(|| {
+                let mut trailers = HeaderMap::new();
+                trailers.insert(
+                    HeaderName::from_static("x-checksum"),
+                    HeaderValue::from_static("abc"),
+                );
+                trailers
+            });
+            Trailers::take(&mut res)
+        };
+
+        let mut bytes = BytesMut::new();
+        let mut enc = TransferEncoding::chunked();
+        enc.set_trailers(trailers());
+        assert!(!enc.encode(b"test", &mut bytes).unwrap());
+        enc.encode_eof(&mut bytes).unwrap();
+        assert_eq!(
+            bytes.split().freeze(),
+            Bytes::from_static(b"4\r\ntest\r\n0\r\nx-checksum: abc\r\n\r\n")
+        );
+
+        // empty chunk also ends the body
+        let mut enc = TransferEncoding::chunked();
+        enc.set_trailers(trailers());
+        assert!(enc.encode(b"", &mut bytes).unwrap());
+        assert_eq!(
+            bytes.split().freeze(),
+            Bytes::from_static(b"0\r\nx-checksum: abc\r\n\r\n")
+        );
+
+        // trailers are ignored when not using chunked encoding
+        let mut enc = TransferEncoding::length(4);
+        enc.set_trailers(trailers());
+        assert!(enc.encode(b"test", &mut bytes).unwrap());
+        enc.encode_eof(&mut bytes).unwrap();
+        assert_eq!(bytes.split().freeze(), Bytes::from_static(b"test"));
+    }
+
     #[actix_rt::test]
     async fn test_camel_case() {
         let mut bytes = BytesMut::with_capacity(2048);
@@ -26,6 +26,17 @@ pub use self::service::{H1Service, H1ServiceHandler};
 pub use self::upgrade::UpgradeHandler;
 pub use self::utils::SendResponse;
 
+pub(crate) use self::decoder::HeadLimits;
+
+/// Default maximum number of headers in HTTP/1 requests.
+pub const DEFAULT_MAX_HEADER_COUNT: usize = decoder::MAX_HEADERS;
+
+/// Default maximum size, in bytes, of HTTP/1 request heads.
+pub const DEFAULT_MAX_HEADER_SIZE: usize = decoder::MAX_BUFFER_SIZE;
+
+/// Default maximum size, in bytes, of HTTP/1 request lines.
+pub const DEFAULT_MAX_REQUEST_LINE_SIZE: usize = decoder::MAX_BUFFER_SIZE;
+
 #[derive(Debug)]
 /// Codec message
 pub enum Message<T> {
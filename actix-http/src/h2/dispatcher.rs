@@ -28,6 +28,7 @@ use crate::{
     header::{
         HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING, UPGRADE,
     },
+    responses::Trailers,
     service::HttpFlow,
     Extensions, Method, OnConnectData, Payload, Request, Response, ResponseHead,
 };
@@ -212,12 +213,15 @@ async fn handle_response<B>(
 where
     B: MessageBody,
 {
-    let (res, body) = res.replace_body(());
+    let (mut res, body) = res.replace_body(());
+
+    // trailers are not sent in response to HEAD requests
+    let trailers = Trailers::take(&mut res).filter(|_| !head_req);
 
     // prepare response.
     let mut size = body.size();
     let res = prepare_response(config, res.head(), &mut size);
-    let eof_or_head = size.is_eof() || head_req;
+    let eof_or_head = (size.is_eof() && trailers.is_none()) || head_req;
 
     // send response head and return on eof.
     let mut stream = tx
@@ -264,10 +268,24 @@ where
         }
     }
 
-    // response body streaming finished. send end of stream and return.
-    stream
-        .send_data(Bytes::new(), true)
-        .map_err(DispatchError::SendData)?;
+    // response body streaming finished. send trailers or end of stream and return.
+    match trailers.map(Trailers::into_headers) {
+        Some(trailers) if !trailers.is_empty() => {
+            let mut map = http::HeaderMap::with_capacity(trailers.len());
+
+            for (name, value) in trailers {
+                map.append(name, value);
+            }
+
+            stream.send_trailers(map).map_err(DispatchError::SendData)?;
+        }
+
+        _ => {
+            stream
+                .send_data(Bytes::new(), true)
+                .map_err(DispatchError::SendData)?;
+        }
+    }
 
     Ok(())
 }
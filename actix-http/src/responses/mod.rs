@@ -9,3 +9,4 @@ pub use self::builder::ResponseBuilder;
 pub(crate) use self::head::BoxedResponseHead;
 pub use self::head::ResponseHead;
 pub use self::response::Response;
+pub(crate) use self::response::Trailers;
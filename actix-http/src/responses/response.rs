@@ -22,6 +22,28 @@ pub struct Response<B> {
     pub(crate) extensions: RefCell<Extensions>,
 }
 
+/// Function producing trailer fields, stored in response extensions by
+/// [`Response::set_trailers`].
+pub(crate) struct Trailers(Box<dyn FnOnce() -> HeaderMap>);
+
+impl Trailers {
+    /// Removes the trailers function from the extensions of `res`, if set.
+    pub(crate) fn take<B>(res: &mut Response<B>) -> Option<Self> {
+        res.extensions_mut().remove::<Self>()
+    }
+
+    /// Calls the trailers function.
+    pub(crate) fn into_headers(self) -> HeaderMap {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for Trailers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trailers").finish_non_exhaustive()
+    }
+}
+
 impl Response<BoxBody> {
     /// Constructs a new response with default body.
     #[inline]
@@ -140,6 +162,33 @@ impl<B> Response<B> {
         self.extensions.borrow_mut()
     }
 
+    /// Sets a function that produces trailer fields to send after the response body.
+    ///
+    /// The function is called once the body has been streamed completely, so it can report values
+    /// computed while streaming, such as checksums.
+    ///
+    /// Trailers are sent by HTTP/2 connections. HTTP/1.1 connections only send them for chunked
+    /// responses that declare the trailer fields using the `Trailer` header; otherwise, they are
+    /// silently dropped. Trailers are never sent for responses to `HEAD` requests.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::{header::{HeaderMap, HeaderName, HeaderValue}, Response};
+    ///
+    /// let mut res = Response::ok();
+    /// res.set_trailers(|| {
+    ///     let mut trailers = HeaderMap::new();
+    ///     trailers.insert(HeaderName::from_static("grpc-status"), HeaderValue::from_static("0"));
+    ///     trailers
+    /// });
+    /// ```
+    pub fn set_trailers<F>(&mut self, trailers: F)
+    where
+        F: FnOnce() -> HeaderMap + 'static,
+    {
+        self.extensions_mut().insert(Trailers(Box::new(trailers)));
+    }
+
     /// Returns a reference to the body of this response.
     #[inline]
     pub fn body(&self) -> &B {
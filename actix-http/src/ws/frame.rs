@@ -198,6 +198,9 @@ impl Parser {
     }
 
     /// Create a new Close control frame.
+    ///
+    /// Descriptions longer than [`CloseReason::MAX_DESCRIPTION_LEN`] bytes are truncated, on a
+    /// character boundary, so that the frame stays within the control frame size limit.
     #[inline]
     pub fn write_close(dst: &mut BytesMut, reason: Option<CloseReason>, mask: bool) {
         let payload = match reason {
@@ -205,7 +208,7 @@ impl Parser {
             Some(reason) => {
                 let mut payload = Into::<u16>::into(reason.code).to_be_bytes().to_vec();
                 if let Some(description) = reason.description {
-                    payload.extend(description.as_bytes());
+                    payload.extend(truncate_close_description(&description).as_bytes());
                 }
                 payload
             }
@@ -215,6 +218,26 @@ impl Parser {
     }
 }
 
+/// Truncates `description` to the longest prefix, ending on a character boundary, that fits in
+/// a close frame.
+fn truncate_close_description(description: &str) -> &str {
+    if description.len() <= CloseReason::MAX_DESCRIPTION_LEN {
+        return description;
+    }
+
+    debug!(
+        "Close reason description exceeds {} bytes. Truncating.",
+        CloseReason::MAX_DESCRIPTION_LEN
+    );
+
+    let mut len = CloseReason::MAX_DESCRIPTION_LEN;
+    while !description.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    &description[..len]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,6 +414,29 @@ mod tests {
         assert_eq!(&buf[..], &v[..]);
     }
 
+    #[test]
+    fn test_close_frame_truncates_description() {
+        // 61 two-byte characters followed by a single-byte one; exactly at the limit
+        let description = format!("{}a", "é".repeat(61));
+
+        let mut buf = BytesMut::new();
+        let reason = (CloseCode::Normal, format!("{}é", description));
+        Parser::write_close(&mut buf, Some(reason.into()), false);
+
+        assert_eq!(buf[1] as usize, 2 + CloseReason::MAX_DESCRIPTION_LEN);
+        let reason = Parser::parse_close_payload(&buf[2..]).unwrap();
+        assert_eq!(reason.description.unwrap(), description);
+
+        // a two-byte character straddling the limit is dropped entirely
+        let mut buf = BytesMut::new();
+        let reason = (CloseCode::Normal, "é".repeat(62));
+        Parser::write_close(&mut buf, Some(reason.into()), false);
+
+        assert_eq!(buf[1] as usize, 2 + 122);
+        let reason = Parser::parse_close_payload(&buf[2..]).unwrap();
+        assert_eq!(reason.description.unwrap(), "é".repeat(61));
+    }
+
     #[test]
     fn test_empty_close_frame() {
         let mut buf = BytesMut::new();
@@ -6,6 +6,8 @@ use std::{
 use base64::prelude::*;
 use tracing::error;
 
+use super::ProtocolError;
+
 /// Operation codes defined in [RFC 6455 §11.8].
 ///
 /// [RFC 6455]: https://datatracker.ietf.org/doc/html/rfc6455#section-11.8
@@ -207,6 +209,46 @@ pub struct CloseReason {
     pub description: Option<String>,
 }
 
+impl CloseReason {
+    /// Maximum length, in bytes, of a close reason's description.
+    ///
+    /// Control frame payloads are limited to 125 bytes, 2 of which are used by the close code.
+    pub const MAX_DESCRIPTION_LEN: usize = 123;
+
+    /// Constructs new close reason with a description, checking that it fits in a close frame.
+    ///
+    /// Any code can be used, including non-standard ones given as a `u16`.
+    ///
+    /// # Errors
+    /// Returns [`ProtocolError::InvalidLength`] with the resulting close frame payload length if
+    /// the description is longer than [`MAX_DESCRIPTION_LEN`](Self::MAX_DESCRIPTION_LEN) bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::ws::{CloseCode, CloseReason};
+    ///
+    /// let reason = CloseReason::with_code_and_reason(4000u16, "session expired").unwrap();
+    /// assert_eq!(reason.code, CloseCode::Other(4000));
+    ///
+    /// assert!(CloseReason::with_code_and_reason(CloseCode::Normal, "x".repeat(124)).is_err());
+    /// ```
+    pub fn with_code_and_reason(
+        code: impl Into<CloseCode>,
+        description: impl Into<String>,
+    ) -> Result<Self, ProtocolError> {
+        let description = description.into();
+
+        if description.len() > Self::MAX_DESCRIPTION_LEN {
+            return Err(ProtocolError::InvalidLength(description.len() + 2));
+        }
+
+        Ok(CloseReason {
+            code: code.into(),
+            description: Some(description),
+        })
+    }
+}
+
 impl From<CloseCode> for CloseReason {
     fn from(code: CloseCode) -> Self {
         CloseReason {
@@ -319,6 +361,24 @@ mod test {
         assert_eq!(&hash, b"cR1dlyUUJKp0s/Bel25u5TgvC3E=");
     }
 
+    #[test]
+    fn close_reason_length() {
+        // 61 two-byte characters followed by a single-byte one; exactly at the limit
+        let description = format!("{}a", "é".repeat(61));
+        assert_eq!(description.len(), CloseReason::MAX_DESCRIPTION_LEN);
+
+        let reason = CloseReason::with_code_and_reason(CloseCode::Away, &*description).unwrap();
+        assert_eq!(reason.code, CloseCode::Away);
+        assert_eq!(reason.description.as_deref(), Some(&*description));
+
+        // one more two-byte character overflows the limit
+        let description = format!("{}é", description);
+        match CloseReason::with_code_and_reason(4000u16, description) {
+            Err(ProtocolError::InvalidLength(127)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
     #[test]
     fn close_code_from_u16() {
         assert_eq!(CloseCode::from(1000u16), CloseCode::Normal);
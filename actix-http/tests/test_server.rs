@@ -249,6 +249,54 @@ async fn http1_malformed_request() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn http1_head_limits() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1_max_header_count(2)
+            .h1_max_header_size(256)
+            .h1_max_request_line_size(32)
+            .h1(|_| async { Ok::<_, Infallible>(Response::ok()) })
+            .tcp()
+    })
+    .await;
+
+    let request = |req: String| {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req.as_bytes());
+        let mut data = String::new();
+        let _ = stream.read_to_string(&mut data);
+        data
+    };
+
+    let data =
+        request("GET /test HTTP/1.1\r\nx-header: 1\r\nconnection: close\r\n\r\n".to_owned());
+    assert!(data.starts_with("HTTP/1.1 200 OK"));
+
+    // too many headers
+    let data = request(
+        "GET /test HTTP/1.1\r\nx-header: 1\r\nx-header: 2\r\nconnection: close\r\n\r\n"
+            .to_owned(),
+    );
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+    // head too large
+    let data = request(format!(
+        "GET /test HTTP/1.1\r\nx-header: {}\r\nconnection: close\r\n\r\n",
+        "a".repeat(256)
+    ));
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+    // request line too long
+    let data = request(format!(
+        "GET /{} HTTP/1.1\r\nconnection: close\r\n\r\n",
+        "a".repeat(32)
+    ));
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn http1_keepalive() {
     let mut srv = test_server(|| {
@@ -898,3 +946,71 @@ async fn h2c_auto() {
 
     srv.stop().await;
 }
+
+#[actix_rt::test]
+async fn response_trailers() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .keep_alive(KeepAlive::Disabled)
+            .finish(|req: Request| async move {
+                let body = BodyStream::new(futures_util::stream::iter([
+                    Ok::<_, Infallible>(Bytes::from_static(b"hello ")),
+                    Ok(Bytes::from_static(b"world")),
+                ]));
+
+                let mut res = Response::build(StatusCode::OK);
+
+                if req.uri().path() == "/declared" {
+                    res.insert_header((header::TRAILER, "x-checksum"));
+                }
+
+                let mut res = res.body(body);
+                res.set_trailers(|| {
+                    let mut trailers = header::HeaderMap::new();
+                    trailers.insert(
+                        header::HeaderName::from_static("x-checksum"),
+                        header::HeaderValue::from_static("abc123"),
+                    );
+                    trailers
+                });
+
+                Ok::<_, Infallible>(res)
+            })
+            .tcp_auto_h2c()
+    })
+    .await;
+
+    // HTTP/1.1 sends trailers only when they are declared
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /declared HTTP/1.1\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.ends_with("\r\n6\r\nhello \r\n5\r\nworld\r\n0\r\nx-checksum: abc123\r\n\r\n"));
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET / HTTP/1.1\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.ends_with("\r\n6\r\nhello \r\n5\r\nworld\r\n0\r\n\r\n"));
+
+    // HTTP/2 always sends trailers
+    let tcp = TcpStream::connect(srv.addr()).await.unwrap();
+    let (h2, connection) = h2::client::handshake(tcp).await.unwrap();
+    tokio::spawn(async move { connection.await.unwrap() });
+    let mut h2 = h2.ready().await.unwrap();
+
+    let (response, _) = h2.send_request(::http::Request::new(()), true).unwrap();
+    let mut body = response.await.unwrap().into_body();
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+    assert_eq!(bytes, b"hello world");
+
+    let trailers = body.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers.get("x-checksum").unwrap(), "abc123");
+
+    srv.stop().await;
+}
@@ -1,5 +1,8 @@
 //! Error and Result module
 
+// derived impls of `MultipartError` refer to its deprecated `Nested` variant
+#![allow(deprecated)]
+
 use actix_web::{
     error::{ParseError, PayloadError},
     http::StatusCode,
@@ -31,9 +34,20 @@ pub enum MultipartError {
     Boundary,
 
     /// Nested multipart is not supported
+    #[deprecated(
+        since = "0.6.1",
+        note = "No longer returned; nested multipart fields are yielded like any other field."
+    )]
     #[display(fmt = "Nested multipart is not supported")]
     Nested,
 
+    /// Field is not a nested multipart stream
+    ///
+    /// Returned by [`Field::into_multipart`](crate::Field::into_multipart) for fields whose
+    /// Content-Type is not `multipart/*`.
+    #[display(fmt = "Field is not a nested multipart stream")]
+    NotNested,
+
     /// Multipart stream is incomplete
     #[display(fmt = "Multipart stream is incomplete")]
     Incomplete,
@@ -75,6 +89,15 @@ pub enum MultipartError {
     #[display(fmt = "Unsupported field `{}`", _0)]
     #[from(ignore)]
     UnsupportedField(#[error(not(source))] String),
+
+    /// Field data exceeded the configured size limit
+    #[display(fmt = "Field `{}` exceeded size limit of {} bytes", name, limit)]
+    #[from(ignore)]
+    FieldSizeExceeded {
+        #[error(not(source))]
+        name: String,
+        limit: usize,
+    },
 }
 
 /// Return `BadRequest` for `MultipartError`
@@ -82,6 +105,7 @@ impl ResponseError for MultipartError {
     fn status_code(&self) -> StatusCode {
         match &self {
             MultipartError::Field { source, .. } => source.as_response_error().status_code(),
+            MultipartError::FieldSizeExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
             _ => StatusCode::BAD_REQUEST,
         }
     }
@@ -95,5 +119,12 @@ mod tests {
     fn test_multipart_error() {
         let resp = MultipartError::Boundary.error_response();
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = MultipartError::FieldSizeExceeded {
+            name: "file".to_owned(),
+            limit: 10,
+        }
+        .error_response();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 }
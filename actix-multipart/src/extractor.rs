@@ -1,7 +1,9 @@
 //! Multipart payload support
 
+use std::collections::HashMap;
+
 use actix_utils::future::{ready, Ready};
-use actix_web::{dev::Payload, Error, FromRequest, HttpRequest};
+use actix_web::{dev::Payload, web, Error, FromRequest, HttpRequest};
 
 use crate::server::Multipart;
 
@@ -9,6 +11,8 @@ use crate::server::Multipart;
 ///
 /// Content-type: multipart/form-data;
 ///
+/// Add a [`MultipartConfig`] to your app data to limit the size of individual fields.
+///
 /// # Examples
 /// ```
 /// use actix_web::{web, HttpResponse, Error};
@@ -35,9 +39,74 @@ impl FromRequest for Multipart {
 
     #[inline]
     fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
-        ready(Ok(match Multipart::boundary(req.headers()) {
-            Ok(boundary) => Multipart::from_boundary(boundary, payload.take()),
+        let multipart = match Multipart::content_type(req.headers()) {
+            Ok(content_type) => Multipart::from_content_type(&content_type, payload.take()),
             Err(err) => Multipart::from_error(err),
+        };
+
+        ready(Ok(match MultipartConfig::from_req(req) {
+            Some(config) => multipart.with_config(config.clone()),
+            None => multipart,
         }))
     }
 }
+
+/// [`Multipart`] extractor configuration.
+///
+/// Add to your app data to have it picked up by [`Multipart`] and
+/// [`MultipartForm`](crate::form::MultipartForm) extractors.
+///
+/// Field limits are checked as each chunk of a field arrives. Once a field exceeds its limit, it
+/// yields a [`MultipartError::FieldSizeExceeded`](crate::MultipartError::FieldSizeExceeded) error
+/// and no more of its data is read.
+///
+/// # Examples
+/// ```
+/// use actix_multipart::MultipartConfig;
+/// use actix_web::App;
+///
+/// let config = MultipartConfig::default()
+///     // allow up to 1 KiB for the "name" field
+///     .field_limit("name", 1024)
+///     // and up to 10 MiB for any other field
+///     .default_field_limit(10 * 1024 * 1024);
+///
+/// let app = App::new().app_data(config);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MultipartConfig {
+    field_limits: HashMap<String, usize>,
+    default_field_limit: Option<usize>,
+}
+
+impl MultipartConfig {
+    /// Sets maximum accepted size, in bytes, for fields with the given name.
+    ///
+    /// Overrides the [default field limit](Self::default_field_limit) for these fields.
+    pub fn field_limit(mut self, name: &str, bytes: usize) -> Self {
+        self.field_limits.insert(name.to_owned(), bytes);
+        self
+    }
+
+    /// Sets maximum accepted size, in bytes, for fields without a specific limit.
+    ///
+    /// By default, fields are not limited.
+    pub fn default_field_limit(mut self, bytes: usize) -> Self {
+        self.default_field_limit = Some(bytes);
+        self
+    }
+
+    /// Returns the size limit for fields with the given name, if limited.
+    pub(crate) fn limit_for(&self, name: &str) -> Option<usize> {
+        self.field_limits
+            .get(name)
+            .copied()
+            .or(self.default_field_limit)
+    }
+
+    /// Extracts multipart config from app data. Check both `T` and `Data<T>`, in that order.
+    pub(crate) fn from_req(req: &HttpRequest) -> Option<&Self> {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|d| d.as_ref()))
+    }
+}
@@ -12,7 +12,7 @@ use derive_more::{Deref, DerefMut};
 use futures_core::future::LocalBoxFuture;
 use futures_util::{TryFutureExt as _, TryStreamExt as _};
 
-use crate::{Field, Multipart, MultipartError};
+use crate::{Field, Multipart, MultipartConfig, MultipartError};
 
 pub mod bytes;
 pub mod json;
@@ -295,6 +295,10 @@ where
     fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
         let mut payload = Multipart::new(req.headers(), payload.take());
 
+        if let Some(config) = MultipartConfig::from_req(req) {
+            payload = payload.with_config(config.clone());
+        }
+
         let config = MultipartFormConfig::from_req(req);
         let mut limits = Limits::new(config.total_limit, config.memory_limit);
 
@@ -309,6 +313,11 @@ where
                 let mut field_limits = HashMap::<String, Option<usize>>::new();
 
                 while let Some(field) = payload.try_next().await? {
+                    // fields of multipart subtypes other than form-data may not have names
+                    if field.content_disposition().get_name().is_none() {
+                        return Err(MultipartError::NoContentDisposition);
+                    }
+
                     // Retrieve the limit for this field
                     let entry = field_limits
                         .entry(field.name().to_owned())
@@ -399,7 +408,10 @@ mod tests {
     use awc::{Client, ClientResponse};
 
     use super::MultipartForm;
-    use crate::form::{bytes::Bytes, tempfile::TempFile, text::Text, MultipartFormConfig};
+    use crate::{
+        form::{bytes::Bytes, tempfile::TempFile, text::Text, MultipartFormConfig},
+        MultipartConfig, MultipartError,
+    };
 
     pub async fn send_form(
         srv: &TestServer,
@@ -739,4 +751,73 @@ mod tests {
         let response = send_form(&srv, form, "/").await;
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
+
+    #[derive(MultipartForm)]
+    struct TestConfigFieldLimits {
+        name: Text<String>,
+        file: Bytes,
+    }
+
+    async fn test_config_field_limits_route(
+        form: MultipartForm<TestConfigFieldLimits>,
+    ) -> impl Responder {
+        assert!(!form.name.is_empty());
+        assert!(!form.file.data.is_empty());
+        HttpResponse::Ok().finish()
+    }
+
+    async fn test_config_field_limits_stream_route(
+        mut payload: crate::Multipart,
+    ) -> Result<HttpResponse, MultipartError> {
+        use futures_util::StreamExt as _;
+
+        while let Some(field) = payload.next().await {
+            let mut field = field?;
+            while let Some(chunk) = field.next().await {
+                chunk?;
+            }
+        }
+
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    #[actix_rt::test]
+    async fn test_config_field_limits() {
+        let srv = actix_test::start(|| {
+            App::new()
+                .route("/", web::post().to(test_config_field_limits_route))
+                .route(
+                    "/stream",
+                    web::post().to(test_config_field_limits_stream_route),
+                )
+                .app_data(
+                    MultipartConfig::default()
+                        .field_limit("name", 10)
+                        .default_field_limit(30),
+                )
+        });
+
+        for uri in ["/", "/stream"] {
+            // Within both limits
+            let mut form = multipart::Form::default();
+            form.add_text("name", "10 bytes!!");
+            form.add_text("file", "this string is 28 bytes long");
+            let response = send_form(&srv, form, uri).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // Exceeds the 10 byte limit for "name"
+            let mut form = multipart::Form::default();
+            form.add_text("name", "11 bytes!!!");
+            form.add_text("file", "this string is 28 bytes long");
+            let response = send_form(&srv, form, uri).await;
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            // Exceeds the 30 byte default limit
+            let mut form = multipart::Form::default();
+            form.add_text("name", "10 bytes!!");
+            form.add_text("file", "this string is more than 30 bytes long");
+            let response = send_form(&srv, form, uri).await;
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+    }
 }
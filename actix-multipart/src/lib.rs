@@ -18,4 +18,5 @@ mod server;
 pub mod form;
 
 pub use self::error::MultipartError;
+pub use self::extractor::MultipartConfig;
 pub use self::server::{Field, Multipart};
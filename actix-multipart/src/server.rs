@@ -4,7 +4,7 @@ use std::{
     cell::{Cell, RefCell, RefMut},
     cmp,
     convert::TryFrom,
-    fmt,
+    fmt, io,
     marker::PhantomData,
     pin::Pin,
     rc::Rc,
@@ -12,27 +12,31 @@ use std::{
 };
 
 use actix_web::{
-    error::{ParseError, PayloadError},
+    error::{ErrorInternalServerError, ParseError, PayloadError},
     http::header::{self, ContentDisposition, HeaderMap, HeaderName, HeaderValue},
 };
 use bytes::{Bytes, BytesMut};
 use futures_core::stream::{LocalBoxStream, Stream};
+use futures_util::StreamExt as _;
 use local_waker::LocalWaker;
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
 
-use crate::error::MultipartError;
+use crate::{error::MultipartError, extractor::MultipartConfig};
 
 const MAX_HEADERS: usize = 32;
 
 /// The server-side implementation of `multipart/form-data` requests.
 ///
-/// This will parse the incoming stream into `MultipartItem` instances via its
-/// Stream implementation.
-/// `MultipartItem::Field` contains multipart field. `MultipartItem::Multipart`
-/// is used for nested multipart streams.
+/// This will parse the incoming stream into [`Field`] instances via its `Stream` implementation.
+///
+/// Other multipart subtypes, such as `multipart/mixed`, are also supported. Their fields are not
+/// required to have a `form-data` Content-Disposition. Fields that are multipart streams
+/// themselves can be parsed using [`Field::into_multipart`].
 pub struct Multipart {
     safety: Safety,
     error: Option<MultipartError>,
     inner: Option<InnerMultipart>,
+    config: Option<MultipartConfig>,
 }
 
 enum InnerMultipartItem {
@@ -58,6 +62,8 @@ enum InnerState {
 struct InnerMultipart {
     payload: PayloadRef,
     boundary: String,
+    /// Whether fields must have a `form-data` Content-Disposition, as in `multipart/form-data`.
+    form_data: bool,
     state: InnerState,
     item: InnerMultipartItem,
 }
@@ -68,40 +74,79 @@ impl Multipart {
     where
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
     {
-        match Self::boundary(headers) {
-            Ok(boundary) => Multipart::from_boundary(boundary, stream),
+        match Self::content_type(headers) {
+            Ok(content_type) => Multipart::from_content_type(&content_type, stream),
             Err(err) => Multipart::from_error(err),
         }
     }
 
-    /// Extract boundary info from headers.
-    pub(crate) fn boundary(headers: &HeaderMap) -> Result<String, MultipartError> {
-        headers
+    /// Extract content type, which must have boundary info, from headers.
+    pub(crate) fn content_type(headers: &HeaderMap) -> Result<mime::Mime, MultipartError> {
+        let content_type = headers
             .get(&header::CONTENT_TYPE)
             .ok_or(MultipartError::NoContentType)?
             .to_str()
             .ok()
             .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
-            .ok_or(MultipartError::ParseContentType)?
+            .ok_or(MultipartError::ParseContentType)?;
+
+        if content_type.get_param(mime::BOUNDARY).is_none() {
+            return Err(MultipartError::Boundary);
+        }
+
+        Ok(content_type)
+    }
+
+    /// Extract boundary info from headers.
+    #[cfg(test)]
+    pub(crate) fn boundary(headers: &HeaderMap) -> Result<String, MultipartError> {
+        Self::content_type(headers)?
             .get_param(mime::BOUNDARY)
             .map(|boundary| boundary.as_str().to_owned())
             .ok_or(MultipartError::Boundary)
     }
 
-    /// Create multipart instance for given boundary and stream
+    /// Create multipart instance for the boundary and subtype of given content type and stream.
+    pub(crate) fn from_content_type<S, E>(content_type: &mime::Mime, stream: S) -> Multipart
+    where
+        S: Stream<Item = Result<Bytes, E>> + 'static,
+        E: Into<MultipartError> + 'static,
+    {
+        match content_type.get_param(mime::BOUNDARY) {
+            Some(boundary) => Multipart::from_parts(
+                boundary.as_str().to_owned(),
+                content_type.subtype() == mime::FORM_DATA,
+                stream,
+            ),
+            None => Multipart::from_error(MultipartError::Boundary),
+        }
+    }
+
+    /// Create `multipart/form-data` instance for given boundary and stream
+    #[cfg(test)]
     pub(crate) fn from_boundary<S>(boundary: String, stream: S) -> Multipart
     where
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
+    {
+        Multipart::from_parts(boundary, true, stream)
+    }
+
+    fn from_parts<S, E>(boundary: String, form_data: bool, stream: S) -> Multipart
+    where
+        S: Stream<Item = Result<Bytes, E>> + 'static,
+        E: Into<MultipartError> + 'static,
     {
         Multipart {
             error: None,
             safety: Safety::new(),
             inner: Some(InnerMultipart {
                 boundary,
+                form_data,
                 payload: PayloadRef::new(PayloadBuffer::new(stream)),
                 state: InnerState::FirstBoundary,
                 item: InnerMultipartItem::None,
             }),
+            config: None,
         }
     }
 
@@ -111,8 +156,15 @@ impl Multipart {
             error: Some(err),
             safety: Safety::new(),
             inner: None,
+            config: None,
         }
     }
+
+    /// Applies the field limits from `config` to each field yielded by this stream.
+    pub(crate) fn with_config(mut self, config: MultipartConfig) -> Multipart {
+        self.config = Some(config);
+        self
+    }
 }
 
 impl Stream for Multipart {
@@ -133,7 +185,17 @@ impl Stream for Multipart {
                     return Poll::Pending;
                 }
 
-                inner.poll(&this.safety, cx)
+                let mut res = inner.poll(&this.safety, cx);
+
+                if let (Poll::Ready(Some(Ok(field))), Some(config)) = (&mut res, &this.config) {
+                    let name = field.content_disposition().get_name().unwrap_or_default();
+
+                    if let Some(limit) = config.limit_for(name) {
+                        field.set_limit(limit);
+                    }
+                }
+
+                res
             }
             None => Poll::Ready(Some(Err(this
                 .error
@@ -337,13 +399,14 @@ impl InnerMultipart {
                 return Poll::Pending;
             };
 
-            // According to RFC 7578 §4.2, a Content-Disposition header must always be present and
-            // set to "form-data".
-
             let content_disposition = headers
                 .get(&header::CONTENT_DISPOSITION)
-                .and_then(|cd| ContentDisposition::from_raw(cd).ok())
-                .filter(|content_disposition| {
+                .and_then(|cd| ContentDisposition::from_raw(cd).ok());
+
+            let cd = if self.form_data {
+                // According to RFC 7578 §4.2, a Content-Disposition header must always be present
+                // and set to "form-data".
+                let content_disposition = content_disposition.filter(|content_disposition| {
                     let is_form_data =
                         content_disposition.disposition == header::DispositionType::FormData;
 
@@ -355,10 +418,17 @@ impl InnerMultipart {
                     is_form_data && has_field_name
                 });
 
-            let cd = if let Some(content_disposition) = content_disposition {
-                content_disposition
+                if let Some(content_disposition) = content_disposition {
+                    content_disposition
+                } else {
+                    return Poll::Ready(Some(Err(MultipartError::NoContentDisposition)));
+                }
             } else {
-                return Poll::Ready(Some(Err(MultipartError::NoContentDisposition)));
+                // other subtypes do not require one; default to inline, as per RFC 2183 §2.8
+                content_disposition.unwrap_or(ContentDisposition {
+                    disposition: header::DispositionType::Inline,
+                    parameters: Vec::new(),
+                })
             };
 
             let ct: Option<mime::Mime> = headers
@@ -368,13 +438,6 @@ impl InnerMultipart {
 
             self.state = InnerState::Boundary;
 
-            // nested multipart stream is not supported
-            if let Some(mime) = &ct {
-                if mime.type_() == mime::MULTIPART {
-                    return Poll::Ready(Some(Err(MultipartError::Nested)));
-                }
-            }
-
             let field =
                 InnerField::new_in_rc(self.payload.clone(), self.boundary.clone(), &headers)?;
 
@@ -405,6 +468,8 @@ pub struct Field {
     headers: HeaderMap,
     inner: Rc<RefCell<InnerField>>,
     safety: Safety,
+    limit: Option<usize>,
+    size: usize,
 }
 
 impl Field {
@@ -421,6 +486,8 @@ impl Field {
             headers,
             inner,
             safety,
+            limit: None,
+            size: 0,
         }
     }
 
@@ -449,6 +516,10 @@ impl Field {
     /// unwrap `.content_disposition().get_name()`. The [name](Self::name) method is provided as
     /// a convenience.
     ///
+    /// This is only validated for `multipart/form-data` streams. Fields of other multipart
+    /// subtypes that do not have a Content-Disposition header get an `inline` one without
+    /// parameters.
+    ///
     /// [RFC 7578 §4.2]: https://datatracker.ietf.org/doc/html/rfc7578#section-4.2
     pub fn content_disposition(&self) -> &ContentDisposition {
         &self.cd
@@ -458,11 +529,130 @@ impl Field {
     ///
     /// See [content_disposition](Self::content_disposition) regarding guarantees about existence of
     /// the name field.
+    ///
+    /// # Panics
+    /// Panics if the field has no name, which is only possible for fields of multipart streams
+    /// other than `multipart/form-data`.
     pub fn name(&self) -> &str {
         self.content_disposition()
             .get_name()
             .expect("field name should be guaranteed to exist in multipart form-data")
     }
+
+    /// Parses the field's data as a nested multipart stream.
+    ///
+    /// Fields with a `multipart/*` content type, such as `multipart/mixed`, contain a multipart
+    /// stream with its own boundary. The nested stream's fields are read from this field's data, so
+    /// it must be consumed before the next field of the outer stream can be read.
+    ///
+    /// If the field's content type is not `multipart/*` or has no boundary, the returned stream
+    /// only yields an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_multipart::Multipart;
+    /// use actix_web::{Error, HttpResponse};
+    /// use futures_util::TryStreamExt as _;
+    ///
+    /// async fn upload(mut payload: Multipart) -> Result<HttpResponse, Error> {
+    ///     let mut files = 0;
+    ///
+    ///     while let Some(field) = payload.try_next().await? {
+    ///         let is_multipart = field
+    ///             .content_type()
+    ///             .map_or(false, |ct| ct.type_() == mime::MULTIPART);
+    ///
+    ///         if is_multipart {
+    ///             // a set of files sent in a single `multipart/mixed` field
+    ///             let mut nested = field.into_multipart();
+    ///
+    ///             while let Some(file) = nested.try_next().await? {
+    ///                 let _ = file.try_collect::<Vec<_>>().await?;
+    ///                 files += 1;
+    ///             }
+    ///         } else {
+    ///             let _ = field.try_collect::<Vec<_>>().await?;
+    ///         }
+    ///     }
+    ///
+    ///     Ok(HttpResponse::Ok().body(format!("received {} files", files)))
+    /// }
+    /// ```
+    pub fn into_multipart(self) -> Multipart {
+        match self.ct.clone() {
+            Some(ct) if ct.type_() == mime::MULTIPART => {
+                Multipart::from_content_type(&ct, self)
+            }
+            Some(_) => Multipart::from_error(MultipartError::NotNested),
+            None => Multipart::from_error(MultipartError::NoContentType),
+        }
+    }
+
+    /// Sets the maximum number of bytes that can be read from this field.
+    ///
+    /// The limit is checked as each chunk arrives. Once it is exceeded, the field stream yields a
+    /// [`MultipartError::FieldSizeExceeded`] error and then ends; the remaining field data is
+    /// skipped when the next field is requested from the [`Multipart`] stream.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+    }
+
+    /// Returns the maximum number of bytes that can be read from this field, if limited.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Drains the field's data into `sink`, returning the number of bytes written.
+    ///
+    /// Errors while reading the field, including exceeding its [limit](Self::set_limit), are
+    /// returned as-is. Errors while writing to `sink` are returned as a [`MultipartError::Field`]
+    /// error, which results in a `500 Internal Server Error` response by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_multipart::Multipart;
+    /// use actix_web::{Error, HttpResponse};
+    /// use futures_util::TryStreamExt as _;
+    ///
+    /// async fn upload(mut payload: Multipart) -> Result<HttpResponse, Error> {
+    ///     let mut written = 0;
+    ///
+    ///     while let Some(mut field) = payload.try_next().await? {
+    ///         let path = std::env::temp_dir().join(field.name());
+    ///         let file = tokio::fs::File::create(path).await?;
+    ///         written += field.write_to(file).await?;
+    ///     }
+    ///
+    ///     Ok(HttpResponse::Ok().body(format!("wrote {} bytes", written)))
+    /// }
+    /// ```
+    pub async fn write_to<W>(&mut self, mut sink: W) -> Result<u64, MultipartError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut written = 0;
+
+        while let Some(chunk) = self.next().await {
+            let chunk = chunk?;
+
+            sink.write_all(&chunk)
+                .await
+                .map_err(|err| self.sink_error(err))?;
+
+            written += chunk.len() as u64;
+        }
+
+        sink.flush().await.map_err(|err| self.sink_error(err))?;
+
+        Ok(written)
+    }
+
+    fn sink_error(&self, err: io::Error) -> MultipartError {
+        MultipartError::Field {
+            field_name: self.cd.get_name().unwrap_or_default().to_owned(),
+            source: ErrorInternalServerError(err),
+        }
+    }
 }
 
 impl Stream for Field {
@@ -481,7 +671,28 @@ impl Stream for Field {
             return Poll::Pending;
         }
 
-        inner.poll(&this.safety)
+        if this.limit.map_or(false, |limit| this.size > limit) {
+            // limit was exceeded on a previous chunk
+            return Poll::Ready(None);
+        }
+
+        let chunk = inner.poll(&this.safety);
+
+        if let Poll::Ready(Some(Ok(chunk))) = &chunk {
+            this.size += chunk.len();
+
+            match this.limit {
+                Some(limit) if this.size > limit => {
+                    return Poll::Ready(Some(Err(MultipartError::FieldSizeExceeded {
+                        name: this.cd.get_name().unwrap_or_default().to_owned(),
+                        limit,
+                    })));
+                }
+                _ => {}
+            }
+        }
+
+        chunk
     }
 }
 
@@ -776,23 +987,29 @@ impl Drop for Safety {
 struct PayloadBuffer {
     eof: bool,
     buf: BytesMut,
-    stream: LocalBoxStream<'static, Result<Bytes, PayloadError>>,
+    stream: LocalBoxStream<'static, Result<Bytes, MultipartError>>,
 }
 
 impl PayloadBuffer {
     /// Constructs new `PayloadBuffer` instance.
-    fn new<S>(stream: S) -> Self
+    fn new<S, E>(stream: S) -> Self
     where
-        S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
+        S: Stream<Item = Result<Bytes, E>> + 'static,
+        E: Into<MultipartError> + 'static,
     {
         PayloadBuffer {
             eof: false,
             buf: BytesMut::new(),
-            stream: Box::pin(stream),
+            stream: Box::pin(stream.map(|res| res.map_err(Into::into))),
         }
     }
 
-    fn poll_stream(&mut self, cx: &mut Context<'_>) -> Result<(), PayloadError> {
+    fn poll_stream(&mut self, cx: &mut Context<'_>) -> Result<(), MultipartError> {
+        // streams, such as fields read as nested multipart streams, are not polled once finished
+        if self.eof {
+            return Ok(());
+        }
+
         loop {
             match Pin::new(&mut self.stream).poll_next(cx) {
                 Poll::Ready(Some(Ok(data))) => self.buf.extend_from_slice(&data),
@@ -1074,6 +1291,106 @@ mod tests {
         }
     }
 
+    #[actix_rt::test]
+    async fn test_write_to() {
+        let (sender, payload) = create_stream();
+        let (bytes, headers) = create_simple_request_with_header();
+
+        sender.send(Ok(bytes)).unwrap();
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        let mut sink = Vec::new();
+        assert_eq!(field.write_to(&mut sink).await.unwrap(), 4);
+        assert_eq!(sink, b"test");
+        drop(field);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        let mut sink = Vec::new();
+        assert_eq!(field.write_to(&mut sink).await.unwrap(), 4);
+        assert_eq!(sink, b"data");
+        drop(field);
+
+        assert!(multipart.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_write_to_limit() {
+        let (sender, payload) = create_stream();
+        let (bytes, headers) = create_simple_request_with_header();
+
+        sender.send(Ok(bytes)).unwrap();
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        field.set_limit(3);
+        assert_eq!(field.limit(), Some(3));
+
+        match field.write_to(Vec::new()).await {
+            Err(MultipartError::FieldSizeExceeded { name, limit }) => {
+                assert_eq!(name, "file");
+                assert_eq!(limit, 3);
+            }
+            _ => unreachable!(),
+        }
+        assert!(field.next().await.is_none());
+        drop(field);
+
+        // remaining fields are still readable
+        let mut field = multipart.next().await.unwrap().unwrap();
+        field.set_limit(4);
+        assert_eq!(get_whole_field(&mut field).await, "data");
+        drop(field);
+
+        assert!(multipart.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_write_to_sink_error() {
+        struct FailingSink;
+
+        impl AsyncWrite for FailingSink {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                _buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "disk full")))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let (sender, payload) = create_stream();
+        let (bytes, headers) = create_simple_request_with_header();
+
+        sender.send(Ok(bytes)).unwrap();
+
+        let mut multipart = Multipart::new(&headers, payload);
+        let mut field = multipart.next().await.unwrap().unwrap();
+
+        match field.write_to(FailingSink).await {
+            Err(err @ MultipartError::Field { .. }) => {
+                assert_eq!(
+                    actix_web::ResponseError::status_code(&err),
+                    actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
     // Loops, collecting all bytes until end-of-field
     async fn get_whole_field(field: &mut Field) -> BytesMut {
         let mut b = BytesMut::new();
@@ -1250,6 +1567,41 @@ mod tests {
         }
     }
 
+    #[actix_rt::test]
+    async fn test_request_set_multipart() {
+        async fn read_field(mut field: Field) -> BytesMut {
+            let mut data = BytesMut::new();
+            while let Some(chunk) = field.next().await {
+                data.extend_from_slice(&chunk.unwrap());
+            }
+            data
+        }
+
+        let (req, mut payload) = TestRequest::post()
+            .set_multipart([
+                actix_web::test::MultipartPart::new("text", "hello\r\n--world"),
+                actix_web::test::MultipartPart::new("file", "data")
+                    .filename("data.bin")
+                    .content_type(mime::APPLICATION_OCTET_STREAM),
+            ])
+            .to_http_parts();
+
+        let mut multipart = Multipart::from_request(&req, &mut payload).await.unwrap();
+
+        let field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(field.name(), "text");
+        assert_eq!(field.content_disposition().get_filename(), None);
+        assert_eq!(read_field(field).await, "hello\r\n--world");
+
+        let field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(field.name(), "file");
+        assert_eq!(field.content_disposition().get_filename(), Some("data.bin"));
+        assert_eq!(field.content_type(), Some(&mime::APPLICATION_OCTET_STREAM));
+        assert_eq!(read_field(field).await, "data");
+
+        assert!(multipart.next().await.is_none());
+    }
+
     #[actix_rt::test]
     async fn no_content_disposition() {
         let bytes = Bytes::from(
@@ -1263,7 +1615,7 @@ mod tests {
         headers.insert(
             header::CONTENT_TYPE,
             header::HeaderValue::from_static(
-                "multipart/mixed; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
+                "multipart/form-data; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
             ),
         );
         let payload = SlowStream::new(bytes);
@@ -1291,7 +1643,7 @@ mod tests {
         headers.insert(
             header::CONTENT_TYPE,
             header::HeaderValue::from_static(
-                "multipart/mixed; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
+                "multipart/form-data; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
             ),
         );
         let payload = SlowStream::new(bytes);
@@ -1305,6 +1657,119 @@ mod tests {
         ));
     }
 
+    #[actix_rt::test]
+    async fn mixed_without_content_disposition() {
+        let bytes = Bytes::from(
+            "--abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\r\n\
+             test\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Disposition: attachment; filename=\"fn.txt\"\r\n\r\n\
+             data\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0--\r\n",
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(
+                "multipart/mixed; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
+            ),
+        );
+        let (sender, payload) = create_stream();
+        sender.send(Ok(bytes)).unwrap();
+        drop(sender);
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        let cd = field.content_disposition();
+        assert_eq!(cd.disposition, DispositionType::Inline);
+        assert!(cd.parameters.is_empty());
+        assert_eq!(get_whole_field(&mut field).await, "test");
+        drop(field);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        let cd = field.content_disposition();
+        assert_eq!(cd.disposition, DispositionType::Attachment);
+        assert_eq!(cd.get_filename(), Some("fn.txt"));
+        assert_eq!(get_whole_field(&mut field).await, "data");
+        drop(field);
+
+        assert!(multipart.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn nested_multipart() {
+        let bytes = Bytes::from(
+            "--abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Disposition: form-data; name=\"files\"\r\n\
+             Content-Type: multipart/mixed; boundary=\"--nested\"\r\n\r\n\
+             ----nested\r\n\
+             Content-Disposition: file; filename=\"a.txt\"\r\n\r\n\
+             first\r\n\
+             ----nested\r\n\
+             Content-Disposition: file; filename=\"b.txt\"\r\n\r\n\
+             second\r\n\
+             ----nested--\r\n\
+             \r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Disposition: form-data; name=\"name\"\r\n\r\n\
+             value\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0--\r\n",
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(
+                "multipart/form-data; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
+            ),
+        );
+        let (sender, payload) = create_stream();
+        sender.send(Ok(bytes)).unwrap();
+        drop(sender);
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(field.name(), "files");
+        let mut nested = field.into_multipart();
+
+        let mut file = nested.next().await.unwrap().unwrap();
+        assert_eq!(file.content_disposition().get_filename(), Some("a.txt"));
+        assert_eq!(get_whole_field(&mut file).await, "first");
+        drop(file);
+
+        let mut file = nested.next().await.unwrap().unwrap();
+        assert_eq!(file.content_disposition().get_filename(), Some("b.txt"));
+        assert_eq!(get_whole_field(&mut file).await, "second");
+        drop(file);
+
+        assert!(nested.next().await.is_none());
+        drop(nested);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(field.name(), "name");
+        assert_eq!(get_whole_field(&mut field).await, "value");
+        drop(field);
+
+        assert!(multipart.next().await.is_none());
+
+        // fields that are not multipart streams
+        let (bytes, headers) = create_simple_request_with_header();
+        let (sender, payload) = create_stream();
+        sender.send(Ok(bytes)).unwrap();
+        drop(sender);
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let field = multipart.next().await.unwrap().unwrap();
+        let mut nested = field.into_multipart();
+        assert!(matches!(
+            nested.next().await.unwrap(),
+            Err(MultipartError::NotNested)
+        ));
+    }
+
     #[actix_rt::test]
     async fn test_drop_multipart_dont_hang() {
         let (sender, payload) = create_stream();
@@ -534,13 +534,34 @@ impl TestServer {
         response.body().limit(10_485_760).await
     }
 
+    /// Create a WebSocket connection request for a given path.
+    ///
+    /// Unlike [`ws_at`](Self::ws_at), the request can be customized, e.g. with authentication
+    /// headers, before connecting.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async fn example(srv: actix_test::TestServer) {
+    /// use actix_web::http::header;
+    ///
+    /// let (_res, framed) = srv
+    ///     .ws_request("/ws")
+    ///     .set_header(header::AUTHORIZATION, "Bearer token")
+    ///     .connect()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn ws_request(&self, path: &str) -> awc::ws::WebsocketsRequest {
+        self.client.ws(self.url(path))
+    }
+
     /// Connect to WebSocket server at a given path.
     pub async fn ws_at(
         &mut self,
         path: &str,
     ) -> Result<Framed<impl AsyncRead + AsyncWrite, ws::Codec>, awc::error::WsClientError> {
-        let url = self.url(path);
-        let connect = self.client.ws(url).connect();
+        let connect = self.ws_request(path).connect();
         connect.await.map(|(_, framed)| framed)
     }
 
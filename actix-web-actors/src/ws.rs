@@ -57,12 +57,15 @@
 //!
 
 use std::{
+    cell::Cell,
     collections::VecDeque,
     convert::TryFrom,
     future::Future,
     io, mem,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use actix::{
@@ -154,6 +157,7 @@ where
     codec: Option<Codec>,
     protocols: Option<&'a [&'a str]>,
     frame_size: Option<usize>,
+    keep_alive: Option<(Duration, Duration)>,
 }
 
 impl<'a, A, T> WsResponseBuilder<'a, A, T>
@@ -172,6 +176,7 @@ where
             codec: None,
             protocols: None,
             frame_size: None,
+            keep_alive: None,
         }
     }
 
@@ -196,6 +201,18 @@ where
         self
     }
 
+    /// Send ping frames at the given interval and close the session if nothing is received from
+    /// the client for `timeout`.
+    ///
+    /// Any frame received from the client counts as a sign of life, not only pongs. Since the
+    /// timeout is checked whenever a ping would be sent, sessions are closed up to one `interval`
+    /// after the timeout elapses. Timed-out sessions are closed with the [`CloseCode::Away`] code
+    /// and the actor is stopped.
+    pub fn keep_alive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.keep_alive = Some((interval, timeout));
+        self
+    }
+
     fn handshake_resp(&self) -> Result<HttpResponseBuilder, HandshakeError> {
         match self.protocols {
             Some(protocols) => handshake_with_protocols(self.req, protocols),
@@ -220,7 +237,8 @@ where
         }
     }
 
-    /// Create a new Websocket context from an actor, request stream, and codec.
+    /// Create a new Websocket context from an actor, request stream, codec, and keep-alive
+    /// settings.
     ///
     /// Returns a pair, where the first item is an addr for the created actor, and the second item
     /// is a stream intended to be set as part of the response
@@ -229,6 +247,7 @@ where
         actor: A,
         stream: S,
         codec: Codec,
+        keep_alive: Option<(Duration, Duration)>,
     ) -> (Addr<A>, impl Stream<Item = Result<Bytes, Error>>)
     where
         A: StreamHandler<Result<Message, ProtocolError>>,
@@ -239,7 +258,24 @@ where
             inner: ContextParts::new(mb.sender_producer()),
             messages: VecDeque::new(),
         };
-        ctx.add_stream(WsStream::new(stream, codec.clone()));
+
+        let mut ws_stream = WsStream::new(stream, codec.clone());
+
+        if let Some((interval, timeout)) = keep_alive {
+            let last_seen = Rc::new(Cell::new(Instant::now()));
+            ws_stream.last_seen = Some(Rc::clone(&last_seen));
+
+            ctx.run_interval(interval, move |_act, ctx: &mut WebsocketContext<A>| {
+                if last_seen.get().elapsed() >= timeout {
+                    ctx.close(Some((CloseCode::Away, "keep-alive timeout").into()));
+                    ctx.stop();
+                } else {
+                    ctx.ping(b"");
+                }
+            });
+        }
+
+        ctx.add_stream(ws_stream);
 
         let addr = ctx.address();
 
@@ -256,20 +292,9 @@ where
     ///
     /// If successful, consume the [`WsResponseBuilder`] and return a [`HttpResponse`] wrapped in
     /// a [`Result`].
-    pub fn start(mut self) -> Result<HttpResponse, Error> {
-        let mut res = self.handshake_resp()?;
-        self.set_frame_size();
-
-        match self.codec {
-            Some(codec) => {
-                let out_stream = WebsocketContext::with_codec(self.actor, self.stream, codec);
-                Ok(res.streaming(out_stream))
-            }
-            None => {
-                let out_stream = WebsocketContext::create(self.actor, self.stream);
-                Ok(res.streaming(out_stream))
-            }
-        }
+    pub fn start(self) -> Result<HttpResponse, Error> {
+        let (_, res) = self.start_with_addr()?;
+        Ok(res)
     }
 
     /// Perform WebSocket handshake and start actor.
@@ -286,18 +311,11 @@ where
         let mut res = self.handshake_resp()?;
         self.set_frame_size();
 
-        match self.codec {
-            Some(codec) => {
-                let (addr, out_stream) =
-                    Self::create_with_codec_addr(self.actor, self.stream, codec);
-                Ok((addr, res.streaming(out_stream)))
-            }
-            None => {
-                let (addr, out_stream) =
-                    WebsocketContext::create_with_addr(self.actor, self.stream);
-                Ok((addr, res.streaming(out_stream)))
-            }
-        }
+        let codec = self.codec.unwrap_or_default();
+        let (addr, out_stream) =
+            Self::create_with_codec_addr(self.actor, self.stream, codec, self.keep_alive);
+
+        Ok((addr, res.streaming(out_stream)))
     }
 }
 
@@ -636,6 +654,12 @@ where
     }
 
     /// Send close frame
+    ///
+    /// Use [`CloseReason::with_code_and_reason`] to check that a description fits in the frame;
+    /// longer descriptions are truncated when the frame is written.
+    ///
+    /// If the actor stops without sending a close frame, one with the [`CloseCode::Normal`] code
+    /// is sent on its behalf.
     #[inline]
     pub fn close(&mut self, reason: Option<CloseReason>) {
         self.write_raw(Message::Close(reason));
@@ -673,6 +697,7 @@ where
     encoder: Codec,
     buf: BytesMut,
     closed: bool,
+    close_sent: bool,
 }
 
 impl<A> WebsocketContextFut<A>
@@ -686,6 +711,7 @@ where
             encoder: codec,
             buf: BytesMut::new(),
             closed: false,
+            close_sent: false,
         }
     }
 }
@@ -706,6 +732,10 @@ where
         // encode messages
         while let Some(item) = this.fut.ctx().messages.pop_front() {
             if let Some(msg) = item {
+                if matches!(msg, Message::Close(_)) {
+                    this.close_sent = true;
+                }
+
                 this.encoder.encode(msg, &mut this.buf)?;
             } else {
                 this.closed = true;
@@ -713,6 +743,15 @@ where
             }
         }
 
+        // send close frame if actor stopped without closing the connection itself
+        if !this.fut.alive() && !this.closed && !this.close_sent {
+            this.close_sent = true;
+            this.encoder.encode(
+                Message::Close(Some(CloseCode::Normal.into())),
+                &mut this.buf,
+            )?;
+        }
+
         if !this.buf.is_empty() {
             Poll::Ready(Some(Ok(this.buf.split().freeze())))
         } else if this.fut.alive() && !this.closed {
@@ -742,6 +781,7 @@ pin_project! {
         decoder: Codec,
         buf: BytesMut,
         closed: bool,
+        last_seen: Option<Rc<Cell<Instant>>>,
     }
 }
 
@@ -755,6 +795,7 @@ where
             decoder: codec,
             buf: BytesMut::new(),
             closed: false,
+            last_seen: None,
         }
     }
 }
@@ -798,6 +839,10 @@ where
                 }
             }
             Some(frm) => {
+                if let Some(last_seen) = this.last_seen {
+                    last_seen.set(Instant::now());
+                }
+
                 let msg = match frm {
                     Frame::Text(data) => {
                         Message::Text(ByteString::try_from(data).map_err(|e| {
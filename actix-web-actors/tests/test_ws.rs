@@ -1,6 +1,11 @@
+use std::time::{Duration, Instant};
+
 use actix::prelude::*;
 use actix_http::ws::Codec;
-use actix_web::{web, App, HttpRequest};
+use actix_web::{
+    http::{header, StatusCode},
+    web, App, HttpRequest, HttpResponse,
+};
 use actix_web_actors::ws;
 use bytes::Bytes;
 use futures_util::{SinkExt as _, StreamExt as _};
@@ -184,3 +189,166 @@ async fn simple_start() {
 
     common_test_code(srv, DEFAULT_FRAME_SIZE).await;
 }
+
+/// Actor that stops itself, without sending a close frame, when receiving any text message.
+struct StoppingWs;
+
+impl Actor for StoppingWs {
+    type Context = ws::WebsocketContext<Self>;
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for StoppingWs {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Text(_)) => ctx.stop(),
+            Ok(ws::Message::Binary(bin)) => {
+                let description = String::from_utf8(bin.to_vec()).unwrap();
+                ctx.close(Some((ws::CloseCode::Other(4000), description).into()));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[actix_rt::test]
+async fn close_frame_on_actor_stop() {
+    let mut srv = actix_test::start(|| {
+        App::new().service(web::resource("/").to(
+            |req: HttpRequest, stream: web::Payload| async move {
+                ws::start(StoppingWs, &req, stream)
+            },
+        ))
+    });
+
+    let mut framed = srv.ws().await.unwrap();
+    framed.send(ws::Message::Text("stop".into())).await.unwrap();
+
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Close(Some(ws::CloseCode::Normal.into())));
+    assert!(framed.next().await.is_none());
+}
+
+#[actix_rt::test]
+async fn close_reason_truncated() {
+    let mut srv = actix_test::start(|| {
+        App::new().service(web::resource("/").to(
+            |req: HttpRequest, stream: web::Payload| async move {
+                ws::start(StoppingWs, &req, stream)
+            },
+        ))
+    });
+
+    let mut framed = srv.ws().await.unwrap();
+
+    // 61 two-byte characters fill 122 bytes, so the last one straddles the 123 byte limit
+    let description = "é".repeat(62);
+    framed
+        .send(ws::Message::Binary(Bytes::from(description)))
+        .await
+        .unwrap();
+
+    let item = framed.next().await.unwrap().unwrap();
+    let reason = match item {
+        ws::Frame::Close(Some(reason)) => reason,
+        _ => panic!("close frame expected"),
+    };
+    assert_eq!(reason.code, ws::CloseCode::Other(4000));
+    assert_eq!(reason.description.unwrap(), "é".repeat(61));
+}
+
+/// Actor that ignores all incoming messages.
+struct SilentWs;
+
+impl Actor for SilentWs {
+    type Context = ws::WebsocketContext<Self>;
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SilentWs {
+    fn handle(&mut self, _: Result<ws::Message, ws::ProtocolError>, _: &mut Self::Context) {}
+}
+
+fn keep_alive_server(interval: Duration, timeout: Duration) -> actix_test::TestServer {
+    actix_test::start(move || {
+        App::new().service(web::resource("/").to(
+            move |req: HttpRequest, stream: web::Payload| async move {
+                ws::WsResponseBuilder::new(SilentWs, &req, stream)
+                    .keep_alive(interval, timeout)
+                    .start()
+            },
+        ))
+    })
+}
+
+#[actix_rt::test]
+async fn keep_alive_timeout() {
+    let mut srv = keep_alive_server(Duration::from_millis(20), Duration::from_millis(100));
+    let mut framed = srv.ws().await.unwrap();
+
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Ping(Bytes::new()));
+
+    // never respond; pings continue until the session is closed
+    let reason = loop {
+        match framed.next().await.unwrap().unwrap() {
+            ws::Frame::Ping(_) => {}
+            ws::Frame::Close(Some(reason)) => break reason,
+            item => panic!("unexpected frame: {:?}", item),
+        }
+    };
+    assert_eq!(reason.code, ws::CloseCode::Away);
+}
+
+#[actix_rt::test]
+async fn keep_alive_any_frame_resets_timeout() {
+    // the timeout is many intervals long so that slow test machines do not miss it
+    let mut srv = keep_alive_server(Duration::from_millis(50), Duration::from_millis(1000));
+    let mut framed = srv.ws().await.unwrap();
+
+    let start = Instant::now();
+
+    // respond to pings with non-pong frames for well over the timeout
+    while start.elapsed() < Duration::from_millis(2500) {
+        match framed.next().await.unwrap().unwrap() {
+            ws::Frame::Ping(_) => framed
+                .send(ws::Message::Text("alive".into()))
+                .await
+                .unwrap(),
+            item => panic!("unexpected frame: {:?}", item),
+        }
+    }
+}
+
+#[actix_rt::test]
+async fn ws_request_with_headers() {
+    let srv = actix_test::start(|| {
+        App::new().service(web::resource("/ws").to(
+            |req: HttpRequest, stream: web::Payload| async move {
+                if req.headers().get(header::AUTHORIZATION).is_none() {
+                    return Ok(HttpResponse::Unauthorized().finish());
+                }
+
+                ws::start(Ws, &req, stream)
+            },
+        ))
+    });
+
+    let res = srv.ws_request("/ws").connect().await;
+    assert!(matches!(
+        res,
+        Err(awc::error::WsClientError::InvalidResponseStatus(
+            StatusCode::UNAUTHORIZED
+        ))
+    ));
+
+    let (res, mut framed) = srv
+        .ws_request("/ws")
+        .set_header(header::AUTHORIZATION, "Bearer token")
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+    framed.send(ws::Message::Text("text".into())).await.unwrap();
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Text(Bytes::from_static(b"text")));
+}
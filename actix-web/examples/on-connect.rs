@@ -1,5 +1,5 @@
 //! This example shows how to use `actix_web::HttpServer::on_connect` to access a lower-level socket
-//! properties and pass them to a handler through request-local data.
+//! properties and pass them to a handler through connection data.
 //!
 //! For an example of extracting a client TLS certificate, see:
 //! <https://github.com/actix/examples/tree/master/https-tls/rustls-client-cert>
@@ -9,8 +9,7 @@
 use std::{any::Any, io, net::SocketAddr};
 
 use actix_web::{
-    dev::Extensions, rt::net::TcpStream, web, App, HttpRequest, HttpResponse, HttpServer,
-    Responder,
+    dev::Extensions, rt::net::TcpStream, web, App, HttpResponse, HttpServer, Responder,
 };
 
 #[allow(dead_code)]
@@ -21,16 +20,11 @@ struct ConnectionInfo {
     ttl: Option<u32>,
 }
 
-async fn route_whoami(req: HttpRequest) -> impl Responder {
-    match req.conn_data::<ConnectionInfo>() {
-        Some(info) => HttpResponse::Ok().body(format!(
-            "Here is some info about your connection:\n\n{:#?}",
-            info
-        )),
-        None => {
-            HttpResponse::InternalServerError().body("Missing expected request extension data")
-        }
-    }
+async fn route_whoami(info: web::ConnData<ConnectionInfo>) -> impl Responder {
+    HttpResponse::Ok().body(format!(
+        "Here is some info about your connection:\n\n{:#?}",
+        *info
+    ))
 }
 
 fn get_conn_info(connection: &dyn Any, data: &mut Extensions) {
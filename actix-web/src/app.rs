@@ -1,6 +1,9 @@
 use std::{cell::RefCell, fmt, future::Future, rc::Rc};
 
-use actix_http::{body::MessageBody, Extensions, Request};
+use actix_http::{
+    body::{EitherBody, MessageBody},
+    Extensions, Request,
+};
 use actix_service::{
     apply, apply_fn_factory, boxed, IntoServiceFactory, ServiceFactory, ServiceFactoryExt,
     Transform,
@@ -8,17 +11,19 @@ use actix_service::{
 use futures_util::FutureExt as _;
 
 use crate::{
-    app_service::{AppEntry, AppInit, AppRoutingFactory},
+    app_service::{AppEntry, AppInit, AppRoutingFactory, ErrorRenderer},
     config::ServiceConfig,
-    data::{Data, DataFactory, FnDataFactory},
+    data::{Data, DataFactory, FnDataFactory, ThreadData},
     dev::ResourceDef,
     error::Error,
+    middleware::Condition,
     resource::Resource,
     route::Route,
     service::{
         AppServiceFactory, BoxedHttpServiceFactory, HttpServiceFactory, ServiceFactoryWrapper,
         ServiceRequest, ServiceResponse,
     },
+    HttpRequest, HttpResponse,
 };
 
 /// The top-level builder for an Actix Web application.
@@ -26,6 +31,7 @@ pub struct App<T> {
     endpoint: T,
     services: Vec<Box<dyn AppServiceFactory>>,
     default: Option<Rc<BoxedHttpServiceFactory>>,
+    error_renderer: Option<ErrorRenderer>,
     factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     data_factories: Vec<FnDataFactory>,
     external: Vec<ResourceDef>,
@@ -43,6 +49,7 @@ impl App<AppEntry> {
             data_factories: Vec::new(),
             services: Vec::new(),
             default: None,
+            error_renderer: None,
             factory_ref,
             external: Vec::new(),
             extensions: Extensions::new(),
@@ -158,6 +165,42 @@ where
         self
     }
 
+    /// Add per-worker application data, constructed by `factory`.
+    ///
+    /// The factory is called once for each application instance, on the worker thread that runs
+    /// it, during application initialization. The value is wrapped in a [`ThreadData<T>`] which can
+    /// be used as an extractor. Since the value never leaves its worker thread, it does not need to
+    /// be `Send` or `Sync`.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use actix_web::{web::{self, ThreadData}, App};
+    ///
+    /// // counts requests handled by the current worker
+    /// async fn index(count: ThreadData<Cell<usize>>) -> String {
+    ///     count.set(count.get() + 1);
+    ///     format!("request #{} on this worker", count.get())
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .app_data_factory(|| Cell::new(0usize))
+    ///     .route("/", web::get().to(index));
+    /// ```
+    ///
+    /// [`ThreadData<T>`]: crate::web::ThreadData
+    pub fn app_data_factory<F, D>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> D + 'static,
+        D: 'static,
+    {
+        self.data_factories.push(Box::new(move || {
+            let data: Box<dyn DataFactory> = Box::new(ThreadData::new(factory()));
+            async move { Ok(data) }.boxed_local()
+        }));
+
+        self
+    }
+
     /// Run external configuration as part of the application building
     /// process
     ///
@@ -204,6 +247,8 @@ where
     /// This is a simplified version of the `App::service()` method.
     /// This method can be used multiple times with same path, in that case
     /// multiple resources with one route would be registered for same resource path.
+    /// Requests for that path whose method is not handled by any of these routes get a
+    /// `405 Method Not Allowed` response, with an `Allow` header listing the methods that are.
     ///
     /// ```
     /// use actix_web::{web, App, HttpResponse};
@@ -281,12 +326,60 @@ where
         self
     }
 
+    /// Sets a function that renders the responses of all errors in the application.
+    ///
+    /// By default, the response for an error is the one produced by its
+    /// [`ResponseError`](crate::ResponseError) implementation, which is usually a plain text body.
+    /// The renderer receives each such error, including ones from extractors that fail before
+    /// their handler runs, and returns the response to send instead. The error is still available
+    /// from the rendered response's [`error`](HttpResponse::error) method.
+    ///
+    /// The default `404 Not Found` and `405 Method Not Allowed` responses, sent when no resource or
+    /// route matches the request, are rendered as errors, too; the `Allow` header of the latter is
+    /// kept. They only carry an error when a renderer is set. Errors that scope and resource
+    /// middleware return as `Err`, such as those of [`Timeout`](crate::middleware::Timeout), are
+    /// rendered with a copy of the request, which has no match info or extensions. The copy is
+    /// made when the request is dropped; if a clone of the request is still held elsewhere at
+    /// that point, the error is not rendered.
+    ///
+    /// Errors are rendered before responses are passed back through the app's middleware, so
+    /// middleware such as [`ErrorHandlers`](crate::middleware::ErrorHandlers) see the rendered
+    /// responses. Errors that the app's own middleware return as `Err` are not rendered.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// let app = App::new()
+    ///     .error_renderer(|err, req| {
+    ///         let res = err.error_response();
+    ///
+    ///         HttpResponse::build(res.status()).json(serde_json::json!({
+    ///             "error": err.to_string(),
+    ///             "path": req.path(),
+    ///         }))
+    ///     })
+    ///     .route("/{id}", web::get().to(|id: web::Path<u32>| async move { id.to_string() }));
+    /// ```
+    pub fn error_renderer<F>(mut self, renderer: F) -> Self
+    where
+        F: Fn(&Error, &HttpRequest) -> HttpResponse + 'static,
+    {
+        let renderer: ErrorRenderer = Rc::new(renderer);
+        self.error_renderer = Some(renderer);
+        self
+    }
+
     /// Register an external resource.
     ///
     /// External resources are useful for URL generation purposes only
     /// and are never considered for matching at request time. Calls to
     /// `HttpRequest::url_for()` will work as expected.
     ///
+    /// The URL pattern must be absolute, including the scheme and host. Like those of other
+    /// resources, its dynamic segments are filled with the (percent-encoded) elements given to
+    /// `url_for()`.
+    ///
     /// ```
     /// use actix_web::{web, App, HttpRequest, HttpResponse, Result};
     ///
@@ -375,12 +468,66 @@ where
             data_factories: self.data_factories,
             services: self.services,
             default: self.default,
+            error_renderer: self.error_renderer,
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
         }
     }
 
+    /// Registers middleware, but only if `enabled` is true.
+    ///
+    /// This is a shorthand for wrapping `mw` in [`middleware::Condition`] and is intended for
+    /// middleware that is enabled or disabled once, when the App is constructed; e.g., using a
+    /// build-time or configuration flag. When disabled, the middleware's service is never
+    /// constructed and requests are passed directly to the wrapped service.
+    ///
+    /// Since the decision is made at construction, it can not depend on individual requests.
+    /// Use custom middleware, or [`wrap_fn`](Self::wrap_fn), for per-request decisions.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{middleware, web, App};
+    ///
+    /// let debug = cfg!(debug_assertions);
+    ///
+    /// let app = App::new()
+    ///     .wrap_conditional(debug, middleware::Logger::default())
+    ///     .route("/", web::get().to(|| async { "Welcome!" }));
+    /// ```
+    ///
+    /// [`middleware::Condition`]: crate::middleware::Condition
+    pub fn wrap_conditional<M, B, B2>(
+        self,
+        enabled: bool,
+        mw: M,
+    ) -> App<
+        impl ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<EitherBody<B, B2>>,
+            Error = Error,
+            InitError = (),
+        >,
+    >
+    where
+        T: ServiceFactory<ServiceRequest, Response = ServiceResponse<B2>>,
+        T::Service: 'static,
+        M: Transform<
+                T::Service,
+                ServiceRequest,
+                Response = ServiceResponse<B>,
+                Error = Error,
+                InitError = (),
+            > + 'static,
+        M::Future: 'static,
+        M::Transform: 'static,
+        B: MessageBody + 'static,
+        B2: MessageBody + 'static,
+    {
+        self.wrap(Condition::new(enabled, mw))
+    }
+
     /// Registers an app-wide function middleware.
     ///
     /// `mw` is a closure that runs during inbound and/or outbound processing in the request
@@ -438,6 +585,7 @@ where
             data_factories: self.data_factories,
             services: self.services,
             default: self.default,
+            error_renderer: self.error_renderer,
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
@@ -463,6 +611,7 @@ where
             services: Rc::new(RefCell::new(self.services)),
             external: RefCell::new(self.external),
             default: self.default,
+            error_renderer: self.error_renderer,
             factory_ref: self.factory_ref,
             extensions: RefCell::new(Some(self.extensions)),
         }
@@ -482,8 +631,11 @@ mod tests {
             Method, StatusCode,
         },
         middleware::DefaultHeaders,
-        service::ServiceRequest,
-        test::{call_service, init_service, read_body, try_init_service, TestRequest},
+        service::{ServiceRequest, ServiceResponse},
+        test::{
+            call_and_read_body, call_service, init_service, read_body, try_init_service,
+            TestRequest,
+        },
         web, HttpRequest, HttpResponse,
     };
 
@@ -530,6 +682,96 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::CREATED);
     }
 
+    #[actix_rt::test]
+    async fn error_renderer() {
+        let srv =
+            init_service(
+                App::new()
+                    .wrap(DefaultHeaders::new().add(("x-test", "1")))
+                    .error_renderer(|err, req| {
+                        HttpResponse::build(err.as_response_error().status_code())
+                            .body(format!("{}: {}", req.path(), err))
+                    })
+                    .route(
+                        "/num/{n}",
+                        web::get().to(|n: web::Path<u32>| async move { n.to_string() }),
+                    )
+                    .route(
+                        "/err",
+                        web::get().to(|| async {
+                            Err::<String, _>(crate::error::ErrorConflict("conflict"))
+                        }),
+                    )
+                    .route("/ok", web::get().to(HttpResponse::Ok))
+                    .service(
+                        web::scope("/mw")
+                            .wrap_fn(|_, _| {
+                                std::future::ready(Err::<ServiceResponse, _>(
+                                    crate::error::ErrorUnauthorized("unauthorized"),
+                                ))
+                            })
+                            .route("", web::get().to(HttpResponse::Ok)),
+                    ),
+            )
+            .await;
+
+        // extractor error
+        let req = TestRequest::with_uri("/num/abc").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(res.response().error().is_some());
+        assert_eq!(res.headers().get("x-test").unwrap(), "1");
+        let body = read_body(res).await;
+        assert!(body.starts_with(b"/num/abc: "));
+
+        // handler error
+        let req = TestRequest::with_uri("/err").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert_eq!(read_body(res).await, "/err: conflict");
+
+        // responses without errors are left unchanged
+        let req = TestRequest::with_uri("/ok").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(read_body(res).await.is_empty());
+
+        // default service
+        let req = TestRequest::with_uri("/missing").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(read_body(res).await, "/missing: Not Found");
+
+        // method mismatch
+        let req = TestRequest::post().uri("/ok").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers().get(header::ALLOW).unwrap(), "GET");
+        assert_eq!(read_body(res).await, "/ok: Method Not Allowed");
+
+        // middleware error
+        let req = TestRequest::with_uri("/mw").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(res.headers().get("x-test").unwrap(), "1");
+        assert_eq!(read_body(res).await, "/mw: unauthorized");
+    }
+
+    #[actix_rt::test]
+    async fn default_errors_without_renderer() {
+        let srv = init_service(App::new().route("/ok", web::get().to(HttpResponse::Ok))).await;
+
+        let req = TestRequest::with_uri("/missing").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(res.response().error().is_none());
+
+        let req = TestRequest::post().uri("/ok").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(res.response().error().is_none());
+    }
+
     // allow deprecated App::data
     #[allow(deprecated)]
     #[actix_rt::test]
@@ -569,6 +811,44 @@ mod tests {
         assert!(srv.is_err());
     }
 
+    #[actix_rt::test]
+    async fn test_app_data_factory() {
+        use std::cell::Cell;
+
+        let calls = Rc::new(Cell::new(0));
+        let calls2 = Rc::clone(&calls);
+
+        let srv = init_service(
+            App::new()
+                // not `Send`
+                .app_data_factory(move || {
+                    calls2.set(calls2.get() + 1);
+                    Rc::new(Cell::new(0usize))
+                })
+                .default_service(web::to(|count: web::ThreadData<Rc<Cell<usize>>>| {
+                    count.set(count.get() + 1);
+                    async move { count.get().to_string() }
+                })),
+        )
+        .await;
+        assert_eq!(calls.get(), 1);
+
+        let body = call_and_read_body(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(body, "1");
+        let body = call_and_read_body(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(body, "2");
+        assert_eq!(calls.get(), 1);
+
+        let srv = init_service(
+            App::new()
+                .app_data_factory(|| 10u32)
+                .default_service(web::to(|_: web::ThreadData<usize>| HttpResponse::Ok())),
+        )
+        .await;
+        let resp = call_service(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[actix_rt::test]
     async fn test_extension() {
         let srv = init_service(App::new().app_data(10usize).service(web::resource("/").to(
@@ -603,6 +883,26 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_wrap_conditional() {
+        for enabled in [true, false] {
+            let srv = init_service(
+                App::new()
+                    .wrap_conditional(
+                        enabled,
+                        DefaultHeaders::new()
+                            .add((header::CONTENT_TYPE, HeaderValue::from_static("0001"))),
+                    )
+                    .route("/test", web::get().to(HttpResponse::Ok)),
+            )
+            .await;
+            let req = TestRequest::with_uri("/test").to_request();
+            let resp = call_service(&srv, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().contains_key(header::CONTENT_TYPE), enabled);
+        }
+    }
+
     #[actix_rt::test]
     async fn test_router_wrap() {
         let srv = init_service(
@@ -692,6 +992,11 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
         let body = read_body(resp).await;
         assert_eq!(body, Bytes::from_static(b"https://youtube.com/watch/12345"));
+
+        // external resources never match requests
+        let req = TestRequest::with_uri("https://youtube.com/watch/12345").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[test]
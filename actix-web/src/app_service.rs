@@ -1,4 +1,9 @@
-use std::{cell::RefCell, mem, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    future::ready,
+    mem,
+    rc::Rc,
+};
 
 use actix_http::Request;
 use actix_router::{Path, ResourceDef, Router, Url};
@@ -11,14 +16,17 @@ use crate::{
     config::{AppConfig, AppService},
     data::FnDataFactory,
     dev::Extensions,
+    error,
     guard::Guard,
-    request::{HttpRequest, HttpRequestPool},
+    http::{header, Method, StatusCode},
+    request::{HttpRequest, HttpRequestPool, SnapshotOnDrop},
     rmap::ResourceMap,
+    route::guarded_method,
     service::{
         AppServiceFactory, BoxedHttpService, BoxedHttpServiceFactory, ServiceRequest,
         ServiceResponse,
     },
-    Error, HttpResponse,
+    Error, HttpMessage as _, HttpResponse,
 };
 
 /// Service factory to convert [`Request`] to a [`ServiceRequest<S>`].
@@ -39,10 +47,14 @@ where
     pub(crate) async_data_factories: Rc<[FnDataFactory]>,
     pub(crate) services: Rc<RefCell<Vec<Box<dyn AppServiceFactory>>>>,
     pub(crate) default: Option<Rc<BoxedHttpServiceFactory>>,
+    pub(crate) error_renderer: Option<ErrorRenderer>,
     pub(crate) factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     pub(crate) external: RefCell<Vec<ResourceDef>>,
 }
 
+/// Renders the responses of errors; see [`App::error_renderer`](crate::App::error_renderer).
+pub(crate) type ErrorRenderer = Rc<dyn Fn(&Error, &HttpRequest) -> HttpResponse>;
+
 impl<T, B> ServiceFactory<Request> for AppInit<T, B>
 where
     T: ServiceFactory<
@@ -66,7 +78,13 @@ where
         // if no user defined default service exists.
         let default = self.default.clone().unwrap_or_else(|| {
             Rc::new(boxed::factory(fn_service(|req: ServiceRequest| async {
-                Ok(req.into_response(HttpResponse::NotFound()))
+                let mut res = HttpResponse::NotFound().finish();
+
+                if renders_errors(&req) {
+                    res = res.with_error(error::ErrorNotFound("Not Found"));
+                }
+
+                Ok(ServiceResponse::new(req.into_parts().0, res))
             })))
         });
 
@@ -85,6 +103,7 @@ where
         // complete pipeline creation.
         *self.factory_ref.borrow_mut() = Some(AppRoutingFactory {
             default,
+            error_renderer: self.error_renderer.clone(),
             services: services
                 .into_iter()
                 .map(|(mut rdef, srv, guards, nested)| {
@@ -250,6 +269,7 @@ pub struct AppRoutingFactory {
         )],
     >,
     default: Rc<BoxedHttpServiceFactory>,
+    error_renderer: Option<ErrorRenderer>,
 }
 
 impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
@@ -275,6 +295,8 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
         // construct default service factory future
         let default_fut = self.default.new_service(());
 
+        let error_renderer = self.error_renderer.clone();
+
         Box::pin(async move {
             let default = default_fut.await?;
 
@@ -285,20 +307,26 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
                 .collect::<Result<Vec<_>, _>>()?
                 .drain(..)
                 .fold(Router::build(), |mut router, (path, guards, service)| {
-                    router.push(path, service, guards);
+                    let entry = RoutingEntry::new(&path, guards);
+                    router.push(path, service, entry);
                     router
                 })
                 .finish();
 
-            Ok(AppRouting { router, default })
+            Ok(AppRouting {
+                router,
+                default,
+                error_renderer,
+            })
         })
     }
 }
 
 /// The Actix Web router default entry point.
 pub struct AppRouting {
-    router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
+    router: Router<BoxedHttpService, RoutingEntry>,
     default: BoxedHttpService,
+    error_renderer: Option<ErrorRenderer>,
 }
 
 impl Service<ServiceRequest> for AppRouting {
@@ -308,18 +336,157 @@ impl Service<ServiceRequest> for AppRouting {
 
     actix_service::always_ready!();
 
-    fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        let res = self.router.recognize_fn(&mut req, |req, guards| {
-            let guard_ctx = req.guard_ctx();
-            guards.iter().all(|guard| guard.check(&guard_ctx))
-        });
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let renderer = match self.error_renderer {
+            Some(ref renderer) => Rc::clone(renderer),
+            None => return route_request(&self.router, &self.default, req),
+        };
+
+        // errors returned as `Err`, e.g. by scope or resource middleware, come without the request;
+        // it is copied into the slot when dropped along with such an error
+        let slot = Rc::new(Cell::new(None));
+        req.extensions_mut()
+            .insert(SnapshotOnDrop(Rc::clone(&slot)));
+
+        let fut = route_request(&self.router, &self.default, req);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(res) => {
+                    res.request().extensions_mut().remove::<SnapshotOnDrop>();
+                    Ok(render_error(res, &renderer))
+                }
+
+                // the request is still referenced elsewhere, so there is no copy to render with
+                Err(err) => match slot.take() {
+                    Some(snapshot) => {
+                        let req = snapshot.into_request();
+
+                        let mut res = renderer(&err, &req);
+                        res.set_error(err);
+
+                        Ok(ServiceResponse::new(req, res))
+                    }
+                    None => Err(err),
+                },
+            }
+        })
+    }
+}
 
-        if let Some((srv, _info)) = res {
-            srv.call(req)
-        } else {
-            self.default.call(req)
+/// Returns true if errors are rendered for the request, in which case default responses should
+/// carry the error they represent.
+pub(crate) fn renders_errors(req: &ServiceRequest) -> bool {
+    req.extensions().contains::<SnapshotOnDrop>()
+}
+
+/// Replaces responses that carry an error with the response rendered for that error.
+///
+/// The error is kept on the rendered response so that middleware can still inspect it. The `Allow`
+/// header of `405 Method Not Allowed` responses is kept, too.
+fn render_error(res: ServiceResponse, renderer: &ErrorRenderer) -> ServiceResponse {
+    if res.response().error().is_none() {
+        return res;
+    }
+
+    let (req, mut res) = res.into_parts();
+    let err = res.take_error().unwrap();
+    let allow = res.headers().get(header::ALLOW).cloned();
+
+    let mut rendered = renderer(&err, &req);
+    rendered.set_error(err);
+
+    if let Some(allow) = allow {
+        if rendered.status() == StatusCode::METHOD_NOT_ALLOWED {
+            rendered.headers_mut().insert(header::ALLOW, allow);
         }
     }
+
+    ServiceResponse::new(req, rendered)
+}
+
+/// Guards of an app or scope routing table entry.
+pub(crate) struct RoutingEntry {
+    guards: Vec<Box<dyn Guard>>,
+    /// Method matched by the entry's method guards, if it has any.
+    method: Option<Method>,
+    /// Whether the entry matches path prefixes, as scopes do, instead of whole paths.
+    prefix: bool,
+}
+
+impl RoutingEntry {
+    pub(crate) fn new(rdef: &ResourceDef, guards: Vec<Box<dyn Guard>>) -> Self {
+        Self {
+            method: guarded_method(&guards),
+            guards,
+            prefix: rdef.is_prefix(),
+        }
+    }
+
+    /// Returns the entry's method if its path matched and its guards, other than method guards,
+    /// pass for `req`.
+    fn allowed_method(&self, req: &ServiceRequest) -> Option<&Method> {
+        if self.prefix {
+            return None;
+        }
+
+        let method = self.method.as_ref()?;
+        let guard_ctx = req.guard_ctx();
+
+        self.guards
+            .iter()
+            .filter(|guard| guard.registered_method().is_none())
+            .all(|guard| guard.check(&guard_ctx))
+            .then(|| method)
+    }
+}
+
+/// Calls the service of the first entry whose path and guards match the request.
+///
+/// If no entry matches, but the paths of one or more resources do and only their method guards
+/// fail, a `405 Method Not Allowed` response is returned with an `Allow` header listing the methods
+/// of those resources. This is the case for resources split across several `route()` calls, for
+/// example. Otherwise, the request is passed to the default service.
+pub(crate) fn route_request(
+    router: &Router<BoxedHttpService, RoutingEntry>,
+    default: &BoxedHttpService,
+    mut req: ServiceRequest,
+) -> LocalBoxFuture<'static, Result<ServiceResponse, Error>> {
+    let mut allowed = Vec::new();
+
+    let res = router.recognize_fn(&mut req, |req, entry| {
+        let guard_ctx = req.guard_ctx();
+
+        if entry.guards.iter().all(|guard| guard.check(&guard_ctx)) {
+            return true;
+        }
+
+        if let Some(method) = entry.allowed_method(req) {
+            if !allowed.contains(method) {
+                allowed.push(method.clone());
+            }
+        }
+
+        false
+    });
+
+    if let Some((srv, _info)) = res {
+        return srv.call(req);
+    }
+
+    if allowed.is_empty() {
+        return default.call(req);
+    }
+
+    let mut res = HttpResponse::MethodNotAllowed()
+        .insert_header(header::Allow(allowed))
+        .finish();
+
+    if renders_errors(&req) {
+        res = res.with_error(error::ErrorMethodNotAllowed("Method Not Allowed"));
+    }
+
+    Box::pin(ready(Ok(ServiceResponse::new(req.into_parts().0, res))))
 }
 
 /// Wrapper service for routing
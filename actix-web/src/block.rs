@@ -0,0 +1,131 @@
+//! For cancellable blocking task documentation, see [`block_cancellable`].
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::error::BlockingError;
+
+/// Cancellation flag passed to [`block_cancellable`] closures.
+///
+/// The flag is set once the future returned by [`block_cancellable`] is dropped before the
+/// closure's result was received; for example, when the client disconnects while a handler is
+/// waiting on it. Closures doing lengthy work should check it periodically and return early once
+/// it is set.
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelFlag {
+    /// Returns true if the closure's result is no longer awaited.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+}
+
+/// Sets the flag when dropped, unless disarmed.
+struct CancelOnDrop(Option<CancelFlag>);
+
+impl CancelOnDrop {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(flag) = self.0.take() {
+            flag.cancel();
+        }
+    }
+}
+
+/// Executes blocking function on a thread pool, returns future that resolves to result of the
+/// function execution. The function is signalled when that future is dropped before completing.
+///
+/// Like [`block`](crate::web::block), but the function receives a [`CancelFlag`] which is set once
+/// the returned future is dropped, e.g. because the client disconnected and the handler awaiting
+/// the result was dropped. The blocking thread cannot be interrupted, so it is up to the function
+/// to check the flag and return early.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error, web, Responder};
+///
+/// async fn index() -> actix_web::Result<impl Responder> {
+///     let count = web::block_cancellable(|flag| {
+///         let mut count = 0_u64;
+///
+///         for _ in 0..1_000_000 {
+///             if flag.is_cancelled() {
+///                 // nobody is waiting for the result anymore
+///                 break;
+///             }
+///
+///             count += 1;
+///         }
+///
+///         count
+///     })
+///     .await
+///     .map_err(error::ErrorInternalServerError)?;
+///
+///     Ok(count.to_string())
+/// }
+/// ```
+pub fn block_cancellable<F, R>(f: F) -> impl Future<Output = Result<R, BlockingError>>
+where
+    F: FnOnce(&CancelFlag) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let flag = CancelFlag::default();
+    let mut guard = CancelOnDrop(Some(flag.clone()));
+
+    let fut = actix_rt::task::spawn_blocking(move || f(&flag));
+
+    async move {
+        let res = fut.await.map_err(|_| BlockingError);
+        guard.disarm();
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn completes() {
+        let cancelled = block_cancellable(|flag| flag.is_cancelled()).await.unwrap();
+        assert!(!cancelled);
+    }
+
+    #[actix_rt::test]
+    async fn cancelled_on_drop() {
+        let (tx, rx) = mpsc::channel();
+
+        let fut = block_cancellable(move |flag| {
+            while !flag.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            tx.send(()).unwrap();
+        });
+
+        drop(fut);
+
+        rx.recv_timeout(Duration::from_secs(5))
+            .expect("closure should observe cancellation");
+    }
+}
@@ -0,0 +1,436 @@
+//! See [`Conditional`] for responder documentation and [`conditional`] for the precondition
+//! check.
+
+use crate::{
+    body::EitherBody,
+    http::{
+        header::{
+            self, EntityTag, Header, HttpDate, IfMatch, IfModifiedSince, IfNoneMatch,
+            IfUnmodifiedSince, TryIntoHeaderValue as _,
+        },
+        Method, StatusCode,
+    },
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// Headers that are copied from the full response to a `304 Not Modified` response.
+///
+/// See [RFC 7232 §4.1](https://datatracker.ietf.org/doc/html/rfc7232#section-4.1).
+const NOT_MODIFIED_HEADERS: &[header::HeaderName] = &[
+    header::CACHE_CONTROL,
+    header::CONTENT_LOCATION,
+    header::DATE,
+    header::EXPIRES,
+    header::VARY,
+];
+
+/// Responder wrapper that evaluates conditional request headers against given validators.
+///
+/// The wrapped responder's validators, an entity tag and/or a last modification date, are
+/// compared with the request's `If-Match`, `If-Unmodified-Since`, `If-None-Match` and
+/// `If-Modified-Since` headers in the order described in [RFC 7232 §6]:
+/// - if a precondition of `If-Match` (using strong comparison) or `If-Unmodified-Since` fails,
+///   `412 Precondition Failed` is sent;
+/// - if `If-None-Match` (using weak comparison) matches, `304 Not Modified` is sent for `GET` and
+///   `HEAD` requests and `412 Precondition Failed` for other methods;
+/// - otherwise, if `If-None-Match` is absent and the resource has not been modified since the
+///   `If-Modified-Since` date, `304 Not Modified` is sent for `GET` and `HEAD` requests.
+///
+/// In all other cases the wrapped response is sent. The `ETag` and `Last-Modified` headers are set
+/// on successful (2xx) and `304 Not Modified` responses. Conditional headers are ignored when the
+/// wrapped responder produces a non-2xx response.
+///
+/// Use `actix-files` for serving files from disk, which handles conditional requests itself.
+///
+/// # Safe Methods Only
+/// The preconditions are evaluated once the handler has produced its response, i.e., after any
+/// work it does has been done. This is fine for `GET` and `HEAD` requests, where the precondition
+/// only avoids sending the response, but not for methods that change state, such as `PUT` or
+/// `DELETE`: a `412 Precondition Failed` response would be sent for a change that has already been
+/// made. Handlers for such methods should call [`conditional`] before doing their work instead.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::EntityTag, web, Responder};
+///
+/// async fn handler() -> impl Responder {
+///     // e.g., the revision of a document loaded from a database
+///     let revision = 7;
+///
+///     web::Conditional::new(format!("document at revision {}", revision))
+///         .etag(EntityTag::new_strong(revision.to_string()))
+/// }
+/// # web::to(handler);
+/// ```
+///
+/// [RFC 7232 §6]: https://datatracker.ietf.org/doc/html/rfc7232#section-6
+#[derive(Debug, Clone)]
+pub struct Conditional<R> {
+    responder: R,
+    etag: Option<EntityTag>,
+    last_modified: Option<HttpDate>,
+}
+
+impl<R: Responder> Conditional<R> {
+    /// Wraps `responder` without any validators.
+    pub fn new(responder: R) -> Self {
+        Self {
+            responder,
+            etag: None,
+            last_modified: None,
+        }
+    }
+
+    /// Sets the entity tag of the response.
+    pub fn etag(mut self, etag: EntityTag) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    /// Sets the last modification date of the response.
+    ///
+    /// Dates are compared with a precision of one second.
+    pub fn last_modified(mut self, last_modified: impl Into<HttpDate>) -> Self {
+        self.last_modified = Some(last_modified.into());
+        self
+    }
+}
+
+/// Evaluates the request's conditional headers against the current validators of its target.
+///
+/// Returns the response to send instead of performing the request, if a precondition fails or
+/// the client's copy of the target is not modified; the preconditions and their evaluation order
+/// are the same as for the [`Conditional`] responder. A returned `304 Not Modified` response has
+/// its `ETag` and `Last-Modified` headers set.
+///
+/// Unlike `Conditional`, this can be called before the handler does any work, making it suitable
+/// for methods that change state.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::EntityTag, web, HttpRequest, HttpResponse};
+///
+/// async fn update(req: HttpRequest, body: String) -> HttpResponse {
+///     // e.g., the revision of a document loaded from a database
+///     let revision = 7;
+///     let etag = EntityTag::new_strong(revision.to_string());
+///
+///     // only update the document if the client's copy is up-to-date
+///     if let Some(res) = web::conditional(&req, Some(&etag), None) {
+///         return res;
+///     }
+///
+///     // store `body` as the document's new revision
+///     # let _ = body;
+///     HttpResponse::NoContent().finish()
+/// }
+/// # web::to(update);
+/// ```
+pub fn conditional(
+    req: &HttpRequest,
+    etag: Option<&EntityTag>,
+    last_modified: Option<HttpDate>,
+) -> Option<HttpResponse> {
+    let status = evaluate(req, etag, last_modified)?;
+    let mut res = HttpResponse::new(status);
+
+    if status == StatusCode::NOT_MODIFIED {
+        set_validators(&mut res, etag, last_modified);
+    }
+
+    Some(res)
+}
+
+/// Evaluates the request's preconditions, returning the status code to respond with instead of
+/// the full response, if any.
+fn evaluate(
+    req: &HttpRequest,
+    etag: Option<&EntityTag>,
+    last_modified: Option<HttpDate>,
+) -> Option<StatusCode> {
+    let is_get_or_head = matches!(*req.method(), Method::GET | Method::HEAD);
+
+    match conditional_header::<IfMatch>(req) {
+        Some(IfMatch::Any) => {}
+
+        Some(IfMatch::Items(items)) => {
+            let matched =
+                etag.map_or(false, |etag| items.iter().any(|item| item.strong_eq(etag)));
+
+            if !matched {
+                return Some(StatusCode::PRECONDITION_FAILED);
+            }
+        }
+
+        None => {
+            if let (Some(last_modified), Some(IfUnmodifiedSince(since))) =
+                (last_modified, conditional_header(req))
+            {
+                if last_modified > since {
+                    return Some(StatusCode::PRECONDITION_FAILED);
+                }
+            }
+        }
+    }
+
+    let not_modified = match conditional_header::<IfNoneMatch>(req) {
+        Some(IfNoneMatch::Any) => true,
+
+        Some(IfNoneMatch::Items(items)) => {
+            etag.map_or(false, |etag| items.iter().any(|item| item.weak_eq(etag)))
+        }
+
+        None => match (last_modified, conditional_header(req)) {
+            (Some(last_modified), Some(IfModifiedSince(since))) => {
+                is_get_or_head && last_modified <= since
+            }
+            _ => false,
+        },
+    };
+
+    match (not_modified, is_get_or_head) {
+        (false, _) => None,
+        (true, true) => Some(StatusCode::NOT_MODIFIED),
+        (true, false) => Some(StatusCode::PRECONDITION_FAILED),
+    }
+}
+
+/// Sets the `ETag` and `Last-Modified` headers of `res`.
+fn set_validators<B>(
+    res: &mut HttpResponse<B>,
+    etag: Option<&EntityTag>,
+    last_modified: Option<HttpDate>,
+) {
+    if let Some(etag) = etag {
+        res.headers_mut()
+            .insert(header::ETAG, etag.clone().try_into_value().unwrap());
+    }
+
+    if let Some(last_modified) = last_modified {
+        res.headers_mut().insert(
+            header::LAST_MODIFIED,
+            last_modified.try_into_value().unwrap(),
+        );
+    }
+}
+
+/// Returns the typed header `H` if present on `req` and valid.
+fn conditional_header<H: Header>(req: &HttpRequest) -> Option<H> {
+    if req.headers().contains_key(H::name()) {
+        H::parse(req).ok()
+    } else {
+        None
+    }
+}
+
+impl<R: Responder> Responder for Conditional<R> {
+    type Body = EitherBody<R::Body>;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let status = evaluate(req, self.etag.as_ref(), self.last_modified);
+        let res = self.responder.respond_to(req);
+
+        if !res.status().is_success() {
+            return res.map_into_left_body();
+        }
+
+        let mut res = match status {
+            None => res.map_into_left_body(),
+
+            Some(StatusCode::NOT_MODIFIED) => {
+                let mut not_modified = HttpResponse::NotModified().finish();
+
+                for name in NOT_MODIFIED_HEADERS {
+                    for value in res.headers().get_all(name) {
+                        not_modified
+                            .headers_mut()
+                            .append(name.clone(), value.clone());
+                    }
+                }
+
+                not_modified.map_into_right_body()
+            }
+
+            Some(status) => return HttpResponse::new(status).map_into_right_body(),
+        };
+
+        set_validators(&mut res, self.etag.as_ref(), self.last_modified);
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::{body, test::TestRequest};
+
+    fn respond(req: TestRequest) -> HttpResponse<EitherBody<&'static str>> {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        Conditional::new("hello")
+            .etag(EntityTag::new_strong("abc".to_owned()))
+            .last_modified(modified)
+            .respond_to(&req.to_http_request())
+    }
+
+    fn date(secs: u64) -> String {
+        HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)).to_string()
+    }
+
+    #[actix_rt::test]
+    async fn unconditional() {
+        let res = respond(TestRequest::default());
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), "\"abc\"");
+        assert_eq!(
+            res.headers()
+                .get(header::LAST_MODIFIED)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            date(1_000_000),
+        );
+        assert_eq!(body::to_bytes(res.into_body()).await.unwrap(), "hello");
+    }
+
+    #[actix_rt::test]
+    async fn if_none_match() {
+        let res = respond(
+            TestRequest::default().insert_header((header::IF_NONE_MATCH, "\"xyz\", W/\"abc\"")),
+        );
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), "\"abc\"");
+        assert!(body::to_bytes(res.into_body()).await.unwrap().is_empty());
+
+        let res = respond(TestRequest::default().insert_header((header::IF_NONE_MATCH, "*")));
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        let res =
+            respond(TestRequest::default().insert_header((header::IF_NONE_MATCH, "\"xyz\"")));
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res =
+            respond(TestRequest::post().insert_header((header::IF_NONE_MATCH, "\"abc\"")));
+        assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+        // If-None-Match takes precedence over If-Modified-Since
+        let res = respond(
+            TestRequest::default()
+                .insert_header((header::IF_NONE_MATCH, "\"xyz\""))
+                .insert_header((header::IF_MODIFIED_SINCE, date(2_000_000))),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn if_modified_since() {
+        let res = respond(
+            TestRequest::default().insert_header((header::IF_MODIFIED_SINCE, date(1_000_000))),
+        );
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        let res = respond(
+            TestRequest::default().insert_header((header::IF_MODIFIED_SINCE, date(999_999))),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = respond(
+            TestRequest::post().insert_header((header::IF_MODIFIED_SINCE, date(1_000_000))),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn if_match() {
+        let res = respond(TestRequest::put().insert_header((header::IF_MATCH, "\"abc\"")));
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // weak entity tags never match strongly
+        let res = respond(TestRequest::put().insert_header((header::IF_MATCH, "W/\"abc\"")));
+        assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+        let res = respond(TestRequest::put().insert_header((header::IF_MATCH, "*")));
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn if_unmodified_since() {
+        let res = respond(
+            TestRequest::put().insert_header((header::IF_UNMODIFIED_SINCE, date(999_999))),
+        );
+        assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+        let res = respond(
+            TestRequest::put().insert_header((header::IF_UNMODIFIED_SINCE, date(1_000_000))),
+        );
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn not_modified_keeps_cache_headers() {
+        let res = Conditional::new(
+            HttpResponse::Ok()
+                .insert_header((header::CACHE_CONTROL, "max-age=60"))
+                .insert_header((header::CONTENT_TYPE, "text/plain"))
+                .body("hello"),
+        )
+        .etag(EntityTag::new_weak("abc".to_owned()))
+        .respond_to(
+            &TestRequest::default()
+                .insert_header((header::IF_NONE_MATCH, "\"abc\""))
+                .to_http_request(),
+        );
+
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), "W/\"abc\"");
+        assert_eq!(
+            res.headers().get(header::CACHE_CONTROL).unwrap(),
+            "max-age=60"
+        );
+        assert!(!res.headers().contains_key(header::CONTENT_TYPE));
+    }
+
+    #[actix_rt::test]
+    async fn precondition_check() {
+        let etag = EntityTag::new_strong("abc".to_owned());
+        let modified = HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000));
+
+        let req = TestRequest::put()
+            .insert_header((header::IF_MATCH, "\"xyz\""))
+            .to_http_request();
+        let res = conditional(&req, Some(&etag), Some(modified)).unwrap();
+        assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+        let req = TestRequest::put()
+            .insert_header((header::IF_MATCH, "\"abc\""))
+            .to_http_request();
+        assert!(conditional(&req, Some(&etag), Some(modified)).is_none());
+
+        let req = TestRequest::default()
+            .insert_header((header::IF_MODIFIED_SINCE, date(1_000_000)))
+            .to_http_request();
+        let res = conditional(&req, Some(&etag), Some(modified)).unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), "\"abc\"");
+        assert!(res.headers().contains_key(header::LAST_MODIFIED));
+
+        assert!(conditional(&TestRequest::default().to_http_request(), None, None).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn non_success_ignored() {
+        let res = Conditional::new(HttpResponse::NotFound().finish())
+            .etag(EntityTag::new_strong("abc".to_owned()))
+            .respond_to(
+                &TestRequest::default()
+                    .insert_header((header::IF_NONE_MATCH, "\"abc\""))
+                    .to_http_request(),
+            );
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(!res.headers().contains_key(header::ETAG));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, rc::Rc};
+use std::{net::SocketAddr, rc::Rc, sync::Arc};
 
 use actix_service::{boxed, IntoServiceFactory, ServiceFactory, ServiceFactoryExt as _};
 
@@ -7,6 +7,7 @@ use crate::{
     dev::{Extensions, ResourceDef},
     error::Error,
     guard::Guard,
+    info::TrustedProxies,
     resource::Resource,
     rmap::ResourceMap,
     route::Route,
@@ -112,11 +113,22 @@ pub struct AppConfig {
     secure: bool,
     host: String,
     addr: SocketAddr,
+    trusted_proxies: Option<Arc<TrustedProxies>>,
 }
 
 impl AppConfig {
     pub(crate) fn new(secure: bool, host: String, addr: SocketAddr) -> Self {
-        AppConfig { secure, host, addr }
+        AppConfig {
+            secure,
+            host,
+            addr,
+            trusted_proxies: None,
+        }
+    }
+
+    pub(crate) fn with_trusted_proxies(mut self, proxies: Option<Arc<TrustedProxies>>) -> Self {
+        self.trusted_proxies = proxies;
+        self
     }
 
     /// Needed in actix-test crate. Semver exempt.
@@ -146,6 +158,10 @@ impl AppConfig {
         self.addr
     }
 
+    pub(crate) fn trusted_proxies(&self) -> Option<&TrustedProxies> {
+        self.trusted_proxies.as_deref()
+    }
+
     #[cfg(test)]
     pub(crate) fn set_host(&mut self, host: &str) {
         self.host = host.to_owned();
@@ -228,6 +244,9 @@ impl ServiceConfig {
 
     /// Default service to be used if no matching resource could be found.
     ///
+    /// The default service is applied to the app or scope this config is merged into, replacing any
+    /// default service it already had. If several configs set one, the last one merged wins.
+    ///
     /// Counterpart to [`App::default_service()`](crate::App::default_service).
     pub fn default_service<F, U>(&mut self, f: F) -> &mut Self
     where
@@ -388,6 +407,35 @@ mod tests {
         assert_eq!(body, Bytes::from_static(b"scoped four oh four"));
     }
 
+    #[actix_rt::test]
+    async fn last_default_service_wins() {
+        fn api(cfg: &mut ServiceConfig) {
+            cfg.route("/api", web::get().to(HttpResponse::Ok))
+                .default_service(web::to(|| HttpResponse::NotFound().body("api")));
+        }
+
+        fn fallback(cfg: &mut ServiceConfig) {
+            cfg.default_service(web::to(|| HttpResponse::NotFound().body("fallback")));
+        }
+
+        let srv = init_service(App::new().configure(api).configure(fallback)).await;
+
+        let req = TestRequest::with_uri("/api").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/unknown").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"fallback"));
+
+        let srv = init_service(App::new().configure(fallback).configure(api)).await;
+
+        let req = TestRequest::with_uri("/unknown").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"api"));
+    }
+
     #[actix_rt::test]
     async fn test_service() {
         let srv = init_service(App::new().configure(|cfg| {
@@ -0,0 +1,141 @@
+use std::{
+    any::type_name,
+    future::{ready, Ready},
+    ops::Deref,
+};
+
+use crate::{dev::Payload, error::ErrorInternalServerError, Error, FromRequest, HttpRequest};
+
+/// Connection data extractor.
+///
+/// Connection data is arbitrary data attached to a connection when it is accepted, using the
+/// [`HttpServer::on_connect`] callback. It can also be accessed using [`HttpRequest::conn_data`].
+/// Extracting `ConnData<T>` fails with a `500 Internal Server Error` if no value of type `T` was
+/// inserted for the connection; use `Option<ConnData<T>>` if the data is not always present.
+///
+/// It uses the same types-as-keys storage system as app and request data, so only one value of
+/// each type can be stored per connection. To avoid collisions when several components store
+/// connection data, wrap values in a newtype specific to each component.
+///
+/// # Lifetime
+/// The `on_connect` callback is called once per connection, not once per request. The same
+/// connection data is, therefore, shared by all requests served on that connection: every request
+/// of a keep-alive HTTP/1.1 connection and every stream of an HTTP/2 connection. It is dropped when
+/// the connection is closed. Data that should not outlive a single request belongs in
+/// [request-local data](crate::web::ReqData) instead.
+///
+/// # Mutating Connection Data
+/// Connection data is shared and can not be mutated after the connection is accepted. Since
+/// extractors must output owned data, only types that `impl Clone` can use this extractor. Use a
+/// type with interior mutability, such as `Rc<Cell<T>>`, to keep state across the requests of a
+/// connection.
+///
+/// # Examples
+/// ```no_run
+/// use std::any::Any;
+/// use actix_web::{dev::Extensions, rt::net::TcpStream, web, App, HttpServer, Responder};
+///
+/// #[derive(Debug, Clone)]
+/// struct PeerTtl(Option<u32>);
+///
+/// fn on_connect(conn: &dyn Any, data: &mut Extensions) {
+///     if let Some(sock) = conn.downcast_ref::<TcpStream>() {
+///         data.insert(PeerTtl(sock.ttl().ok()));
+///     }
+/// }
+///
+/// async fn handler(ttl: web::ConnData<PeerTtl>) -> impl Responder {
+///     format!("{:?}", ttl.0)
+/// }
+///
+/// # async fn run() -> std::io::Result<()> {
+/// HttpServer::new(|| App::new().default_service(web::to(handler)))
+///     .on_connect(on_connect)
+///     .bind(("127.0.0.1", 8080))?
+///     .run()
+///     .await
+/// # }
+/// ```
+///
+/// [`HttpServer::on_connect`]: crate::HttpServer::on_connect
+#[derive(Debug, Clone)]
+pub struct ConnData<T: Clone + 'static>(T);
+
+impl<T: Clone + 'static> ConnData<T> {
+    /// Consumes the `ConnData`, returning its wrapped data.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Clone + 'static> Deref for ConnData<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone + 'static> FromRequest for ConnData<T> {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        if let Some(data) = req.conn_data::<T>() {
+            ready(Ok(ConnData(data.clone())))
+        } else {
+            log::debug!(
+                "Failed to construct ConnData extractor. \
+                 Request path: {:?} (type: {})",
+                req.path(),
+                type_name::<T>(),
+            );
+            ready(Err(ErrorInternalServerError(
+                "Missing expected connection data",
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http::StatusCode, test::TestRequest};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Peer(&'static str);
+
+    #[actix_rt::test]
+    async fn conn_data_extractor() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_conn_data(Peer("client"))
+            .insert_conn_data(42u32)
+            .to_http_parts();
+
+        let peer = ConnData::<Peer>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(*peer, Peer("client"));
+        assert_eq!(peer.into_inner(), Peer("client"));
+
+        let num = ConnData::<u32>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(*num, 42);
+
+        let err = ConnData::<u64>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let opt = Option::<ConnData<u64>>::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert!(opt.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn missing_conn_data() {
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+        assert!(ConnData::<Peer>::from_request(&req, &mut pl).await.is_err());
+    }
+}
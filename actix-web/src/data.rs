@@ -1,8 +1,9 @@
-use std::{any::type_name, ops::Deref, sync::Arc};
+use std::{any::type_name, future::Future, ops::Deref, rc::Rc, sync::Arc};
 
 use actix_http::Extensions;
 use actix_utils::future::{err, ok, Ready};
 use futures_core::future::LocalBoxFuture;
+use once_cell::sync::OnceCell;
 use serde::Serialize;
 
 use crate::{dev::Payload, error, Error, FromRequest, HttpRequest};
@@ -97,6 +98,24 @@ impl<T> Data<T> {
 }
 
 impl<T: ?Sized> Data<T> {
+    /// Create new `Data` instance from an existing `Arc`, without wrapping it again.
+    ///
+    /// The allocation, and its reference count, are shared with any other clones of the `Arc`,
+    /// such as ones held by background tasks.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::sync::{atomic::AtomicUsize, Arc};
+    /// # use actix_web::web::Data;
+    /// let counter = Arc::new(AtomicUsize::new(0));
+    /// let data = Data::from_arc(Arc::clone(&counter));
+    ///
+    /// assert!(Arc::ptr_eq(&counter, &data.into_inner()));
+    /// ```
+    pub fn from_arc(arc: Arc<T>) -> Data<T> {
+        Data(arc)
+    }
+
     /// Returns reference to inner `T`.
     pub fn get_ref(&self) -> &T {
         self.0.as_ref()
@@ -108,6 +127,85 @@ impl<T: ?Sized> Data<T> {
     }
 }
 
+/// Lazily-initialized data.
+///
+/// Wrapping data in a [`OnceCell`] allows it to be shared before it is constructed; for example,
+/// when construction is expensive or depends on information only available at request time. The
+/// first handler to call [`get_or_init`](Self::get_or_init) or
+/// [`get_or_init_async`](Self::get_or_init_async) initializes it for all clones of the `Data`.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web::{self, Data}, App, HttpResponse, Responder};
+/// use once_cell::sync::OnceCell;
+///
+/// struct Pool;
+///
+/// async fn connect() -> Pool {
+///     Pool
+/// }
+///
+/// async fn index(pool: Data<OnceCell<Pool>>) -> impl Responder {
+///     let _pool: &Pool = pool.get_or_init_async(connect).await;
+///     HttpResponse::Ok()
+/// }
+///
+/// let app = App::new()
+///     .app_data(Data::<OnceCell<Pool>>::uninit())
+///     .route("/", web::get().to(index));
+/// ```
+impl<T> Data<OnceCell<T>> {
+    /// Create new `Data` instance that has not been initialized yet.
+    pub fn uninit() -> Self {
+        Data::new(OnceCell::new())
+    }
+
+    /// Returns reference to inner `T`, initializing it with `f` if it has not been initialized yet.
+    ///
+    /// If multiple threads call this concurrently, only one of them runs `f`; the others block
+    /// until it completes.
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(f)
+    }
+
+    /// Returns reference to inner `T`, initializing it with the output of `f` if it has not been
+    /// initialized yet.
+    ///
+    /// Unlike [`get_or_init`](Self::get_or_init), concurrent callers do not wait for each other. If
+    /// multiple callers race to initialize the data, each may run `f`, but only the first value to
+    /// be ready is stored and the others are dropped.
+    pub async fn get_or_init_async<F, Fut>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        if let Some(val) = self.0.get() {
+            return val;
+        }
+
+        let val = f().await;
+
+        // keep first value if initialized while `f` was running
+        let _ = self.0.set(val);
+
+        self.0.get().expect("value is set above")
+    }
+
+    /// Returns reference to inner `T`, or an error if it has not been initialized yet.
+    ///
+    /// The error results in a `500 Internal Server Error` response.
+    pub fn try_get(&self) -> Result<&T, Error> {
+        self.0.get().ok_or_else(|| {
+            log::debug!(
+                "Accessed `Data<OnceCell<{}>>` before it was initialized.",
+                type_name::<T>(),
+            );
+
+            error::ErrorInternalServerError("Requested application data is not initialized.")
+        })
+    }
+}
+
 impl<T: ?Sized> Deref for Data<T> {
     type Target = Arc<T>;
 
@@ -124,7 +222,7 @@ impl<T: ?Sized> Clone for Data<T> {
 
 impl<T: ?Sized> From<Arc<T>> for Data<T> {
     fn from(arc: Arc<T>) -> Self {
-        Data(arc)
+        Data::from_arc(arc)
     }
 }
 
@@ -172,6 +270,110 @@ impl<T: ?Sized + 'static> DataFactory for Data<T> {
     }
 }
 
+/// Per-worker application data wrapper and extractor.
+///
+/// Unlike [`Data`], which is shared by all workers, a separate `ThreadData` value is constructed
+/// for each worker by the factory given to [`App::app_data_factory`](crate::App::app_data_factory).
+/// The value is only ever accessed from the worker thread it was constructed on, so it does not
+/// need to be `Send` or `Sync`; this makes it suitable for resources such as non-thread-safe
+/// random number generators, per-worker connections, or scratch buffers. `ThreadData` is cheap
+/// to clone; internally, it uses an `Rc`.
+///
+/// If thread data is not set for a handler, using the `ThreadData<T>` extractor causes a `500
+/// Internal Server Error` response.
+///
+/// # Examples
+/// ```
+/// use std::cell::RefCell;
+/// use actix_web::{web::{self, ThreadData}, App, HttpResponse, Responder};
+///
+/// async fn index(buf: ThreadData<RefCell<Vec<u8>>>) -> impl Responder {
+///     let mut buf = buf.borrow_mut();
+///     buf.clear();
+///     buf.extend_from_slice(b"hello world");
+///     HttpResponse::Ok().body(buf.clone())
+/// }
+///
+/// let app = App::new()
+///     .app_data_factory(|| RefCell::new(Vec::<u8>::with_capacity(4096)))
+///     .route("/", web::get().to(index));
+/// ```
+#[derive(Debug)]
+pub struct ThreadData<T: ?Sized>(Rc<T>);
+
+impl<T> ThreadData<T> {
+    /// Create new `ThreadData` instance.
+    pub fn new(state: T) -> ThreadData<T> {
+        ThreadData(Rc::new(state))
+    }
+}
+
+impl<T: ?Sized> ThreadData<T> {
+    /// Returns reference to inner `T`.
+    pub fn get_ref(&self) -> &T {
+        self.0.as_ref()
+    }
+
+    /// Unwraps to the internal `Rc<T>`
+    pub fn into_inner(self) -> Rc<T> {
+        self.0
+    }
+}
+
+impl<T: ?Sized> Deref for ThreadData<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> Clone for ThreadData<T> {
+    fn clone(&self) -> ThreadData<T> {
+        ThreadData(Rc::clone(&self.0))
+    }
+}
+
+impl<T: ?Sized> From<Rc<T>> for ThreadData<T> {
+    fn from(rc: Rc<T>) -> Self {
+        ThreadData(rc)
+    }
+}
+
+impl<T: ?Sized + 'static> FromRequest for ThreadData<T> {
+    type Error = Error;
+    type Future = std::future::Ready<Result<Self, Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        std::future::ready(match req.app_data::<ThreadData<T>>() {
+            Some(st) => Ok(st.clone()),
+            None => {
+                log::debug!(
+                    "Failed to extract `ThreadData<{}>` for `{}` handler. For the ThreadData \
+                    extractor to work correctly, pass a factory returning the data to \
+                    `App::app_data_factory()`. Ensure that types align in both the set and \
+                    retrieve calls.",
+                    type_name::<T>(),
+                    req.match_name().unwrap_or_else(|| req.path())
+                );
+
+                Err(error::ErrorInternalServerError(
+                    "Requested application data is not configured correctly. \
+                    View/enable debug logs for more details.",
+                ))
+            }
+        })
+    }
+}
+
+impl<T: ?Sized + 'static> DataFactory for ThreadData<T> {
+    fn create(&self, extensions: &mut Extensions) -> bool {
+        extensions.insert(ThreadData(Rc::clone(&self.0)));
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,6 +509,12 @@ mod tests {
         let data_new = Data::new(String::from("test-123"));
         let data_from_arc = Data::from(Arc::new(String::from("test-123")));
         assert_eq!(data_new.0, data_from_arc.0);
+
+        let arc = Arc::new(String::from("test-123"));
+        let data = Data::from_arc(Arc::clone(&arc));
+        assert_eq!(Arc::strong_count(&arc), 2);
+        assert_eq!(data.get_ref(), "test-123");
+        assert!(Arc::ptr_eq(&arc, &data.into_inner()));
     }
 
     #[actix_rt::test]
@@ -362,4 +570,49 @@ mod tests {
         let ref_data = data_arc.get_ref();
         assert_eq!(data_arc.get_num(), ref_data.get_num())
     }
+
+    #[actix_rt::test]
+    async fn test_lazy_data() {
+        let data = Data::<OnceCell<String>>::uninit();
+        let data2 = Data::clone(&data);
+
+        let err = data.try_get().unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        assert_eq!(data.get_or_init(|| "first".to_owned()), "first");
+        assert_eq!(data2.get_or_init(|| unreachable!()), "first");
+        assert_eq!(data2.try_get().unwrap(), "first");
+    }
+
+    #[actix_rt::test]
+    async fn test_lazy_data_async() {
+        async fn handler(data: Data<OnceCell<usize>>) -> HttpResponse {
+            let val = data.get_or_init_async(|| async { 42 }).await;
+            HttpResponse::Ok().body(val.to_string())
+        }
+
+        let data = Data::<OnceCell<usize>>::uninit();
+
+        let srv = init_service(
+            App::new()
+                .app_data(Data::clone(&data))
+                .service(web::resource("/").to(handler)),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(crate::test::read_body(resp).await, "42");
+
+        // initialized value is shared with the app
+        assert_eq!(*data.try_get().unwrap(), 42);
+        assert_eq!(
+            *data.get_or_init_async(|| async { unreachable!() }).await,
+            42
+        );
+    }
 }
@@ -20,8 +20,10 @@ pub use actix_http::encoding::Decoder as Decompress;
 pub use crate::config::{AppConfig, AppService};
 #[doc(hidden)]
 pub use crate::handler::Handler;
-pub use crate::info::{ConnectionInfo, PeerAddr};
+pub use crate::info::{ConnectionInfo, ForwardedElement, PeerAddr};
+pub use crate::middleware::body_tee::TeeBody;
 pub use crate::rmap::ResourceMap;
+pub use crate::server::ExpectAction;
 pub use crate::service::{HttpServiceFactory, ServiceRequest, ServiceResponse, WebService};
 
 pub use crate::types::{JsonBody, Readlines, UrlEncoded};
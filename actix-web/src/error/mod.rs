@@ -15,7 +15,7 @@ use serde_urlencoded::de::Error as FormDeError;
 use serde_urlencoded::ser::Error as FormError;
 use url::ParseError as UrlParseError;
 
-use crate::http::StatusCode;
+use crate::http::{header::HeaderName, StatusCode};
 
 #[allow(clippy::module_inception)]
 mod error;
@@ -166,6 +166,45 @@ impl ResponseError for JsonPayloadError {
     }
 }
 
+/// A set of errors that can occur when reading newline-delimited JSON payloads.
+#[derive(Debug, Display, Error)]
+#[non_exhaustive]
+pub enum NdJsonError {
+    /// Content type error.
+    #[display(fmt = "Content type error")]
+    ContentType,
+
+    /// Line is longer than allowed. (default: 256kB)
+    #[display(
+        fmt = "NDJSON line {} is longer than allowed (limit: {} bytes).",
+        line,
+        limit
+    )]
+    LineOverflow { line: usize, limit: usize },
+
+    /// Payload ended in the middle of a line.
+    #[display(fmt = "NDJSON payload ended with incomplete line {}.", line)]
+    Incomplete { line: usize },
+
+    /// Line could not be deserialized. Following lines can still be read.
+    #[display(fmt = "NDJSON deserialize error on line {}: {}", line, source)]
+    Deserialize { line: usize, source: JsonError },
+
+    /// Payload error.
+    #[display(fmt = "Error that occur during reading payload: {}", _0)]
+    Payload(PayloadError),
+}
+
+impl ResponseError for NdJsonError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::LineOverflow { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Payload(err) => err.status_code(),
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
 /// A set of errors that can occur during parsing request paths
 #[derive(Debug, Display, Error)]
 #[non_exhaustive]
@@ -197,6 +236,52 @@ impl ResponseError for QueryPayloadError {
     }
 }
 
+/// A set of errors that can occur when extracting typed headers.
+#[derive(Debug, Display, Error)]
+#[non_exhaustive]
+pub enum HeaderError {
+    /// Header is not present in the request.
+    #[display(fmt = "Header `{}` is missing", _0)]
+    Missing(#[error(not(source))] HeaderName),
+
+    /// Header is present in the request but could not be parsed.
+    #[display(fmt = "Header `{}` could not be parsed", _0)]
+    Invalid(#[error(not(source))] HeaderName),
+}
+
+impl HeaderError {
+    /// Returns name of the header that failed to be extracted.
+    pub fn header_name(&self) -> &HeaderName {
+        match self {
+            HeaderError::Missing(name) | HeaderError::Invalid(name) => name,
+        }
+    }
+}
+
+/// Return `BadRequest` for `HeaderError`
+impl ResponseError for HeaderError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// Errors that can occur when adding a partitioned cookie to a response.
+#[cfg(feature = "cookies")]
+#[derive(Debug, Display, Error)]
+#[non_exhaustive]
+pub enum PartitionedCookieError {
+    /// Partitioned cookie does not have the `Secure` attribute.
+    #[display(fmt = "Partitioned cookie must have the Secure attribute")]
+    NotSecure,
+
+    /// Partitioned cookie does not have the `SameSite=None` attribute.
+    #[display(fmt = "Partitioned cookie must have the SameSite=None attribute")]
+    NotSameSiteNone,
+}
+
+#[cfg(feature = "cookies")]
+impl ResponseError for PartitionedCookieError {}
+
 /// Error type returned when reading body as lines.
 #[derive(Debug, Display, Error, From)]
 #[non_exhaustive]
@@ -264,6 +349,14 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[test]
+    fn test_header_error() {
+        let err = HeaderError::Missing(crate::http::header::DATE);
+        assert_eq!(err.header_name(), crate::http::header::DATE);
+        assert_eq!(err.to_string(), "Header `date` is missing");
+        assert_eq!(err.error_response().status(), StatusCode::BAD_REQUEST);
+    }
+
     #[test]
     fn test_readlines_error() {
         let resp = ReadlinesError::LimitOverflow.error_response();
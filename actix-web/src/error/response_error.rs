@@ -77,6 +77,18 @@ impl ResponseError for serde_json::Error {}
 
 impl ResponseError for serde_urlencoded::ser::Error {}
 
+/// Responds with `400 Bad Request` and a JSON body describing the fields that failed validation.
+#[cfg(feature = "validation")]
+impl ResponseError for validator::ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+}
+
 impl ResponseError for std::str::Utf8Error {
     fn status_code(&self) -> StatusCode {
         StatusCode::BAD_REQUEST
@@ -121,6 +133,7 @@ impl ResponseError for actix_http::error::PayloadError {
     fn status_code(&self) -> StatusCode {
         match *self {
             actix_http::error::PayloadError::Overflow => StatusCode::PAYLOAD_TOO_LARGE,
+            actix_http::error::PayloadError::Timeout => StatusCode::REQUEST_TIMEOUT,
             _ => StatusCode::BAD_REQUEST,
         }
     }
@@ -1,8 +1,9 @@
 use actix_http::{header, uri::Uri, RequestHead};
+use smallvec::SmallVec;
 
 use super::{Guard, GuardContext};
 
-/// Creates a guard that matches requests targetting a specific host.
+/// Creates a guard that matches requests targeting a specific host.
 ///
 /// # Matching Host
 /// This guard will:
@@ -53,11 +54,14 @@ use super::{Guard, GuardContext};
 ///     }));
 /// ```
 ///
+/// For matching a family of hosts, such as the subdomains of a multi-tenant app, see
+/// [`HostGuard::wildcard`].
+///
 /// [virtual hosting]: https://en.wikipedia.org/wiki/Virtual_hosting
 #[allow(non_snake_case)]
 pub fn Host(host: impl AsRef<str>) -> HostGuard {
     HostGuard {
-        host: host.as_ref().to_string(),
+        host: HostMatcher::Exact(host.as_ref().to_string()),
         scheme: None,
     }
 }
@@ -70,13 +74,177 @@ fn get_host_uri(req: &RequestHead) -> Option<Uri> {
         .and_then(|host| host.parse().ok())
 }
 
-#[doc(hidden)]
+/// Labels captured from the request's host by a [wildcard host guard](HostGuard::wildcard).
+///
+/// Inserted into request-local data when a wildcard host guard with at least one named label
+/// matches, so it can be extracted in handlers using [`ReqData`](crate::web::ReqData).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostCaptures {
+    captures: SmallVec<[(String, String); 1]>,
+}
+
+impl HostCaptures {
+    /// Returns the captured label with the given name, if present.
+    ///
+    /// Captured labels are always lowercase.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.captures
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the captured `(name, label)` pairs, in pattern order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.captures
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+#[derive(Debug)]
+enum HostLabel {
+    Literal(String),
+    Capture(String),
+    Any,
+}
+
+#[derive(Debug)]
+enum HostMatcher {
+    Exact(String),
+    Wildcard {
+        labels: Vec<HostLabel>,
+        port: Option<u16>,
+    },
+}
+
+impl HostMatcher {
+    fn parse_wildcard(pattern: &str) -> Self {
+        let (host, port) = match pattern.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().unwrap_or_else(|_| {
+                    panic!("invalid port in wildcard host pattern: {:?}", pattern)
+                });
+                (host, Some(port))
+            }
+            None => (pattern, None),
+        };
+
+        let labels = host
+            .split('.')
+            .map(|label| {
+                if label == "*" {
+                    HostLabel::Any
+                } else if let Some(name) = label
+                    .strip_prefix('{')
+                    .and_then(|label| label.strip_suffix('}'))
+                {
+                    assert!(
+                        !name.is_empty(),
+                        "empty capture name in wildcard host pattern: {:?}",
+                        pattern
+                    );
+                    HostLabel::Capture(name.to_owned())
+                } else {
+                    assert!(
+                        !label.is_empty(),
+                        "empty label in wildcard host pattern: {:?}",
+                        pattern
+                    );
+                    HostLabel::Literal(label.to_ascii_lowercase())
+                }
+            })
+            .collect();
+
+        HostMatcher::Wildcard { labels, port }
+    }
+
+    /// Returns `None` if `uri` does not match, otherwise the labels captured from its host.
+    fn matches(&self, uri: &Uri) -> Option<HostCaptures> {
+        let uri_host = uri.host()?;
+
+        match self {
+            HostMatcher::Exact(host) => (host == uri_host).then(HostCaptures::default),
+
+            HostMatcher::Wildcard { labels, port } => {
+                if port.is_some() && *port != uri.port_u16() {
+                    return None;
+                }
+
+                let mut req_labels = uri_host.split('.');
+                let mut captures = HostCaptures::default();
+
+                for label in labels {
+                    let req_label = req_labels.next().filter(|label| !label.is_empty())?;
+
+                    match label {
+                        HostLabel::Literal(literal) => {
+                            if !literal.eq_ignore_ascii_case(req_label) {
+                                return None;
+                            }
+                        }
+                        HostLabel::Capture(name) => captures
+                            .captures
+                            .push((name.clone(), req_label.to_ascii_lowercase())),
+                        HostLabel::Any => {}
+                    }
+                }
+
+                // request's host has more labels than the pattern
+                if req_labels.next().is_some() {
+                    return None;
+                }
+
+                Some(captures)
+            }
+        }
+    }
+}
+
+/// Guard that matches requests targeting a specific host or family of hosts.
+///
+/// Construct a `HostGuard` using [`Host`] or [`HostGuard::wildcard`].
+#[derive(Debug)]
 pub struct HostGuard {
-    host: String,
+    host: HostMatcher,
     scheme: Option<String>,
 }
 
 impl HostGuard {
+    /// Creates a guard that matches hosts against a pattern of dot-separated labels.
+    ///
+    /// Each label of the pattern matches exactly one label of the request's host:
+    /// - `{name}` matches any label and captures it under `name`;
+    /// - `*` matches any label without capturing it;
+    /// - any other label must match exactly, ignoring ASCII case.
+    ///
+    /// The request's port is ignored unless the pattern ends with one (e.g., `*.example.com:8080`),
+    /// in which case the request must explicitly target that port. Scheme matching using
+    /// [`scheme`](Self::scheme) works the same as for [`Host`] guards.
+    ///
+    /// When the guard matches and the pattern contains named labels, a [`HostCaptures`] is inserted
+    /// into request-local data.
+    ///
+    /// # Panics
+    /// Panics if the pattern contains an empty label, an empty capture name, or an invalid port.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{guard::{HostCaptures, HostGuard}, web, HttpResponse};
+    ///
+    /// web::scope("")
+    ///     .guard(HostGuard::wildcard("{tenant}.example.com"))
+    ///     .default_service(web::to(|captures: web::ReqData<HostCaptures>| async move {
+    ///         HttpResponse::Ok().body(format!("tenant: {}", captures.get("tenant").unwrap()))
+    ///     }));
+    /// ```
+    pub fn wildcard(pattern: impl AsRef<str>) -> HostGuard {
+        HostGuard {
+            host: HostMatcher::parse_wildcard(pattern.as_ref()),
+            scheme: None,
+        }
+    }
+
     /// Set request scheme to match
     pub fn scheme<H: AsRef<str>>(mut self, scheme: H) -> HostGuard {
         self.scheme = Some(scheme.as_ref().to_string());
@@ -94,25 +262,31 @@ impl Guard for HostGuard {
             None => return false,
         };
 
-        match req_host_uri.host() {
+        let captures = match self.host.matches(&req_host_uri) {
             // fall through to scheme checks
-            Some(uri_host) if self.host == uri_host => {}
+            Some(captures) => captures,
 
             // Either:
             // - request's host does not match guard's host;
             // - It was possible that the parsed URI from request target did not contain a host.
-            _ => return false,
-        }
+            None => return false,
+        };
 
         if let Some(ref scheme) = self.scheme {
             if let Some(ref req_host_uri_scheme) = req_host_uri.scheme_str() {
-                return scheme == req_host_uri_scheme;
+                if scheme != req_host_uri_scheme {
+                    return false;
+                }
             }
 
             // TODO: is this the correct behavior?
             // falls through if scheme cannot be determined
         }
 
+        if !captures.captures.is_empty() {
+            ctx.req_data_mut().insert(captures);
+        }
+
         // all conditions passed
         true
     }
@@ -121,7 +295,7 @@ impl Guard for HostGuard {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test::TestRequest;
+    use crate::{test::TestRequest, HttpMessage as _};
 
     #[test]
     fn host_from_header() {
@@ -206,4 +380,72 @@ mod tests {
         let host = Host("localhost");
         assert!(!host.check(&req.guard_ctx()));
     }
+
+    fn host_req(host: &'static str) -> crate::dev::ServiceRequest {
+        TestRequest::default()
+            .insert_header((header::HOST, header::HeaderValue::from_static(host)))
+            .to_srv_request()
+    }
+
+    #[test]
+    fn wildcard_captures() {
+        let host = HostGuard::wildcard("{tenant}.Example.com");
+
+        let req = host_req("ACME.example.COM:8080");
+        assert!(host.check(&req.guard_ctx()));
+        let captures = req.extensions().get::<HostCaptures>().cloned().unwrap();
+        assert_eq!(captures.get("tenant"), Some("acme"));
+        assert_eq!(captures.iter().collect::<Vec<_>>(), [("tenant", "acme")]);
+
+        let req = host_req("example.com");
+        assert!(!host.check(&req.guard_ctx()));
+
+        let req = host_req("a.b.example.com");
+        assert!(!host.check(&req.guard_ctx()));
+        assert!(req.extensions().get::<HostCaptures>().is_none());
+
+        let req = host_req("acme.example.org");
+        assert!(!host.check(&req.guard_ctx()));
+
+        let host = HostGuard::wildcard("{region}.*.{tenant}.example.com");
+        let req = host_req("eu.api.acme.example.com");
+        assert!(host.check(&req.guard_ctx()));
+        let captures = req.extensions().get::<HostCaptures>().cloned().unwrap();
+        assert_eq!(captures.get("region"), Some("eu"));
+        assert_eq!(captures.get("tenant"), Some("acme"));
+        assert_eq!(captures.get("api"), None);
+
+        let host = HostGuard::wildcard("*.example.com");
+        let req = host_req("acme.example.com");
+        assert!(host.check(&req.guard_ctx()));
+        assert!(req.extensions().get::<HostCaptures>().is_none());
+    }
+
+    #[test]
+    fn wildcard_port_and_scheme() {
+        let host = HostGuard::wildcard("{tenant}.example.com:8080");
+        assert!(host.check(&host_req("acme.example.com:8080").guard_ctx()));
+        assert!(!host.check(&host_req("acme.example.com:8081").guard_ctx()));
+        assert!(!host.check(&host_req("acme.example.com").guard_ctx()));
+
+        let host = HostGuard::wildcard("{tenant}.example.com").scheme("https");
+        assert!(host.check(&host_req("https://acme.example.com").guard_ctx()));
+        assert!(host.check(&host_req("acme.example.com").guard_ctx()));
+
+        let req = host_req("http://acme.example.com");
+        assert!(!host.check(&req.guard_ctx()));
+        assert!(req.extensions().get::<HostCaptures>().is_none());
+    }
+
+    #[test]
+    #[should_panic = "invalid port"]
+    fn wildcard_invalid_port() {
+        HostGuard::wildcard("{tenant}.example.com:http");
+    }
+
+    #[test]
+    #[should_panic = "empty label"]
+    fn wildcard_empty_label() {
+        HostGuard::wildcard("{tenant}..example.com");
+    }
 }
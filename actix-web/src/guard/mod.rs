@@ -13,7 +13,9 @@
 //! Commonly-used guards are provided in this module as well as a way of creating a guard from a
 //! closure ([`fn_guard`]). The [`Not`], [`Any`], and [`All`] guards are noteworthy, as they can be
 //! used to compose other guards in a more flexible and semantic way than calling `.guard(...)` on
-//! services multiple times (which might have different combining behavior than you want).
+//! services multiple times (which might have different combining behavior than you want). When
+//! guards are assembled at runtime, the [`any`], [`all`], and [`not`] functions build the same
+//! combinators from collections of boxed guards.
 //!
 //! There are shortcuts for routes with method guards in the [`web`](crate::web) module:
 //! [`web::get()`](crate::web::get), [`web::post()`](crate::web::post), etc. The routes created by
@@ -60,7 +62,7 @@ mod acceptable;
 mod host;
 
 pub use self::acceptable::Acceptable;
-pub use self::host::{Host, HostGuard};
+pub use self::host::{Host, HostCaptures, HostGuard};
 
 /// Provides access to request parts that are useful during routing.
 #[derive(Debug)]
@@ -114,12 +116,35 @@ impl<'a> GuardContext<'a> {
 pub trait Guard {
     /// Returns true if predicate condition is met for a given request.
     fn check(&self, ctx: &GuardContext<'_>) -> bool;
+
+    /// Returns the method matched by this guard if it is a [method guard](Method).
+    ///
+    /// Used to determine the methods listed in the `Allow` header of `405 Method Not Allowed`
+    /// responses.
+    #[doc(hidden)]
+    fn registered_method(&self) -> Option<&HttpMethod> {
+        None
+    }
 }
 
 impl Guard for Rc<dyn Guard> {
     fn check(&self, ctx: &GuardContext<'_>) -> bool {
         (**self).check(ctx)
     }
+
+    fn registered_method(&self) -> Option<&HttpMethod> {
+        (**self).registered_method()
+    }
+}
+
+impl Guard for Box<dyn Guard> {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        (**self).check(ctx)
+    }
+
+    fn registered_method(&self) -> Option<&HttpMethod> {
+        (**self).registered_method()
+    }
 }
 
 /// Creates a guard using the given function.
@@ -181,6 +206,33 @@ pub fn Any<F: Guard + 'static>(guard: F) -> AnyGuard {
     }
 }
 
+/// Creates a guard that matches if any of the given guards match.
+///
+/// Guards are checked in order and checking stops at the first match. An empty collection never
+/// matches. The returned [`AnyGuard`] can be extended further using [`AnyGuard::or`].
+///
+/// This is useful when the set of guards is built programmatically, e.g., from configuration.
+///
+/// # Examples
+/// ```
+/// use actix_web::{guard::{self, Guard}, web, HttpResponse};
+///
+/// let hosts = ["www.rust-lang.org", "crates.io"];
+/// let guards = hosts.map(|host| Box::new(guard::Host(host)) as Box<dyn Guard>);
+///
+/// web::route()
+///     .guard(guard::any(guards))
+///     .to(|| HttpResponse::Ok());
+/// ```
+pub fn any<I>(guards: I) -> AnyGuard
+where
+    I: IntoIterator<Item = Box<dyn Guard>>,
+{
+    AnyGuard {
+        guards: guards.into_iter().collect(),
+    }
+}
+
 /// A collection of guards that match if the disjunction of their `check` outcomes is true.
 ///
 /// That is, only one contained guard needs to match in order for the aggregate guard to match.
@@ -233,6 +285,34 @@ pub fn All<F: Guard + 'static>(guard: F) -> AllGuard {
     }
 }
 
+/// Creates a guard that matches if all of the given guards match.
+///
+/// Guards are checked in order and checking stops at the first guard that does not match. An empty
+/// collection always matches. The returned [`AllGuard`] can be extended further using
+/// [`AllGuard::and`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{guard::{self, Guard}, web, HttpResponse};
+///
+/// let guards: Vec<Box<dyn Guard>> = vec![
+///     Box::new(guard::Get()),
+///     Box::new(guard::Header("accept", "text/plain")),
+/// ];
+///
+/// web::route()
+///     .guard(guard::all(guards))
+///     .to(|| HttpResponse::Ok());
+/// ```
+pub fn all<I>(guards: I) -> AllGuard
+where
+    I: IntoIterator<Item = Box<dyn Guard>>,
+{
+    AllGuard {
+        guards: guards.into_iter().collect(),
+    }
+}
+
 /// A collection of guards that match if the conjunction of their `check` outcomes is true.
 ///
 /// That is, **all** contained guard needs to match in order for the aggregate guard to match.
@@ -283,6 +363,25 @@ impl<G: Guard> Guard for Not<G> {
     }
 }
 
+/// Creates a guard that inverts the outcome of the given guard.
+///
+/// Equivalent to constructing [`Not`] directly; provided for symmetry with [`any`] and [`all`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{guard, web, HttpResponse};
+///
+/// web::route()
+///     .guard(guard::not(guard::any([
+///         Box::new(guard::Get()) as Box<dyn guard::Guard>,
+///         Box::new(guard::Head()),
+///     ])))
+///     .to(|| HttpResponse::Ok());
+/// ```
+pub fn not<G: Guard>(guard: G) -> Not<G> {
+    Not(guard)
+}
+
 /// Creates a guard that matches a specified HTTP method.
 #[allow(non_snake_case)]
 pub fn Method(method: HttpMethod) -> impl Guard {
@@ -310,6 +409,10 @@ impl Guard for MethodGuard {
 
         ctx.head().method == self.0
     }
+
+    fn registered_method(&self) -> Option<&HttpMethod> {
+        Some(&self.0)
+    }
 }
 
 macro_rules! method_guard {
@@ -400,6 +503,31 @@ mod tests {
         assert!(!hdr.check(&req.guard_ctx()));
     }
 
+    #[test]
+    fn collection_fns() {
+        let req = TestRequest::default()
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .to_srv_request();
+        let ctx = req.guard_ctx();
+
+        let matching = || -> Box<dyn Guard> { Box::new(Header("content-type", "text/plain")) };
+        let failing = || -> Box<dyn Guard> { Box::new(Post()) };
+
+        assert!(any([failing(), matching()]).check(&ctx));
+        assert!(!any([failing(), failing()]).check(&ctx));
+        assert!(!any(None).check(&ctx));
+        assert!(any([failing()]).or(Get()).check(&ctx));
+
+        assert!(all([matching(), Box::new(Get()) as Box<dyn Guard>]).check(&ctx));
+        assert!(!all([matching(), failing()]).check(&ctx));
+        assert!(all(None).check(&ctx));
+        assert!(!all([matching()]).and(Post()).check(&ctx));
+
+        assert!(not(all([failing()])).check(&ctx));
+        assert!(!not(matching()).check(&ctx));
+        assert!(any(vec![Box::new(not(failing())) as Box<dyn Guard>]).check(&ctx));
+    }
+
     #[test]
     fn method_guards() {
         let get_req = TestRequest::get().to_srv_request();
@@ -1,3 +1,5 @@
+use std::cmp::Reverse;
+
 use language_tags::LanguageTag;
 
 use super::{common_header, Preference, Quality, QualityItem};
@@ -139,6 +141,136 @@ impl AcceptLanguage {
 
         types.into_iter().map(|qitem| qitem.item).collect()
     }
+
+    /// Chooses the best of the `supported` languages, accounting for [q-factor weighting] and
+    /// [language ranges].
+    ///
+    /// Each supported language is given the quality of the most specific language range that
+    /// matches it, where a range matches a language if it is equal to, or a prefix of, it (e.g.,
+    /// `en` matches `en-US`) and the wildcard `*` matches any language. Languages whose quality is
+    /// zero are never chosen. If no range matches a supported language, it may still be chosen as
+    /// a fallback if it is a prefix of a range (e.g., range `en-GB` falls back to `en`), but only
+    /// if no language of the same quality matches directly.
+    ///
+    /// Among languages of equal quality, the one matching the range listed first in the header is
+    /// chosen, then the one listed first in `supported`. Returns the first supported language if
+    /// the header is empty, and `None` if no supported language is acceptable.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::AcceptLanguage;
+    ///
+    /// let supported = ["en-US".parse().unwrap(), "fr".parse().unwrap()];
+    ///
+    /// let header = AcceptLanguage(vec!["de-CH".parse().unwrap(), "en;q=0.9".parse().unwrap()]);
+    /// assert_eq!(header.negotiate(&supported).unwrap().as_str(), "en-US");
+    ///
+    /// let header = AcceptLanguage(vec!["de".parse().unwrap(), "*;q=0.1".parse().unwrap()]);
+    /// assert_eq!(header.negotiate(&supported).unwrap().as_str(), "en-US");
+    ///
+    /// let header = AcceptLanguage(vec!["de".parse().unwrap()]);
+    /// assert!(header.negotiate(&supported).is_none());
+    /// ```
+    ///
+    /// [q-factor weighting]: https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.2
+    /// [language ranges]: https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.1
+    pub fn negotiate(&self, supported: &[LanguageTag]) -> Option<LanguageTag> {
+        if self.0.is_empty() {
+            return supported.first().cloned();
+        }
+
+        let mut best: Option<(&LanguageTag, LanguageScore)> = None;
+
+        for lang in supported {
+            let score = match self.score(lang) {
+                Some(score) if score.quality > Quality::ZERO => score,
+                _ => continue,
+            };
+
+            // only change if strictly better so earlier supported languages win ties
+            if best.as_ref().map_or(true, |(_, best)| score > *best) {
+                best = Some((lang, score));
+            }
+        }
+
+        best.map(|(lang, _)| lang.clone())
+    }
+
+    fn score(&self, lang: &LanguageTag) -> Option<LanguageScore> {
+        // (specificity, score) of most specific matching range
+        let mut matched: Option<(usize, LanguageScore)> = None;
+
+        for (idx, pref) in self.0.iter().enumerate() {
+            let (specificity, kind) = match &pref.item {
+                Preference::Any => (0, MatchKind::Wildcard),
+                Preference::Specific(range) if range_matches(range.as_str(), lang.as_str()) => {
+                    (range.as_str().len(), MatchKind::Range)
+                }
+                Preference::Specific(_) => continue,
+            };
+
+            if matched.map_or(true, |(max, _)| specificity > max) {
+                matched = Some((specificity, LanguageScore::new(pref.quality, kind, idx)));
+            }
+        }
+
+        if let Some((_, score)) = matched {
+            return Some(score);
+        }
+
+        // fall back to languages that are a prefix of a range, using the best such range
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, pref)| match &pref.item {
+                Preference::Specific(range) if range_matches(lang.as_str(), range.as_str()) => {
+                    Some(LanguageScore::new(pref.quality, MatchKind::Fallback, idx))
+                }
+                _ => None,
+            })
+            .fold(None, |best: Option<LanguageScore>, score| match best {
+                Some(best) if best >= score => Some(best),
+                _ => Some(score),
+            })
+    }
+}
+
+/// How a supported language was matched, from least to most preferable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchKind {
+    Fallback,
+    Wildcard,
+    Range,
+}
+
+/// Orders supported languages by quality, then match kind, then position of matching range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct LanguageScore {
+    quality: Quality,
+    kind: MatchKind,
+    position: Reverse<usize>,
+}
+
+impl LanguageScore {
+    fn new(quality: Quality, kind: MatchKind, idx: usize) -> Self {
+        Self {
+            quality,
+            kind,
+            position: Reverse(idx),
+        }
+    }
+}
+
+/// Returns true if language `range` matches language `tag`, using basic filtering.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.1>.
+fn range_matches(range: &str, tag: &str) -> bool {
+    match tag.get(..range.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(range) => {
+            tag.len() == range.len() || tag.as_bytes()[range.len()] == b'-'
+        }
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -220,4 +352,55 @@ mod tests {
         let test = AcceptLanguage(vec![]);
         assert_eq!(test.preference(), Preference::Any);
     }
+
+    #[test]
+    fn negotiation() {
+        fn negotiate(header: &str, supported: &[&str]) -> Option<String> {
+            let req = crate::test::TestRequest::default()
+                .insert_header((ACCEPT_LANGUAGE, header))
+                .to_http_request();
+            let header = AcceptLanguage::parse(&req).unwrap();
+            let supported = supported
+                .iter()
+                .map(|lang| lang.parse().unwrap())
+                .collect::<Vec<_>>();
+
+            header
+                .negotiate(&supported)
+                .map(|lang| lang.as_str().to_owned())
+        }
+
+        let supported = ["en-US", "en-GB", "fr", "de-CH"];
+
+        assert_eq!(negotiate("", &supported).unwrap(), "en-US");
+        assert_eq!(negotiate("fr", &supported).unwrap(), "fr");
+        assert_eq!(
+            negotiate("es-MX, en-gb;q=0.5", &supported).unwrap(),
+            "en-GB"
+        );
+
+        // ranges match more specific tags
+        assert_eq!(negotiate("en", &supported).unwrap(), "en-US");
+        assert_eq!(negotiate("de", &supported).unwrap(), "de-CH");
+        assert!(negotiate("de-AT", &supported).is_none());
+
+        // equal quality prefers header order
+        assert_eq!(negotiate("fr, en", &supported).unwrap(), "fr");
+        assert_eq!(negotiate("en-GB, en-US", &supported).unwrap(), "en-GB");
+        assert_eq!(negotiate("en;q=0.5, fr;q=0.8", &supported).unwrap(), "fr");
+
+        // wildcards
+        assert_eq!(negotiate("es, *;q=0.1", &supported).unwrap(), "en-US");
+        assert_eq!(negotiate("*, en;q=0", &supported).unwrap(), "fr");
+        assert_eq!(negotiate("*, en-US;q=0", &supported).unwrap(), "en-GB");
+        assert!(negotiate("es", &supported).is_none());
+        assert!(negotiate("*;q=0", &supported).is_none());
+
+        // fallback to less specific supported languages
+        assert_eq!(negotiate("FR-ca, en-gb;q=0.5", &supported).unwrap(), "fr");
+        assert_eq!(negotiate("fr-CH, en;q=0.9", &supported).unwrap(), "fr");
+        assert_eq!(negotiate("fr-CH, en-US", &supported).unwrap(), "en-US");
+        assert_eq!(negotiate("fr-CH, *", &supported).unwrap(), "en-US");
+        assert_eq!(negotiate("en-AU", &["en", "en-GB"]).unwrap(), "en");
+    }
 }
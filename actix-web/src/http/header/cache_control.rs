@@ -44,6 +44,16 @@ common_header! {
     ///     CacheDirective::Extension("foo".to_owned(), Some("bar".to_owned())),
     /// ]));
     /// ```
+    ///
+    /// Common sets of directives can also be built fluently:
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::CacheControl;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// // Cache-Control: public, max-age=31536000, immutable
+    /// builder.insert_header(CacheControl::public().max_age(31_536_000).immutable());
+    /// ```
     (CacheControl, header::CACHE_CONTROL) => (CacheDirective)+
 
     test_parse_and_format {
@@ -78,6 +88,48 @@ common_header! {
             ]))
         );
 
+        common_header_test!(
+            extended_directives,
+            vec![
+                &b"public, max-age=600, immutable"[..],
+                &b"stale-while-revalidate=30, stale-if-error=86400"[..],
+            ],
+            Some(CacheControl(vec![
+                CacheDirective::Public,
+                CacheDirective::MaxAge(600),
+                CacheDirective::Extension("immutable".to_owned(), None),
+                CacheDirective::Extension("stale-while-revalidate".to_owned(), Some("30".to_owned())),
+                CacheDirective::Extension("stale-if-error".to_owned(), Some("86400".to_owned())),
+            ]))
+        );
+
+        #[test]
+        fn builder() {
+            let cache_control = CacheControl::public()
+                .max_age(3600)
+                .s_max_age(600)
+                .stale_while_revalidate(30)
+                .stale_if_error(60)
+                .immutable();
+
+            assert_eq!(
+                cache_control.to_string(),
+                "public, max-age=3600, s-maxage=600, stale-while-revalidate=30, \
+                 stale-if-error=60, immutable",
+            );
+
+            let req = test::TestRequest::default()
+                .insert_header((header::CACHE_CONTROL, cache_control.to_string()))
+                .finish();
+            assert_eq!(Header::parse(&req).ok(), Some(cache_control));
+
+            assert_eq!(CacheControl::no_store().to_string(), "no-store");
+            assert_eq!(
+                CacheControl::private().no_cache().must_revalidate().to_string(),
+                "private, no-cache, must-revalidate",
+            );
+        }
+
         #[test]
         fn parse_quote_form() {
             let req = test::TestRequest::default()
@@ -92,6 +144,88 @@ common_header! {
     }
 }
 
+impl CacheControl {
+    /// Constructs a `Cache-Control: public` header; more directives can be chained on.
+    pub fn public() -> Self {
+        CacheControl(vec![CacheDirective::Public])
+    }
+
+    /// Constructs a `Cache-Control: private` header; more directives can be chained on.
+    pub fn private() -> Self {
+        CacheControl(vec![CacheDirective::Private])
+    }
+
+    /// Constructs a `Cache-Control: no-store` header; more directives can be chained on.
+    pub fn no_store() -> Self {
+        CacheControl(vec![CacheDirective::NoStore])
+    }
+
+    /// Appends a directive.
+    pub fn directive(mut self, directive: CacheDirective) -> Self {
+        self.0.push(directive);
+        self
+    }
+
+    /// Appends a `no-cache` directive.
+    pub fn no_cache(self) -> Self {
+        self.directive(CacheDirective::NoCache)
+    }
+
+    /// Appends a `no-transform` directive.
+    pub fn no_transform(self) -> Self {
+        self.directive(CacheDirective::NoTransform)
+    }
+
+    /// Appends a `max-age` directive with the given number of seconds.
+    pub fn max_age(self, secs: u32) -> Self {
+        self.directive(CacheDirective::MaxAge(secs))
+    }
+
+    /// Appends an `s-maxage` directive with the given number of seconds.
+    pub fn s_max_age(self, secs: u32) -> Self {
+        self.directive(CacheDirective::SMaxAge(secs))
+    }
+
+    /// Appends a `must-revalidate` directive.
+    pub fn must_revalidate(self) -> Self {
+        self.directive(CacheDirective::MustRevalidate)
+    }
+
+    /// Appends a `proxy-revalidate` directive.
+    pub fn proxy_revalidate(self) -> Self {
+        self.directive(CacheDirective::ProxyRevalidate)
+    }
+
+    /// Appends an `immutable` extension directive, defined in [RFC 8246].
+    ///
+    /// [RFC 8246]: https://datatracker.ietf.org/doc/html/rfc8246
+    pub fn immutable(self) -> Self {
+        self.directive(CacheDirective::Extension("immutable".to_owned(), None))
+    }
+
+    /// Appends a `stale-while-revalidate` extension directive with the given number of seconds,
+    /// defined in [RFC 5861].
+    ///
+    /// [RFC 5861]: https://datatracker.ietf.org/doc/html/rfc5861
+    pub fn stale_while_revalidate(self, secs: u32) -> Self {
+        self.directive(CacheDirective::Extension(
+            "stale-while-revalidate".to_owned(),
+            Some(secs.to_string()),
+        ))
+    }
+
+    /// Appends a `stale-if-error` extension directive with the given number of seconds, defined
+    /// in [RFC 5861].
+    ///
+    /// [RFC 5861]: https://datatracker.ietf.org/doc/html/rfc5861
+    pub fn stale_if_error(self, secs: u32) -> Self {
+        self.directive(CacheDirective::Extension(
+            "stale-if-error".to_owned(),
+            Some(secs.to_string()),
+        ))
+    }
+}
+
 /// `CacheControl` contains a list of these directives.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CacheDirective {
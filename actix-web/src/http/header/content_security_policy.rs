@@ -0,0 +1,361 @@
+//! The `Content-Security-Policy` header and associated types.
+//!
+//! # References
+//! - "Content Security Policy Level 3": <https://www.w3.org/TR/CSP3>
+//! - MDN: <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy>
+
+use std::fmt::{self, Write as _};
+
+use super::{Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer};
+use crate::{error::ParseError, http::header, HttpMessage};
+
+/// A single policy directive in a [`ContentSecurityPolicy`].
+///
+/// Consists of a directive name (e.g., `script-src`) and a, possibly empty, list of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CspDirective {
+    name: String,
+    values: Vec<String>,
+}
+
+impl CspDirective {
+    /// Constructs new directive from a name and its values.
+    ///
+    /// Directive names are case-insensitive and are stored in lowercase.
+    pub fn new<I, V>(name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<String>,
+    {
+        let mut name = name.into();
+        name.make_ascii_lowercase();
+
+        Self {
+            name,
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the directive name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the directive values (e.g., source expressions).
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+}
+
+impl fmt::Display for CspDirective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        for value in &self.values {
+            write!(f, " {}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `Content-Security-Policy` header, defined in [CSP Level 3].
+///
+/// Allows servers to control the resources a user agent is allowed to load for a given page.
+///
+/// Directives are constructed using the builder methods on this type. Setting a directive that is
+/// already present replaces its values, except [`script_nonce`](Self::script_nonce) which appends
+/// to the `script-src` directive.
+///
+/// # ABNF
+/// ```text
+/// Content-Security-Policy = 1#serialized-policy
+/// serialized-policy       = serialized-directive *( OWS ";" [ OWS serialized-directive ] )
+/// serialized-directive    = directive-name [ RWS directive-value ]
+/// ```
+///
+/// # Example Values
+/// - `default-src 'self'`
+/// - `default-src 'self'; img-src *; script-src 'nonce-2726c7f26c'`
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::ContentSecurityPolicy, HttpResponse};
+///
+/// let csp = ContentSecurityPolicy::new()
+///     .default_src(["'self'"])
+///     .img_src(["'self'", "https://img.example.com"])
+///     .script_nonce("2726c7f26c")
+///     .report_uri("/csp-reports");
+///
+/// assert_eq!(
+///     csp.to_string(),
+///     "default-src 'self'; img-src 'self' https://img.example.com; \
+///      script-src 'nonce-2726c7f26c'; report-uri /csp-reports",
+/// );
+///
+/// let res = HttpResponse::Ok().insert_header(csp).finish();
+/// ```
+///
+/// [CSP Level 3]: https://www.w3.org/TR/CSP3/#csp-header
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<CspDirective>,
+}
+
+macro_rules! source_list_directives {
+    ($($(#[$meta:meta])* $method:ident => $name:literal,)+) => {
+        $(
+            $(#[$meta])*
+            pub fn $method<I, V>(self, sources: I) -> Self
+            where
+                I: IntoIterator<Item = V>,
+                V: Into<String>,
+            {
+                self.directive($name, sources)
+            }
+        )+
+    };
+}
+
+impl ContentSecurityPolicy {
+    /// Constructs new, empty policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a directive with the given name and values, replacing any existing directive with the
+    /// same name.
+    pub fn directive<I, V>(mut self, name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<String>,
+    {
+        let directive = CspDirective::new(name, values);
+
+        match self
+            .directives
+            .iter_mut()
+            .find(|existing| existing.name == directive.name)
+        {
+            Some(existing) => *existing = directive,
+            None => self.directives.push(directive),
+        }
+
+        self
+    }
+
+    source_list_directives! {
+        /// Sets the `default-src` directive.
+        default_src => "default-src",
+        /// Sets the `script-src` directive.
+        script_src => "script-src",
+        /// Sets the `style-src` directive.
+        style_src => "style-src",
+        /// Sets the `img-src` directive.
+        img_src => "img-src",
+        /// Sets the `connect-src` directive.
+        connect_src => "connect-src",
+        /// Sets the `font-src` directive.
+        font_src => "font-src",
+        /// Sets the `object-src` directive.
+        object_src => "object-src",
+        /// Sets the `media-src` directive.
+        media_src => "media-src",
+        /// Sets the `frame-src` directive.
+        frame_src => "frame-src",
+        /// Sets the `worker-src` directive.
+        worker_src => "worker-src",
+        /// Sets the `frame-ancestors` directive.
+        frame_ancestors => "frame-ancestors",
+        /// Sets the `base-uri` directive.
+        base_uri => "base-uri",
+        /// Sets the `form-action` directive.
+        form_action => "form-action",
+    }
+
+    /// Adds a `'nonce-<nonce>'` source to the `script-src` directive, creating the directive if
+    /// it does not exist.
+    pub fn script_nonce(mut self, nonce: impl fmt::Display) -> Self {
+        let source = format!("'nonce-{}'", nonce);
+
+        match self
+            .directives
+            .iter_mut()
+            .find(|directive| directive.name == "script-src")
+        {
+            Some(directive) => directive.values.push(source),
+            None => self
+                .directives
+                .push(CspDirective::new("script-src", [source])),
+        }
+
+        self
+    }
+
+    /// Sets the `report-uri` directive.
+    pub fn report_uri(self, uri: impl Into<String>) -> Self {
+        self.directive("report-uri", [uri])
+    }
+
+    /// Sets the `report-to` directive.
+    pub fn report_to(self, group: impl Into<String>) -> Self {
+        self.directive("report-to", [group])
+    }
+
+    /// Sets the `upgrade-insecure-requests` directive.
+    pub fn upgrade_insecure_requests(self) -> Self {
+        self.directive("upgrade-insecure-requests", None::<String>)
+    }
+
+    /// Returns the values of the directive with the given name, if it is present.
+    ///
+    /// Directive names are matched case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&[String]> {
+        self.directives
+            .iter()
+            .find(|directive| directive.name.eq_ignore_ascii_case(name))
+            .map(CspDirective::values)
+    }
+
+    /// Returns an iterator over the policy's directives, in order.
+    pub fn directives(&self) -> impl Iterator<Item = &CspDirective> {
+        self.directives.iter()
+    }
+
+    /// Parses a policy from a raw header value.
+    ///
+    /// As per the spec, empty directives are skipped and only the first occurrence of a repeated
+    /// directive is kept.
+    pub fn from_raw(hv: &HeaderValue) -> Result<Self, ParseError> {
+        let hv = hv.to_str().map_err(|_| ParseError::Header)?;
+
+        let mut csp = ContentSecurityPolicy::new();
+
+        for directive in hv.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+
+            let name = match tokens.next() {
+                Some(name) => name.to_ascii_lowercase(),
+                None => continue,
+            };
+
+            if csp.get(&name).is_none() {
+                csp.directives.push(CspDirective::new(name, tokens));
+            }
+        }
+
+        if csp.directives.is_empty() {
+            return Err(ParseError::Header);
+        }
+
+        Ok(csp)
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut iter = self.directives.iter();
+
+        if let Some(directive) = iter.next() {
+            write!(f, "{}", directive)?;
+        }
+
+        iter.try_for_each(|directive| write!(f, "; {}", directive))
+    }
+}
+
+impl TryIntoHeaderValue for ContentSecurityPolicy {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for ContentSecurityPolicy {
+    fn name() -> HeaderName {
+        header::CONTENT_SECURITY_POLICY
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        match msg.headers().get(Self::name()) {
+            Some(hv) => Self::from_raw(hv),
+            None => Err(ParseError::Header),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_replaces_directives() {
+        let csp = ContentSecurityPolicy::new()
+            .default_src(["'self'"])
+            .script_src(["'self'"])
+            .default_src(["'none'"])
+            .upgrade_insecure_requests();
+
+        assert_eq!(
+            csp.to_string(),
+            "default-src 'none'; script-src 'self'; upgrade-insecure-requests"
+        );
+        assert_eq!(csp.get("DEFAULT-SRC").unwrap(), ["'none'"]);
+        assert!(csp.get("img-src").is_none());
+    }
+
+    #[test]
+    fn script_nonce_appends() {
+        let csp = ContentSecurityPolicy::new()
+            .script_src(["'strict-dynamic'"])
+            .script_nonce("abc");
+        assert_eq!(csp.to_string(), "script-src 'strict-dynamic' 'nonce-abc'");
+
+        let csp = ContentSecurityPolicy::new().script_nonce("abc");
+        assert_eq!(csp.to_string(), "script-src 'nonce-abc'");
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let raw = "default-src 'self'; img-src 'self' https://img.example.com; \
+                   script-src 'nonce-abc'; report-uri /csp; upgrade-insecure-requests";
+        let csp = ContentSecurityPolicy::from_raw(&HeaderValue::from_static(raw)).unwrap();
+
+        assert_eq!(
+            csp.get("img-src").unwrap(),
+            ["'self'", "https://img.example.com"]
+        );
+        assert_eq!(
+            csp.get("upgrade-insecure-requests").unwrap(),
+            [] as [String; 0]
+        );
+        assert_eq!(csp.to_string(), raw);
+
+        let hv = csp.clone().try_into_value().unwrap();
+        assert_eq!(ContentSecurityPolicy::from_raw(&hv).unwrap(), csp);
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let hv = HeaderValue::from_static(" ;Default-Src  'self' ;; default-src 'none';");
+        let csp = ContentSecurityPolicy::from_raw(&hv).unwrap();
+        assert_eq!(csp.to_string(), "default-src 'self'");
+
+        let hv = HeaderValue::from_static(" ; ");
+        assert!(ContentSecurityPolicy::from_raw(&hv).is_err());
+    }
+
+    #[test]
+    fn parse_from_request() {
+        let req = crate::test::TestRequest::default()
+            .insert_header((header::CONTENT_SECURITY_POLICY, "default-src 'self'"))
+            .to_http_request();
+
+        let csp = ContentSecurityPolicy::parse(&req).unwrap();
+        assert_eq!(csp, ContentSecurityPolicy::new().default_src(["'self'"]));
+    }
+}
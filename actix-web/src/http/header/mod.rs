@@ -26,6 +26,7 @@ mod cache_control;
 mod content_disposition;
 mod content_language;
 mod content_range;
+mod content_security_policy;
 mod content_type;
 mod date;
 mod encoding;
@@ -41,6 +42,7 @@ mod last_modified;
 mod macros;
 mod preference;
 mod range;
+mod retry_after;
 
 #[cfg(test)]
 pub(crate) use macros::common_header_test;
@@ -55,6 +57,7 @@ pub use self::cache_control::{CacheControl, CacheDirective};
 pub use self::content_disposition::{ContentDisposition, DispositionParam, DispositionType};
 pub use self::content_language::ContentLanguage;
 pub use self::content_range::{ContentRange, ContentRangeSpec};
+pub use self::content_security_policy::{ContentSecurityPolicy, CspDirective};
 pub use self::content_type::ContentType;
 pub use self::date::Date;
 pub use self::encoding::Encoding;
@@ -69,6 +72,7 @@ pub use self::if_unmodified_since::IfUnmodifiedSince;
 pub use self::last_modified::LastModified;
 pub use self::preference::Preference;
 pub use self::range::{ByteRangeSpec, Range};
+pub use self::retry_after::RetryAfter;
 
 /// Format writer ([`fmt::Write`]) for a [`BytesMut`].
 #[derive(Debug, Default)]
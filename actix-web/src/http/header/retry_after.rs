@@ -0,0 +1,151 @@
+use std::{
+    fmt::{self, Display, Write},
+    time::Duration,
+};
+
+use super::{
+    from_one_raw_str, Header, HeaderName, HeaderValue, HttpDate, InvalidHeaderValue,
+    TryIntoHeaderValue, Writer,
+};
+use crate::error::ParseError;
+use crate::http::header;
+use crate::HttpMessage;
+
+/// `Retry-After` header, defined
+/// in [RFC 7231 §7.1.3](https://datatracker.ietf.org/doc/html/rfc7231#section-7.1.3)
+///
+/// The `Retry-After` header field indicates how long the user agent ought to wait before making a
+/// follow-up request. It is usually sent with `503 Service Unavailable` or `429 Too Many Requests`
+/// responses, to indicate how long the service is expected to be unavailable, and with `3xx`
+/// responses, to indicate the minimum time to wait before issuing the redirected request.
+///
+/// When parsing, a delay in seconds is tried first, then an HTTP-date. Delays are sent with
+/// whole-second precision; any fractional part is discarded.
+///
+/// # ABNF
+/// ```plain
+/// Retry-After = HTTP-date / delay-seconds
+/// delay-seconds = 1*DIGIT
+/// ```
+///
+/// # Example Values
+///
+/// * `Fri, 31 Dec 1999 23:59:59 GMT`
+/// * `120`
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{http::header::RetryAfter, HttpResponse};
+///
+/// let mut builder = HttpResponse::TooManyRequests();
+/// builder.insert_header(
+///     RetryAfter::Delay(Duration::from_secs(120))
+/// );
+/// ```
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use actix_web::{http::header::RetryAfter, HttpResponse};
+///
+/// let mut builder = HttpResponse::ServiceUnavailable();
+/// let back_online = SystemTime::now() + Duration::from_secs(60 * 60);
+/// builder.insert_header(
+///     RetryAfter::Date(back_online.into())
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryAfter {
+    /// The time to wait after the response is received.
+    Delay(Duration),
+
+    /// The date after which to retry.
+    Date(HttpDate),
+}
+
+impl Header for RetryAfter {
+    fn name() -> HeaderName {
+        header::RETRY_AFTER
+    }
+
+    #[inline]
+    fn parse<T>(msg: &T) -> Result<Self, ParseError>
+    where
+        T: HttpMessage,
+    {
+        let secs: Result<u64, _> = from_one_raw_str(msg.headers().get(&header::RETRY_AFTER));
+        if let Ok(secs) = secs {
+            return Ok(RetryAfter::Delay(Duration::from_secs(secs)));
+        }
+        let date: Result<HttpDate, _> =
+            from_one_raw_str(msg.headers().get(&header::RETRY_AFTER));
+        if let Ok(date) = date {
+            return Ok(RetryAfter::Date(date));
+        }
+        Err(ParseError::Header)
+    }
+}
+
+impl Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RetryAfter::Delay(ref x) => Display::fmt(&x.as_secs(), f),
+            RetryAfter::Date(ref x) => Display::fmt(x, f),
+        }
+    }
+}
+
+impl TryIntoHeaderValue for RetryAfter {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+#[cfg(test)]
+mod test_parse_and_format {
+    use std::{
+        str,
+        time::{Duration, SystemTime},
+    };
+
+    use super::RetryAfter as HeaderField;
+    use crate::http::header::*;
+
+    crate::http::header::common_header_test!(test1, [b"Fri, 31 Dec 1999 23:59:59 GMT"]);
+    crate::http::header::common_header_test!(test2, [b"120"]);
+    crate::http::header::common_header_test!(test3, vec![b"-1"], None::<RetryAfter>);
+    crate::http::header::common_header_test!(test4, vec![b"soon"], None::<RetryAfter>);
+
+    #[test]
+    fn parsed_variants() {
+        let req = crate::test::TestRequest::default()
+            .insert_header((RETRY_AFTER, "120"))
+            .to_http_request();
+        assert_eq!(
+            RetryAfter::parse(&req).unwrap(),
+            RetryAfter::Delay(Duration::from_secs(120))
+        );
+
+        let req = crate::test::TestRequest::default()
+            .insert_header((RETRY_AFTER, "Thu, 01 Jan 1970 00:02:00 GMT"))
+            .to_http_request();
+        assert_eq!(
+            RetryAfter::parse(&req).unwrap(),
+            RetryAfter::Date(HttpDate::from(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(120)
+            ))
+        );
+    }
+
+    #[test]
+    fn delay_precision() {
+        let value = RetryAfter::Delay(Duration::from_millis(1500))
+            .try_into_value()
+            .unwrap();
+        assert_eq!(value, "1");
+    }
+}
@@ -1,4 +1,7 @@
-use std::{convert::Infallible, net::SocketAddr};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
 
 use actix_utils::future::{err, ok, Ready};
 use derive_more::{Display, Error};
@@ -16,9 +19,84 @@ static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
 static X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
 static X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
 
-/// Trim whitespace then any quote marks.
-fn unquote(val: &str) -> &str {
-    val.trim().trim_start_matches('"').trim_end_matches('"')
+/// Splits `val` on `sep`, ignoring separators inside quoted strings.
+fn split_unquoted(val: &str, sep: char) -> impl Iterator<Item = &str> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    val.split(move |ch| {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch == sep && !in_quotes {
+            return true;
+        }
+
+        false
+    })
+}
+
+/// Trims whitespace and, if quoted, removes quote marks and escapes from a parameter value.
+fn unquote(val: &str) -> String {
+    let val = val.trim();
+
+    let quoted = match val.strip_prefix('"') {
+        Some(quoted) => quoted,
+        None => return val.to_owned(),
+    };
+
+    let mut unquoted = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => break,
+            '\\' => unquoted.extend(chars.next()),
+            _ => unquoted.push(ch),
+        }
+    }
+
+    unquoted
+}
+
+/// Parses the elements of all `Forwarded` headers.
+fn parse_forwarded(req: &RequestHead) -> Vec<ForwardedElement> {
+    req.headers
+        .get_all(&header::FORWARDED)
+        .filter_map(|hdr| hdr.to_str().ok())
+        // "for=1.2.3.4;proto=https, for=5.6.7.8"
+        .flat_map(|val| split_unquoted(val, ','))
+        // ["for=1.2.3.4;proto=https", " for=5.6.7.8"]
+        .filter_map(|element| {
+            let mut parsed = ForwardedElement::default();
+            let mut is_empty = true;
+
+            for pair in split_unquoted(element, ';') {
+                let mut items = pair.splitn(2, '=');
+
+                let (name, val) = match (items.next(), items.next()) {
+                    (Some(name), Some(val)) => (name.trim(), unquote(val)),
+                    _ => continue,
+                };
+
+                let field = match name.to_lowercase().as_str() {
+                    "for" => &mut parsed.for_,
+                    "by" => &mut parsed.by,
+                    "host" => &mut parsed.host,
+                    "proto" => &mut parsed.proto,
+                    _ => continue,
+                };
+
+                field.get_or_insert(val);
+                is_empty = false;
+            }
+
+            (!is_empty).then(|| parsed)
+        })
+        .collect()
 }
 
 /// Extracts and trims first value for given header name.
@@ -28,6 +106,98 @@ fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<
     Some(val)
 }
 
+/// Set of trusted proxy addresses, configured with
+/// [`HttpServer::trusted_proxies`](crate::HttpServer::trusted_proxies).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TrustedProxies(Vec<IpCidr>);
+
+impl TrustedProxies {
+    /// Parses list of IP addresses and CIDR ranges, such as `10.0.0.0/8` and `::1`.
+    pub(crate) fn parse<I>(proxies: I) -> Result<Self, String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        proxies
+            .into_iter()
+            .map(|proxy| IpCidr::parse(proxy.as_ref().trim()))
+            .collect::<Result<_, _>>()
+            .map(TrustedProxies)
+    }
+
+    /// Returns true if `ip` is in one of the trusted ranges.
+    pub(crate) fn contains(&self, ip: IpAddr) -> bool {
+        let ip = canonical_ip(ip);
+        self.0.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    /// Returns true if `node` is a trusted proxy's IP address.
+    fn contains_node(&self, node: &str) -> bool {
+        node_ip(node).map_or(false, |ip| self.contains(ip))
+    }
+}
+
+/// IP address range in CIDR notation.
+#[derive(Debug, Clone, Copy)]
+struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    fn parse(cidr: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid trusted proxy address or range: {:?}", cidr);
+
+        let mut parts = cidr.splitn(2, '/');
+        let addr = parts.next().unwrap_or_default();
+        let addr = canonical_ip(addr.parse::<IpAddr>().map_err(|_| invalid())?);
+
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+
+        let prefix_len = match parts.next() {
+            Some(len) => len.parse::<u8>().map_err(|_| invalid())?,
+            None => max_len,
+        };
+
+        if prefix_len > max_len {
+            return Err(invalid());
+        }
+
+        Ok(IpCidr { addr, prefix_len })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Converts IPv4-mapped IPv6 addresses, as reported by dual-stack sockets, to IPv4 addresses.
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                IpAddr::V4((u32::from(hi) << 16 | u32::from(lo)).into())
+            }
+            _ => ip,
+        },
+        ip => ip,
+    }
+}
+
 /// HTTP connection information.
 ///
 /// `ConnectionInfo` implements `FromRequest` and can be extracted in handlers.
@@ -51,7 +221,8 @@ fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<
 /// Parses `Forwarded` header information according to [RFC 7239][rfc7239] but does not try to
 /// interpret the values for each property. As such, the getter methods on `ConnectionInfo` return
 /// strings instead of IP addresses or other types to acknowledge that they may be
-/// [obfuscated][rfc7239-63] or [unknown][rfc7239-62].
+/// [obfuscated][rfc7239-63] or [unknown][rfc7239-62]. All proxy hops listed in `Forwarded` headers
+/// are available from [`forwarded`](Self::forwarded).
 ///
 /// If the older, related headers are also present (eg. `X-Forwarded-For`), then `Forwarded`
 /// is preferred.
@@ -65,69 +236,110 @@ pub struct ConnectionInfo {
     scheme: String,
     peer_addr: Option<String>,
     realip_remote_addr: Option<String>,
+    forwarded: Vec<ForwardedElement>,
 }
 
 impl ConnectionInfo {
     pub(crate) fn new(req: &RequestHead, cfg: &AppConfig) -> ConnectionInfo {
+        let trusted_proxies = cfg.trusted_proxies();
+
+        // when trusted proxies are configured, forwarding headers are only honored for requests
+        // made by one of them
+        let is_forwarded_trusted = match trusted_proxies {
+            Some(proxies) => req
+                .peer_addr
+                .map_or(false, |addr| proxies.contains(addr.ip())),
+            None => true,
+        };
+
         let mut host = None;
         let mut scheme = None;
-        let mut realip_remote_addr = None;
-
-        for (name, val) in req
-            .headers
-            .get_all(&header::FORWARDED)
-            .filter_map(|hdr| hdr.to_str().ok())
-            // "for=1.2.3.4, for=5.6.7.8; scheme=https"
-            .flat_map(|val| val.split(';'))
-            // ["for=1.2.3.4, for=5.6.7.8", " scheme=https"]
-            .flat_map(|vals| vals.split(','))
-            // ["for=1.2.3.4", " for=5.6.7.8", " scheme=https"]
-            .flat_map(|pair| {
-                let mut items = pair.trim().splitn(2, '=');
-                Some((items.next()?, items.next()?))
-            })
-        {
-            // [(name , val      ), ...                                    ]
-            // [("for", "1.2.3.4"), ("for", "5.6.7.8"), ("scheme", "https")]
-
-            // taking the first value for each property is correct because spec states that first
-            // "for" value is client and rest are proxies; multiple values other properties have
-            // no defined semantics
-            //
-            // > In a chain of proxy servers where this is fully utilized, the first
-            // > "for" parameter will disclose the client where the request was first
-            // > made, followed by any subsequent proxy identifiers.
-            // --- https://datatracker.ietf.org/doc/html/rfc7239#section-5.2
-
-            match name.trim().to_lowercase().as_str() {
-                "for" => realip_remote_addr.get_or_insert_with(|| unquote(val)),
-                "proto" => scheme.get_or_insert_with(|| unquote(val)),
-                "host" => host.get_or_insert_with(|| unquote(val)),
-                "by" => {
-                    // TODO: implement https://datatracker.ietf.org/doc/html/rfc7239#section-5.1
-                    continue;
+
+        let forwarded = parse_forwarded(req);
+
+        // elements that identify a node, ordered from client to closest proxy
+        let forwarded_for = forwarded
+            .iter()
+            .filter(|element| element.for_.is_some())
+            .collect::<Vec<_>>();
+        let forwarded_nodes = forwarded_for
+            .iter()
+            .filter_map(|element| element.for_.as_deref())
+            .collect::<Vec<_>>();
+
+        if is_forwarded_trusted {
+            match trusted_proxies {
+                // host and proto are only meaningful alongside the client node they were sent
+                // with; when no element identifies a node, the closest proxy's element is used
+                Some(_) => {
+                    let element = match client_index(&forwarded_nodes, trusted_proxies) {
+                        Some(idx) => forwarded_for.get(idx).copied(),
+                        None => forwarded.last(),
+                    };
+
+                    if let Some(element) = element {
+                        scheme = element.proto.as_deref();
+                        host = element.host.as_deref();
+                    }
                 }
-                _ => continue,
-            };
+
+                // taking the first value for each property is correct because spec states that
+                // first "for" value is client and rest are proxies; multiple values other
+                // properties have no defined semantics
+                //
+                // > In a chain of proxy servers where this is fully utilized, the first
+                // > "for" parameter will disclose the client where the request was first
+                // > made, followed by any subsequent proxy identifiers.
+                // --- https://datatracker.ietf.org/doc/html/rfc7239#section-5.2
+                None => {
+                    for element in &forwarded {
+                        scheme = scheme.or(element.proto.as_deref());
+                        host = host.or(element.host.as_deref());
+                    }
+                }
+            }
         }
 
+        let forwarded_header = |name| {
+            Some(())
+                .filter(|_| is_forwarded_trusted)
+                .and_then(|_| first_header_value(req, name))
+        };
+
         let scheme = scheme
-            .or_else(|| first_header_value(req, &X_FORWARDED_PROTO))
+            .or_else(|| forwarded_header(&X_FORWARDED_PROTO))
             .or_else(|| req.uri.scheme().map(Scheme::as_str))
             .or_else(|| Some("https").filter(|_| cfg.secure()))
             .unwrap_or("http")
             .to_owned();
 
         let host = host
-            .or_else(|| first_header_value(req, &X_FORWARDED_HOST))
+            .or_else(|| forwarded_header(&X_FORWARDED_HOST))
             .or_else(|| req.headers.get(&header::HOST)?.to_str().ok())
             .or_else(|| req.uri.authority().map(Authority::as_str))
             .unwrap_or_else(|| cfg.host())
             .to_owned();
 
-        let realip_remote_addr = realip_remote_addr
-            .or_else(|| first_header_value(req, &X_FORWARDED_FOR))
-            .map(str::to_owned);
+        let realip_remote_addr = if is_forwarded_trusted {
+            let mut nodes = forwarded_nodes;
+
+            if nodes.is_empty() {
+                // proxies may append a new header line instead of extending the existing one
+                nodes = req
+                    .headers
+                    .get_all(&X_FORWARDED_FOR)
+                    .filter_map(|hdr| hdr.to_str().ok())
+                    .flat_map(|val| val.split(','))
+                    .map(str::trim)
+                    .collect();
+            }
+
+            client_index(&nodes, trusted_proxies).map(|idx| nodes[idx])
+        } else {
+            None
+        };
+
+        let realip_remote_addr = realip_remote_addr.map(str::to_owned);
 
         let peer_addr = req.peer_addr.map(|addr| addr.ip().to_string());
 
@@ -136,9 +348,40 @@ impl ConnectionInfo {
             scheme,
             peer_addr,
             realip_remote_addr,
+            forwarded,
         }
     }
 
+    /// Elements of the request's `Forwarded` headers, in order.
+    ///
+    /// Each element describes one hop in the chain of proxies the request passed through. The first
+    /// element is added by the proxy closest to the client, and the last by the proxy closest to
+    /// this server. Elements from multiple `Forwarded` headers are combined in header order.
+    ///
+    /// # Security
+    /// Clients can send `Forwarded` headers themselves. Only elements appended by proxies you trust
+    /// (i.e., at the end of the list) should be relied upon, e.g., for IP allow-listing.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::test::TestRequest;
+    /// let req = TestRequest::default()
+    ///     .insert_header(("forwarded", r#"for=192.0.2.60;proto=https, for="[2001:db8::17]:4711""#))
+    ///     .to_http_request();
+    /// let conn = req.connection_info();
+    /// let forwarded = conn.forwarded();
+    ///
+    /// assert_eq!(forwarded.len(), 2);
+    /// assert_eq!(forwarded[0].forwarded_for(), Some("192.0.2.60"));
+    /// assert_eq!(forwarded[0].proto(), Some("https"));
+    /// assert_eq!(forwarded[1].forwarded_for(), Some("[2001:db8::17]:4711"));
+    /// assert_eq!(forwarded[1].for_ip(), Some("2001:db8::17".parse().unwrap()));
+    /// ```
+    #[inline]
+    pub fn forwarded(&self) -> &[ForwardedElement] {
+        &self.forwarded
+    }
+
     /// Real IP (remote address) of client that initiated request.
     ///
     /// The address is resolved through the following, in order:
@@ -148,8 +391,10 @@ impl ConnectionInfo {
     ///
     /// # Security
     /// Do not use this function for security purposes unless you can be sure that the `Forwarded`
-    /// and `X-Forwarded-For` headers cannot be spoofed by the client. If you are running without a
-    /// proxy then [obtaining the peer address](Self::peer_addr) would be more appropriate.
+    /// and `X-Forwarded-For` headers cannot be spoofed by the client. Configure
+    /// [`HttpServer::trusted_proxies`](crate::HttpServer::trusted_proxies) to only honor these
+    /// headers when they were added by your proxies. If you are running without a proxy then
+    /// [obtaining the peer address](Self::peer_addr) would be more appropriate.
     #[inline]
     pub fn realip_remote_addr(&self) -> Option<&str> {
         self.realip_remote_addr
@@ -196,6 +441,92 @@ impl ConnectionInfo {
     }
 }
 
+/// A single element of a `Forwarded` header, describing one proxy hop.
+///
+/// Values are unquoted but otherwise uninterpreted. Node identifiers (the `for` and `by` values)
+/// can be IP addresses, optionally with a port, or [obfuscated][rfc7239-63] or
+/// [unknown][rfc7239-62] identifiers. See [`ConnectionInfo::forwarded`].
+///
+/// [rfc7239-62]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.2
+/// [rfc7239-63]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.3
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForwardedElement {
+    for_: Option<String>,
+    by: Option<String>,
+    host: Option<String>,
+    proto: Option<String>,
+}
+
+impl ForwardedElement {
+    /// Node that made the request to the proxy; the `for` parameter.
+    pub fn forwarded_for(&self) -> Option<&str> {
+        self.for_.as_deref()
+    }
+
+    /// Interface on which the proxy received the request; the `by` parameter.
+    pub fn by(&self) -> Option<&str> {
+        self.by.as_deref()
+    }
+
+    /// `Host` header of the request received by the proxy; the `host` parameter.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Protocol used to make the request to the proxy; the `proto` parameter.
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+
+    /// IP address of the `for` node, without any port.
+    ///
+    /// Returns `None` if there is no `for` parameter or the node is obfuscated or unknown.
+    pub fn for_ip(&self) -> Option<IpAddr> {
+        self.for_.as_deref().and_then(node_ip)
+    }
+
+    /// IP address of the `by` node, without any port.
+    ///
+    /// Returns `None` if there is no `by` parameter or the node is obfuscated or unknown.
+    pub fn by_ip(&self) -> Option<IpAddr> {
+        self.by.as_deref().and_then(node_ip)
+    }
+}
+
+/// Picks the client from a chain of forwarded-for nodes, ordered from client to closest proxy,
+/// returning its index.
+///
+/// Without trusted proxies, the first node is used. Otherwise, the chain is walked back from the
+/// closest proxy and the first node that is not a trusted proxy is used, falling back to the first
+/// node when all of them are trusted.
+fn client_index(nodes: &[&str], trusted_proxies: Option<&TrustedProxies>) -> Option<usize> {
+    let client = match trusted_proxies {
+        Some(proxies) => nodes.iter().rposition(|node| !proxies.contains_node(node)),
+        None => None,
+    };
+
+    client.or_else(|| Some(0).filter(|_| !nodes.is_empty()))
+}
+
+/// Parses IP address from node identifier such as `192.0.2.60`, `192.0.2.60:80` or `[2001:db8::1]`.
+fn node_ip(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+
+    // obfuscated ports, e.g. "[2001:db8::1]:_abc", are not valid socket addresses
+    let ip = match node.strip_prefix('[') {
+        Some(rest) => rest.split(']').next()?,
+        None => node.split(':').next()?,
+    };
+
+    ip.parse().ok()
+}
+
 impl FromRequest for ConnectionInfo {
     type Error = Infallible;
     type Future = Ready<Result<Self, Self::Error>>;
@@ -255,6 +586,8 @@ impl FromRequest for PeerAddr {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::test::TestRequest;
 
@@ -390,6 +723,165 @@ mod tests {
         assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
     }
 
+    #[test]
+    fn forwarded_elements() {
+        let req = TestRequest::default()
+            .append_header((
+                header::FORWARDED,
+                r#"for=192.0.2.43;proto=http;by="[2001:db8::1]:80", for=unknown;host=example.com"#,
+            ))
+            .append_header((header::FORWARDED, r#"For="_gazonk"; BY=_proxy"#))
+            .to_http_request();
+        let info = req.connection_info();
+        let forwarded = info.forwarded();
+        assert_eq!(forwarded.len(), 3);
+
+        assert_eq!(forwarded[0].forwarded_for(), Some("192.0.2.43"));
+        assert_eq!(forwarded[0].for_ip(), Some(IpAddr::from([192, 0, 2, 43])));
+        assert_eq!(forwarded[0].proto(), Some("http"));
+        assert_eq!(forwarded[0].by(), Some("[2001:db8::1]:80"));
+        assert_eq!(forwarded[0].by_ip(), Some("2001:db8::1".parse().unwrap()));
+        assert_eq!(forwarded[0].host(), None);
+
+        assert_eq!(forwarded[1].forwarded_for(), Some("unknown"));
+        assert_eq!(forwarded[1].for_ip(), None);
+        assert_eq!(forwarded[1].host(), Some("example.com"));
+
+        assert_eq!(forwarded[2].forwarded_for(), Some("_gazonk"));
+        assert_eq!(forwarded[2].for_ip(), None);
+        assert_eq!(forwarded[2].by(), Some("_proxy"));
+
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.43"));
+        assert_eq!(info.host(), "example.com");
+    }
+
+    #[test]
+    fn forwarded_quoted_separators() {
+        let req = TestRequest::default()
+            .insert_header((
+                header::FORWARDED,
+                r#"for="[2001:db8::17]:_port";host="a,b;c=\"d\"", ;, for=198.51.100.17"#,
+            ))
+            .to_http_request();
+        let info = req.connection_info();
+        let forwarded = info.forwarded();
+        assert_eq!(forwarded.len(), 2);
+
+        assert_eq!(forwarded[0].forwarded_for(), Some("[2001:db8::17]:_port"));
+        assert_eq!(forwarded[0].for_ip(), Some("2001:db8::17".parse().unwrap()));
+        assert_eq!(forwarded[0].host(), Some(r#"a,b;c="d""#));
+        assert_eq!(
+            forwarded[1].for_ip(),
+            Some(IpAddr::from([198, 51, 100, 17]))
+        );
+
+        let req = TestRequest::default().to_http_request();
+        assert!(req.connection_info().forwarded().is_empty());
+    }
+
+    #[test]
+    fn trusted_proxy_ranges() {
+        let proxies = TrustedProxies::parse(["10.0.0.0/8", "192.0.2.1", "fd00::/8", "::/0"]);
+        let proxies = proxies.unwrap();
+        assert!(proxies.contains(IpAddr::from([10, 1, 2, 3])));
+        assert!(proxies.contains(IpAddr::from([192, 0, 2, 1])));
+        assert!(!proxies.contains(IpAddr::from([192, 0, 2, 2])));
+        assert!(!proxies.contains(IpAddr::from([11, 0, 0, 1])));
+        assert!(proxies.contains("2001:db8::1".parse().unwrap()));
+
+        let proxies = TrustedProxies::parse(["127.0.0.0/8", "fd00::/8"]).unwrap();
+        assert!(proxies.contains("::ffff:127.0.0.1".parse().unwrap()));
+        assert!(proxies.contains("fdab::1".parse().unwrap()));
+        assert!(!proxies.contains("::1".parse().unwrap()));
+
+        let proxies = TrustedProxies::parse(["0.0.0.0/0"]).unwrap();
+        assert!(proxies.contains(IpAddr::from([1, 2, 3, 4])));
+
+        assert!(TrustedProxies::parse(["10.0.0.0/33"]).is_err());
+        assert!(TrustedProxies::parse(["10.0.0/8"]).is_err());
+        assert!(TrustedProxies::parse(["localhost"]).is_err());
+    }
+
+    #[test]
+    fn trusted_proxies() {
+        let cfg = AppConfig::default().with_trusted_proxies(Some(Arc::new(
+            TrustedProxies::parse(["10.0.0.0/8"]).unwrap(),
+        )));
+
+        // untrusted peer; forwarding headers are ignored
+        let req = TestRequest::default()
+            .peer_addr("192.0.2.1:1234".parse().unwrap())
+            .insert_header((header::HOST, "example.com"))
+            .insert_header((
+                header::FORWARDED,
+                "for=198.51.100.17;proto=https;host=a.com",
+            ))
+            .insert_header((X_FORWARDED_FOR, "198.51.100.18"))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.1"));
+        assert_eq!(info.scheme(), "http");
+        assert_eq!(info.host(), "example.com");
+
+        // trusted peer; client is the last address that is not a trusted proxy
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:1234".parse().unwrap())
+            .insert_header((
+                header::FORWARDED,
+                "for=203.0.113.5, for=198.51.100.17;proto=https;host=a.com, for=10.0.0.2",
+            ))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("198.51.100.17"));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "a.com");
+
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:1234".parse().unwrap())
+            .insert_header((X_FORWARDED_FOR, "203.0.113.5, 198.51.100.18, 10.0.0.2"))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("198.51.100.18"));
+
+        // host and proto sent by the client itself are ignored
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:1234".parse().unwrap())
+            .insert_header((header::HOST, "example.com"))
+            .insert_header((
+                header::FORWARDED,
+                "for=203.0.113.5;proto=https;host=evil.com, for=198.51.100.17, for=10.0.0.2",
+            ))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("198.51.100.17"));
+        assert_eq!(info.scheme(), "http");
+        assert_eq!(info.host(), "example.com");
+
+        // proxies appending their own X-Forwarded-For header lines
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:1234".parse().unwrap())
+            .append_header((X_FORWARDED_FOR, "203.0.113.5"))
+            .append_header((X_FORWARDED_FOR, "198.51.100.18, 10.0.0.2"))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("198.51.100.18"));
+
+        // all forwarded addresses are trusted proxies
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:1234".parse().unwrap())
+            .insert_header((X_FORWARDED_FOR, "10.0.0.3, 10.0.0.2"))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), Some("10.0.0.3"));
+
+        // unknown peer address
+        let req = TestRequest::default()
+            .insert_header((X_FORWARDED_FOR, "198.51.100.18"))
+            .to_http_request();
+        let info = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(info.realip_remote_addr(), None);
+    }
+
     #[test]
     fn scheme_from_uri() {
         let req = TestRequest::get()
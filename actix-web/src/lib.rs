@@ -66,6 +66,7 @@
 //! - `openssl` - HTTPS support via `openssl` crate, supports `HTTP/2`
 //! - `rustls` - HTTPS support via `rustls` crate, supports `HTTP/2`
 //! - `secure-cookies` - secure cookies support
+//! - `validation` - validating extractor via the `validator` crate
 
 #![deny(rust_2018_idioms, nonstandard_style)]
 #![warn(future_incompatible)]
@@ -76,7 +77,10 @@
 
 mod app;
 mod app_service;
+mod block;
+mod conditional;
 mod config;
+mod conn_data;
 mod data;
 pub mod dev;
 pub mod error;
@@ -87,9 +91,12 @@ mod helpers;
 pub mod http;
 mod info;
 pub mod middleware;
+mod negotiate;
+mod range_body;
 mod redirect;
 mod request;
 mod request_data;
+mod request_id;
 mod resource;
 mod response;
 mod rmap;
@@ -98,6 +105,7 @@ pub mod rt;
 mod scope;
 mod server;
 mod service;
+mod sse;
 pub mod test;
 pub(crate) mod types;
 pub mod web;
@@ -0,0 +1,296 @@
+//! For middleware documentation, see [`BodyTee`].
+
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    body::{BodySize, MessageBody},
+    dev::{Service, Transform},
+    service::{ServiceRequest, ServiceResponse},
+    Error,
+};
+
+/// Middleware for observing the bytes of response bodies as they are sent.
+///
+/// By default, the callback is called with each chunk yielded by the response body, as it is
+/// yielded, so the body is never buffered. Use [`BodyTee::collect`] to have it called once with
+/// the full body instead.
+///
+/// The body sent to the client is not altered in any way. Chunks are only passed to the callback
+/// after the inner body yields them successfully; if the body errors or is dropped early (e.g.,
+/// because the client disconnected), the callback observes the chunks yielded until then, or, for
+/// collecting middleware, is not called at all.
+///
+/// Combine with [`Condition::new_fn`](super::Condition::new_fn) to only observe the responses to
+/// some requests.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::BodyTee, web::Bytes, App};
+///
+/// let app = App::new().wrap(BodyTee::new(|chunk: Bytes| {
+///     log::trace!("sent {} bytes", chunk.len());
+/// }));
+///
+/// let app = App::new().wrap(BodyTee::collect(|body: Bytes| {
+///     log::info!("sent body: {:?}", body);
+/// }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BodyTee<F> {
+    callback: F,
+    collect: bool,
+}
+
+impl<F> BodyTee<F>
+where
+    F: Fn(Bytes) + Clone,
+{
+    /// Constructs new middleware that calls `callback` with each chunk of each response body.
+    pub fn new(callback: F) -> Self {
+        Self {
+            callback,
+            collect: false,
+        }
+    }
+
+    /// Constructs new middleware that calls `callback` with each complete response body.
+    ///
+    /// Response bodies are collected in memory, alongside being sent, until they are complete.
+    pub fn collect(callback: F) -> Self {
+        Self {
+            callback,
+            collect: true,
+        }
+    }
+}
+
+impl<S, B, F> Transform<S, ServiceRequest> for BodyTee<F>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+    F: Fn(Bytes) + Clone,
+{
+    type Response = ServiceResponse<TeeBody<B, F>>;
+    type Error = Error;
+    type Transform = BodyTeeMiddleware<S, F>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(BodyTeeMiddleware {
+            service,
+            tee: self.clone(),
+        }))
+    }
+}
+
+pub struct BodyTeeMiddleware<S, F> {
+    service: S,
+    tee: BodyTee<F>,
+}
+
+impl<S, B, F> Service<ServiceRequest> for BodyTeeMiddleware<S, F>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+    F: Fn(Bytes) + Clone,
+{
+    type Response = ServiceResponse<TeeBody<B, F>>;
+    type Error = Error;
+    type Future = BodyTeeFuture<S::Future, F>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        BodyTeeFuture {
+            fut: self.service.call(req),
+            tee: Some(self.tee.clone()),
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    pub struct BodyTeeFuture<Fut, F> {
+        #[pin]
+        fut: Fut,
+        tee: Option<BodyTee<F>>,
+    }
+}
+
+impl<Fut, B, F> Future for BodyTeeFuture<Fut, F>
+where
+    Fut: Future<Output = Result<ServiceResponse<B>, Error>>,
+    B: MessageBody,
+{
+    type Output = Result<ServiceResponse<TeeBody<B, F>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let res = ready!(this.fut.poll(cx))?;
+        let BodyTee { callback, collect } = this.tee.take().unwrap();
+
+        Poll::Ready(Ok(res.map_body(move |_, body| TeeBody {
+            buf: collect.then(BytesMut::new),
+            body,
+            callback,
+        })))
+    }
+}
+
+pin_project! {
+    /// Response body wrapper used by [`BodyTee`](crate::middleware::BodyTee).
+    pub struct TeeBody<B, F> {
+        #[pin]
+        body: B,
+        callback: F,
+        // only used when collecting
+        buf: Option<BytesMut>,
+    }
+}
+
+impl<B, F> MessageBody for TeeBody<B, F>
+where
+    B: MessageBody,
+    F: Fn(Bytes),
+{
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        let chunk = ready!(this.body.poll_next(cx));
+
+        match chunk {
+            Some(Ok(ref bytes)) => match this.buf {
+                Some(buf) => buf.extend_from_slice(bytes),
+                None => (this.callback)(bytes.clone()),
+            },
+
+            // incomplete body is not passed to callback
+            Some(Err(_)) => *this.buf = None,
+
+            None => {
+                if let Some(buf) = this.buf.take() {
+                    (this.callback)(buf.freeze());
+                }
+            }
+        }
+
+        Poll::Ready(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        body::BodyStream,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    type Chunks = Rc<RefCell<Vec<Bytes>>>;
+
+    fn recorder() -> (Chunks, impl Fn(Bytes) + Clone) {
+        let chunks = Chunks::default();
+        let chunks2 = Rc::clone(&chunks);
+        (chunks, move |chunk| chunks2.borrow_mut().push(chunk))
+    }
+
+    async fn streaming() -> HttpResponse {
+        let chunks = ["hello", " ", "world"].map(|chunk| Ok::<_, Error>(Bytes::from(chunk)));
+        HttpResponse::Ok().streaming(stream::iter(chunks))
+    }
+
+    #[actix_rt::test]
+    async fn chunks() {
+        let (chunks, callback) = recorder();
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(BodyTee::new(callback))
+                .default_service(web::to(streaming)),
+        )
+        .await;
+
+        let res = test::call_service(&srv, TestRequest::default().to_request()).await;
+        assert!(chunks.borrow().is_empty());
+
+        assert_eq!(test::read_body(res).await, "hello world");
+        assert_eq!(*chunks.borrow(), ["hello", " ", "world"]);
+    }
+
+    #[actix_rt::test]
+    async fn collect() {
+        let (chunks, callback) = recorder();
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(BodyTee::collect(callback))
+                .default_service(web::to(streaming)),
+        )
+        .await;
+
+        let res = test::call_service(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(test::read_body(res).await, "hello world");
+        assert_eq!(*chunks.borrow(), ["hello world"]);
+    }
+
+    #[actix_rt::test]
+    async fn errors_and_early_drop() {
+        let body = || {
+            BodyStream::new(stream::iter([
+                Ok(Bytes::from_static(b"hello")),
+                Err(crate::error::ErrorInternalServerError("oops")),
+            ]))
+        };
+
+        let (chunks, callback) = recorder();
+        let tee = TeeBody {
+            body: body(),
+            callback,
+            buf: None,
+        };
+        assert!(crate::body::to_bytes(tee).await.is_err());
+        assert_eq!(*chunks.borrow(), ["hello"]);
+
+        let (chunks, callback) = recorder();
+        let tee = TeeBody {
+            body: body(),
+            callback: callback.clone(),
+            buf: Some(BytesMut::new()),
+        };
+        assert!(crate::body::to_bytes(tee).await.is_err());
+        assert!(chunks.borrow().is_empty());
+
+        let tee = TeeBody {
+            body: Bytes::from_static(b"hello"),
+            callback,
+            buf: Some(BytesMut::new()),
+        };
+        drop(tee);
+        assert!(chunks.borrow().is_empty());
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use actix_http::encoding::Encoder;
+use actix_http::{encoding::Encoder, header::ContentEncoding};
 use actix_service::{Service, Transform};
 use actix_utils::future::{ok, Either, Ready};
 use futures_core::ready;
@@ -15,7 +15,7 @@ use once_cell::sync::Lazy;
 use pin_project_lite::pin_project;
 
 use crate::{
-    body::{EitherBody, MessageBody},
+    body::{BodySize, EitherBody, MessageBody},
     http::{
         header::{self, AcceptEncoding, Encoding, HeaderValue},
         StatusCode,
@@ -45,6 +45,12 @@ use crate::{
 ///
 /// A (naïve) example serving an pre-compressed Gzip file is included below.
 ///
+/// # Skipping Compression
+/// Some payloads, such as images or archives, gain little from compression. To send a response
+/// uncompressed, either set a `Content-Encoding: identity` header on it or insert the
+/// [`SkipCompression`] marker into its extensions. No `Vary` header is added by `Compress` to
+/// responses it does not compress.
+///
 /// # Examples
 /// To enable automatic payload compression just include `Compress` as a top-level middleware:
 /// ```
@@ -71,9 +77,66 @@ use crate::{
 /// ```
 ///
 /// [feature flags]: ../index.html#crate-features
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 #[non_exhaustive]
-pub struct Compress;
+pub struct Compress {
+    min_size: u64,
+    compress_streams: bool,
+}
+
+impl Compress {
+    /// Sets the minimum body size, in bytes, for a response to be compressed.
+    ///
+    /// Responses with a known body size smaller than `min_size` are sent uncompressed, since small
+    /// payloads often grow when compressed. Whether streaming bodies, whose size is not known up
+    /// front, are compressed is controlled separately using
+    /// [`compress_streams`](Self::compress_streams).
+    ///
+    /// By default, responses are compressed regardless of size.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{middleware::Compress, App};
+    ///
+    /// let app = App::new().wrap(Compress::default().min_size(1024));
+    /// ```
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size as u64;
+        self
+    }
+
+    /// Sets whether streaming response bodies, whose size is not known up front, are compressed.
+    ///
+    /// Defaults to `true`.
+    pub fn compress_streams(mut self, compress_streams: bool) -> Self {
+        self.compress_streams = compress_streams;
+        self
+    }
+}
+
+impl Default for Compress {
+    fn default() -> Self {
+        Self {
+            min_size: 0,
+            compress_streams: true,
+        }
+    }
+}
+
+/// Response extension marker that prevents [`Compress`] from compressing a response.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::SkipCompression, HttpResponse};
+///
+/// async fn handler() -> HttpResponse {
+///     let mut res = HttpResponse::Ok().body(vec![0x89, b'P', b'N', b'G']);
+///     res.extensions_mut().insert(SkipCompression);
+///     res
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SkipCompression;
 
 impl<S, B> Transform<S, ServiceRequest> for Compress
 where
@@ -87,12 +150,16 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(CompressMiddleware { service })
+        ok(CompressMiddleware {
+            service,
+            config: self.clone(),
+        })
     }
 }
 
 pub struct CompressMiddleware<S> {
     service: S,
+    config: Compress,
 }
 
 impl<S, B> Service<ServiceRequest> for CompressMiddleware<S>
@@ -118,6 +185,7 @@ where
                 return Either::left(CompressResponse {
                     encoding: Encoding::identity(),
                     fut: self.service.call(req),
+                    config: self.config.clone(),
                     _phantom: PhantomData,
                 })
             }
@@ -145,6 +213,7 @@ where
             Some(encoding) => Either::left(CompressResponse {
                 fut: self.service.call(req),
                 encoding,
+                config: self.config.clone(),
                 _phantom: PhantomData,
             }),
         }
@@ -159,6 +228,7 @@ pin_project! {
         #[pin]
         fut: S::Future,
         encoding: Encoding,
+        config: Compress,
         _phantom: PhantomData<B>,
     }
 }
@@ -175,7 +245,16 @@ where
 
         match ready!(this.fut.poll(cx)) {
             Ok(resp) => {
+                let skip = match resp.response().body().size() {
+                    BodySize::Sized(size) => size < this.config.min_size,
+                    BodySize::Stream => !this.config.compress_streams,
+                    BodySize::None => false,
+                };
+
                 let enc = match this.encoding {
+                    _ if skip || resp.response().extensions().contains::<SkipCompression>() => {
+                        ContentEncoding::Identity
+                    }
                     Encoding::Known(enc) => *enc,
                     Encoding::Unknown(enc) => {
                         unimplemented!("encoding {} should not be here", enc);
@@ -192,7 +271,7 @@ where
     }
 }
 
-static SUPPORTED_ENCODINGS_STRING: Lazy<String> = Lazy::new(|| {
+pub(super) static SUPPORTED_ENCODINGS_STRING: Lazy<String> = Lazy::new(|| {
     #[allow(unused_mut)] // only unused when no compress features enabled
     let mut encoding: Vec<&str> = vec![];
 
@@ -324,4 +403,135 @@ mod tests {
         assert!(vary_headers.contains(&HeaderValue::from_static("x-test")));
         assert!(vary_headers.contains(&HeaderValue::from_static("accept-encoding")));
     }
+
+    #[actix_rt::test]
+    async fn skips_compression() {
+        const DATA: &str = const_str::repeat!("hello world ", 100);
+
+        let app = test::init_service({
+            App::new()
+                .wrap(Compress::default())
+                .route("/", web::get().to(|| HttpResponse::Ok().body(DATA)))
+                .route(
+                    "/marker",
+                    web::get().to(|| {
+                        let mut res = HttpResponse::Ok().body(DATA);
+                        res.extensions_mut().insert(SkipCompression);
+                        res
+                    }),
+                )
+                .route(
+                    "/identity",
+                    web::get().to(|| {
+                        HttpResponse::Ok()
+                            .insert_header(ContentEncoding::Identity)
+                            .body(DATA)
+                    }),
+                )
+        })
+        .await;
+
+        let req = test::TestRequest::default()
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(header::VARY).unwrap(), "accept-encoding");
+
+        let req = test::TestRequest::default()
+            .uri("/marker")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert!(res.headers().get(header::VARY).is_none());
+        assert_eq!(test::read_body(res).await, DATA.as_bytes());
+
+        let req = test::TestRequest::default()
+            .uri("/identity")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "identity"
+        );
+        assert!(res.headers().get(header::VARY).is_none());
+        assert_eq!(test::read_body(res).await, DATA.as_bytes());
+    }
+
+    #[actix_rt::test]
+    async fn min_size() {
+        const DATA: &str = const_str::repeat!("hello world ", 100);
+
+        let app = test::init_service({
+            App::new()
+                .wrap(Compress::default().min_size(DATA.len() + 1))
+                .route("/sized", web::get().to(|| HttpResponse::Ok().body(DATA)))
+                .route(
+                    "/stream",
+                    web::get().to(|| {
+                        HttpResponse::Ok().streaming(futures_util::stream::once(async {
+                            Ok::<_, Error>(web::Bytes::from_static(DATA.as_bytes()))
+                        }))
+                    }),
+                )
+        })
+        .await;
+
+        let req = test::TestRequest::default()
+            .uri("/sized")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert!(res.headers().get(header::VARY).is_none());
+        assert_eq!(test::read_body(res).await, DATA.as_bytes());
+
+        // streams are compressed by default, regardless of minimum size
+        let req = test::TestRequest::default()
+            .uri("/stream")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(gzip_decode(test::read_body(res).await), DATA.as_bytes());
+
+        let app = test::init_service({
+            App::new()
+                .wrap(
+                    Compress::default()
+                        .min_size(DATA.len())
+                        .compress_streams(false),
+                )
+                .route("/sized", web::get().to(|| HttpResponse::Ok().body(DATA)))
+                .route(
+                    "/stream",
+                    web::get().to(|| {
+                        HttpResponse::Ok().streaming(futures_util::stream::once(async {
+                            Ok::<_, Error>(web::Bytes::from_static(DATA.as_bytes()))
+                        }))
+                    }),
+                )
+        })
+        .await;
+
+        let req = test::TestRequest::default()
+            .uri("/sized")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(gzip_decode(test::read_body(res).await), DATA.as_bytes());
+
+        let req = test::TestRequest::default()
+            .uri("/stream")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(test::read_body(res).await, DATA.as_bytes());
+    }
 }
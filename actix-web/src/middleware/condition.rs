@@ -3,6 +3,7 @@
 use std::{
     future::Future,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
 };
 
@@ -12,11 +13,19 @@ use pin_project_lite::pin_project;
 
 use crate::{
     body::EitherBody,
-    dev::{Service, ServiceResponse, Transform},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
 };
 
 /// Middleware for conditionally enabling other middleware.
 ///
+/// Middleware can be enabled once, when the app is constructed, using [`Condition::new`], or for
+/// each request, using [`Condition::new_fn`].
+///
+/// With [`Condition::new`], the decision is made when the service is constructed; when disabled,
+/// the wrapped middleware's service is never created and requests are passed straight to the
+/// inner service. [`App::wrap_conditional`], [`Scope::wrap_conditional`], and
+/// [`Resource::wrap_conditional`] are shorthands for registering middleware this way.
+///
 /// # Examples
 /// ```
 /// use actix_web::middleware::{Condition, NormalizePath};
@@ -26,12 +35,17 @@ use crate::{
 /// let app = App::new()
 ///     .wrap(Condition::new(enable_normalize, NormalizePath::default()));
 /// ```
-pub struct Condition<T> {
+///
+/// [`App::wrap_conditional`]: crate::App::wrap_conditional
+/// [`Scope::wrap_conditional`]: crate::Scope::wrap_conditional
+/// [`Resource::wrap_conditional`]: crate::Resource::wrap_conditional
+pub struct Condition<T, P = bool> {
     transformer: T,
-    enable: bool,
+    enable: P,
 }
 
 impl<T> Condition<T> {
+    /// Constructs new middleware that wraps services with `transformer` if `enable` is true.
     pub fn new(enable: bool, transformer: T) -> Self {
         Self {
             transformer,
@@ -40,6 +54,38 @@ impl<T> Condition<T> {
     }
 }
 
+impl<T, F> Condition<T, Rc<F>>
+where
+    F: Fn(&ServiceRequest) -> bool,
+{
+    /// Constructs new middleware that decides, for each request, whether to use `transformer`.
+    ///
+    /// Requests for which `predicate` returns true are handled by the service wrapped with
+    /// `transformer`; other requests are passed straight to the wrapped service. Either way, the
+    /// wrapped service is called exactly once per request.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{
+    ///     http::header,
+    ///     middleware::{Condition, DefaultHeaders},
+    ///     App,
+    /// };
+    ///
+    /// // only mark responses to unauthenticated requests as public
+    /// let app = App::new().wrap(Condition::new_fn(
+    ///     |req| !req.headers().contains_key(header::AUTHORIZATION),
+    ///     DefaultHeaders::new().add((header::CACHE_CONTROL, "public")),
+    /// ));
+    /// ```
+    pub fn new_fn(predicate: F, transformer: T) -> Self {
+        Self {
+            transformer,
+            enable: Rc::new(predicate),
+        }
+    }
+}
+
 impl<S, T, Req, BE, BD, Err> Transform<S, Req> for Condition<T>
 where
     S: Service<Req, Response = ServiceResponse<BD>, Error = Err> + 'static,
@@ -68,6 +114,39 @@ where
     }
 }
 
+impl<S, T, F, BE, BD, Err> Transform<S, ServiceRequest> for Condition<T, Rc<F>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<BD>, Error = Err> + 'static,
+    T: Transform<Rc<S>, ServiceRequest, Response = ServiceResponse<BE>, Error = Err>,
+    T::Future: 'static,
+    T::InitError: 'static,
+    T::Transform: 'static,
+    F: Fn(&ServiceRequest) -> bool + 'static,
+{
+    type Response = ServiceResponse<EitherBody<BE, BD>>;
+    type Error = Err;
+    type Transform = ConditionFnMiddleware<T::Transform, Rc<S>, F>;
+    type InitError = T::InitError;
+    type Future = LocalBoxFuture<'static, Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        let service = Rc::new(service);
+        let fut = self.transformer.new_transform(Rc::clone(&service));
+        let predicate = Rc::clone(&self.enable);
+
+        async move {
+            let wrapped_svc = fut.await?;
+
+            Ok(ConditionFnMiddleware {
+                enabled: wrapped_svc,
+                disabled: service,
+                predicate,
+            })
+        }
+        .boxed_local()
+    }
+}
+
 pub enum ConditionMiddleware<E, D> {
     Enable(E),
     Disable(D),
@@ -101,6 +180,41 @@ where
     }
 }
 
+pub struct ConditionFnMiddleware<E, D, F> {
+    enabled: E,
+    disabled: D,
+    predicate: Rc<F>,
+}
+
+impl<E, D, F, BE, BD, Err> Service<ServiceRequest> for ConditionFnMiddleware<E, D, F>
+where
+    E: Service<ServiceRequest, Response = ServiceResponse<BE>, Error = Err>,
+    D: Service<ServiceRequest, Response = ServiceResponse<BD>, Error = Err>,
+    F: Fn(&ServiceRequest) -> bool,
+{
+    type Response = ServiceResponse<EitherBody<BE, BD>>;
+    type Error = Err;
+    type Future = ConditionMiddlewareFuture<E::Future, D::Future>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // either service may be called next
+        ready!(self.enabled.poll_ready(cx))?;
+        self.disabled.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if (self.predicate)(&req) {
+            ConditionMiddlewareFuture::Enabled {
+                fut: self.enabled.call(req),
+            }
+        } else {
+            ConditionMiddlewareFuture::Disabled {
+                fut: self.disabled.call(req),
+            }
+        }
+    }
+}
+
 pin_project! {
     #[doc(hidden)]
     #[project = ConditionProj]
@@ -130,6 +244,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use actix_service::IntoService as _;
 
     use super::*;
@@ -204,4 +320,43 @@ mod tests {
             test::call_service(&mw, TestRequest::default().to_srv_request()).await;
         assert_eq!(resp.headers().get(CONTENT_TYPE), None);
     }
+
+    #[actix_rt::test]
+    async fn test_predicate() {
+        let calls = Rc::new(Cell::new(0));
+
+        let srv = {
+            let calls = Rc::clone(&calls);
+
+            move |req: ServiceRequest| {
+                calls.set(calls.get() + 1);
+
+                async move {
+                    let resp =
+                        HttpResponse::InternalServerError().message_body(String::new())?;
+                    Ok(req.into_response(resp))
+                }
+            }
+        };
+
+        let mw = ErrorHandlers::new().handler(StatusCode::INTERNAL_SERVER_ERROR, render_500);
+
+        let mw = Condition::new_fn(|req| req.headers().contains_key("x-enable"), mw)
+            .new_transform(srv.into_service())
+            .await
+            .unwrap();
+
+        let req = TestRequest::default()
+            .insert_header(("x-enable", "1"))
+            .to_srv_request();
+        let resp: ServiceResponse<EitherBody<EitherBody<_, _>, String>> =
+            test::call_service(&mw, req).await;
+        assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), "0001");
+        assert_eq!(calls.get(), 1);
+
+        let resp: ServiceResponse<EitherBody<EitherBody<_, _>, String>> =
+            test::call_service(&mw, TestRequest::default().to_srv_request()).await;
+        assert_eq!(resp.headers().get(CONTENT_TYPE), None);
+        assert_eq!(calls.get(), 2);
+    }
 }
@@ -0,0 +1,418 @@
+//! For middleware documentation, see [`DecompressPayload`].
+
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_http::{encoding::Decoder, header::ContentEncoding};
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+
+use super::{compress::SUPPORTED_ENCODINGS_STRING, payload_limit::LimitedPayload};
+use crate::{
+    body::EitherBody,
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    error::PayloadError,
+    http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH},
+    Error, HttpMessage as _, HttpResponse,
+};
+
+/// Middleware for decompressing request payloads.
+///
+/// Requests with a `Content-Encoding` header naming a supported encoding have their payload
+/// replaced by a stream of the decompressed body. The `Content-Encoding` and `Content-Length`
+/// headers, which describe the compressed body, are removed so that extractors and handlers see
+/// the request as if it had been sent uncompressed. This includes extractors, such as
+/// [`Payload`](crate::web::Payload), that do not decompress request payloads themselves.
+///
+/// The supported encodings are those enabled by the crate's `compress-*` features. Requests with
+/// other encodings, including lists of multiple encodings, are passed through unchanged by
+/// default, or can be [rejected](Self::reject_unsupported) with a `415 Unsupported Media Type`
+/// response.
+///
+/// # Decompressed Size Limit
+/// Small compressed bodies can decompress into very large ones. Use [`limit`](Self::limit) to
+/// bound the size of decompressed bodies. Once more than the limit has been decompressed, the
+/// payload stream yields a [`PayloadError::Overflow`](crate::error::PayloadError::Overflow) error,
+/// which body extractors turn into a `413 Payload Too Large` response. Extractors apply their own
+/// limits, such as [`PayloadConfig`](crate::web::PayloadConfig), to the decompressed body.
+///
+/// When a limit is set, compressed chunks are fed to the decoder in pieces of at most 1 KiB, so
+/// that the limit is checked before a single, highly compressed chunk is inflated in full.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::DecompressPayload, web, App};
+///
+/// let app = App::new()
+///     // decompress request bodies up to 16 MB
+///     .wrap(DecompressPayload::new().limit(16 * 1024 * 1024).reject_unsupported(true))
+///     .route("/", web::post().to(|body: web::Bytes| async move { body }));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecompressPayload {
+    limit: Option<usize>,
+    reject_unsupported: bool,
+}
+
+impl DecompressPayload {
+    /// Constructs new middleware that decompresses request payloads without a size limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size, in bytes, of decompressed request payloads.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets whether requests with an unsupported `Content-Encoding` are rejected.
+    ///
+    /// Rejected requests receive a `415 Unsupported Media Type` response with an `Accept-Encoding`
+    /// header listing the supported encodings. Defaults to `false`.
+    pub fn reject_unsupported(mut self, reject: bool) -> Self {
+        self.reject_unsupported = reject;
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for DecompressPayload
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = DecompressPayloadMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DecompressPayloadMiddleware {
+            service,
+            config: *self,
+        }))
+    }
+}
+
+pub struct DecompressPayloadMiddleware<S> {
+    service: S,
+    config: DecompressPayload,
+}
+
+impl<S, B> Service<ServiceRequest> for DecompressPayloadMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = DecompressPayloadFuture<S::Future, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let encoding = match req.headers().get(CONTENT_ENCODING) {
+            None => None,
+            Some(val) => match val.to_str().ok().and_then(|val| val.parse().ok()) {
+                Some(ContentEncoding::Identity) => None,
+                Some(encoding) if is_supported(encoding) => Some(encoding),
+
+                _ if self.config.reject_unsupported => {
+                    let res = HttpResponse::UnsupportedMediaType()
+                        .insert_header((ACCEPT_ENCODING, SUPPORTED_ENCODINGS_STRING.as_str()))
+                        .body("Unsupported request content encoding.");
+
+                    return DecompressPayloadFuture::Rejected {
+                        res: Some(req.into_response(res).map_into_right_body()),
+                    };
+                }
+
+                _ => None,
+            },
+        };
+
+        if let Some(encoding) = encoding {
+            let headers = req.headers_mut();
+            headers.remove(CONTENT_ENCODING);
+            headers.remove(CONTENT_LENGTH);
+
+            let payload: Payload = match self.config.limit {
+                Some(limit) => {
+                    let chunks = BoundedChunks::new(req.take_payload(), MAX_DECODE_CHUNK_SIZE);
+                    let payload: Payload = Payload::Stream {
+                        payload: Box::pin(Decoder::new(chunks, encoding)),
+                    };
+
+                    Payload::Stream {
+                        payload: Box::pin(LimitedPayload::new(payload, limit)),
+                    }
+                }
+
+                None => Payload::Stream {
+                    payload: Box::pin(Decoder::new(req.take_payload(), encoding)),
+                },
+            };
+
+            req.set_payload(payload);
+        }
+
+        DecompressPayloadFuture::Service {
+            fut: self.service.call(req),
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    #[project = DecompressPayloadProj]
+    pub enum DecompressPayloadFuture<F, B> {
+        Rejected { res: Option<ServiceResponse<EitherBody<B>>> },
+        Service { #[pin] fut: F },
+    }
+}
+
+impl<F, B> Future for DecompressPayloadFuture<F, B>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+{
+    type Output = Result<ServiceResponse<EitherBody<B>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            DecompressPayloadProj::Rejected { res } => {
+                Poll::Ready(Ok(res.take().expect("future polled after completion")))
+            }
+            DecompressPayloadProj::Service { fut } => {
+                Poll::Ready(Ok(ready!(fut.poll(cx))?.map_into_left_body()))
+            }
+        }
+    }
+}
+
+/// Size of the largest piece of compressed data fed to the decoder at once when a limit is set.
+const MAX_DECODE_CHUNK_SIZE: usize = 1024;
+
+pin_project! {
+    /// Stream that splits the chunks of another stream into pieces of bounded size.
+    struct BoundedChunks<S> {
+        #[pin]
+        stream: S,
+        max_size: usize,
+        pending: Bytes,
+    }
+}
+
+impl<S> BoundedChunks<S> {
+    fn new(stream: S, max_size: usize) -> Self {
+        Self {
+            stream,
+            max_size,
+            pending: Bytes::new(),
+        }
+    }
+}
+
+impl<S> Stream for BoundedChunks<S>
+where
+    S: Stream<Item = Result<Bytes, PayloadError>>,
+{
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while this.pending.is_empty() {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => *this.pending = chunk,
+                other => return Poll::Ready(other),
+            }
+        }
+
+        let len = this.pending.len().min(*this.max_size);
+        Poll::Ready(Some(Ok(this.pending.split_to(len))))
+    }
+}
+
+fn is_supported(encoding: ContentEncoding) -> bool {
+    match encoding {
+        #[cfg(feature = "compress-brotli")]
+        ContentEncoding::Brotli => true,
+        #[cfg(feature = "compress-gzip")]
+        ContentEncoding::Gzip | ContentEncoding::Deflate => true,
+        #[cfg(feature = "compress-zstd")]
+        ContentEncoding::Zstd => true,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "compress-gzip")]
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App, HttpRequest,
+    };
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn echo(req: HttpRequest, body: web::Bytes) -> HttpResponse {
+        assert!(!req.headers().contains_key(CONTENT_ENCODING));
+        HttpResponse::Ok().body(body)
+    }
+
+    #[actix_rt::test]
+    async fn decompresses() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(DecompressPayload::new())
+                .default_service(web::to(echo)),
+        )
+        .await;
+
+        let body = gzip(b"hello world");
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "gzip"))
+            .insert_header((CONTENT_LENGTH, body.len()))
+            .set_payload(body)
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "hello world");
+
+        let req = TestRequest::post().set_payload("hello world").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(test::read_body(res).await, "hello world");
+    }
+
+    #[actix_rt::test]
+    async fn raw_payload() {
+        let srv =
+            test::init_service(App::new().wrap(DecompressPayload::new()).default_service(
+                web::to(|mut payload: web::Payload| async move {
+                    use futures_util::StreamExt as _;
+
+                    let mut body = web::BytesMut::new();
+                    while let Some(chunk) = payload.next().await {
+                        body.extend_from_slice(&chunk?);
+                    }
+
+                    Ok::<_, Error>(HttpResponse::Ok().body(body))
+                }),
+            ))
+            .await;
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "gzip"))
+            .set_payload(gzip(b"hello world"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(test::read_body(res).await, "hello world");
+    }
+
+    #[actix_rt::test]
+    async fn limit() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(DecompressPayload::new().limit(1024))
+                .default_service(web::to(echo)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "gzip"))
+            .set_payload(gzip(&[b'a'; 1024]))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let bomb = gzip(&[b'a'; 1024 * 1024]);
+        assert!(bomb.len() < 1024 * 8);
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "gzip"))
+            .set_payload(bomb)
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[actix_rt::test]
+    async fn bounded_chunks() {
+        use futures_util::{stream, StreamExt as _};
+
+        let chunks = stream::iter([
+            Ok(Bytes::from_static(b"12345")),
+            Ok(Bytes::new()),
+            Ok(Bytes::from_static(b"67")),
+            Err(PayloadError::Incomplete(None)),
+        ]);
+
+        let pieces = BoundedChunks::new(chunks, 2).collect::<Vec<_>>().await;
+        let pieces = pieces
+            .iter()
+            .map(|piece| piece.as_ref().ok().map(|piece| &piece[..]))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            pieces,
+            [
+                Some(&b"12"[..]),
+                Some(&b"34"[..]),
+                Some(&b"5"[..]),
+                Some(&b"67"[..]),
+                None
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn unsupported_encodings() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(DecompressPayload::new())
+                .default_service(web::to(|body: web::Bytes| async move { body })),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "compress"))
+            .set_payload(Bytes::from_static(b"data"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "data");
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(DecompressPayload::new().reject_unsupported(true))
+                .default_service(web::to(|body: web::Bytes| async move { body })),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_ENCODING, "compress"))
+            .set_payload(Bytes::from_static(b"data"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(res
+            .headers()
+            .get(ACCEPT_ENCODING)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .contains("gzip"));
+    }
+}
@@ -214,6 +214,51 @@ impl<B> ErrorHandlers<B> {
         self
     }
 
+    /// Register async error handler for specified status code.
+    ///
+    /// The handler's returned response, which may have a new body, is used in place of the
+    /// original. Async and sync handlers for different status codes can be registered on the same
+    /// `ErrorHandlers` instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{
+    ///     dev::ServiceResponse, http::StatusCode, middleware::ErrorHandlers, web, App,
+    ///     HttpResponse, Result,
+    /// };
+    ///
+    /// async fn render_error_page<B>(res: ServiceResponse<B>) -> Result<ServiceResponse> {
+    ///     // e.g., fetch a template from a remote store
+    ///     let page = "<h1>Something went wrong</h1>";
+    ///
+    ///     let (req, res) = res.into_parts();
+    ///     let res = res.set_body(page);
+    ///
+    ///     Ok(ServiceResponse::new(req, res).map_into_boxed_body())
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .wrap(
+    ///         ErrorHandlers::new()
+    ///             .handler_async(StatusCode::INTERNAL_SERVER_ERROR, render_error_page),
+    ///     )
+    ///     .service(web::resource("/").route(web::get().to(HttpResponse::InternalServerError)));
+    /// ```
+    pub fn handler_async<F, Fut>(self, status: StatusCode, handler: F) -> Self
+    where
+        F: Fn(ServiceResponse<B>) -> Fut + 'static,
+        Fut: Future<Output = Result<ServiceResponse, Error>> + 'static,
+        B: 'static,
+    {
+        self.handler(status, move |res| {
+            let fut = handler(res);
+
+            Ok(ErrorHandlerResponse::Future(Box::pin(async move {
+                Ok(fut.await?.map_into_right_body())
+            })))
+        })
+    }
+
     /// Register a default error handler.
     ///
     /// Any request with a status code that hasn't been given a specific other handler (by calling
@@ -464,6 +509,68 @@ mod tests {
         assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), "0001");
     }
 
+    #[actix_rt::test]
+    async fn handler_async_composes_with_sync() {
+        async fn error_handler_async<B>(res: ServiceResponse<B>) -> Result<ServiceResponse> {
+            let body = futures_util::future::ready("rendered error page").await;
+
+            let (req, res) = res.into_parts();
+            let res = res.set_body(body);
+
+            Ok(ServiceResponse::new(req, res).map_into_boxed_body())
+        }
+
+        #[allow(clippy::unnecessary_wraps)]
+        fn error_handler_sync<B>(
+            mut res: ServiceResponse<B>,
+        ) -> Result<ErrorHandlerResponse<B>> {
+            res.response_mut()
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("0001"));
+
+            Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
+        }
+
+        let make_mw = |status| async move {
+            ErrorHandlers::new()
+                .handler_async(StatusCode::INTERNAL_SERVER_ERROR, error_handler_async)
+                .handler(StatusCode::NOT_FOUND, error_handler_sync)
+                .new_transform(test::status_service(status).into_service())
+                .await
+                .unwrap()
+        };
+
+        let mw = make_mw(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let res = test::call_service(&mw, TestRequest::default().to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(test::read_body(res).await, "rendered error page");
+
+        let mw = make_mw(StatusCode::NOT_FOUND).await;
+        let res = test::call_service(&mw, TestRequest::default().to_srv_request()).await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "0001");
+    }
+
+    #[actix_rt::test]
+    async fn handler_async_error() {
+        async fn error_handler<B>(_res: ServiceResponse<B>) -> Result<ServiceResponse> {
+            Err(crate::error::ErrorBadGateway("remote sink unavailable"))
+        }
+
+        let srv = test::status_service(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mw = ErrorHandlers::new()
+            .handler_async(StatusCode::INTERNAL_SERVER_ERROR, error_handler)
+            .new_transform(srv.into_service())
+            .await
+            .unwrap();
+
+        let err = mw
+            .call(TestRequest::default().to_srv_request())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::BAD_GATEWAY);
+    }
+
     #[actix_rt::test]
     async fn changes_body_type() {
         #[allow(clippy::unnecessary_wraps)]
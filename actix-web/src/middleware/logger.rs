@@ -24,9 +24,10 @@ use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 use crate::{
     body::{BodySize, MessageBody},
-    http::header::HeaderName,
+    http::header::{HeaderName, HeaderValue},
+    request_id::RequestId,
     service::{ServiceRequest, ServiceResponse},
-    Error, Result,
+    Error, HttpMessage as _, Result,
 };
 
 /// Middleware for logging request and response summaries to the terminal.
@@ -67,6 +68,9 @@ use crate::{
 /// `%b` | Size of response in bytes, including HTTP headers
 /// `%T` | Time taken to serve the request, in seconds to 6 decimal places
 /// `%D` | Time taken to serve the request, in milliseconds
+/// `%{us}T` | Time taken to serve the request, in microseconds
+/// `%{TTFB}` | Time taken until the first chunk of the response body was produced, in milliseconds
+/// `%{RequestId}` | Request ID assigned by the [`SetRequestId`](super::SetRequestId) middleware
 /// `%U` | Request URL
 /// `%{r}a` | "Real IP" remote address **\***
 /// `%{FOO}i` | `request.headers["FOO"]`
@@ -75,7 +79,16 @@ use crate::{
 /// `%{FOO}xi` | [Custom request replacement](Logger::custom_request_replace) labelled "FOO"
 /// `%{FOO}xo` | [Custom response replacement](Logger::custom_response_replace) labelled "FOO"
 ///
+/// # Timing
+/// All times are measured from when the request started processing. `%T`, `%D`, and `%{us}T` are
+/// measured up to when the response body has been fully sent (i.e., when the log line is written).
+/// `%{TTFB}` is measured up to when the response body first yields a non-empty chunk; it is
+/// printed as "-" for responses with an empty body.
+///
 /// # Security
+/// Header values are logged as-is by default. Use [`redact_header`](Logger::redact_header) or
+/// [`mask_header`](Logger::mask_header) to keep credentials and other sensitive values out of logs.
+///
 /// **\*** "Real IP" remote address is calculated using
 /// [`ConnectionInfo::realip_remote_addr()`](crate::dev::ConnectionInfo::realip_remote_addr())
 ///
@@ -140,6 +153,64 @@ impl Logger {
         self
     }
 
+    /// Logs the values of the named header as `[redacted]` instead of the actual value.
+    ///
+    /// Applies to both request (`%{FOO}i`) and response (`%{FOO}o`) header tokens. Header names are
+    /// matched case-insensitively. Missing headers are still logged as "-".
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::middleware::Logger;
+    /// Logger::new(r#"%a "%r" %s %{Authorization}i"#).redact_header("authorization");
+    /// ```
+    pub fn redact_header(self, name: &str) -> Self {
+        self.set_header_redaction(name, HeaderRedaction::Redact)
+    }
+
+    /// Logs the values of the named header as a short, stable hash instead of the actual value.
+    ///
+    /// Masked values are logged like `[hash:3a7bd3e2]`. This allows correlating log lines that
+    /// share a value, such as a session cookie, without revealing the value itself. The hash is
+    /// not cryptographically secure, so low-entropy values may still be guessable.
+    ///
+    /// Applies to both request (`%{FOO}i`) and response (`%{FOO}o`) header tokens. Header names are
+    /// matched case-insensitively. Missing headers are still logged as "-".
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::middleware::Logger;
+    /// Logger::new(r#"%a "%r" %s %{Cookie}i"#).mask_header("cookie");
+    /// ```
+    pub fn mask_header(self, name: &str) -> Self {
+        self.set_header_redaction(name, HeaderRedaction::Mask)
+    }
+
+    fn set_header_redaction(mut self, name: &str, redaction: HeaderRedaction) -> Self {
+        let inner = Rc::get_mut(&mut self.0).unwrap();
+
+        let mut found = false;
+
+        for unit in &mut inner.format.0 {
+            if let FormatText::RequestHeader(header, mode)
+            | FormatText::ResponseHeader(header, mode) = unit
+            {
+                if header.as_str().eq_ignore_ascii_case(name) {
+                    *mode = redaction;
+                    found = true;
+                }
+            }
+        }
+
+        if !found {
+            debug!(
+                "Attempted to set redaction for header not present in log format: {}",
+                name
+            );
+        }
+
+        self
+    }
+
     /// Register a function that receives a ServiceRequest and returns a String for use in the
     /// log line. The label passed as the first argument should match a replacement substring in
     /// the logger format like `%{label}xi`.
@@ -393,6 +464,7 @@ where
             time,
             format,
             size: 0,
+            first_byte: false,
             log_target,
         })))
     }
@@ -404,6 +476,7 @@ pin_project! {
         body: B,
         format: Option<Format>,
         size: usize,
+        first_byte: bool,
         time: OffsetDateTime,
         log_target: Cow<'static, str>,
     }
@@ -443,6 +516,18 @@ impl<B: MessageBody> MessageBody for StreamLog<B> {
 
         match ready!(this.body.poll_next(cx)) {
             Some(Ok(chunk)) => {
+                if !*this.first_byte && !chunk.is_empty() {
+                    *this.first_byte = true;
+
+                    if let Some(ref mut format) = this.format {
+                        let now = OffsetDateTime::now_utc();
+
+                        for unit in &mut format.0 {
+                            unit.render_first_byte(*this.time, now);
+                        }
+                    }
+                }
+
                 *this.size += chunk.len();
                 Poll::Ready(Some(Ok(chunk)))
             }
@@ -469,7 +554,10 @@ impl Format {
     /// Returns `None` if the format string syntax is incorrect.
     pub fn new(s: &str) -> Format {
         log::trace!("Access log format: {}", s);
-        let fmt = Regex::new(r"%(\{([A-Za-z0-9\-_]+)\}([aioe]|x[io])|[%atPrUsbTD]?)").unwrap();
+        let fmt = Regex::new(
+            r"%(\{([A-Za-z0-9\-_]+)\}([aioeT]|x[io])|\{TTFB\}|\{RequestId\}|[%atPrUsbTD]?)",
+        )
+        .unwrap();
 
         let mut idx = 0;
         let mut results = Vec::new();
@@ -490,13 +578,22 @@ impl Format {
                             unreachable!("regex and code mismatch")
                         }
                     }
-                    "i" => {
-                        FormatText::RequestHeader(HeaderName::try_from(key.as_str()).unwrap())
-                    }
-                    "o" => {
-                        FormatText::ResponseHeader(HeaderName::try_from(key.as_str()).unwrap())
-                    }
+                    "i" => FormatText::RequestHeader(
+                        HeaderName::try_from(key.as_str()).unwrap(),
+                        HeaderRedaction::None,
+                    ),
+                    "o" => FormatText::ResponseHeader(
+                        HeaderName::try_from(key.as_str()).unwrap(),
+                        HeaderRedaction::None,
+                    ),
                     "e" => FormatText::EnvironHeader(key.as_str().to_owned()),
+                    "T" => {
+                        if key.as_str() == "us" {
+                            FormatText::TimeMicros
+                        } else {
+                            FormatText::Str(m.as_str().to_owned())
+                        }
+                    }
                     "xi" => FormatText::CustomRequest(key.as_str().to_owned(), None),
                     "xo" => FormatText::CustomResponse(key.as_str().to_owned(), None),
                     _ => unreachable!(),
@@ -513,6 +610,8 @@ impl Format {
                     "U" => FormatText::UrlPath,
                     "T" => FormatText::Time,
                     "D" => FormatText::TimeMillis,
+                    "{TTFB}" => FormatText::TimeToFirstByte,
+                    "{RequestId}" => FormatText::RequestId,
                     _ => FormatText::Str(m.as_str().to_owned()),
                 });
             }
@@ -539,16 +638,54 @@ enum FormatText {
     ResponseSize,
     Time,
     TimeMillis,
+    TimeMicros,
+    TimeToFirstByte,
+    RequestId,
     RemoteAddr,
     RealIpRemoteAddr,
     UrlPath,
-    RequestHeader(HeaderName),
-    ResponseHeader(HeaderName),
+    RequestHeader(HeaderName, HeaderRedaction),
+    ResponseHeader(HeaderName, HeaderRedaction),
     EnvironHeader(String),
     CustomRequest(String, Option<CustomRequestFn>),
     CustomResponse(String, Option<CustomResponseFn>),
 }
 
+/// How a header value is rendered in the log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeaderRedaction {
+    /// Log the value as-is.
+    None,
+
+    /// Log `[redacted]`.
+    Redact,
+
+    /// Log a truncated hash of the value.
+    Mask,
+}
+
+impl HeaderRedaction {
+    fn render(self, value: Option<&HeaderValue>) -> String {
+        let value = match value {
+            Some(value) => value,
+            None => return "-".to_owned(),
+        };
+
+        match self {
+            HeaderRedaction::None => value.to_str().unwrap_or("-").to_owned(),
+            HeaderRedaction::Redact => "[redacted]".to_owned(),
+            HeaderRedaction::Mask => format!("[hash:{:08x}]", fnv1a(value.as_bytes()) >> 32),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash. Used over `std`'s hasher so that masked values are stable across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 #[derive(Clone)]
 struct CustomRequestFn {
     inner_fn: Rc<dyn Fn(&ServiceRequest) -> String>,
@@ -604,6 +741,12 @@ impl FormatText {
                 let rt = (rt.whole_nanoseconds() as f64) / 1_000_000.0;
                 fmt.write_fmt(format_args!("{:.6}", rt))
             }
+            FormatText::TimeMicros => {
+                let rt = OffsetDateTime::now_utc() - entry_time;
+                rt.whole_microseconds().fmt(fmt)
+            }
+            // not rendered when body produced no data
+            FormatText::TimeToFirstByte => "-".fmt(fmt),
             FormatText::EnvironHeader(ref name) => {
                 if let Ok(val) = env::var(name) {
                     fmt.write_fmt(format_args!("{}", val))
@@ -621,17 +764,15 @@ impl FormatText {
                 *self = FormatText::Str(format!("{}", res.status().as_u16()))
             }
 
-            FormatText::ResponseHeader(ref name) => {
-                let s = if let Some(val) = res.headers().get(name) {
-                    if let Ok(s) = val.to_str() {
-                        s
-                    } else {
-                        "-"
-                    }
-                } else {
-                    "-"
+            FormatText::ResponseHeader(ref name, redaction) => {
+                *self = FormatText::Str(redaction.render(res.headers().get(name)))
+            }
+
+            FormatText::RequestId => {
+                *self = match res.request().extensions().get::<RequestId>() {
+                    Some(id) => FormatText::Str(id.as_str().to_owned()),
+                    None => FormatText::Str("-".to_owned()),
                 };
-                *self = FormatText::Str(s.to_string())
             }
 
             FormatText::CustomResponse(_, res_fn) => {
@@ -647,6 +788,14 @@ impl FormatText {
         }
     }
 
+    fn render_first_byte(&mut self, entry_time: OffsetDateTime, now: OffsetDateTime) {
+        if let FormatText::TimeToFirstByte = self {
+            let rt = now - entry_time;
+            let rt = (rt.whole_nanoseconds() as f64) / 1_000_000.0;
+            *self = FormatText::Str(format!("{:.6}", rt));
+        }
+    }
+
     fn render_request(&mut self, now: OffsetDateTime, req: &ServiceRequest) {
         match self {
             FormatText::RequestLine => {
@@ -669,17 +818,8 @@ impl FormatText {
             }
             FormatText::UrlPath => *self = FormatText::Str(req.path().to_string()),
             FormatText::RequestTime => *self = FormatText::Str(now.format(&Rfc3339).unwrap()),
-            FormatText::RequestHeader(ref name) => {
-                let s = if let Some(val) = req.headers().get(name) {
-                    if let Ok(s) = val.to_str() {
-                        s
-                    } else {
-                        "-"
-                    }
-                } else {
-                    "-"
-                };
-                *self = FormatText::Str(s.to_string());
+            FormatText::RequestHeader(ref name, redaction) => {
+                *self = FormatText::Str(redaction.render(req.headers().get(name)));
             }
             FormatText::RemoteAddr => {
                 let s = if let Some(peer) = req.connection_info().peer_addr() {
@@ -1011,4 +1151,126 @@ mod tests {
         let req = TestRequest::default().to_srv_request();
         srv.call(req).await.unwrap();
     }
+
+    #[actix_rt::test]
+    async fn test_timing_formats() {
+        let format = Format::new("%{us}T %{TTFB} %{ms}T %{TTFB}i");
+        assert!(matches!(format.0[0], FormatText::TimeMicros));
+        assert!(matches!(format.0[2], FormatText::TimeToFirstByte));
+        assert!(matches!(&format.0[4], FormatText::Str(s) if s == "%{ms}T"));
+        assert!(matches!(format.0[6], FormatText::RequestHeader(_, _)));
+
+        let now = OffsetDateTime::now_utc();
+        let render = |fmt: &mut fmt::Formatter<'_>| {
+            for unit in &format.0[..3] {
+                unit.render(fmt, 1024, now)?;
+            }
+            Ok(())
+        };
+        let s = format!("{}", FormatDisplay(&render));
+        let (micros, ttfb) = s.split_once(' ').unwrap();
+        assert!(micros.parse::<u64>().is_ok());
+        assert_eq!(ttfb, "-");
+    }
+
+    #[actix_rt::test]
+    async fn test_time_to_first_byte() {
+        let srv = |req: ServiceRequest| {
+            std::future::ready(Ok(req.into_response(HttpResponse::Ok().body("hello"))))
+        };
+        let logger = Logger::new("%{TTFB}");
+
+        let srv = logger.new_transform(srv.into_service()).await.unwrap();
+
+        let req = TestRequest::default().to_srv_request();
+        let mut body = srv.call(req).await.unwrap().into_body();
+
+        let ttfb = |body: &StreamLog<_>| body.format.as_ref().unwrap().0[0].clone();
+        assert!(matches!(ttfb(&body), FormatText::TimeToFirstByte));
+
+        let chunk = actix_utils::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), "hello");
+
+        match ttfb(&body) {
+            FormatText::Str(ms) => assert!(ms.parse::<f64>().is_ok()),
+            ft => panic!("expected rendered TTFB, found {:?}", ft),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_header_redaction() {
+        let logger = Logger::new("%{Authorization}i %{COOKIE}i %{Set-Cookie}o %{X-Missing}i")
+            .redact_header("AUTHORIZATION")
+            .mask_header("cookie")
+            .mask_header("set-cookie")
+            .redact_header("x-missing");
+        let mut format = logger.0.format.clone();
+
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .insert_header((header::COOKIE, "session=abc"))
+            .to_srv_request();
+
+        let now = OffsetDateTime::now_utc();
+        for unit in &mut format.0 {
+            unit.render_request(now, &req);
+        }
+
+        let req = TestRequest::default().to_http_request();
+        let res = ServiceResponse::new(
+            req,
+            HttpResponse::Ok()
+                .insert_header((header::SET_COOKIE, "session=abc"))
+                .finish(),
+        );
+        for unit in &mut format.0 {
+            unit.render_response(&res);
+        }
+
+        let render = |fmt: &mut fmt::Formatter<'_>| {
+            for unit in &format.0 {
+                unit.render(fmt, 1024, now)?;
+            }
+            Ok(())
+        };
+        let s = format!("{}", FormatDisplay(&render));
+
+        let hash = format!("[hash:{:08x}]", fnv1a(b"session=abc") >> 32);
+        assert_eq!(s, format!("[redacted] {} {} -", hash, hash));
+        assert!(!s.contains("secret"));
+    }
+
+    #[actix_rt::test]
+    async fn test_request_id() {
+        let mut format = Format::new("%{RequestId} %s");
+
+        let req = TestRequest::default().to_http_request();
+        let res = ServiceResponse::new(req, HttpResponse::Ok().finish());
+        for unit in &mut format.0 {
+            unit.render_response(&res);
+        }
+
+        let req = TestRequest::default().to_http_request();
+        req.extensions_mut()
+            .insert(RequestId::new("abc-123".to_owned()));
+        let res = ServiceResponse::new(req, HttpResponse::Ok().finish());
+        let mut id_format = Format::new("%{RequestId} %s");
+        for unit in &mut id_format.0 {
+            unit.render_response(&res);
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let render = |format: &Format| {
+            let render = |fmt: &mut fmt::Formatter<'_>| {
+                for unit in &format.0 {
+                    unit.render(fmt, 1024, now)?;
+                }
+                Ok(())
+            };
+            format!("{}", FormatDisplay(&render))
+        };
+
+        assert_eq!(render(&format), "- 200");
+        assert_eq!(render(&id_format), "abc-123 200");
+    }
 }
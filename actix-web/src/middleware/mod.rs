@@ -1,5 +1,6 @@
 //! A collection of common middleware.
 
+pub(crate) mod body_tee;
 mod compat;
 mod condition;
 mod default_headers;
@@ -8,7 +9,11 @@ mod logger;
 #[cfg(test)]
 mod noop;
 mod normalize;
+mod payload_limit;
+mod set_request_id;
+mod timeout;
 
+pub use self::body_tee::BodyTee;
 pub use self::compat::Compat;
 pub use self::condition::Condition;
 pub use self::default_headers::DefaultHeaders;
@@ -16,13 +21,21 @@ pub use self::err_handlers::{ErrorHandlerResponse, ErrorHandlers};
 pub use self::logger::Logger;
 #[cfg(test)]
 pub(crate) use self::noop::Noop;
-pub use self::normalize::{NormalizePath, TrailingSlash};
+pub use self::normalize::{NormalizePath, NormalizePathRedirect, TrailingSlash};
+pub use self::payload_limit::PayloadLimit;
+pub use self::set_request_id::SetRequestId;
+pub(crate) use self::timeout::RouteTimeout;
+pub use self::timeout::Timeout;
 
 #[cfg(feature = "__compress")]
 mod compress;
+#[cfg(feature = "__compress")]
+mod decompress_payload;
 
 #[cfg(feature = "__compress")]
-pub use self::compress::Compress;
+pub use self::compress::{Compress, SkipCompression};
+#[cfg(feature = "__compress")]
+pub use self::decompress_payload::DecompressPayload;
 
 #[cfg(test)]
 mod tests {
@@ -1,14 +1,23 @@
-//! For middleware documentation, see [`NormalizePath`].
+//! For middleware documentation, see [`NormalizePath`] and [`NormalizePathRedirect`].
+
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 use actix_http::uri::{PathAndQuery, Uri};
 use actix_service::{Service, Transform};
-use actix_utils::future::{ready, Ready};
 use bytes::Bytes;
+use futures_core::ready;
+use pin_project_lite::pin_project;
 use regex::Regex;
 
 use crate::{
+    body::EitherBody,
+    http::header::{self, HeaderMap},
     service::{ServiceRequest, ServiceResponse},
-    Error,
+    Error, HttpResponse,
 };
 
 /// Determines the behavior of the [`NormalizePath`] middleware.
@@ -53,6 +62,9 @@ impl Default for TrailingSlash {
 /// trailing slashes or else they will be inaccessible (or vice versa when using the
 /// `TrailingSlash::Always` behavior), as shown in the example tests below.
 ///
+/// To redirect clients to the normalized path instead of rewriting it, see
+/// [`NormalizePathRedirect`].
+///
 /// # Examples
 /// ```
 /// use actix_web::{web, middleware, App};
@@ -111,6 +123,17 @@ impl NormalizePath {
     pub fn trim() -> Self {
         Self::new(TrailingSlash::Trim)
     }
+
+    /// Constructs a new [`NormalizePathRedirect`] middleware with [trim](TrailingSlash::Trim)
+    /// semantics.
+    pub fn redirect() -> NormalizePathRedirect {
+        Self::trim().use_redirects()
+    }
+
+    /// Converts into a [`NormalizePathRedirect`] middleware with the same trailing slash style.
+    pub fn use_redirects(self) -> NormalizePathRedirect {
+        NormalizePathRedirect::new(self.0)
+    }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for NormalizePath
@@ -151,58 +174,231 @@ where
     actix_service::forward_ready!(service);
 
     fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        let head = req.head_mut();
-
-        let original_path = head.uri.path();
-
-        // An empty path here means that the URI has no valid path. We skip normalization in this
-        // case, because adding a path can make the URI invalid
-        if !original_path.is_empty() {
-            // Either adds a string to the end (duplicates will be removed anyways) or trims all
-            // slashes from the end
-            let path = match self.trailing_slash_behavior {
-                TrailingSlash::Always => format!("{}/", original_path),
-                TrailingSlash::MergeOnly => original_path.to_string(),
-                TrailingSlash::Trim => original_path.trim_end_matches('/').to_string(),
-            };
-
-            // normalize multiple /'s to one /
-            let path = self.merge_slash.replace_all(&path, "/");
-
-            // Ensure root paths are still resolvable. If resulting path is blank after previous
-            // step it means the path was one or more slashes. Reduce to single slash.
-            let path = if path.is_empty() { "/" } else { path.as_ref() };
-
-            // Check whether the path has been changed
-            //
-            // This check was previously implemented as string length comparison
-            //
-            // That approach fails when a trailing slash is added,
-            // and a duplicate slash is removed,
-            // since the length of the strings remains the same
-            //
-            // For example, the path "/v1//s" will be normalized to "/v1/s/"
-            // Both of the paths have the same length,
-            // so the change can not be deduced from the length comparison
-            if path != original_path {
-                let mut parts = head.uri.clone().into_parts();
-                let query = parts.path_and_query.as_ref().and_then(|pq| pq.query());
-
-                let path = match query {
-                    Some(q) => Bytes::from(format!("{}?{}", path, q)),
-                    None => Bytes::copy_from_slice(path.as_bytes()),
+        if let Some(path_and_query) =
+            normalize_path(&self.merge_slash, self.trailing_slash_behavior, req.uri())
+        {
+            rewrite_path(&mut req, path_and_query);
+        }
+
+        self.service.call(req)
+    }
+}
+
+/// Middleware for redirecting clients to the normalized path of their request.
+///
+/// Paths are normalized in the same way as by [`NormalizePath`]. Instead of rewriting the path
+/// in place, the middleware responds with a `308 Permanent Redirect` to the normalized path
+/// (keeping the original query string) whenever normalization changes it, so that clients converge
+/// on a single canonical URL.
+///
+/// Requests with a body (i.e., those with a non-zero `Content-Length` or a `Transfer-Encoding`
+/// header) are rewritten in place rather than redirected, unless enabled with
+/// [`redirect_with_body`](Self::redirect_with_body()).
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, middleware, App};
+///
+/// # actix_web::rt::System::new().block_on(async {
+/// let app = App::new()
+///     .wrap(middleware::NormalizePath::redirect())
+///     .route("/test", web::get().to(|| async { "test" }));
+///
+/// use actix_web::http::{header, StatusCode};
+/// use actix_web::test::{call_service, init_service, TestRequest};
+///
+/// let app = init_service(app).await;
+///
+/// let req = TestRequest::with_uri("//test/?page=2").to_request();
+/// let res = call_service(&app, req).await;
+/// assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+/// assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/test?page=2");
+/// # })
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizePathRedirect {
+    trailing_slash: TrailingSlash,
+    redirect_with_body: bool,
+}
+
+impl NormalizePathRedirect {
+    /// Constructs a new `NormalizePathRedirect` middleware with the specified trailing slash style.
+    pub fn new(trailing_slash_style: TrailingSlash) -> Self {
+        Self {
+            trailing_slash: trailing_slash_style,
+            redirect_with_body: false,
+        }
+    }
+
+    /// Sets whether requests with a body are also redirected.
+    ///
+    /// When disabled, which is the default, such requests are rewritten in place instead.
+    pub fn redirect_with_body(mut self, enabled: bool) -> Self {
+        self.redirect_with_body = enabled;
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for NormalizePathRedirect
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = NormalizePathRedirectMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(NormalizePathRedirectMiddleware {
+            service,
+            merge_slash: Regex::new("//+").unwrap(),
+            trailing_slash_behavior: self.trailing_slash,
+            redirect_with_body: self.redirect_with_body,
+        }))
+    }
+}
+
+pub struct NormalizePathRedirectMiddleware<S> {
+    service: S,
+    merge_slash: Regex,
+    trailing_slash_behavior: TrailingSlash,
+    redirect_with_body: bool,
+}
+
+impl<S, B> Service<ServiceRequest> for NormalizePathRedirectMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = NormalizePathRedirectFuture<S::Future>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        if let Some(path_and_query) =
+            normalize_path(&self.merge_slash, self.trailing_slash_behavior, req.uri())
+        {
+            if self.redirect_with_body || !has_body(req.headers()) {
+                let res = HttpResponse::PermanentRedirect()
+                    .insert_header((header::LOCATION, path_and_query.as_str()))
+                    .finish();
+
+                return NormalizePathRedirectFuture::Redirect {
+                    res: Some(req.into_response(res)),
                 };
-                parts.path_and_query = Some(PathAndQuery::from_maybe_shared(path).unwrap());
+            }
+
+            rewrite_path(&mut req, path_and_query);
+        }
+
+        NormalizePathRedirectFuture::Service {
+            fut: self.service.call(req),
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    #[project = NormalizePathRedirectProj]
+    pub enum NormalizePathRedirectFuture<F> {
+        Service { #[pin] fut: F },
+        Redirect { res: Option<ServiceResponse> },
+    }
+}
 
-                let uri = Uri::from_parts(parts).unwrap();
-                req.match_info_mut().get_mut().update(&uri);
-                req.head_mut().uri = uri;
+impl<F, B> Future for NormalizePathRedirectFuture<F>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+{
+    type Output = Result<ServiceResponse<EitherBody<B>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            NormalizePathRedirectProj::Service { fut } => {
+                Poll::Ready(Ok(ready!(fut.poll(cx))?.map_into_left_body()))
             }
+            NormalizePathRedirectProj::Redirect { res } => Poll::Ready(Ok(res
+                .take()
+                .expect("NormalizePathRedirectFuture polled after completion")
+                .map_into_right_body())),
         }
-        self.service.call(req)
     }
 }
 
+/// Returns the normalized path and query of `uri`, if normalization changes its path.
+fn normalize_path(
+    merge_slash: &Regex,
+    trailing_slash_behavior: TrailingSlash,
+    uri: &Uri,
+) -> Option<PathAndQuery> {
+    let original_path = uri.path();
+
+    // An empty path here means that the URI has no valid path. We skip normalization in this
+    // case, because adding a path can make the URI invalid
+    if original_path.is_empty() {
+        return None;
+    }
+
+    // Either adds a string to the end (duplicates will be removed anyways) or trims all
+    // slashes from the end
+    let path = match trailing_slash_behavior {
+        TrailingSlash::Always => format!("{}/", original_path),
+        TrailingSlash::MergeOnly => original_path.to_string(),
+        TrailingSlash::Trim => original_path.trim_end_matches('/').to_string(),
+    };
+
+    // normalize multiple /'s to one /
+    let path = merge_slash.replace_all(&path, "/");
+
+    // Ensure root paths are still resolvable. If resulting path is blank after previous
+    // step it means the path was one or more slashes. Reduce to single slash.
+    let path = if path.is_empty() { "/" } else { path.as_ref() };
+
+    // Check whether the path has been changed
+    //
+    // This check was previously implemented as string length comparison
+    //
+    // That approach fails when a trailing slash is added,
+    // and a duplicate slash is removed,
+    // since the length of the strings remains the same
+    //
+    // For example, the path "/v1//s" will be normalized to "/v1/s/"
+    // Both of the paths have the same length,
+    // so the change can not be deduced from the length comparison
+    if path == original_path {
+        return None;
+    }
+
+    let path = match uri.query() {
+        Some(q) => Bytes::from(format!("{}?{}", path, q)),
+        None => Bytes::copy_from_slice(path.as_bytes()),
+    };
+
+    Some(PathAndQuery::from_maybe_shared(path).unwrap())
+}
+
+/// Replaces the path and query of the request's URI and updates its match info accordingly.
+fn rewrite_path(req: &mut ServiceRequest, path_and_query: PathAndQuery) {
+    let mut parts = req.uri().clone().into_parts();
+    parts.path_and_query = Some(path_and_query);
+
+    let uri = Uri::from_parts(parts).unwrap();
+    req.match_info_mut().get_mut().update(&uri);
+    req.head_mut().uri = uri;
+}
+
+/// Returns true if the request headers indicate that a body is present.
+fn has_body(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::TRANSFER_ENCODING)
+        || headers
+            .get(header::CONTENT_LENGTH)
+            .map_or(false, |len| len != "0")
+}
+
 #[cfg(test)]
 mod tests {
     use actix_http::StatusCode;
@@ -486,4 +682,108 @@ mod tests {
         let res = normalize.call(req).await.unwrap();
         assert!(res.status().is_success());
     }
+
+    #[actix_rt::test]
+    async fn redirect_to_normalized_path() {
+        let app = init_service(
+            App::new()
+                .wrap(NormalizePath::redirect())
+                .service(web::resource("/").to(HttpResponse::Ok))
+                .service(web::resource("/v1/something").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let tests = vec![
+            ("///", "/"),
+            ("/v1/something/", "/v1/something"),
+            ("//v1//something", "/v1/something"),
+            ("/v1/something//?query=test", "/v1/something?query=test"),
+        ];
+
+        for (uri, location) in tests {
+            let req = TestRequest::with_uri(uri).to_request();
+            let res = call_service(&app, req).await;
+            assert_eq!(
+                res.status(),
+                StatusCode::PERMANENT_REDIRECT,
+                "Failed uri: {}",
+                uri
+            );
+            assert_eq!(
+                res.headers().get(header::LOCATION).unwrap(),
+                location,
+                "Failed uri: {}",
+                uri
+            );
+        }
+
+        for uri in ["/", "/v1/something", "/v1/something?query=test"] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let res = call_service(&app, req).await;
+            assert_eq!(res.status(), StatusCode::OK, "Failed uri: {}", uri);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn redirect_always_trailing_slash() {
+        let app = init_service(
+            App::new()
+                .wrap(NormalizePath::new(TrailingSlash::Always).use_redirects())
+                .service(web::resource("/v1/something/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/v1//something").to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "/v1/something/"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn redirect_skips_requests_with_body() {
+        let app = init_service(
+            App::new()
+                .wrap(NormalizePath::redirect())
+                .service(web::resource("/v1/something").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/v1/something/")
+            .insert_header((header::CONTENT_LENGTH, 4))
+            .set_payload("data")
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // body-less requests are still redirected, regardless of method
+        let req = TestRequest::post()
+            .uri("/v1/something/")
+            .insert_header((header::CONTENT_LENGTH, 0))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let app = init_service(
+            App::new()
+                .wrap(NormalizePath::redirect().redirect_with_body(true))
+                .service(web::resource("/v1/something").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::put()
+            .uri("/v1/something/")
+            .insert_header((header::CONTENT_LENGTH, 4))
+            .set_payload("data")
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "/v1/something"
+        );
+    }
 }
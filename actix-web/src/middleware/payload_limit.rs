@@ -0,0 +1,305 @@
+//! For middleware documentation, see [`PayloadLimit`].
+
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_http::error::PayloadError;
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+
+use crate::{
+    body::EitherBody,
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::CONTENT_LENGTH,
+    Error, HttpMessage as _, HttpResponse,
+};
+
+/// Middleware for limiting the size of request bodies.
+///
+/// Requests with a `Content-Length` header greater than the limit are rejected with a
+/// `413 Payload Too Large` response before the wrapped service is called.
+///
+/// Since the `Content-Length` header may be missing (e.g., for chunked bodies) or wrong, the
+/// request body stream is also limited. Once more than the limit has been read from it, the stream
+/// yields a [`PayloadError::Overflow`] error, which body extractors turn into a `413 Payload Too
+/// Large` response.
+///
+/// # Overriding The Limit
+/// A `PayloadLimit` registered as app data takes precedence over the middleware's own limit.
+/// Middleware can only see the app data of the service it wraps and of that service's parents, so
+/// route-specific limits should be registered on the scope or resource that is wrapped.
+///
+/// Extractors apply their own limits, such as [`PayloadConfig`](crate::web::PayloadConfig), to
+/// the already-limited body.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::PayloadLimit, web, App, HttpResponse};
+///
+/// let app = App::new().service(
+///     web::scope("/upload")
+///         // limit uploads to 64 MB
+///         .app_data(PayloadLimit::new(64 * 1024 * 1024))
+///         .wrap(PayloadLimit::new(256 * 1024))
+///         .default_service(web::to(HttpResponse::Ok)),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadLimit {
+    limit: usize,
+}
+
+impl PayloadLimit {
+    /// Constructs new middleware that limits request bodies to `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// Returns the limit, in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for PayloadLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = PayloadLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(PayloadLimitMiddleware {
+            service,
+            limit: self.limit,
+        }))
+    }
+}
+
+pub struct PayloadLimitMiddleware<S> {
+    service: S,
+    limit: usize,
+}
+
+impl<S, B> Service<ServiceRequest> for PayloadLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = PayloadLimitFuture<S::Future, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let limit = req
+            .app_data::<PayloadLimit>()
+            .map_or(self.limit, PayloadLimit::limit);
+
+        let length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok())
+            .and_then(|len| len.parse::<u64>().ok());
+
+        if matches!(length, Some(length) if length > limit as u64) {
+            let res = HttpResponse::PayloadTooLarge()
+                .body(format!("Payload exceeds the limit of {} bytes.", limit));
+
+            return PayloadLimitFuture::Rejected {
+                res: Some(req.into_response(res).map_into_right_body()),
+            };
+        }
+
+        let payload = req.take_payload();
+        req.set_payload(Payload::Stream {
+            payload: Box::pin(LimitedPayload::new(payload, limit)),
+        });
+
+        PayloadLimitFuture::Service {
+            fut: self.service.call(req),
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    #[project = PayloadLimitProj]
+    pub enum PayloadLimitFuture<F, B> {
+        Rejected { res: Option<ServiceResponse<EitherBody<B>>> },
+        Service { #[pin] fut: F },
+    }
+}
+
+impl<F, B> Future for PayloadLimitFuture<F, B>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+{
+    type Output = Result<ServiceResponse<EitherBody<B>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            PayloadLimitProj::Rejected { res } => {
+                Poll::Ready(Ok(res.take().expect("future polled after completion")))
+            }
+            PayloadLimitProj::Service { fut } => {
+                Poll::Ready(Ok(ready!(fut.poll(cx))?.map_into_left_body()))
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Request body stream that errors once more than a limit has been read from it.
+    pub(super) struct LimitedPayload {
+        #[pin]
+        payload: Payload,
+        remaining: usize,
+        overflowed: bool,
+    }
+}
+
+impl LimitedPayload {
+    pub(super) fn new(payload: Payload, limit: usize) -> Self {
+        Self {
+            payload,
+            remaining: limit,
+            overflowed: false,
+        }
+    }
+}
+
+impl Stream for LimitedPayload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if *this.overflowed {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.payload.poll_next(cx)) {
+            Some(Ok(chunk)) if chunk.len() > *this.remaining => {
+                *this.overflowed = true;
+                Poll::Ready(Some(Err(PayloadError::Overflow)))
+            }
+            Some(Ok(chunk)) => {
+                *this.remaining -= chunk.len();
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            item => Poll::Ready(item),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    async fn echo(body: web::Bytes) -> HttpResponse {
+        HttpResponse::Ok().body(body)
+    }
+
+    fn chunked(chunks: &'static [&'static [u8]]) -> Payload {
+        let stream = stream::iter(chunks.iter().map(|chunk| Ok(Bytes::from_static(chunk))));
+        Payload::Stream {
+            payload: Box::pin(stream),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn content_length() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(PayloadLimit::new(4))
+                .default_service(web::to(echo)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_LENGTH, "4"))
+            .set_payload("1234")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "1234");
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_LENGTH, "5"))
+            .set_payload("12345")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            test::read_body(res).await,
+            "Payload exceeds the limit of 4 bytes."
+        );
+    }
+
+    #[actix_rt::test]
+    async fn stream() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(PayloadLimit::new(4))
+                .default_service(web::to(echo)),
+        )
+        .await;
+
+        let (req, _) = TestRequest::post()
+            .to_request()
+            .replace_payload(chunked(&[b"12", b"34"]));
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "1234");
+
+        // content length is not to be trusted
+        let (req, _) = TestRequest::post()
+            .insert_header((CONTENT_LENGTH, "2"))
+            .to_request()
+            .replace_payload(chunked(&[b"12", b"34", b"5"]));
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[actix_rt::test]
+    async fn app_data_override() {
+        let srv = test::init_service(
+            App::new().service(
+                web::scope("/upload")
+                    .app_data(PayloadLimit::new(8))
+                    .wrap(PayloadLimit::new(4))
+                    .default_service(web::to(echo)),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/upload")
+            .set_payload("12345678")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::post()
+            .uri("/upload")
+            .set_payload("123456789")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,296 @@
+//! For middleware documentation, see [`SetRequestId`].
+
+use std::{
+    fmt::{self, Write as _},
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use rand::RngCore as _;
+
+use crate::{
+    dev::{Service, Transform},
+    http::header::{HeaderName, HeaderValue},
+    request_id::RequestId,
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpMessage as _,
+};
+
+/// Middleware for assigning a correlation ID to each request.
+///
+/// The ID is taken from the request's `X-Request-Id` header, if present, or otherwise produced by
+/// the ID generator, which creates random (version 4) UUIDs by default. It is then:
+/// - stored in the request extensions, from where handlers can read it using the
+///   [`web::RequestId`](crate::web::RequestId) extractor;
+/// - available to the [`Logger`](super::Logger) middleware with the `%{RequestId}` format token;
+/// - echoed on the response in the same header.
+///
+/// Inbound IDs are accepted as-is, as long as they are non-empty and only contain visible ASCII
+/// characters. When requests can come from untrusted clients, consider stripping the header at the
+/// edge of the network.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::HeaderName, middleware, web, App};
+///
+/// let app = App::new()
+///     .wrap(middleware::Logger::new("%{RequestId} %r %s"))
+///     .wrap(
+///         middleware::SetRequestId::new()
+///             .header(HeaderName::from_static("x-correlation-id"))
+///             .generator(|| format!("req-{}", rand::random::<u32>())),
+///     )
+///     .route("/", web::get().to(|id: web::RequestId| async move { id.into_inner() }));
+/// ```
+#[derive(Clone)]
+pub struct SetRequestId {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    header: HeaderName,
+    generator: Box<dyn Fn() -> String>,
+}
+
+impl Default for SetRequestId {
+    fn default() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                header: HeaderName::from_static("x-request-id"),
+                generator: Box::new(uuid_v4),
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for SetRequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetRequestId")
+            .field("header", &self.inner.header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SetRequestId {
+    /// Constructs new `SetRequestId` middleware using the `X-Request-Id` header and UUID IDs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the header that is read from requests and set on responses.
+    pub fn header(mut self, header: HeaderName) -> Self {
+        Rc::get_mut(&mut self.inner)
+            .expect("SetRequestId must be configured before it is cloned")
+            .header = header;
+        self
+    }
+
+    /// Sets the function used to generate IDs for requests without one.
+    ///
+    /// Generated IDs should only contain visible ASCII characters; otherwise they are still made
+    /// available to handlers and the logger, but are not echoed on the response.
+    pub fn generator<F>(mut self, generator: F) -> Self
+    where
+        F: Fn() -> String + 'static,
+    {
+        Rc::get_mut(&mut self.inner)
+            .expect("SetRequestId must be configured before it is cloned")
+            .generator = Box::new(generator);
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SetRequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = SetRequestIdMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SetRequestIdMiddleware {
+            service,
+            inner: Rc::clone(&self.inner),
+        }))
+    }
+}
+
+pub struct SetRequestIdMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for SetRequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = SetRequestIdFuture<S, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let inbound = req
+            .headers()
+            .get(&self.inner.header)
+            .filter(|val| !val.is_empty())
+            .and_then(|val| val.to_str().ok().map(|id| (id.to_owned(), val.clone())));
+
+        let (id, value) = match inbound {
+            Some((id, val)) => (id, Some(val)),
+            None => {
+                let id = (self.inner.generator)();
+                let val = HeaderValue::from_str(&id).ok();
+                (id, val)
+            }
+        };
+
+        req.extensions_mut().insert(RequestId::new(id));
+
+        SetRequestIdFuture {
+            fut: self.service.call(req),
+            header: value.map(|val| (self.inner.header.clone(), val)),
+            _body: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    pub struct SetRequestIdFuture<S: Service<ServiceRequest>, B> {
+        #[pin]
+        fut: S::Future,
+        header: Option<(HeaderName, HeaderValue)>,
+        _body: PhantomData<B>,
+    }
+}
+
+impl<S, B> Future for SetRequestIdFuture<S, B>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Output = <S::Future as Future>::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut res = ready!(this.fut.poll(cx))?;
+
+        if let Some((name, value)) = this.header.take() {
+            res.headers_mut().insert(name, value);
+        }
+
+        Poll::Ready(Ok(res))
+    }
+}
+
+/// Generates a random (version 4) UUID in its hyphenated string form.
+fn uuid_v4() -> String {
+    let mut bytes = [0; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    // version 4, RFC 4122 variant
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let mut id = String::with_capacity(36);
+
+    for (idx, byte) in bytes.iter().enumerate() {
+        if matches!(idx, 4 | 6 | 8 | 10) {
+            id.push('-');
+        }
+
+        let _ = write!(id, "{:02x}", byte);
+    }
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        middleware::Logger,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    #[test]
+    fn uuid_format() {
+        let id = uuid_v4();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.as_bytes()[14], b'4');
+        assert!(matches!(id.as_bytes()[19], b'8' | b'9' | b'a' | b'b'));
+        assert_eq!(id.matches('-').count(), 4);
+        assert_ne!(id, uuid_v4());
+    }
+
+    #[actix_rt::test]
+    async fn generates_and_propagates() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(Logger::new("%{RequestId}"))
+                .wrap(SetRequestId::new())
+                .route(
+                    "/",
+                    web::get().to(|id: web::RequestId| async move { id.into_inner() }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let id = res.headers().get("x-request-id").unwrap().clone();
+        assert_eq!(id.len(), 36);
+        assert_eq!(test::read_body(res).await, id.as_bytes());
+
+        let req = TestRequest::default()
+            .insert_header(("x-request-id", "abc-123"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get("x-request-id").unwrap(), "abc-123");
+        assert_eq!(test::read_body(res).await, "abc-123");
+    }
+
+    #[actix_rt::test]
+    async fn custom_header_and_generator() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(
+                    SetRequestId::new()
+                        .header(HeaderName::from_static("x-correlation-id"))
+                        .generator(|| "generated".to_owned()),
+                )
+                .default_service(web::to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .insert_header(("x-request-id", "ignored"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get("x-correlation-id").unwrap(), "generated");
+        assert!(!res.headers().contains_key("x-request-id"));
+    }
+
+    #[actix_rt::test]
+    async fn missing_middleware() {
+        let srv = test::init_service(
+            App::new().default_service(web::to(|_: web::RequestId| HttpResponse::Ok())),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
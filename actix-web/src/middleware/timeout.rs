@@ -0,0 +1,374 @@
+//! For middleware documentation, see [`Timeout`].
+
+use std::{
+    cell::Cell,
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_rt::time::{sleep, Instant, Sleep};
+use pin_project_lite::pin_project;
+
+use crate::{
+    dev::{Extensions, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::StatusCode,
+    Error, HttpMessage as _,
+};
+
+/// Middleware for limiting how long the wrapped service may take to produce a response.
+///
+/// If the wrapped service does not produce a response within the timeout, its future is dropped,
+/// cancelling the handler at its current await point, and an error is returned instead. The
+/// error's response has the status `504 Gateway Timeout`, unless configured otherwise using
+/// [`status`](Self::status).
+///
+/// The timeout only covers producing the response head. Once the wrapped service has returned a
+/// response, its body is streamed to the client without a deadline; the timeout is never applied
+/// to responses that have started being sent.
+///
+/// # Overriding The Timeout
+/// A `Timeout` registered as app data, on a scope, resource, or route, takes precedence over the
+/// middleware's own timeout and status; the most specific one is used. Since a route's app data is
+/// only available once the route is called, a timeout registered on a route replaces the
+/// middleware's timer at that point, counting from when the middleware was called.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{middleware::Timeout, web, App, HttpResponse};
+///
+/// let timeout = Timeout::new(Duration::from_secs(5));
+///
+/// let app = App::new()
+///     .service(
+///         web::scope("/reports")
+///             // reports are slow to generate
+///             .app_data(Timeout::new(Duration::from_secs(60)))
+///             .wrap(timeout)
+///             .default_service(web::to(HttpResponse::Ok)),
+///     )
+///     .service(
+///         web::scope("/api")
+///             .wrap(timeout)
+///             .route("/users", web::get().to(HttpResponse::Ok))
+///             .route(
+///                 "/export",
+///                 web::get()
+///                     .app_data(Timeout::new(Duration::from_secs(30)))
+///                     .to(HttpResponse::Ok),
+///             ),
+///     );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    timeout: Duration,
+    status: StatusCode,
+}
+
+impl Timeout {
+    /// Constructs new middleware that times out responses after `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            status: StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// Sets the status code of the error response sent when the timeout expires.
+    ///
+    /// Defaults to `504 Gateway Timeout`.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Returns the timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Timeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TimeoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TimeoutMiddleware {
+            service,
+            timeout: *self,
+        }))
+    }
+}
+
+pub struct TimeoutMiddleware<S> {
+    service: S,
+    timeout: Timeout,
+}
+
+impl<S, B> Service<ServiceRequest> for TimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = TimeoutFuture<S::Future, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let timeout = req.app_data::<Timeout>().copied().unwrap_or(self.timeout);
+
+        // shared with outer timeout middleware, if any
+        let route_timeout = req.req_data::<RouteTimeout>().unwrap_or_default();
+        req.extensions_mut().insert(route_timeout.clone());
+
+        TimeoutFuture {
+            status: timeout.status,
+            start: Instant::now(),
+            sleep: sleep(timeout.timeout),
+            route_timeout,
+            fut: self.service.call(req),
+            _body: PhantomData,
+        }
+    }
+}
+
+/// Request-local slot through which the route handling a request passes its `Timeout` app data
+/// to the timeout middleware wrapping it.
+#[derive(Clone, Default)]
+pub(crate) struct RouteTimeout(Rc<Cell<Option<Timeout>>>);
+
+impl RouteTimeout {
+    /// Passes the `Timeout` in a route's app data, if any, to the request's timeout middleware.
+    pub(crate) fn set(req: &ServiceRequest, route_data: &Extensions) {
+        if let Some(timeout) = route_data.get::<Timeout>() {
+            if let Some(slot) = req.extensions().get::<RouteTimeout>() {
+                slot.0.set(Some(*timeout));
+            }
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    pub struct TimeoutFuture<F, B> {
+        // dropped once the timeout expires
+        #[pin]
+        fut: F,
+        #[pin]
+        sleep: Sleep,
+        start: Instant,
+        status: StatusCode,
+        route_timeout: RouteTimeout,
+        _body: PhantomData<B>,
+    }
+}
+
+impl<F, B> Future for TimeoutFuture<F, B>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+{
+    type Output = Result<ServiceResponse<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Poll::Ready(res) = this.fut.poll(cx) {
+            return Poll::Ready(res);
+        }
+
+        if let Some(timeout) = this.route_timeout.0.take() {
+            *this.status = timeout.status;
+            this.sleep.as_mut().reset(*this.start + timeout.timeout);
+        }
+
+        match this.sleep.poll(cx) {
+            Poll::Ready(()) => {
+                log::debug!("Request timed out; responding with {}", this.status);
+                Poll::Ready(Err(
+                    InternalError::new("Request timed out.", *this.status).into()
+                ))
+            }
+
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+    use crate::{
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    async fn slow() -> &'static str {
+        actix_rt::time::sleep(Duration::from_millis(200)).await;
+        "done"
+    }
+
+    #[actix_rt::test]
+    async fn completes_in_time() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(Timeout::new(Duration::from_secs(5)))
+                .default_service(web::to(|| async { "hello" })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "hello");
+    }
+
+    #[actix_rt::test]
+    async fn times_out_and_cancels() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPPED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(
+                    Timeout::new(Duration::from_millis(10))
+                        .status(StatusCode::SERVICE_UNAVAILABLE),
+                )
+                .default_service(web::to(|| async {
+                    let _guard = Guard;
+                    slow().await
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let err = test::try_call_service(&srv, req).await.unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert!(DROPPED.load(Ordering::SeqCst));
+    }
+
+    #[actix_rt::test]
+    async fn app_data_override() {
+        let srv = test::init_service(
+            App::new()
+                .service(
+                    web::scope("/reports")
+                        .app_data(Timeout::new(Duration::from_secs(5)))
+                        .wrap(Timeout::new(Duration::from_millis(10)))
+                        .default_service(web::to(slow)),
+                )
+                .service(
+                    web::scope("")
+                        .wrap(Timeout::new(Duration::from_millis(10)))
+                        .default_service(web::to(slow)),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/reports").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/other").to_request();
+        let err = test::try_call_service(&srv, req).await.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(err.to_string(), "Request timed out.");
+    }
+
+    #[actix_rt::test]
+    async fn route_app_data_override() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(Timeout::new(Duration::from_millis(10)))
+                .route(
+                    "/slow",
+                    web::get()
+                        .app_data(Timeout::new(Duration::from_secs(5)))
+                        .to(slow),
+                )
+                .route(
+                    "/fast",
+                    web::get()
+                        .app_data(
+                            Timeout::new(Duration::from_millis(10))
+                                .status(StatusCode::SERVICE_UNAVAILABLE),
+                        )
+                        .to(slow),
+                )
+                .route("/default", web::get().to(slow)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/slow").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "done");
+
+        let req = TestRequest::with_uri("/fast").to_request();
+        let err = test::try_call_service(&srv, req).await.unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let req = TestRequest::with_uri("/default").to_request();
+        let err = test::try_call_service(&srv, req).await.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[actix_rt::test]
+    async fn body_not_timed_out() {
+        let polled = Rc::new(Cell::new(false));
+        let polled2 = Rc::clone(&polled);
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(Timeout::new(Duration::from_millis(10)))
+                .default_service(web::to(move || {
+                    let polled = Rc::clone(&polled2);
+
+                    let body = futures_util::stream::once(async move {
+                        actix_rt::time::sleep(Duration::from_millis(50)).await;
+                        polled.set(true);
+                        Ok::<_, Error>(web::Bytes::from_static(b"late"))
+                    });
+
+                    HttpResponse::Ok().streaming(body)
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "late");
+        assert!(polled.get());
+    }
+}
@@ -0,0 +1,241 @@
+//! See [`Negotiate`] for responder documentation.
+
+use std::{fmt, ops::Deref};
+
+use mime::Mime;
+use serde::Serialize;
+
+use crate::{
+    body::EitherBody,
+    error::JsonPayloadError,
+    http::{
+        header::{Accept, Header as _, Quality},
+        StatusCode,
+    },
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// Content-negotiating responder that renders either JSON or HTML.
+///
+/// The wrapped value is serialized to `application/json` using its `Serialize` implementation or
+/// rendered to `text/html` using its `Display` implementation, depending on which of the two is
+/// preferred by the request's `Accept` header. The `Display` implementation is responsible for
+/// producing well-formed HTML, including escaping any untrusted content.
+///
+/// Media ranges are matched as described in [RFC 7231 §5.3.2]: each format is given the q-factor
+/// of the most specific range that matches it (e.g., `text/html` over `text/*` over `*/*`) and the
+/// format with the highest non-zero q-factor is chosen. JSON is chosen when both formats are
+/// equally preferred, or when the request has no valid `Accept` header.
+///
+/// If neither format is acceptable, a `406 Not Acceptable` response is sent instead.
+///
+/// # Examples
+/// ```
+/// use std::fmt;
+/// use actix_web::{web, Responder};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     id: u32,
+/// }
+///
+/// impl fmt::Display for User {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "<h1>User #{}</h1>", self.id)
+///     }
+/// }
+///
+/// async fn handler() -> impl Responder {
+///     // `{"id":42}` when the client prefers JSON, `<h1>User #42</h1>` when it prefers HTML
+///     web::Negotiate(User { id: 42 })
+/// }
+/// # web::to(handler);
+/// ```
+///
+/// [RFC 7231 §5.3.2]: https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Negotiate<T>(pub T);
+
+impl<T> Negotiate<T> {
+    /// Unwraps into inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Negotiate<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Formats that [`Negotiate`] can produce, in order of server preference.
+const FORMATS: [Mime; 2] = [mime::APPLICATION_JSON, mime::TEXT_HTML];
+
+/// Returns the index into [`FORMATS`] of the format preferred by `accept`, if any is acceptable.
+fn negotiate(accept: Option<&Accept>) -> Option<usize> {
+    let accept = match accept {
+        Some(accept) if !accept.is_empty() => accept,
+        _ => return Some(0),
+    };
+
+    let mut best = None;
+    let mut best_quality = Quality::ZERO;
+
+    for (idx, format) in FORMATS.iter().enumerate() {
+        let quality = format_quality(accept, format);
+
+        // only change if strictly greater so that ties go to the server's preferred format
+        if quality > best_quality {
+            best = Some(idx);
+            best_quality = quality;
+        }
+    }
+
+    best
+}
+
+/// Returns the q-factor given to `format` by the most specific matching media range.
+fn format_quality(accept: &Accept, format: &Mime) -> Quality {
+    let mut best: Option<(u8, Quality)> = None;
+
+    for range in accept.iter() {
+        let specificity = match (range.item.type_(), range.item.subtype()) {
+            (mime::STAR, _) => 0,
+            (ty, mime::STAR) if ty == format.type_() => 1,
+            (ty, subty) if ty == format.type_() && subty == format.subtype() => 2,
+            _ => continue,
+        };
+
+        // first occurrence wins among equally specific ranges
+        if best.map_or(true, |(best_specificity, _)| specificity > best_specificity) {
+            best = Some((specificity, range.quality));
+        }
+    }
+
+    best.map_or(Quality::ZERO, |(_, quality)| quality)
+}
+
+impl<T> Responder for Negotiate<T>
+where
+    T: Serialize + fmt::Display,
+{
+    type Body = EitherBody<String>;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let accept = Accept::parse(req).ok();
+
+        let (content_type, body) = match negotiate(accept.as_ref()) {
+            Some(0) => match serde_json::to_string(&self.0) {
+                Ok(body) => (mime::APPLICATION_JSON, body),
+                Err(err) => {
+                    return HttpResponse::from_error(JsonPayloadError::Serialize(err))
+                        .map_into_right_body();
+                }
+            },
+
+            Some(_) => (mime::TEXT_HTML_UTF_8, self.0.to_string()),
+
+            None => {
+                return HttpResponse::new(StatusCode::NOT_ACCEPTABLE).map_into_right_body();
+            }
+        };
+
+        match HttpResponse::Ok()
+            .content_type(content_type)
+            .message_body(body)
+        {
+            Ok(res) => res.map_into_left_body(),
+            Err(err) => HttpResponse::from_error(err).map_into_right_body(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        body,
+        http::header::{self, HeaderValue},
+        test::TestRequest,
+    };
+
+    #[derive(Serialize)]
+    struct Page {
+        title: &'static str,
+    }
+
+    impl fmt::Display for Page {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "<h1>{}</h1>", self.title)
+        }
+    }
+
+    async fn respond(
+        accept: Option<&'static str>,
+    ) -> (StatusCode, Option<HeaderValue>, String) {
+        let mut req = TestRequest::default();
+
+        if let Some(accept) = accept {
+            req = req.insert_header((header::ACCEPT, accept));
+        }
+
+        let res = Negotiate(Page { title: "hello" }).respond_to(&req.to_http_request());
+        let status = res.status();
+        let content_type = res.headers().get(header::CONTENT_TYPE).cloned();
+        let body = body::to_bytes(res.into_body()).await.unwrap();
+
+        (
+            status,
+            content_type,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn json_by_default() {
+        for accept in [
+            None,
+            Some(""),
+            Some("*/*"),
+            Some("application/json, text/html"),
+        ] {
+            let (status, content_type, body) = respond(accept).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type.unwrap(), "application/json");
+            assert_eq!(body, r#"{"title":"hello"}"#);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn html_when_preferred() {
+        for accept in [
+            "text/html",
+            "text/*",
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+            "application/json;q=0.5, text/html",
+            "application/json;q=0, */*",
+        ] {
+            let (status, content_type, body) = respond(Some(accept)).await;
+            assert_eq!(status, StatusCode::OK, "Accept: {}", accept);
+            assert_eq!(content_type.unwrap(), "text/html; charset=utf-8");
+            assert_eq!(body, "<h1>hello</h1>");
+        }
+    }
+
+    #[actix_rt::test]
+    async fn not_acceptable() {
+        for accept in [
+            "image/png",
+            "text/plain",
+            "text/html;q=0, application/*;q=0",
+        ] {
+            let (status, content_type, _) = respond(Some(accept)).await;
+            assert_eq!(status, StatusCode::NOT_ACCEPTABLE, "Accept: {}", accept);
+            assert!(content_type.is_none());
+        }
+    }
+}
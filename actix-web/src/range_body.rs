@@ -0,0 +1,290 @@
+//! See [`RangeBody`] for responder documentation.
+
+use bytes::{BufMut as _, Bytes, BytesMut};
+use mime::Mime;
+
+use crate::{
+    http::{
+        header::{self, ContentRange, ContentRangeSpec, ContentType, Header as _, Range},
+        Method,
+    },
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// Responder for in-memory bytes that supports range requests.
+///
+/// Parses the request's `Range` header and responds with the requested part(s) of the body:
+/// - without a (valid) `Range` header, the full body is sent with `200 OK`;
+/// - if one range is satisfiable, that range is sent with `206 Partial Content` and a
+///   `Content-Range` header;
+/// - if multiple ranges are satisfiable, they are sent with `206 Partial Content` as a
+///   `multipart/byteranges` body;
+/// - if no range is satisfiable, `416 Range Not Satisfiable` is sent with a `Content-Range` header
+///   carrying the body's length.
+///
+/// All responses set `Accept-Ranges: bytes`. Ranges are only honored for `GET` requests, as
+/// described in [RFC 7233 §3.1]. Overlapping and adjacent ranges are coalesced and sent in
+/// ascending order.
+///
+/// Use `actix-files` for serving ranges of files from disk.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web::{Bytes, RangeBody}, Responder};
+///
+/// async fn handler() -> impl Responder {
+///     // e.g., a blob loaded from a database
+///     let blob = Bytes::from_static(b"hello world");
+///
+///     RangeBody::new(blob).content_type(mime::TEXT_PLAIN)
+/// }
+/// # actix_web::web::to(handler);
+/// ```
+///
+/// [RFC 7233 §3.1]: https://datatracker.ietf.org/doc/html/rfc7233#section-3.1
+#[derive(Debug, Clone)]
+pub struct RangeBody {
+    body: Bytes,
+    content_type: Mime,
+}
+
+impl RangeBody {
+    /// Constructs new range responder for the given body.
+    ///
+    /// The content type defaults to `application/octet-stream`.
+    pub fn new(body: impl Into<Bytes>) -> Self {
+        Self {
+            body: body.into(),
+            content_type: mime::APPLICATION_OCTET_STREAM,
+        }
+    }
+
+    /// Sets the content type of the body.
+    ///
+    /// For multi-range responses, this content type is set on each part.
+    pub fn content_type(mut self, content_type: Mime) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Returns the satisfiable, coalesced, end-inclusive ranges requested by `req`.
+    ///
+    /// Returns `None` if the request does not contain a `Range` header that should be honored.
+    fn requested_ranges(&self, req: &HttpRequest) -> Option<Vec<(u64, u64)>> {
+        if req.method() != Method::GET {
+            return None;
+        }
+
+        let specs = match Range::parse(req) {
+            Ok(Range::Bytes(specs)) => specs,
+            _ => return None,
+        };
+
+        let len = self.body.len() as u64;
+
+        let mut ranges = specs
+            .iter()
+            .filter_map(|spec| spec.to_satisfiable_range(len))
+            .collect::<Vec<_>>();
+
+        ranges.sort_unstable();
+
+        let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+
+        for (start, end) in ranges {
+            match coalesced.last_mut() {
+                Some((_, last_end)) if start <= *last_end + 1 => {
+                    *last_end = (*last_end).max(end);
+                }
+                _ => coalesced.push((start, end)),
+            }
+        }
+
+        Some(coalesced)
+    }
+
+    fn slice(&self, (start, end): (u64, u64)) -> Bytes {
+        self.body.slice(start as usize..=end as usize)
+    }
+
+    fn content_range(&self, range: Option<(u64, u64)>) -> ContentRange {
+        ContentRange(ContentRangeSpec::Bytes {
+            range,
+            instance_length: Some(self.body.len() as u64),
+        })
+    }
+
+    fn multipart_body(&self, ranges: &[(u64, u64)]) -> (String, Bytes) {
+        let boundary = byteranges_boundary(&self.body);
+        let mut body = BytesMut::new();
+
+        for &range in ranges {
+            body.put_slice(b"--");
+            body.put_slice(boundary.as_bytes());
+            body.put_slice(b"\r\nContent-Type: ");
+            body.put_slice(self.content_type.as_ref().as_bytes());
+            body.put_slice(b"\r\nContent-Range: ");
+            body.put_slice(self.content_range(Some(range)).to_string().as_bytes());
+            body.put_slice(b"\r\n\r\n");
+            body.put_slice(&self.slice(range));
+            body.put_slice(b"\r\n");
+        }
+
+        body.put_slice(b"--");
+        body.put_slice(boundary.as_bytes());
+        body.put_slice(b"--\r\n");
+
+        (boundary, body.freeze())
+    }
+}
+
+/// Generates a boundary that does not occur in `body`.
+fn byteranges_boundary(body: &[u8]) -> String {
+    (0..)
+        .map(|n| format!("actix-web-byteranges-{:08x}", n))
+        .find(|boundary| {
+            !body
+                .windows(boundary.len())
+                .any(|window| window == boundary.as_bytes())
+        })
+        .unwrap()
+}
+
+impl Responder for RangeBody {
+    type Body = Bytes;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let (mut res, body) = match self.requested_ranges(req) {
+            None => {
+                let mut res = HttpResponse::Ok();
+                res.insert_header(ContentType(self.content_type.clone()));
+                (res, self.body.clone())
+            }
+
+            Some(ranges) if ranges.is_empty() => {
+                let mut res = HttpResponse::RangeNotSatisfiable();
+                res.insert_header(self.content_range(None));
+                (res, Bytes::new())
+            }
+
+            Some(ranges) if ranges.len() == 1 => {
+                let mut res = HttpResponse::PartialContent();
+                res.insert_header(ContentType(self.content_type.clone()))
+                    .insert_header(self.content_range(Some(ranges[0])));
+                (res, self.slice(ranges[0]))
+            }
+
+            Some(ranges) => {
+                let (boundary, body) = self.multipart_body(&ranges);
+
+                let mut res = HttpResponse::PartialContent();
+                res.insert_header((
+                    header::CONTENT_TYPE,
+                    format!("multipart/byteranges; boundary={}", boundary),
+                ));
+                (res, body)
+            }
+        };
+
+        res.insert_header((header::ACCEPT_RANGES, "bytes"))
+            .message_body(body)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{body, http::StatusCode, test::TestRequest};
+
+    async fn respond(req: TestRequest) -> HttpResponse<Bytes> {
+        RangeBody::new("0123456789")
+            .content_type(mime::TEXT_PLAIN)
+            .respond_to(&req.to_http_request())
+    }
+
+    fn header(res: &HttpResponse<Bytes>, name: header::HeaderName) -> &str {
+        res.headers().get(name).unwrap().to_str().unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn full_body() {
+        for req in [
+            TestRequest::get(),
+            TestRequest::get().insert_header((header::RANGE, "bytes=foo")),
+            TestRequest::get().insert_header((header::RANGE, "lines=1-2")),
+            TestRequest::post().insert_header((header::RANGE, "bytes=0-1")),
+        ] {
+            let res = respond(req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(header(&res, header::ACCEPT_RANGES), "bytes");
+            assert_eq!(header(&res, header::CONTENT_TYPE), "text/plain");
+            assert!(res.headers().get(header::CONTENT_RANGE).is_none());
+            assert_eq!(body::to_bytes(res.into_body()).await.unwrap(), "0123456789");
+        }
+    }
+
+    #[actix_rt::test]
+    async fn single_range() {
+        for (range, content_range, body) in [
+            ("bytes=2-4", "bytes 2-4/10", "234"),
+            ("bytes=7-", "bytes 7-9/10", "789"),
+            ("bytes=-2", "bytes 8-9/10", "89"),
+            ("bytes=8-100", "bytes 8-9/10", "89"),
+            ("bytes=0-1,2-3,1-2", "bytes 0-3/10", "0123"),
+            ("bytes=20-30,1-1", "bytes 1-1/10", "1"),
+        ] {
+            let res = respond(TestRequest::get().insert_header((header::RANGE, range))).await;
+            assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT, "{}", range);
+            assert_eq!(header(&res, header::ACCEPT_RANGES), "bytes");
+            assert_eq!(header(&res, header::CONTENT_TYPE), "text/plain");
+            assert_eq!(header(&res, header::CONTENT_RANGE), content_range);
+            assert_eq!(body::to_bytes(res.into_body()).await.unwrap(), body);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn multiple_ranges() {
+        let req = TestRequest::get().insert_header((header::RANGE, "bytes=7-8,0-1"));
+        let res = respond(req).await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            header(&res, header::CONTENT_TYPE),
+            "multipart/byteranges; boundary=actix-web-byteranges-00000000"
+        );
+        assert_eq!(
+            body::to_bytes(res.into_body()).await.unwrap(),
+            "--actix-web-byteranges-00000000\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Range: bytes 0-1/10\r\n\r\n\
+             01\r\n\
+             --actix-web-byteranges-00000000\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Range: bytes 7-8/10\r\n\r\n\
+             78\r\n\
+             --actix-web-byteranges-00000000--\r\n"
+        );
+
+        // boundary is changed when it would occur in the body
+        let res = RangeBody::new("actix-web-byteranges-00000000").respond_to(
+            &TestRequest::get()
+                .insert_header((header::RANGE, "bytes=0-0,2-2"))
+                .to_http_request(),
+        );
+        assert_eq!(
+            header(&res, header::CONTENT_TYPE),
+            "multipart/byteranges; boundary=actix-web-byteranges-00000001"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn unsatisfiable() {
+        for range in ["bytes=10-", "bytes=20-30", "bytes=-0"] {
+            let res = respond(TestRequest::get().insert_header((header::RANGE, range))).await;
+            assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE, "{}", range);
+            assert_eq!(header(&res, header::ACCEPT_RANGES), "bytes");
+            assert_eq!(header(&res, header::CONTENT_RANGE), "bytes */10");
+            assert!(body::to_bytes(res.into_body()).await.unwrap().is_empty());
+        }
+    }
+}
@@ -46,6 +46,7 @@ pub struct Redirect {
     from: Cow<'static, str>,
     to: Cow<'static, str>,
     status_code: StatusCode,
+    preserve_query: bool,
 }
 
 impl Redirect {
@@ -57,8 +58,9 @@ impl Redirect {
     /// a normal `Resource` or `Route`.
     ///
     /// The `to` argument can be path or URL; whatever is provided shall be used verbatim when
-    /// setting the redirect location. This means that relative paths can be used to navigate
-    /// relatively to matched paths.
+    /// setting the redirect location, except that characters not allowed in URLs, such as spaces
+    /// and non-ASCII characters, are percent-encoded. This means that relative paths can be used to
+    /// navigate relatively to matched paths.
     ///
     /// Prefer [`Redirect::to()`](Self::to) when using `Redirect` as a responder since `from` has
     /// no meaning in that context.
@@ -75,6 +77,7 @@ impl Redirect {
             from: from.into(),
             to: to.into(),
             status_code: StatusCode::TEMPORARY_REDIRECT,
+            preserve_query: false,
         }
     }
 
@@ -97,6 +100,7 @@ impl Redirect {
             from: "/".into(),
             to: to.into(),
             status_code: StatusCode::TEMPORARY_REDIRECT,
+            preserve_query: false,
         }
     }
 
@@ -145,6 +149,22 @@ impl Redirect {
         self.status_code = status;
         self
     }
+
+    /// Appends the query string of the request being redirected to the redirect location.
+    ///
+    /// If the location already has a query string, the request's query string is appended to it,
+    /// separated by `&`.
+    ///
+    /// ```
+    /// # use actix_web::{web::Redirect, App};
+    /// App::new()
+    ///     // redirects "/old?page=2" to "/new?page=2"
+    ///     .service(Redirect::new("/old", "/new").preserve_query());
+    /// ```
+    pub fn preserve_query(mut self) -> Self {
+        self.preserve_query = true;
+        self
+    }
 }
 
 impl HttpServiceFactory for Redirect {
@@ -163,10 +183,22 @@ impl HttpServiceFactory for Redirect {
 impl Responder for Redirect {
     type Body = ();
 
-    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
         let mut res = HttpResponse::with_body(self.status_code, ());
 
-        if let Ok(hdr_val) = self.to.parse() {
+        let mut location = encode_location(&self.to).into_owned();
+
+        let query = req.query_string();
+        if self.preserve_query && !query.is_empty() {
+            // query goes before fragment, if any
+            let fragment = location.find('#').map(|idx| location.split_off(idx));
+
+            location.push(if location.contains('?') { '&' } else { '?' });
+            location.push_str(&encode_location(query));
+            location.extend(fragment);
+        }
+
+        if let Ok(hdr_val) = location.parse() {
             res.headers_mut().insert(LOCATION, hdr_val);
         } else {
             log::error!(
@@ -179,6 +211,31 @@ impl Responder for Redirect {
     }
 }
 
+/// Percent-encodes the bytes of `location` that are not allowed in URI references.
+///
+/// Percent signs are kept as-is so that locations that are already encoded are not changed.
+fn encode_location(location: &str) -> Cow<'_, str> {
+    fn is_allowed(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || b"!#$%&'()*+,-./:;=?@[]_~".contains(&byte)
+    }
+
+    if location.bytes().all(is_allowed) {
+        return Cow::Borrowed(location);
+    }
+
+    let mut encoded = String::with_capacity(location.len() + 16);
+
+    for byte in location.bytes() {
+        if is_allowed(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    Cow::Owned(encoded)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{dev::Service, http::StatusCode, test, App};
@@ -235,4 +292,48 @@ mod tests {
         let hdr = res.headers().get(&LOCATION).unwrap();
         assert_eq!(hdr.to_str().unwrap(), "https://duck.com");
     }
+
+    #[actix_rt::test]
+    async fn preserve_query() {
+        let svc = test::init_service(
+            App::new()
+                .service(Redirect::new("/one", "/two").preserve_query())
+                .service(Redirect::new("/three", "/four?a=1#top").preserve_query())
+                .service(Redirect::new("/five", "/six")),
+        )
+        .await;
+
+        let req = test::TestRequest::default().uri("/one?b=2").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(&LOCATION).unwrap(), "/two?b=2");
+
+        let req = test::TestRequest::default().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(&LOCATION).unwrap(), "/two");
+
+        let req = test::TestRequest::default().uri("/three?b=2").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(&LOCATION).unwrap(), "/four?a=1&b=2#top");
+
+        let req = test::TestRequest::default().uri("/five?b=2").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(&LOCATION).unwrap(), "/six");
+    }
+
+    #[actix_rt::test]
+    async fn location_is_encoded() {
+        let req = test::TestRequest::default().to_http_request();
+
+        let res = Redirect::to("/caf\u{e9} menu?q=a%20b").respond_to(&req);
+        assert_eq!(
+            res.headers().get(&LOCATION).unwrap(),
+            "/caf%C3%A9%20menu?q=a%20b"
+        );
+
+        let res = Redirect::to("https://duck.com/?q=\"quoted\"").respond_to(&req);
+        assert_eq!(
+            res.headers().get(&LOCATION).unwrap(),
+            "https://duck.com/?q=%22quoted%22"
+        );
+    }
 }
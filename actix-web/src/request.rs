@@ -1,8 +1,9 @@
 use std::{
-    cell::{Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefCell, RefMut},
     fmt, net,
     rc::Rc,
     str,
+    sync::Arc,
 };
 
 use actix_http::{Message, RequestHead};
@@ -17,14 +18,23 @@ use crate::{
     config::AppConfig,
     dev::{Extensions, Payload},
     error::UrlGenerationError,
-    http::{header::HeaderMap, Method, Uri, Version},
+    http::{
+        header::HeaderMap,
+        uri::{self, Authority, InvalidUri, PathAndQuery, Scheme},
+        Method, Uri, Version,
+    },
     info::ConnectionInfo,
     rmap::ResourceMap,
     Error, FromRequest, HttpMessage,
 };
 
+/// Parsed request cookies, cached in request extensions.
 #[cfg(feature = "cookies")]
-struct Cookies(Vec<Cookie<'static>>);
+struct Cookies {
+    /// `Cookie` header values the cookies were parsed from.
+    source: Vec<actix_http::header::HeaderValue>,
+    cookies: Vec<Cookie<'static>>,
+}
 
 /// An incoming request.
 #[derive(Clone)]
@@ -42,6 +52,7 @@ pub(crate) struct HttpRequestInner {
     pub(crate) app_data: SmallVec<[Rc<Extensions>; 4]>,
     pub(crate) conn_data: Option<Rc<Extensions>>,
     pub(crate) extensions: Rc<RefCell<Extensions>>,
+    pub(crate) matched_pattern: Option<Arc<str>>,
     app_state: Rc<AppInitServiceState>,
 }
 
@@ -66,12 +77,59 @@ impl HttpRequest {
                 app_data: data,
                 conn_data,
                 extensions,
+                matched_pattern: None,
+            }),
+        }
+    }
+}
+
+/// Copy of the parts of a request needed to construct an equivalent request after the original
+/// has been dropped.
+pub(crate) struct RequestSnapshot {
+    head: RequestHead,
+    app_data: SmallVec<[Rc<Extensions>; 4]>,
+    conn_data: Option<Rc<Extensions>>,
+    app_state: Rc<AppInitServiceState>,
+}
+
+/// Marker in a request's extensions asking for a snapshot of the request to be stored in the slot
+/// when the last reference to the request is dropped.
+pub(crate) struct SnapshotOnDrop(pub(crate) Rc<Cell<Option<RequestSnapshot>>>);
+
+impl RequestSnapshot {
+    /// Constructs a request from the snapshot.
+    ///
+    /// The request has no match info and its extensions are empty.
+    pub(crate) fn into_request(self) -> HttpRequest {
+        let mut head = Message::new();
+        *head = self.head;
+
+        HttpRequest {
+            inner: Rc::new(HttpRequestInner {
+                path: Path::new(Url::new(head.uri.clone())),
+                head,
+                app_data: self.app_data,
+                conn_data: self.conn_data,
+                extensions: Rc::new(RefCell::new(Extensions::new())),
+                matched_pattern: None,
+                app_state: self.app_state,
             }),
         }
     }
 }
 
 impl HttpRequest {
+    /// Copies the request's head and data containers, so that an equivalent request can be
+    /// constructed once this one is gone.
+    pub(crate) fn snapshot(&self) -> RequestSnapshot {
+        RequestSnapshot {
+            head: RequestHead::clone(&self.inner.head),
+            app_data: self.inner.app_data.clone(),
+            conn_data: self.inner.conn_data.clone(),
+            app_state: Rc::clone(&self.inner.app_state),
+        }
+    }
+
     /// This method returns reference to the request head
     #[inline]
     pub fn head(&self) -> &RequestHead {
@@ -159,6 +217,21 @@ impl HttpRequest {
         self.resource_map().match_pattern(self.path())
     }
 
+    /// The resource definition pattern that matched the path, without allocating.
+    ///
+    /// Same as [`match_pattern`](Self::match_pattern) but returns a borrowed pattern, making it
+    /// better suited to use in per-request logging and metrics.
+    #[inline]
+    pub fn match_pattern_str(&self) -> Option<&str> {
+        self.resource_map().match_pattern_str(self.path())
+    }
+
+    /// The full pattern of the resource that handled the request, if recorded during routing.
+    #[inline]
+    pub(crate) fn matched_pattern(&self) -> Option<&Arc<str>> {
+        self.inner.matched_pattern.as_ref()
+    }
+
     /// The resource name that matched the path. Useful for logging and metrics.
     ///
     /// Returns a None when no resource is fully matched, including default services.
@@ -260,6 +333,58 @@ impl HttpRequest {
         Ref::map(self.extensions(), |data| data.get().unwrap())
     }
 
+    /// Returns the absolute URI of the current request, as seen by the client.
+    ///
+    /// The scheme and host are resolved by [`connection_info`](Self::connection_info), honoring the
+    /// `Forwarded` and `X-Forwarded-*` headers; when the request passed through multiple proxies,
+    /// the values added by the proxy closest to the client are used. If the request has no host
+    /// information at all, the configured [server hostname](crate::HttpServer::server_hostname) is
+    /// used. The path and query are taken from the request's URI.
+    ///
+    /// # Errors
+    /// Returns an error if the resolved scheme or host is not valid in a URI; e.g., when a proxy
+    /// header contains garbage.
+    ///
+    /// # Security
+    /// Forwarded headers can be set by any client. Only rely on this method for security purposes,
+    /// such as building OAuth callbacks, when a trusted reverse proxy overwrites these headers.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::{test::TestRequest, http::header};
+    /// let req = TestRequest::with_uri("/login?next=%2Fhome")
+    ///     .insert_header((header::FORWARDED, "proto=https;host=example.com"))
+    ///     .to_http_request();
+    ///
+    /// assert_eq!(
+    ///     req.uri_with_base().unwrap(),
+    ///     "https://example.com/login?next=%2Fhome",
+    /// );
+    /// ```
+    pub fn uri_with_base(&self) -> Result<Uri, InvalidUri> {
+        let (scheme, authority) = {
+            let info = self.connection_info();
+            (
+                info.scheme().parse::<Scheme>()?,
+                info.host().parse::<Authority>()?,
+            )
+        };
+
+        let path_and_query = self
+            .uri()
+            .path_and_query()
+            .cloned()
+            .unwrap_or_else(|| PathAndQuery::from_static("/"));
+
+        let mut parts = uri::Parts::default();
+        parts.scheme = Some(scheme);
+        parts.authority = Some(authority);
+        parts.path_and_query = Some(path_and_query);
+
+        // all parts are present so this can not fail
+        Ok(Uri::from_parts(parts).unwrap())
+    }
+
     /// Returns a reference to the application's connection configuration.
     #[inline]
     pub fn app_config(&self) -> &AppConfig {
@@ -310,12 +435,24 @@ impl HttpRequest {
     }
 
     /// Load request cookies.
+    ///
+    /// The `Cookie` headers are parsed on first use and the result is cached in the request
+    /// extensions, so repeated calls (including through [`cookie`](Self::cookie)) are cheap. The
+    /// cache is refreshed if the `Cookie` headers have changed since they were parsed, e.g.
+    /// because a middleware modified them.
     #[cfg(feature = "cookies")]
     pub fn cookies(&self) -> Result<Ref<'_, Vec<Cookie<'static>>>, CookieParseError> {
         use actix_http::header::COOKIE;
 
-        if self.extensions().get::<Cookies>().is_none() {
+        let is_fresh = match self.extensions().get::<Cookies>() {
+            Some(cached) => cached.source.iter().eq(self.headers().get_all(COOKIE)),
+            None => false,
+        };
+
+        if !is_fresh {
+            let mut source = Vec::new();
             let mut cookies = Vec::new();
+
             for hdr in self.headers().get_all(COOKIE) {
                 let s = str::from_utf8(hdr.as_bytes()).map_err(CookieParseError::from)?;
                 for cookie_str in s.split(';').map(|s| s.trim()) {
@@ -323,12 +460,15 @@ impl HttpRequest {
                         cookies.push(Cookie::parse_encoded(cookie_str)?.into_owned());
                     }
                 }
+
+                source.push(hdr.clone());
             }
-            self.extensions_mut().insert(Cookies(cookies));
+
+            self.extensions_mut().insert(Cookies { source, cookies });
         }
 
         Ok(Ref::map(self.extensions(), |ext| {
-            &ext.get::<Cookies>().unwrap().0
+            &ext.get::<Cookies>().unwrap().cookies
         }))
     }
 
@@ -344,6 +484,31 @@ impl HttpRequest {
         }
         None
     }
+
+    /// Returns the value of the named request cookie, parsed as `T`.
+    ///
+    /// Returns `None` if the cookie is not present or its value fails to parse.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::test::TestRequest;
+    /// let req = TestRequest::default()
+    ///     .insert_header(("cookie", "page=2; theme=dark"))
+    ///     .to_http_request();
+    ///
+    /// assert_eq!(req.cookie_typed::<u32>("page"), Some(2));
+    /// assert_eq!(req.cookie_typed::<u32>("theme"), None);
+    /// assert_eq!(req.cookie_typed::<u32>("missing"), None);
+    /// ```
+    #[cfg(feature = "cookies")]
+    pub fn cookie_typed<T: str::FromStr>(&self, name: &str) -> Option<T> {
+        let cookies = self.cookies().ok()?;
+
+        cookies
+            .iter()
+            .find(|cookie| cookie.name() == name)
+            .and_then(|cookie| cookie.value().parse().ok())
+    }
 }
 
 impl HttpMessage for HttpRequest {
@@ -372,6 +537,19 @@ impl HttpMessage for HttpRequest {
 
 impl Drop for HttpRequest {
     fn drop(&mut self) {
+        if Rc::strong_count(&self.inner) == 1 {
+            let marker = self
+                .inner
+                .extensions
+                .try_borrow_mut()
+                .ok()
+                .and_then(|mut ext| ext.remove::<SnapshotOnDrop>());
+
+            if let Some(SnapshotOnDrop(slot)) = marker {
+                slot.set(Some(self.snapshot()));
+            }
+        }
+
         // if possible, contribute to current worker's HttpRequest allocation pool
 
         // This relies on no weak references to inner existing anywhere within the codebase.
@@ -391,6 +569,8 @@ impl Drop for HttpRequest {
                 // dispatcher, too.
                 inner.conn_data = None;
 
+                inner.matched_pattern = None;
+
                 // a re-borrow of pool is necessary here.
                 let req = Rc::clone(&self.inner);
                 self.app_state().pool().push(req);
@@ -559,6 +739,27 @@ mod tests {
         assert!(cookie.is_none());
     }
 
+    #[test]
+    #[cfg(feature = "cookies")]
+    fn test_request_cookies_cache() {
+        let mut req = TestRequest::default()
+            .insert_header((header::COOKIE, "count=1; name=value"))
+            .to_srv_request();
+
+        assert_eq!(req.cookie_typed::<u32>("count"), Some(1));
+        assert_eq!(req.cookie_typed::<u32>("name"), None);
+        assert_eq!(req.cookie("name").unwrap().value(), "value");
+
+        // cached cookies are re-parsed when the header changes
+        req.headers_mut()
+            .insert(header::COOKIE, header::HeaderValue::from_static("count=2"));
+        assert_eq!(req.cookie_typed::<u32>("count"), Some(2));
+        assert!(req.cookie("name").is_none());
+
+        req.headers_mut().remove(header::COOKIE);
+        assert!(req.cookies().unwrap().is_empty());
+    }
+
     #[test]
     fn test_request_query() {
         let req = TestRequest::with_uri("/?id=test").to_http_request();
@@ -616,6 +817,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_uri_with_base() {
+        let req = TestRequest::with_uri("/path?q=1").to_http_request();
+        assert_eq!(
+            req.uri_with_base().unwrap(),
+            "http://localhost:8080/path?q=1"
+        );
+
+        let req = TestRequest::with_uri("/path")
+            .insert_header((header::HOST, "www.rust-lang.org"))
+            .to_http_request();
+        assert_eq!(
+            req.uri_with_base().unwrap(),
+            "http://www.rust-lang.org/path"
+        );
+
+        let req = TestRequest::with_uri("/a/b?c=d")
+            .insert_header((header::HOST, "internal:8080"))
+            .insert_header(("x-forwarded-proto", "https, http"))
+            .insert_header(("x-forwarded-host", "example.com, proxy.internal"))
+            .to_http_request();
+        assert_eq!(req.uri_with_base().unwrap(), "https://example.com/a/b?c=d");
+
+        let req = TestRequest::with_uri("/")
+            .insert_header((
+                header::FORWARDED,
+                "for=1.2.3.4;proto=https;host=example.com:8443, for=5.6.7.8;host=proxy",
+            ))
+            .to_http_request();
+        assert_eq!(req.uri_with_base().unwrap(), "https://example.com:8443/");
+
+        let req = TestRequest::with_uri("/")
+            .insert_header(("x-forwarded-host", "bad host"))
+            .to_http_request();
+        assert!(req.uri_with_base().is_err());
+    }
+
     #[test]
     fn test_match_name() {
         let mut rdef = ResourceDef::new("/index.html");
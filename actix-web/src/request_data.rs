@@ -10,8 +10,8 @@ use crate::{
 /// Request-local data extractor.
 ///
 /// Request-local data is arbitrary data attached to an individual request, usually
-/// by middleware. It can be set via `extensions_mut` on [`HttpRequest`][htr_ext_mut]
-/// or [`ServiceRequest`][srv_ext_mut].
+/// by middleware. It can be set via [`ServiceRequest::insert_req_data`][srv_insert] or
+/// `extensions_mut` on [`HttpRequest`][htr_ext_mut] or [`ServiceRequest`][srv_ext_mut].
 ///
 /// Unlike app data, request data is dropped when the request has finished processing. This makes it
 /// useful as a kind of messaging system between middleware and request handlers. It uses the same
@@ -24,7 +24,39 @@ use crate::{
 /// re-insert the cloned data back into the extensions map. A `DerefMut` impl is intentionally not
 /// provided to make this potential foot-gun more obvious.
 ///
+/// # Errors
+/// Extraction fails with a `500 Internal Server Error` response if no data of type `T` was
+/// inserted. Use `Option<ReqData<T>>` if the data is not guaranteed to be present.
+///
 /// # Examples
+/// An authentication middleware inserting the current user, which a handler then extracts:
+/// ```
+/// use actix_web::{dev::Service as _, web, App, Responder};
+///
+/// #[derive(Debug, Clone)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// async fn profile(user: web::ReqData<User>) -> impl Responder {
+///     format!("Hello, {}!", user.name)
+/// }
+///
+/// let app = App::new()
+///     .wrap_fn(|mut req, srv| {
+///         // e.g., looked up from a session cookie
+///         req.insert_req_data(User { name: "ferris".to_owned() });
+///
+///         // the value can be read back without holding a borrow of the request extensions
+///         let user = req.req_data::<User>().unwrap();
+///         log::info!("request from {}", user.name);
+///
+///         srv.call(req)
+///     })
+///     .route("/profile", web::get().to(profile));
+/// ```
+///
+/// Use an `Option` extractor if middleware is not guaranteed to add this type of request data:
 /// ```no_run
 /// # use actix_web::{web, HttpResponse, HttpRequest, Responder, HttpMessage as _};
 ///
@@ -36,7 +68,6 @@ use crate::{
 ///     req: HttpRequest,
 ///     opt_flag: Option<web::ReqData<FlagFromMiddleware>>,
 /// ) -> impl Responder {
-///     // use an option extractor if middleware is not guaranteed to add this type of req data
 ///     if let Some(flag) = opt_flag {
 ///         assert_eq!(&flag.into_inner(), req.extensions().get::<FlagFromMiddleware>().unwrap());
 ///     }
@@ -45,6 +76,7 @@ use crate::{
 /// }
 /// ```
 ///
+/// [srv_insert]: crate::dev::ServiceRequest::insert_req_data
 /// [htr_ext_mut]: crate::HttpRequest::extensions_mut
 /// [srv_ext_mut]: crate::dev::ServiceRequest::extensions_mut
 #[derive(Debug, Clone)]
@@ -140,6 +172,38 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_rt::test]
+    async fn req_data_round_trip() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct User(&'static str);
+
+        let srv = init_service(
+            App::new()
+                .wrap_fn(|mut req, srv| {
+                    if req.method() == Method::POST {
+                        assert!(req.insert_req_data(User("ferris")).is_none());
+                        assert_eq!(req.req_data::<User>(), Some(User("ferris")));
+                    }
+
+                    srv.call(req)
+                })
+                .default_service(web::to(|user: ReqData<User>| {
+                    assert_eq!(user.into_inner(), User("ferris"));
+                    HttpResponse::Ok()
+                })),
+        )
+        .await;
+
+        let req = TestRequest::post().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // missing data
+        let req = TestRequest::get().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[actix_rt::test]
     async fn req_data_internal_mutability() {
         let srv = init_service(
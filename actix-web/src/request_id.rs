@@ -0,0 +1,77 @@
+use std::{fmt, future::Ready, ops::Deref};
+
+use crate::{
+    dev::Payload, error::ErrorInternalServerError, Error, FromRequest, HttpMessage as _,
+    HttpRequest,
+};
+
+/// Request ID extractor.
+///
+/// Extracts the correlation ID assigned to the request by the
+/// [`SetRequestId`](crate::middleware::SetRequestId) middleware. Extraction fails with a
+/// `500 Internal Server Error` if the middleware is not registered for the request.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware, web, App};
+///
+/// async fn index(id: web::RequestId) -> String {
+///     format!("request {} handled", id)
+/// }
+///
+/// let app = App::new()
+///     .wrap(middleware::SetRequestId::new())
+///     .route("/", web::get().to(index));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(String);
+
+impl RequestId {
+    pub(crate) fn new(id: String) -> Self {
+        Self(id)
+    }
+
+    /// Returns the request ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the `RequestId`, returning the ID string.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for RequestId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromRequest for RequestId {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        std::future::ready(match req.extensions().get::<RequestId>() {
+            Some(id) => Ok(id.clone()),
+            None => {
+                log::debug!(
+                    "Failed to extract `RequestId` for request to {:?}; \
+                     the `SetRequestId` middleware is not registered",
+                    req.path(),
+                );
+
+                Err(ErrorInternalServerError("Missing request ID."))
+            }
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+use std::{cell::RefCell, fmt, future::Future, rc::Rc, sync::Arc};
 
 use actix_http::Extensions;
 use actix_router::{IntoPatterns, Patterns};
@@ -8,14 +8,18 @@ use actix_service::{
 };
 use futures_core::future::LocalBoxFuture;
 use futures_util::future::join_all;
+use once_cell::unsync::OnceCell;
 
 use crate::{
-    body::MessageBody,
+    app_service::renders_errors,
+    body::{EitherBody, MessageBody},
     data::Data,
     dev::{ensure_leading_slash, AppService, ResourceDef},
+    error,
     guard::{self, Guard},
     handler::Handler,
-    http::header,
+    http::{header, Method},
+    middleware::Condition,
     route::{Route, RouteService},
     service::{
         BoxedHttpService, BoxedHttpServiceFactory, HttpServiceFactory, ServiceRequest,
@@ -44,7 +48,8 @@ use crate::{
 /// ```
 ///
 /// If no matching route is found, an empty 405 response is returned which includes an
-/// [appropriate Allow header][RFC 9110 §15.5.6]. This default behavior can be overridden using
+/// [appropriate Allow header][RFC 9110 §15.5.6]. The Allow header only lists methods of routes
+/// whose other guards pass for the request. This default behavior can be overridden using
 /// [`default_service()`](Self::default_service).
 ///
 /// [RFC 9110 §15.5.6]: https://www.rfc-editor.org/rfc/rfc9110.html#section-15.5.6
@@ -55,10 +60,18 @@ pub struct Resource<T = ResourceEndpoint> {
     routes: Vec<Route>,
     app_data: Option<Extensions>,
     guards: Vec<Box<dyn Guard>>,
+    route_guards: RouteGuards,
     default: BoxedHttpServiceFactory,
     factory_ref: Rc<RefCell<Option<ResourceFactory>>>,
 }
 
+/// Guards checked after a resource has matched, before selecting one of its routes.
+#[derive(Default)]
+struct RouteGuards {
+    all: Vec<Box<dyn Guard>>,
+    method: Vec<(Method, Box<dyn Guard>)>,
+}
+
 impl Resource {
     /// Constructs new resource that matches a `path` pattern.
     pub fn new<T: IntoPatterns>(path: T) -> Resource {
@@ -71,21 +84,26 @@ impl Resource {
             endpoint: ResourceEndpoint::new(fref.clone()),
             factory_ref: fref,
             guards: Vec::new(),
+            route_guards: RouteGuards::default(),
             app_data: None,
             default: boxed::factory(fn_service(|req: ServiceRequest| async {
                 use crate::HttpMessage as _;
 
                 let allowed = req.extensions().get::<guard::RegisteredMethods>().cloned();
 
+                let mut res = HttpResponse::MethodNotAllowed();
+
                 if let Some(methods) = allowed {
-                    Ok(req.into_response(
-                        HttpResponse::MethodNotAllowed()
-                            .insert_header(header::Allow(methods.0))
-                            .finish(),
-                    ))
-                } else {
-                    Ok(req.into_response(HttpResponse::MethodNotAllowed()))
+                    res.insert_header(header::Allow(methods.0));
+                }
+
+                let mut res = res.finish();
+
+                if renders_errors(&req) {
+                    res = res.with_error(error::ErrorMethodNotAllowed("Method Not Allowed"));
                 }
+
+                Ok(ServiceResponse::new(req.into_parts().0, res))
             })),
         }
     }
@@ -129,6 +147,57 @@ where
         self
     }
 
+    /// Add guard that applies to all of the resource's routes.
+    ///
+    /// Unlike [`guard`](Self::guard), which makes the resource not match at all (so that routing
+    /// continues with other services), a failing guard added here results in the resource's
+    /// [default service](Self::default_service) being called. By default, this is a
+    /// `405 Method Not Allowed` response with an empty Allow header.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, guard, App, HttpResponse};
+    ///
+    /// let app = App::new().service(
+    ///     web::resource("/admin")
+    ///         .guard_all(guard::Header("x-admin", "1"))
+    ///         .get(|| HttpResponse::Ok())
+    ///         .post(|| HttpResponse::Ok())
+    /// );
+    /// ```
+    pub fn guard_all<G: Guard + 'static>(mut self, guard: G) -> Self {
+        self.route_guards.all.push(Box::new(guard));
+        self
+    }
+
+    /// Add guard that applies to routes for the given method.
+    ///
+    /// Requests with this method must pass `guard` to be handled by any of the resource's routes.
+    /// If it fails, the resource's [default service](Self::default_service) is called instead.
+    /// The method is not included in the default `405 Method Not Allowed` response's Allow header
+    /// while the guard fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, guard, http::Method, App, HttpResponse};
+    ///
+    /// async fn handler() -> HttpResponse {
+    ///     HttpResponse::Ok().finish()
+    /// }
+    ///
+    /// let app = App::new().service(
+    ///     web::resource("/x")
+    ///         .get(handler)
+    ///         .put(handler)
+    ///         // only PUT requests need to be authorized
+    ///         .method_guard(Method::PUT, guard::Header("authorization", "secret"))
+    /// );
+    /// ```
+    pub fn method_guard<G: Guard + 'static>(mut self, method: Method, guard: G) -> Self {
+        self.route_guards.method.push((method, Box::new(guard)));
+        self
+    }
+
     pub(crate) fn add_guards(mut self, guards: Vec<Box<dyn Guard>>) -> Self {
         self.guards.extend(guards);
         self
@@ -283,6 +352,7 @@ where
             rdef: self.rdef,
             name: self.name,
             guards: self.guards,
+            route_guards: self.route_guards,
             routes: self.routes,
             default: self.default,
             app_data: self.app_data,
@@ -290,6 +360,61 @@ where
         }
     }
 
+    /// Registers middleware, but only if `enabled` is true.
+    ///
+    /// This is a shorthand for wrapping `mw` in [`middleware::Condition`] and is intended for
+    /// middleware that is enabled or disabled once, when the Resource is constructed; e.g., using a
+    /// build-time or configuration flag. When disabled, the middleware's service is never
+    /// constructed and requests are passed directly to the wrapped service.
+    ///
+    /// Since the decision is made at construction, it can not depend on individual requests.
+    /// Use custom middleware, or [`wrap_fn`](Self::wrap_fn), for per-request decisions.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{middleware, web, App};
+    ///
+    /// let debug = cfg!(debug_assertions);
+    ///
+    /// let app = App::new().service(
+    ///     web::resource("/")
+    ///         .wrap_conditional(debug, middleware::Logger::default())
+    ///         .get(|| async { "Welcome!" }),
+    /// );
+    /// ```
+    ///
+    /// [`middleware::Condition`]: crate::middleware::Condition
+    pub fn wrap_conditional<M, B, B2>(
+        self,
+        enabled: bool,
+        mw: M,
+    ) -> Resource<
+        impl ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<EitherBody<B, B2>>,
+            Error = Error,
+            InitError = (),
+        >,
+    >
+    where
+        T: ServiceFactory<ServiceRequest, Response = ServiceResponse<B2>>,
+        T::Service: 'static,
+        M: Transform<
+                T::Service,
+                ServiceRequest,
+                Response = ServiceResponse<B>,
+                Error = Error,
+                InitError = (),
+            > + 'static,
+        M::Future: 'static,
+        M::Transform: 'static,
+        B: MessageBody + 'static,
+        B2: MessageBody + 'static,
+    {
+        self.wrap(Condition::new(enabled, mw))
+    }
+
     /// Registers a resource function middleware.
     ///
     /// `mw` is a closure that runs during inbound and/or outbound processing in the request
@@ -321,6 +446,7 @@ where
             rdef: self.rdef,
             name: self.name,
             guards: self.guards,
+            route_guards: self.route_guards,
             routes: self.routes,
             default: self.default,
             app_data: self.app_data,
@@ -440,17 +566,32 @@ where
 
         *self.factory_ref.borrow_mut() = Some(ResourceFactory {
             routes: self.routes,
+            route_guards: Rc::new(self.route_guards),
             default: self.default,
         });
 
         let resource_data = self.app_data.map(Rc::new);
 
+        // full pattern of this resource; looked up in the resource map once it is complete
+        let pattern = Rc::new(OnceCell::<Arc<str>>::new());
+        let pattern_rdef = rdef.clone();
+
         // wraps endpoint service (including middleware) call and injects app data for this scope
         let endpoint = apply_fn_factory(self.endpoint, move |mut req: ServiceRequest, srv| {
             if let Some(ref data) = resource_data {
                 req.add_data_container(Rc::clone(data));
             }
 
+            let matched_pattern = pattern
+                .get_or_try_init(|| {
+                    req.resource_map()
+                        .resource_pattern(req.path(), &pattern_rdef)
+                        .ok_or(())
+                })
+                .ok()
+                .cloned();
+            req.set_matched_pattern(matched_pattern);
+
             let fut = srv.call(req);
 
             async { Ok(fut.await?.map_into_boxed_body()) }
@@ -462,6 +603,7 @@ where
 
 pub struct ResourceFactory {
     routes: Vec<Route>,
+    route_guards: Rc<RouteGuards>,
     default: BoxedHttpServiceFactory,
 }
 
@@ -480,6 +622,8 @@ impl ServiceFactory<ServiceRequest> for ResourceFactory {
         // construct route service factory futures
         let factory_fut = join_all(self.routes.iter().map(|route| route.new_service(())));
 
+        let route_guards = Rc::clone(&self.route_guards);
+
         Box::pin(async move {
             let default = default_fut.await?;
             let routes = factory_fut
@@ -487,16 +631,63 @@ impl ServiceFactory<ServiceRequest> for ResourceFactory {
                 .into_iter()
                 .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(ResourceService { routes, default })
+            let has_methods = routes.iter().any(RouteService::has_method);
+
+            Ok(ResourceService {
+                routes,
+                has_methods,
+                route_guards,
+                default,
+            })
         })
     }
 }
 
 pub struct ResourceService {
     routes: Vec<RouteService>,
+    /// Whether any route has method guards.
+    has_methods: bool,
+    route_guards: Rc<RouteGuards>,
     default: BoxedHttpService,
 }
 
+impl ResourceService {
+    /// Returns true if the resource-wide guards and the guards for `method` pass.
+    fn check_route_guards(&self, req: &ServiceRequest, method: &Method) -> bool {
+        let ctx = req.guard_ctx();
+
+        self.route_guards.all.iter().all(|guard| guard.check(&ctx))
+            && self
+                .route_guards
+                .method
+                .iter()
+                .filter(|(guard_method, _)| guard_method == method)
+                .all(|(_, guard)| guard.check(&ctx))
+    }
+
+    /// Determines the allowed methods for a request that did not match any route.
+    ///
+    /// Only methods of routes whose non-method guards pass are included. Returns `None` if no
+    /// route has a method guard.
+    fn allowed_methods(&self, req: &ServiceRequest) -> Option<Vec<Method>> {
+        if !self.has_methods {
+            return None;
+        }
+
+        let mut allowed = Vec::new();
+
+        for route in &self.routes {
+            if let Some(method) = route.allowed_method(req) {
+                if !allowed.contains(method) && self.check_route_guards(req, method) {
+                    allowed.push(method.clone());
+                }
+            }
+        }
+
+        Some(allowed)
+    }
+}
+
 impl Service<ServiceRequest> for ResourceService {
     type Response = ServiceResponse;
     type Error = Error;
@@ -505,9 +696,23 @@ impl Service<ServiceRequest> for ResourceService {
     actix_service::always_ready!();
 
     fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        for route in &self.routes {
-            if route.check(&mut req) {
-                return route.call(req);
+        use crate::HttpMessage as _;
+
+        if self.check_route_guards(&req, &req.head().method) {
+            for route in &self.routes {
+                if route.check(&mut req) {
+                    return route.call(req);
+                }
+            }
+        }
+
+        match self.allowed_methods(&req) {
+            Some(methods) => {
+                req.extensions_mut()
+                    .insert(guard::RegisteredMethods(methods));
+            }
+            None => {
+                req.extensions_mut().remove::<guard::RegisteredMethods>();
             }
         }
 
@@ -616,6 +821,28 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_middleware_conditional() {
+        for enabled in [true, false] {
+            let srv = init_service(
+                App::new().service(
+                    web::resource("/test")
+                        .wrap_conditional(
+                            enabled,
+                            DefaultHeaders::new()
+                                .add((header::CONTENT_TYPE, HeaderValue::from_static("0001"))),
+                        )
+                        .route(web::get().to(HttpResponse::Ok)),
+                ),
+            )
+            .await;
+            let req = TestRequest::with_uri("/test").to_request();
+            let resp = call_service(&srv, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().contains_key(header::CONTENT_TYPE), enabled);
+        }
+    }
+
     #[actix_rt::test]
     async fn test_middleware_fn() {
         let srv = init_service(
@@ -675,6 +902,118 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_rt::test]
+    async fn test_allow_header_excludes_guarded_methods() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/test")
+                    .route(
+                        web::get()
+                            .guard(guard::Header("x-key", "1"))
+                            .to(HttpResponse::Ok),
+                    )
+                    .route(web::post().to(HttpResponse::Ok)),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "POST");
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::PUT)
+            .insert_header(("x-key", "1"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "GET, POST");
+    }
+
+    #[actix_rt::test]
+    async fn test_allow_header_with_inverted_method_guard() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/test")
+                    .route(
+                        web::get()
+                            .guard(guard::Header("x-key", "1"))
+                            .to(HttpResponse::Ok),
+                    )
+                    .route(
+                        web::post()
+                            .guard(guard::Not(guard::Get()))
+                            .to(HttpResponse::Ok),
+                    ),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::DELETE)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "POST");
+    }
+
+    #[actix_rt::test]
+    async fn test_method_guard() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/test")
+                    .get(HttpResponse::Ok)
+                    .put(HttpResponse::Created)
+                    .method_guard(Method::PUT, guard::Header("x-key", "1")),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::PUT)
+            .insert_header(("x-key", "1"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::PUT)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "GET");
+    }
+
+    #[actix_rt::test]
+    async fn test_guard_all() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/test")
+                    .guard_all(guard::Header("x-key", "1"))
+                    .get(HttpResponse::Ok)
+                    .post(HttpResponse::Ok),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::POST)
+            .insert_header(("x-key", "1"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/test").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "");
+    }
+
     #[actix_rt::test]
     async fn test_default_resource() {
         let srv = init_service(
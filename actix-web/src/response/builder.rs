@@ -1,21 +1,25 @@
 use std::{
     cell::{Ref, RefMut},
     convert::TryInto,
+    fmt,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
 };
 
 use actix_http::{error::HttpError, Response, ResponseHead};
-use bytes::Bytes;
-use futures_core::Stream;
+use bytes::{Bytes, BytesMut};
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
 use serde::Serialize;
 
+#[cfg(feature = "cookies")]
+use super::response::partitioned_cookie_value;
 use crate::{
-    body::{BodyStream, BoxBody, MessageBody},
+    body::{BodySize, BodyStream, BoxBody, MessageBody},
     dev::Extensions,
-    error::{Error, JsonPayloadError},
-    http::header::{self, HeaderName, TryIntoHeaderPair, TryIntoHeaderValue},
+    error::{self, Error, JsonPayloadError},
+    http::header::{self, HeaderMap, HeaderName, TryIntoHeaderPair, TryIntoHeaderValue},
     http::{ConnectionType, StatusCode},
     BoxError, HttpRequest, HttpResponse, Responder,
 };
@@ -263,6 +267,89 @@ impl HttpResponseBuilder {
         }
     }
 
+    /// Add a cookie to the response, with the `Partitioned` attribute set if `partitioned` is true.
+    ///
+    /// Partitioned cookies (also known as CHIPS) are stored separately for each top-level site,
+    /// which allows, e.g., embedded iframes to keep using cookies when third-party cookies are
+    /// blocked. Browsers only accept them when they also have the `Secure` attribute and, to be
+    /// sent in cross-site requests, `SameSite=None`. Building the response fails, as it does for
+    /// other invalid headers, when a partitioned cookie lacks either of them. Use
+    /// [`HttpResponse::add_partitioned_cookie`] to get a
+    /// [`PartitionedCookieError`](crate::error::PartitionedCookieError) describing the problem.
+    ///
+    /// With `partitioned` set to false, this is the same as [`cookie`](Self::cookie).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{HttpResponse, cookie::{Cookie, SameSite}};
+    ///
+    /// let res = HttpResponse::Ok()
+    ///     .partitioned_cookie(
+    ///         Cookie::build("session", "value")
+    ///             .path("/")
+    ///             .secure(true)
+    ///             .same_site(SameSite::None)
+    ///             .finish(),
+    ///         true,
+    ///     )
+    ///     .finish();
+    ///
+    /// let cookie = res.headers().get("set-cookie").unwrap().to_str().unwrap();
+    /// assert!(cookie.ends_with("; Partitioned"));
+    /// ```
+    #[cfg(feature = "cookies")]
+    pub fn partitioned_cookie(
+        &mut self,
+        cookie: cookie::Cookie<'_>,
+        partitioned: bool,
+    ) -> &mut Self {
+        if !partitioned {
+            return self.cookie(cookie);
+        }
+
+        match partitioned_cookie_value(&cookie) {
+            Ok(hdr_val) => self.append_header((header::SET_COOKIE, hdr_val)),
+            Err(_) => {
+                // browsers reject partitioned cookies without `Secure` and `SameSite=None`, so they
+                // are treated like any other invalid `Set-Cookie` header value
+                let err = header::HeaderValue::from_bytes(b"\n").unwrap_err();
+                self.error = Some(err.into());
+                self
+            }
+        }
+    }
+
+    /// Sets a function that produces trailer fields to send after the response body.
+    ///
+    /// See [`HttpResponse::set_trailers`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::header::{self, HeaderMap, HeaderName, HeaderValue}, HttpResponse};
+    ///
+    /// let res = HttpResponse::Ok()
+    ///     // HTTP/1.1 clients only receive trailers that are declared in advance
+    ///     .insert_header((header::TRAILER, "grpc-status"))
+    ///     .trailers(|| {
+    ///         let mut trailers = HeaderMap::new();
+    ///         trailers.insert(HeaderName::from_static("grpc-status"), HeaderValue::from_static("0"));
+    ///         trailers
+    ///     })
+    ///     .streaming(futures_util::stream::empty::<Result<_, actix_web::Error>>());
+    /// ```
+    pub fn trailers<F>(&mut self, trailers: F) -> &mut Self
+    where
+        F: FnOnce() -> HeaderMap + 'static,
+    {
+        if self.error.is_none() {
+            if let Some(res) = self.res.as_mut() {
+                res.set_trailers(trailers);
+            }
+        }
+
+        self
+    }
+
     /// Returns a reference to the response-local data/extensions container.
     #[inline]
     pub fn extensions(&self) -> Ref<'_, Extensions> {
@@ -314,6 +401,43 @@ impl HttpResponseBuilder {
         Ok(HttpResponse::from(res))
     }
 
+    /// Set a body written by `f` and build the `HttpResponse`.
+    ///
+    /// The closure writes directly into the buffer that becomes the response body, avoiding an
+    /// intermediate `String` or `Vec`. If it returns an error, a `500 Internal Server Error`
+    /// response is built instead.
+    ///
+    /// `HttpResponseBuilder` can not be used after this call.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fmt::Write as _;
+    /// use actix_web::HttpResponse;
+    ///
+    /// let rows = [("apples", 3), ("pears", 5)];
+    ///
+    /// let res = HttpResponse::Ok()
+    ///     .content_type("text/csv")
+    ///     .body_with_writer(|buf| {
+    ///         for (name, count) in rows {
+    ///             writeln!(buf, "{},{}", name, count)?;
+    ///         }
+    ///
+    ///         Ok(())
+    ///     });
+    /// ```
+    pub fn body_with_writer<F>(&mut self, f: F) -> HttpResponse
+    where
+        F: FnOnce(&mut BytesMut) -> fmt::Result,
+    {
+        let mut buf = BytesMut::new();
+
+        match f(&mut buf) {
+            Ok(()) => self.body(buf.freeze()),
+            Err(err) => HttpResponse::from_error(error::ErrorInternalServerError(err)),
+        }
+    }
+
     /// Set a streaming body and build the `HttpResponse`.
     ///
     /// `HttpResponseBuilder` can not be used after this call.
@@ -348,6 +472,60 @@ impl HttpResponseBuilder {
         }
     }
 
+    /// Set a streaming JSON array body and build the `HttpResponse`.
+    ///
+    /// Each successful item of the stream is serialized and sent to the client as it is produced,
+    /// so that the collection is never buffered as a whole. The body is a JSON array of the items;
+    /// an empty stream results in `[]`. As with [`json`](Self::json), the `Content-Type` is set to
+    /// `application/json` unless it has already been set.
+    ///
+    /// If the stream yields an error, or an item fails to serialize, the response body ends with
+    /// that error, which closes the connection.
+    ///
+    /// `HttpResponseBuilder` can not be used after this call.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{get, Error, HttpResponse, Responder};
+    /// use futures_util::stream;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Record {
+    ///     id: u32,
+    /// }
+    ///
+    /// #[get("/records")]
+    /// async fn records() -> impl Responder {
+    ///     let records = (1..=3).map(|id| Ok::<_, Error>(Record { id }));
+    ///
+    ///     // sends `[{"id":1},{"id":2},{"id":3}]`
+    ///     HttpResponse::Ok().json_stream(stream::iter(records))
+    /// }
+    /// ```
+    pub fn json_stream<S, T, E>(&mut self, stream: S) -> HttpResponse
+    where
+        S: Stream<Item = Result<T, E>> + 'static,
+        T: Serialize,
+        E: Into<Error>,
+    {
+        let contains = if let Some(parts) = self.inner() {
+            parts.headers.contains_key(header::CONTENT_TYPE)
+        } else {
+            true
+        };
+
+        if !contains {
+            self.insert_header((header::CONTENT_TYPE, mime::APPLICATION_JSON));
+        }
+
+        self.body(JsonArrayStream {
+            stream,
+            started: false,
+            done: false,
+        })
+    }
+
     /// Set an empty body and build the `HttpResponse`.
     ///
     /// `HttpResponseBuilder` can not be used after this call.
@@ -373,6 +551,64 @@ impl HttpResponseBuilder {
     }
 }
 
+pin_project! {
+    /// Body that serializes the items of a stream as the elements of a JSON array.
+    struct JsonArrayStream<S> {
+        #[pin]
+        stream: S,
+        started: bool,
+        done: bool,
+    }
+}
+
+impl<S, T, E> MessageBody for JsonArrayStream<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Serialize,
+    E: Into<Error>,
+{
+    type Error = Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        let item = match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(item)) => item,
+            Some(Err(err)) => {
+                *this.done = true;
+                return Poll::Ready(Some(Err(err.into())));
+            }
+            None => {
+                *this.done = true;
+                let end: &'static [u8] = if *this.started { b"]" } else { b"[]" };
+                return Poll::Ready(Some(Ok(Bytes::from_static(end))));
+            }
+        };
+
+        let mut buf = vec![if *this.started { b',' } else { b'[' }];
+        *this.started = true;
+
+        if let Err(err) = serde_json::to_writer(&mut buf, &item) {
+            *this.done = true;
+            return Poll::Ready(Some(Err(JsonPayloadError::Serialize(err).into())));
+        }
+
+        Poll::Ready(Some(Ok(Bytes::from(buf))))
+    }
+}
+
 impl From<HttpResponseBuilder> for HttpResponse {
     fn from(mut builder: HttpResponseBuilder) -> Self {
         builder.finish()
@@ -471,6 +707,89 @@ mod tests {
         assert_body_eq!(res, br#"["v1","v2","v3"]"#);
     }
 
+    #[actix_rt::test]
+    async fn test_body_with_writer() {
+        use std::fmt::Write as _;
+
+        let res = HttpResponse::Ok()
+            .insert_header((header::CONTENT_TYPE, "text/csv"))
+            .body_with_writer(|buf| {
+                for n in 1..=3 {
+                    writeln!(buf, "row,{}", n)?;
+                }
+
+                Ok(())
+            });
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+        assert_eq!(
+            body::to_bytes(res.into_body()).await.unwrap(),
+            "row,1\nrow,2\nrow,3\n"
+        );
+
+        let res = HttpResponse::Ok().body_with_writer(|buf| {
+            buf.write_str("partial")?;
+            Err(fmt::Error)
+        });
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_rt::test]
+    async fn test_json_stream() {
+        use std::collections::HashMap;
+
+        use futures_util::stream;
+
+        fn ok<T>(items: Vec<T>) -> impl Stream<Item = Result<T, Error>> {
+            stream::iter(items.into_iter().map(Ok))
+        }
+
+        let res = HttpResponse::Ok().json_stream(ok(vec!["v1", "v2", "v3"]));
+        let ct = res.headers().get(CONTENT_TYPE).unwrap();
+        assert_eq!(ct, HeaderValue::from_static("application/json"));
+        assert_body_eq!(res, br#"["v1","v2","v3"]"#);
+
+        let res = HttpResponse::Ok().json_stream(ok(Vec::<u32>::new()));
+        assert_body_eq!(res, b"[]");
+
+        // content type override
+        let res = HttpResponse::Ok()
+            .insert_header((CONTENT_TYPE, "text/json"))
+            .json_stream(ok(vec![1, 2]));
+        let ct = res.headers().get(CONTENT_TYPE).unwrap();
+        assert_eq!(ct, HeaderValue::from_static("text/json"));
+        assert_body_eq!(res, b"[1,2]");
+
+        let cx = &mut Context::from_waker(futures_util::task::noop_waker_ref());
+
+        // maps with non-string keys fail to serialize
+        let bad = HashMap::from([(vec![1], 1)]);
+        let mut body = HttpResponse::Ok()
+            .json_stream(ok(vec![HashMap::new(), bad, HashMap::new()]))
+            .into_body();
+
+        let chunk = body::MessageBody::poll_next(Pin::new(&mut body), cx);
+        assert!(matches!(chunk, Poll::Ready(Some(Ok(chunk))) if chunk == "[{}"));
+        assert!(body::to_bytes(body).await.is_err());
+
+        // stream errors end the body
+        let items = vec![
+            Ok(1),
+            Err(error::ErrorInternalServerError("db gone")),
+            Ok(3),
+        ];
+        let mut body = HttpResponse::Ok()
+            .json_stream(stream::iter(items))
+            .into_body();
+
+        let chunk = body::MessageBody::poll_next(Pin::new(&mut body), cx);
+        assert!(matches!(chunk, Poll::Ready(Some(Ok(chunk))) if chunk == "[1"));
+        let chunk = body::MessageBody::poll_next(Pin::new(&mut body), cx);
+        assert!(matches!(chunk, Poll::Ready(Some(Err(err))) if err.to_string() == "db gone"));
+        let chunk = body::MessageBody::poll_next(Pin::new(&mut body), cx);
+        assert!(matches!(chunk, Poll::Ready(None)));
+    }
+
     #[actix_rt::test]
     async fn test_serde_json_in_body() {
         let resp = HttpResponse::Ok().body(
@@ -532,4 +851,21 @@ mod tests {
         assert!(headers.contains(&HeaderValue::from_static("application/octet-stream")));
         assert!(headers.contains(&HeaderValue::from_static("application/json")));
     }
+
+    #[cfg(feature = "cookies")]
+    #[test]
+    fn partitioned_cookie() {
+        use cookie::Cookie;
+
+        let res = HttpResponse::Ok()
+            .partitioned_cookie(Cookie::new("name", "value"), false)
+            .finish();
+        assert_eq!(res.headers().get(header::SET_COOKIE).unwrap(), "name=value");
+
+        let res = HttpResponse::Ok()
+            .partitioned_cookie(Cookie::new("name", "value"), true)
+            .finish();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(header::SET_COOKIE).is_none());
+    }
 }
@@ -4,7 +4,7 @@ use std::{
 };
 
 use actix_http::{
-    body::{BoxBody, EitherBody, MessageBody},
+    body::{BoxBody, EitherBody, EitherBody3, MessageBody},
     header::HeaderMap,
     Extensions, Response, ResponseHead, StatusCode,
 };
@@ -15,9 +15,11 @@ use {
         error::HttpError,
         header::{self, HeaderValue},
     },
-    cookie::Cookie,
+    cookie::{Cookie, SameSite},
 };
 
+#[cfg(feature = "cookies")]
+use crate::error::PartitionedCookieError;
 use crate::{error::Error, HttpRequest, HttpResponseBuilder, Responder};
 
 /// An outgoing response.
@@ -80,6 +82,22 @@ impl<B> HttpResponse<B> {
         self.error.as_ref()
     }
 
+    /// Takes the source `error` out of this response.
+    pub(crate) fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Sets the source `error` for this response.
+    pub(crate) fn set_error(&mut self, error: Error) {
+        self.error = Some(error);
+    }
+
+    /// Sets the source `error` for this response, without changing the response itself.
+    pub(crate) fn with_error(mut self, error: Error) -> Self {
+        self.set_error(error);
+        self
+    }
+
     /// Get the response status code
     #[inline]
     pub fn status(&self) -> StatusCode {
@@ -123,6 +141,20 @@ impl<B> HttpResponse<B> {
             .map_err(Into::into)
     }
 
+    /// Add a cookie with the `Partitioned` attribute to this response.
+    ///
+    /// See [`HttpResponseBuilder::partitioned_cookie`] for details about partitioned cookies.
+    ///
+    /// # Errors
+    /// Returns an error if the cookie does not have both `Secure` and `SameSite=None` attributes,
+    /// or if it results in a malformed `Set-Cookie` header.
+    #[cfg(feature = "cookies")]
+    pub fn add_partitioned_cookie(&mut self, cookie: &Cookie<'_>) -> Result<(), Error> {
+        let hdr_val = partitioned_cookie_value(cookie)?;
+        self.headers_mut().append(header::SET_COOKIE, hdr_val);
+        Ok(())
+    }
+
     /// Add a "removal" cookie to the response that matches attributes of given cookie.
     ///
     /// This will cause browsers/clients to remove stored cookies with this name.
@@ -213,6 +245,21 @@ impl<B> HttpResponse<B> {
         self.res.extensions_mut()
     }
 
+    /// Sets a function that produces trailer fields to send after the response body.
+    ///
+    /// The function is called once the body has been streamed completely, so it can report values
+    /// computed while streaming, such as checksums.
+    ///
+    /// Trailers are sent by HTTP/2 connections. HTTP/1.1 connections only send them for chunked
+    /// responses that declare the trailer fields using the `Trailer` header; otherwise, they are
+    /// silently dropped.
+    pub fn set_trailers<F>(&mut self, trailers: F)
+    where
+        F: FnOnce() -> HeaderMap + 'static,
+    {
+        self.res.set_trailers(trailers)
+    }
+
     /// Returns a reference to this response's body.
     #[inline]
     pub fn body(&self) -> &B {
@@ -281,6 +328,30 @@ impl<B> HttpResponse<B> {
         self.map_body(|_, body| EitherBody::right(body))
     }
 
+    /// Map the current body type `B` to `EitherBody3::First(B)`.
+    ///
+    /// Useful for middleware which can generate two other kinds of responses.
+    #[inline]
+    pub fn map_into_first_body<B2, B3>(self) -> HttpResponse<EitherBody3<B, B2, B3>> {
+        self.map_body(|_, body| EitherBody3::first(body))
+    }
+
+    /// Map the current body type `B` to `EitherBody3::Second(B)`.
+    ///
+    /// Useful for middleware which can generate two other kinds of responses.
+    #[inline]
+    pub fn map_into_second_body<B1, B3>(self) -> HttpResponse<EitherBody3<B1, B, B3>> {
+        self.map_body(|_, body| EitherBody3::second(body))
+    }
+
+    /// Map the current body type `B` to `EitherBody3::Third(B)`.
+    ///
+    /// Useful for middleware which can generate two other kinds of responses.
+    #[inline]
+    pub fn map_into_third_body<B1, B2>(self) -> HttpResponse<EitherBody3<B1, B2, B>> {
+        self.map_body(|_, body| EitherBody3::third(body))
+    }
+
     /// Map the current body to a type-erased `BoxBody`.
     #[inline]
     pub fn map_into_boxed_body(self) -> HttpResponse<BoxBody>
@@ -395,6 +466,21 @@ impl<'a> Iterator for CookieIter<'a> {
     }
 }
 
+/// Formats `cookie` as a `Set-Cookie` header value with the `Partitioned` attribute.
+#[cfg(feature = "cookies")]
+pub(crate) fn partitioned_cookie_value(cookie: &Cookie<'_>) -> Result<HeaderValue, Error> {
+    if cookie.secure() != Some(true) {
+        return Err(PartitionedCookieError::NotSecure.into());
+    }
+
+    if cookie.same_site() != Some(SameSite::None) {
+        return Err(PartitionedCookieError::NotSameSiteNone.into());
+    }
+
+    HeaderValue::from_str(&format!("{}; Partitioned", cookie))
+        .map_err(|err| HttpError::from(err).into())
+}
+
 #[cfg(test)]
 mod tests {
     use static_assertions::assert_impl_all;
@@ -416,6 +502,47 @@ mod tests {
         let dbg = format!("{:?}", resp);
         assert!(dbg.contains("HttpResponse"));
     }
+
+    #[cfg(feature = "cookies")]
+    #[test]
+    fn partitioned_cookie() {
+        use crate::http::header::SET_COOKIE;
+
+        let mut res = HttpResponse::Ok().finish();
+
+        let cookie = Cookie::build("name", "value")
+            .secure(true)
+            .same_site(SameSite::None)
+            .finish();
+        res.add_partitioned_cookie(&cookie).unwrap();
+        assert_eq!(
+            res.headers().get(SET_COOKIE).unwrap(),
+            "name=value; SameSite=None; Secure; Partitioned"
+        );
+
+        // partitioned attribute is tolerated when parsing
+        let parsed = res.cookies().next().unwrap();
+        assert_eq!(parsed.name(), "name");
+        assert_eq!(parsed.same_site(), Some(SameSite::None));
+
+        let err = res
+            .add_partitioned_cookie(&Cookie::build("name", "value").secure(true).finish())
+            .unwrap_err();
+        assert!(matches!(
+            err.as_error::<PartitionedCookieError>(),
+            Some(PartitionedCookieError::NotSameSiteNone)
+        ));
+
+        let insecure = Cookie::build("name", "value")
+            .same_site(SameSite::None)
+            .finish();
+        let err = res.add_partitioned_cookie(&insecure).unwrap_err();
+        assert!(matches!(
+            err.as_error::<PartitionedCookieError>(),
+            Some(PartitionedCookieError::NotSecure)
+        ));
+        assert_eq!(res.headers().get_all(SET_COOKIE).count(), 1);
+    }
 }
 
 #[cfg(test)]
@@ -3,10 +3,12 @@ use std::{
     cell::RefCell,
     fmt::Write as _,
     rc::{Rc, Weak},
+    sync::Arc,
 };
 
 use actix_router::ResourceDef;
 use ahash::AHashMap;
+use once_cell::unsync::OnceCell;
 use url::Url;
 
 use crate::{error::UrlGenerationError, request::HttpRequest};
@@ -25,6 +27,9 @@ pub struct ResourceMap {
 
     /// Must be `None` for "edge" nodes.
     nodes: Option<Vec<Rc<ResourceMap>>>,
+
+    /// Full resource pattern of this node, including those of its parents. Lazily computed.
+    full_pattern: OnceCell<Option<Arc<str>>>,
 }
 
 impl ResourceMap {
@@ -35,6 +40,7 @@ impl ResourceMap {
             named: AHashMap::default(),
             parent: RefCell::new(Weak::new()),
             nodes: Some(Vec::new()),
+            full_pattern: OnceCell::new(),
         }
     }
 
@@ -89,6 +95,7 @@ impl ResourceMap {
                 named: AHashMap::default(),
                 parent: RefCell::new(Weak::new()),
                 nodes: None,
+                full_pattern: OnceCell::new(),
             });
 
             if let Some(name) = pattern.name() {
@@ -178,24 +185,55 @@ impl ResourceMap {
     /// Returns the full resource pattern matched against a path or None if no full match
     /// is possible.
     pub fn match_pattern(&self, path: &str) -> Option<String> {
-        self.find_matching_node(path)?.root_rmap_fn(
-            String::with_capacity(AVG_PATH_LEN),
-            |mut acc, node| {
-                let pattern = node.pattern.pattern()?;
-                acc.push_str(pattern);
-                Some(acc)
-            },
-        )
+        self.match_pattern_str(path).map(ToOwned::to_owned)
+    }
+
+    /// Returns the full resource pattern matched against a path or None if no full match
+    /// is possible.
+    ///
+    /// Unlike [`match_pattern`](Self::match_pattern), this does not allocate.
+    pub fn match_pattern_str(&self, path: &str) -> Option<&str> {
+        self.find_matching_node(path)?
+            .full_pattern()
+            .map(|pattern| &**pattern)
+    }
+
+    /// Returns shared, full resource pattern matched against a path, as long as the matched
+    /// resource is defined by `rdef`.
+    pub(crate) fn resource_pattern(&self, path: &str, rdef: &ResourceDef) -> Option<Arc<str>> {
+        self._find_matching_node(path, &|node| node.pattern == *rdef)
+            .flatten()?
+            .full_pattern()
+            .cloned()
+    }
+
+    /// Returns full resource pattern of this node, computing and caching it on first use.
+    fn full_pattern(&self) -> Option<&Arc<str>> {
+        self.full_pattern
+            .get_or_init(|| {
+                let pattern =
+                    self.root_rmap_fn(String::with_capacity(AVG_PATH_LEN), |mut acc, node| {
+                        let pattern = node.pattern.pattern()?;
+                        acc.push_str(pattern);
+                        Some(acc)
+                    })?;
+
+                Some(Arc::from(pattern))
+            })
+            .as_ref()
     }
 
     fn find_matching_node(&self, path: &str) -> Option<&ResourceMap> {
-        self._find_matching_node(path).flatten()
+        self._find_matching_node(path, &|_| true).flatten()
     }
 
-    /// Returns `None` if root pattern doesn't match;
+    /// Returns `None` if root pattern doesn't match or `self` is an edge node rejected by `accept`;
     /// `Some(None)` if root pattern matches but there is no matching child pattern.
     /// Don't search sideways when `Some(none)` is returned.
-    fn _find_matching_node(&self, path: &str) -> Option<Option<&ResourceMap>> {
+    fn _find_matching_node<F>(&self, path: &str, accept: &F) -> Option<Option<&ResourceMap>>
+    where
+        F: Fn(&ResourceMap) -> bool,
+    {
         let matched_len = self.pattern.find_match(path)?;
         let path = &path[matched_len..];
 
@@ -203,12 +241,13 @@ impl ResourceMap {
             // find first sub-node to match remaining path
             Some(nodes) => nodes
                 .iter()
-                .filter_map(|node| node._find_matching_node(path))
+                .filter_map(|node| node._find_matching_node(path, accept))
                 .next()
                 .flatten(),
 
-            // only terminate at edge nodes
-            None => Some(self),
+            // only terminate at accepted edge nodes
+            None if accept(self) => Some(self),
+            None => return None,
         })
     }
 
@@ -310,6 +349,15 @@ mod tests {
             root.match_pattern("/user/22/post/other-post/comment/42"),
             Some("/user/{id}/post/{post_id}/comment/{comment_id}".to_owned())
         );
+
+        // borrowed patterns are computed once and then reused
+        assert!(root.match_pattern_str("/bar").is_none());
+        let pattern = root.match_pattern_str("/user/22/profile").unwrap();
+        assert_eq!(pattern, "/user/{id}/profile");
+        assert!(std::ptr::eq(
+            pattern,
+            root.match_pattern_str("/user/33/profile").unwrap()
+        ));
     }
 
     #[test]
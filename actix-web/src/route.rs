@@ -1,6 +1,6 @@
 use std::{mem, rc::Rc};
 
-use actix_http::{body::MessageBody, Method};
+use actix_http::{body::MessageBody, Extensions, Method};
 use actix_service::{
     apply,
     boxed::{self, BoxService},
@@ -11,7 +11,7 @@ use futures_core::future::LocalBoxFuture;
 use crate::{
     guard::{self, Guard},
     handler::{handler_service, Handler},
-    middleware::Compat,
+    middleware::{Compat, RouteTimeout},
     service::{BoxedHttpServiceFactory, ServiceRequest, ServiceResponse},
     Error, FromRequest, HttpResponse, Responder,
 };
@@ -23,6 +23,7 @@ use crate::{
 pub struct Route {
     service: BoxedHttpServiceFactory,
     guards: Rc<Vec<Box<dyn Guard>>>,
+    app_data: Option<Rc<Extensions>>,
 }
 
 impl Route {
@@ -34,6 +35,7 @@ impl Route {
                 Ok(req.into_response(HttpResponse::NotFound()))
             })),
             guards: Rc::new(Vec::new()),
+            app_data: None,
         }
     }
 
@@ -59,6 +61,7 @@ impl Route {
         Route {
             service: boxed::factory(apply(Compat::new(mw), self.service)),
             guards: self.guards,
+            app_data: self.app_data,
         }
     }
 
@@ -78,17 +81,41 @@ impl ServiceFactory<ServiceRequest> for Route {
     fn new_service(&self, _: ()) -> Self::Future {
         let fut = self.service.new_service(());
         let guards = self.guards.clone();
+        let method = guarded_method(&guards);
+        let app_data = self.app_data.clone();
 
         Box::pin(async move {
             let service = fut.await?;
-            Ok(RouteService { service, guards })
+            Ok(RouteService {
+                service,
+                guards,
+                method,
+                app_data,
+            })
         })
     }
 }
 
+/// Returns the method that the method guards in `guards` match.
+///
+/// Returns `None` if there are no method guards, or if they match different methods, in which case
+/// the guards never pass.
+pub(crate) fn guarded_method(guards: &[Box<dyn Guard>]) -> Option<Method> {
+    let mut methods = guards.iter().filter_map(|guard| guard.registered_method());
+    let method = methods.next()?;
+
+    if methods.all(|other| other == method) {
+        Some(method.clone())
+    } else {
+        None
+    }
+}
+
 pub struct RouteService {
     service: BoxService<ServiceRequest, ServiceResponse, Error>,
     guards: Rc<Vec<Box<dyn Guard>>>,
+    method: Option<Method>,
+    app_data: Option<Rc<Extensions>>,
 }
 
 impl RouteService {
@@ -103,6 +130,23 @@ impl RouteService {
         }
         true
     }
+
+    /// Returns the method of the route's method guards if its other guards pass.
+    pub(crate) fn allowed_method(&self, req: &ServiceRequest) -> Option<&Method> {
+        let method = self.method.as_ref()?;
+        let guard_ctx = req.guard_ctx();
+
+        self.guards
+            .iter()
+            .filter(|guard| guard.registered_method().is_none())
+            .all(|guard| guard.check(&guard_ctx))
+            .then(|| method)
+    }
+
+    /// Returns true if the route has method guards.
+    pub(crate) fn has_method(&self) -> bool {
+        self.method.is_some()
+    }
 }
 
 impl Service<ServiceRequest> for RouteService {
@@ -112,7 +156,12 @@ impl Service<ServiceRequest> for RouteService {
 
     actix_service::forward_ready!(service);
 
-    fn call(&self, req: ServiceRequest) -> Self::Future {
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        if let Some(ref data) = self.app_data {
+            RouteTimeout::set(&req, data);
+            req.add_data_container(Rc::clone(data));
+        }
+
         self.service.call(req)
     }
 }
@@ -158,6 +207,39 @@ impl Route {
         self
     }
 
+    /// Add route data.
+    ///
+    /// Data of different types from parent contexts will still be accessible. Any `Data<T>` types
+    /// set here can be extracted in handlers using the `Data<T>` extractor.
+    ///
+    /// Route data takes precedence over data of the same type set on the resource, scope or app,
+    /// so it can be used to give a single route its own extractor configuration. Data is looked up
+    /// on the route first, then on the resource, any enclosing scopes, and finally the app.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// let app = App::new().service(
+    ///     web::resource("/upload")
+    ///         .app_data(web::JsonConfig::default().limit(4096))
+    ///         // larger JSON limit for PUT requests only
+    ///         .route(
+    ///             web::put()
+    ///                 .app_data(web::JsonConfig::default().limit(1 << 20))
+    ///                 .to(|body: web::Json<serde_json::Value>| async move { body }),
+    ///         )
+    ///         .route(web::post().to(|body: web::Json<serde_json::Value>| async move { body })),
+    /// );
+    /// ```
+    pub fn app_data<U: 'static>(mut self, data: U) -> Self {
+        let app_data = self.app_data.get_or_insert_with(Default::default);
+
+        Rc::get_mut(app_data).unwrap().insert(data);
+
+        self
+    }
+
     /// Set handler function, use request extractors for parameters.
     ///
     /// # Examples
@@ -452,4 +534,56 @@ mod tests {
             Bytes::from_static(b"Goodbye, and thanks for all the fish!")
         );
     }
+
+    #[actix_rt::test]
+    async fn route_app_data() {
+        let srv = init_service(
+            App::new()
+                .app_data(web::JsonConfig::default().limit(1))
+                .service(
+                    web::resource("/test")
+                        .app_data(web::JsonConfig::default().limit(8))
+                        .route(
+                            web::put()
+                                .app_data(web::JsonConfig::default().limit(64))
+                                .to(|body: web::Json<String>| async move { body.into_inner() }),
+                        )
+                        .route(
+                            web::post()
+                                .to(|body: web::Json<String>| async move { body.into_inner() }),
+                        ),
+                ),
+        )
+        .await;
+
+        let body = r#""medium sized body""#;
+
+        let req = TestRequest::put()
+            .uri("/test")
+            .insert_header(header::ContentType::json())
+            .set_payload(body)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            read_body(res).await,
+            Bytes::from_static(b"medium sized body")
+        );
+
+        let req = TestRequest::post()
+            .uri("/test")
+            .insert_header(header::ContentType::json())
+            .set_payload(body)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let req = TestRequest::post()
+            .uri("/test")
+            .insert_header(header::ContentType::json())
+            .set_payload(r#""small""#)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
 }
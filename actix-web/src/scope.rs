@@ -1,6 +1,9 @@
 use std::{cell::RefCell, fmt, future::Future, mem, rc::Rc};
 
-use actix_http::{body::MessageBody, Extensions};
+use actix_http::{
+    body::{EitherBody, MessageBody},
+    Extensions,
+};
 use actix_router::{ResourceDef, Router};
 use actix_service::{
     apply, apply_fn_factory, boxed, IntoServiceFactory, Service, ServiceFactory,
@@ -10,10 +13,12 @@ use futures_core::future::LocalBoxFuture;
 use futures_util::future::join_all;
 
 use crate::{
+    app_service::{route_request, RoutingEntry},
     config::ServiceConfig,
     data::Data,
     dev::AppService,
     guard::Guard,
+    middleware::Condition,
     rmap::ResourceMap,
     service::{
         AppServiceFactory, BoxedHttpService, BoxedHttpServiceFactory, HttpServiceFactory,
@@ -115,6 +120,10 @@ where
     /// Data of different types from parent contexts will still be accessible. Any `Data<T>` types
     /// set here can be extracted in handlers using the `Data<T>` extractor.
     ///
+    /// Data is looked up from the innermost context outwards: route, resource, the nearest scope,
+    /// its parent scopes and finally the app. The first context that has data of the requested
+    /// type wins, so data set here is visible to nested scopes unless they set their own.
+    ///
     /// # Examples
     /// ```
     /// use std::cell::Cell;
@@ -164,6 +173,10 @@ where
     /// This function is useful for moving parts of configuration to a different module or library.
     /// For example, some of the resource's configuration could be moved to different module.
     ///
+    /// Data added using [`ServiceConfig::app_data`] becomes data of this scope, as if it was added
+    /// using [`app_data`](Self::app_data) at this point. It is visible to all services of this
+    /// scope, including nested scopes, but not to services outside of it.
+    ///
     /// ```
     /// use actix_web::{web, middleware, App, HttpResponse};
     ///
@@ -244,6 +257,8 @@ where
     /// This is a simplified version of the `Scope::service()` method.
     /// This method can be called multiple times, in that case
     /// multiple resources with one route would be registered for same resource path.
+    /// Requests for that path whose method is not handled by any of these routes get a
+    /// `405 Method Not Allowed` response, with an `Allow` header listing the methods that are.
     ///
     /// ```
     /// use actix_web::{web, App, HttpResponse};
@@ -270,6 +285,30 @@ where
     ///
     /// If a default service is not registered, it will fall back to the default service of
     /// the parent [`App`](crate::App) (see [`App::default_service`](crate::App::default_service)).
+    ///
+    /// The default service only handles requests that match this scope's prefix and runs inside
+    /// the scope's middleware chain.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// let app = App::new()
+    ///     .service(
+    ///         web::scope("/api")
+    ///             .route("/users", web::get().to(HttpResponse::Ok))
+    ///             // unmatched `/api/*` paths get a JSON 404
+    ///             .default_service(web::to(|| async {
+    ///                 HttpResponse::NotFound().json(serde_json::json!({ "error": "not found" }))
+    ///             })),
+    ///     )
+    ///     // other unmatched paths get an HTML 404
+    ///     .default_service(web::to(|| async {
+    ///         HttpResponse::NotFound()
+    ///             .content_type("text/html")
+    ///             .body("<h1>Not Found</h1>")
+    ///     }));
+    /// ```
     pub fn default_service<F, U>(mut self, f: F) -> Self
     where
         F: IntoServiceFactory<U, ServiceRequest>,
@@ -331,6 +370,61 @@ where
         }
     }
 
+    /// Registers middleware, but only if `enabled` is true.
+    ///
+    /// This is a shorthand for wrapping `mw` in [`middleware::Condition`] and is intended for
+    /// middleware that is enabled or disabled once, when the Scope is constructed; e.g., using a
+    /// build-time or configuration flag. When disabled, the middleware's service is never
+    /// constructed and requests are passed directly to the wrapped service.
+    ///
+    /// Since the decision is made at construction, it can not depend on individual requests.
+    /// Use custom middleware, or [`wrap_fn`](Self::wrap_fn), for per-request decisions.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{middleware, web, App};
+    ///
+    /// let normalize = std::env::var("NORMALIZE_PATH").is_ok();
+    ///
+    /// let app = App::new().service(
+    ///     web::scope("/api")
+    ///         .wrap_conditional(normalize, middleware::NormalizePath::trim())
+    ///         .route("/users", web::get().to(|| async { "users" })),
+    /// );
+    /// ```
+    ///
+    /// [`middleware::Condition`]: crate::middleware::Condition
+    pub fn wrap_conditional<M, B, B2>(
+        self,
+        enabled: bool,
+        mw: M,
+    ) -> Scope<
+        impl ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<EitherBody<B, B2>>,
+            Error = Error,
+            InitError = (),
+        >,
+    >
+    where
+        T: ServiceFactory<ServiceRequest, Response = ServiceResponse<B2>>,
+        T::Service: 'static,
+        M: Transform<
+                T::Service,
+                ServiceRequest,
+                Response = ServiceResponse<B>,
+                Error = Error,
+                InitError = (),
+            > + 'static,
+        M::Future: 'static,
+        M::Transform: 'static,
+        B: MessageBody + 'static,
+        B2: MessageBody + 'static,
+    {
+        self.wrap(Condition::new(enabled, mw))
+    }
+
     /// Registers a scope-wide function middleware.
     ///
     /// `mw` is a closure that runs during inbound and/or outbound processing in the request
@@ -489,7 +583,8 @@ impl ServiceFactory<ServiceRequest> for ScopeFactory {
                 .collect::<Result<Vec<_>, _>>()?
                 .drain(..)
                 .fold(Router::build(), |mut router, (path, guards, service)| {
-                    router.push(path, service, guards);
+                    let entry = RoutingEntry::new(&path, guards);
+                    router.push(path, service, entry);
                     router
                 })
                 .finish();
@@ -500,7 +595,7 @@ impl ServiceFactory<ServiceRequest> for ScopeFactory {
 }
 
 pub struct ScopeService {
-    router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
+    router: Router<BoxedHttpService, RoutingEntry>,
     default: BoxedHttpService,
 }
 
@@ -511,17 +606,8 @@ impl Service<ServiceRequest> for ScopeService {
 
     actix_service::always_ready!();
 
-    fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        let res = self.router.recognize_fn(&mut req, |req, guards| {
-            let guard_ctx = req.guard_ctx();
-            guards.iter().all(|guard| guard.check(&guard_ctx))
-        });
-
-        if let Some((srv, _info)) = res {
-            srv.call(req)
-        } else {
-            self.default.call(req)
-        }
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        route_request(&self.router, &self.default, req)
     }
 }
 
@@ -694,6 +780,83 @@ mod tests {
             .method(Method::POST)
             .to_request();
         let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "GET, DELETE");
+
+        let req = TestRequest::with_uri("/app/path2").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_scope_route_method_not_allowed() {
+        let srv = init_service(
+            App::new()
+                .route("/", web::get().to(HttpResponse::Ok))
+                .route("/", web::post().to(HttpResponse::Ok))
+                .service(
+                    web::scope("/app")
+                        .guard(guard::Header("x-app", "1"))
+                        .service(
+                            web::scope("/v1")
+                                .route("/{id}", web::get().to(HttpResponse::Ok))
+                                .route(
+                                    "/{id}",
+                                    web::put()
+                                        .guard(guard::Header("x-admin", "1"))
+                                        .to(HttpResponse::Ok),
+                                )
+                                .route("/{id}", web::patch().to(HttpResponse::Ok))
+                                .route(
+                                    "/{id}",
+                                    web::post()
+                                        .guard(guard::Not(guard::Get()))
+                                        .to(HttpResponse::Ok),
+                                ),
+                        ),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/").method(Method::PUT).to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "GET, POST");
+
+        // methods of routes whose other guards fail are not allowed
+        let req = TestRequest::with_uri("/app/v1/42")
+            .method(Method::DELETE)
+            .insert_header(("x-app", "1"))
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            resp.headers().get(header::ALLOW).unwrap(),
+            "GET, PATCH, POST"
+        );
+
+        let req = TestRequest::with_uri("/app/v1/42")
+            .method(Method::DELETE)
+            .insert_header(("x-app", "1"))
+            .insert_header(("x-admin", "1"))
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            resp.headers().get(header::ALLOW).unwrap(),
+            "GET, PUT, PATCH, POST"
+        );
+
+        let req = TestRequest::with_uri("/app/v1/42")
+            .method(Method::PATCH)
+            .insert_header(("x-app", "1"))
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // scope guards are not method guards
+        let req = TestRequest::with_uri("/app/v1/42").to_request();
+        let resp = srv.call(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
@@ -907,6 +1070,60 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
+    #[actix_rt::test]
+    async fn test_default_resource_with_guarded_routes() {
+        async fn json_not_found() -> HttpResponse {
+            HttpResponse::NotFound()
+                .content_type("application/json")
+                .body("{}")
+        }
+
+        async fn html_not_found() -> HttpResponse {
+            HttpResponse::NotFound().content_type("text/html").finish()
+        }
+
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::scope("/api")
+                        .route(
+                            "/user",
+                            web::get()
+                                .guard(guard::Header("x-api-key", "secret"))
+                                .to(HttpResponse::Ok),
+                        )
+                        .default_service(web::to(json_not_found)),
+                )
+                .default_service(web::to(html_not_found)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/api/user")
+            .insert_header(("x-api-key", "secret"))
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // guard failure falls through to scope default
+        for uri in ["/api/user", "/api/non-exist"] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let resp = srv.call(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+            assert_eq!(
+                resp.headers().get(header::CONTENT_TYPE).unwrap(),
+                "application/json"
+            );
+        }
+
+        let req = TestRequest::with_uri("/non-exist").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html"
+        );
+    }
+
     #[actix_rt::test]
     async fn test_default_resource_propagation() {
         let srv = init_service(
@@ -955,6 +1172,29 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_middleware_conditional() {
+        for enabled in [true, false] {
+            let srv = init_service(
+                App::new().service(
+                    web::scope("app")
+                        .wrap_conditional(
+                            enabled,
+                            DefaultHeaders::new()
+                                .add((header::CONTENT_TYPE, HeaderValue::from_static("0001"))),
+                        )
+                        .service(web::resource("/test").route(web::get().to(HttpResponse::Ok))),
+                ),
+            )
+            .await;
+
+            let req = TestRequest::with_uri("/app/test").to_request();
+            let resp = call_service(&srv, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().contains_key(header::CONTENT_TYPE), enabled);
+        }
+    }
+
     #[actix_rt::test]
     async fn test_middleware_body_type() {
         // Compile test that Scope accepts any body type; test for `EitherBody`
@@ -1098,6 +1338,55 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_rt::test]
+    async fn test_nested_scope_app_data() {
+        async fn handler(
+            app: web::Data<u8>,
+            outer: web::Data<u16>,
+            inner: web::Data<u32>,
+            resource: web::Data<u64>,
+            route: web::Data<i8>,
+            overridden: web::Data<&'static str>,
+        ) -> String {
+            format!(
+                "{} {} {} {} {} {}",
+                **app, **outer, **inner, **resource, **route, **overridden
+            )
+        }
+
+        let srv = init_service(
+            App::new()
+                .app_data(web::Data::new(1u8))
+                .app_data(web::Data::new("app"))
+                .service(web::scope("/outer").configure(|cfg| {
+                    cfg.app_data(web::Data::new(2u16))
+                        .app_data(web::Data::new("outer"))
+                        .service(
+                            web::scope("/inner")
+                                .app_data(web::Data::new(3u32))
+                                .configure(|cfg| {
+                                    cfg.app_data(web::Data::new("inner"));
+                                })
+                                .service(
+                                    web::resource("/resource")
+                                        .app_data(web::Data::new(4u64))
+                                        .route(
+                                            web::get()
+                                                .app_data(web::Data::new(5i8))
+                                                .to(handler),
+                                        ),
+                                ),
+                        );
+                })),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/outer/inner/resource").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, "1 2 3 4 5 inner");
+    }
+
     #[actix_rt::test]
     async fn test_scope_config() {
         let srv = init_service(App::new().service(web::scope("/app").configure(|s| {
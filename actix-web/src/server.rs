@@ -1,17 +1,24 @@
 use std::{
     any::Any,
-    cmp, fmt, io,
+    cmp, fmt,
+    future::Future,
+    io,
     marker::PhantomData,
     net,
     sync::{Arc, Mutex},
     time::Duration,
 };
 
-use actix_http::{body::MessageBody, Extensions, HttpService, KeepAlive, Request, Response};
+use actix_http::{
+    body::{BoxBody, MessageBody},
+    h1, ConnectionType, Extensions, HttpService, KeepAlive, Request, RequestHead, Response,
+    StatusCode,
+};
 use actix_server::{Server, ServerBuilder};
 use actix_service::{
-    map_config, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
+    fn_service, map_config, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
 };
+use futures_core::future::BoxFuture;
 
 #[cfg(feature = "openssl")]
 use actix_tls::accept::openssl::reexports::{AlpnError, SslAcceptor, SslAcceptorBuilder};
@@ -20,8 +27,12 @@ use actix_tls::accept::rustls::reexports::ServerConfig as RustlsServerConfig;
 
 #[cfg(any(feature = "openssl", feature = "rustls"))]
 use actix_http::TlsAcceptorConfig;
+#[cfg(feature = "rustls")]
+use actix_tls::accept::rustls::TlsStream as RustlsStream;
 
-use crate::{config::AppConfig, Error};
+#[cfg(feature = "rustls")]
+use crate::web::PeerCertificates;
+use crate::{config::AppConfig, info::TrustedProxies, Error};
 
 struct Socket {
     scheme: &'static str,
@@ -35,6 +46,23 @@ struct Config {
     client_disconnect_timeout: Duration,
     #[cfg(any(feature = "openssl", feature = "rustls"))]
     tls_handshake_timeout: Option<Duration>,
+    max_header_count: usize,
+    max_header_size: usize,
+    max_request_line_size: usize,
+    on_expect_continue: Option<ExpectFn>,
+    trusted_proxies: Option<Arc<TrustedProxies>>,
+}
+
+type ExpectFn = Arc<dyn Fn(&RequestHead) -> ExpectAction + Send + Sync>;
+
+/// Outcome of an [`on_expect_continue`](HttpServer::on_expect_continue) callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectAction {
+    /// Send a `100 Continue` interim response and handle the request as usual.
+    Continue,
+
+    /// Respond with the given status code without reading the request body.
+    Reject(StatusCode),
 }
 
 /// An HTTP Server.
@@ -76,6 +104,8 @@ where
     builder: ServerBuilder,
     #[allow(clippy::type_complexity)]
     on_connect_fn: Option<Arc<dyn Fn(&dyn Any, &mut Extensions) + Send + Sync>>,
+    shutdown_timeout: Duration,
+    on_shutdown: Vec<ShutdownHook>,
     _phantom: PhantomData<(S, B)>,
 }
 
@@ -104,11 +134,18 @@ where
                 client_disconnect_timeout: Duration::from_secs(1),
                 #[cfg(any(feature = "rustls", feature = "openssl"))]
                 tls_handshake_timeout: None,
+                max_header_count: h1::DEFAULT_MAX_HEADER_COUNT,
+                max_header_size: h1::DEFAULT_MAX_HEADER_SIZE,
+                max_request_line_size: h1::DEFAULT_MAX_REQUEST_LINE_SIZE,
+                on_expect_continue: None,
+                trusted_proxies: None,
             })),
             backlog: 1024,
             sockets: Vec::new(),
             builder: ServerBuilder::default(),
             on_connect_fn: None,
+            shutdown_timeout: Duration::from_secs(30),
+            on_shutdown: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -233,16 +270,143 @@ where
         self.client_disconnect_timeout(Duration::from_millis(dur))
     }
 
+    /// Sets the maximum number of headers in HTTP/1 requests.
+    ///
+    /// Requests with more headers are rejected with a `431 Request Header Fields Too Large`
+    /// response.
+    ///
+    /// By default the limit is [`DEFAULT_MAX_HEADER_COUNT`](h1::DEFAULT_MAX_HEADER_COUNT).
+    pub fn max_header_count(self, count: usize) -> Self {
+        self.config.lock().unwrap().max_header_count = count;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of HTTP/1 request heads.
+    ///
+    /// The size includes the request line and all headers. Requests with larger heads are rejected
+    /// with a `431 Request Header Fields Too Large` response.
+    ///
+    /// By default the limit is [`DEFAULT_MAX_HEADER_SIZE`](h1::DEFAULT_MAX_HEADER_SIZE).
+    pub fn max_header_size(self, size: usize) -> Self {
+        self.config.lock().unwrap().max_header_size = size;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of HTTP/1 request lines, excluding the line ending.
+    ///
+    /// Requests with longer request lines (e.g., because of a long URI) are rejected with a
+    /// `431 Request Header Fields Too Large` response. Request lines are also limited by
+    /// [`max_header_size`](Self::max_header_size).
+    ///
+    /// By default the limit is
+    /// [`DEFAULT_MAX_REQUEST_LINE_SIZE`](h1::DEFAULT_MAX_REQUEST_LINE_SIZE).
+    pub fn max_request_line_size(self, size: usize) -> Self {
+        self.config.lock().unwrap().max_request_line_size = size;
+        self
+    }
+
+    /// Sets the proxies whose forwarding headers are trusted.
+    ///
+    /// `proxies` is a list of IP addresses and CIDR ranges, such as `"10.0.0.0/8"` or `"::1"`.
+    ///
+    /// Once set, [`ConnectionInfo`](crate::dev::ConnectionInfo) only honors the `Forwarded` and
+    /// `X-Forwarded-*` headers of requests whose peer address is in one of these ranges. For other
+    /// requests, including those from Unix domain sockets, the headers are ignored and, e.g.,
+    /// [`realip_remote_addr`](crate::dev::ConnectionInfo::realip_remote_addr) returns the peer
+    /// address. The client address is taken to be the last forwarded-for address that does not
+    /// belong to a trusted proxy, so that clients cannot spoof it by sending their own headers.
+    ///
+    /// By default, forwarding headers are always honored.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` with kind [`InvalidInput`](io::ErrorKind::InvalidInput) if any of the
+    /// `proxies` is not a valid IP address or CIDR range.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{dev::ConnectionInfo, web, App, HttpServer};
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// HttpServer::new(|| {
+    ///     App::new().route(
+    ///         "/",
+    ///         web::get().to(|conn: ConnectionInfo| async move {
+    ///             conn.realip_remote_addr().unwrap_or("unknown").to_owned()
+    ///         }),
+    ///     )
+    /// })
+    /// .trusted_proxies(["10.0.0.0/8", "127.0.0.1"])?
+    /// .bind(("127.0.0.1", 8080))?
+    /// .run()
+    /// .await
+    /// # }
+    /// ```
+    pub fn trusted_proxies<P>(self, proxies: P) -> io::Result<Self>
+    where
+        P: IntoIterator,
+        P::Item: AsRef<str>,
+    {
+        let proxies = TrustedProxies::parse(proxies)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        self.config.lock().unwrap().trusted_proxies = Some(Arc::new(proxies));
+        Ok(self)
+    }
+
+    /// Sets function that decides how HTTP/1 requests with an `Expect: 100-continue` header are
+    /// handled.
+    ///
+    /// The function is called with the request head before any of the request body is read.
+    /// Returning [`ExpectAction::Continue`] sends a `100 Continue` interim response and passes the
+    /// request on to the application. Returning [`ExpectAction::Reject`] immediately sends a
+    /// response with the given status code and closes the connection, so clients waiting for
+    /// `100 Continue` need not upload the body at all.
+    ///
+    /// By default, all such requests are continued.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{dev::ExpectAction, http::{header, StatusCode}, web, App, HttpServer};
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// HttpServer::new(|| App::new().route("/upload", web::post().to(|| async { "done" })))
+    ///     .on_expect_continue(|head| {
+    ///         if head.headers().contains_key(header::AUTHORIZATION) {
+    ///             ExpectAction::Continue
+    ///         } else {
+    ///             ExpectAction::Reject(StatusCode::UNAUTHORIZED)
+    ///         }
+    ///     })
+    ///     .bind(("127.0.0.1", 8080))?
+    ///     .run()
+    ///     .await
+    /// # }
+    /// ```
+    pub fn on_expect_continue<CB>(self, f: CB) -> Self
+    where
+        CB: Fn(&RequestHead) -> ExpectAction + Send + Sync + 'static,
+    {
+        self.config.lock().unwrap().on_expect_continue = Some(Arc::new(f));
+        self
+    }
+
     /// Sets function that will be called once before each connection is handled.
     ///
     /// It will receive a `&std::any::Any`, which contains underlying connection type and an
-    /// [Extensions] container so that connection data can be accessed in middleware and handlers.
+    /// [Extensions] container so that connection data can be accessed in middleware and handlers,
+    /// using [`HttpRequest::conn_data`](crate::HttpRequest::conn_data) or the
+    /// [`ConnData`](crate::web::ConnData) extractor.
+    ///
+    /// Connection data is shared by all requests served on the connection and is dropped when the
+    /// connection is closed.
     ///
     /// # Connection Types
     /// - `actix_tls::accept::openssl::TlsStream<actix_web::rt::net::TcpStream>` when using OpenSSL.
     /// - `actix_tls::accept::rustls::TlsStream<actix_web::rt::net::TcpStream>` when using Rustls.
     /// - `actix_web::rt::net::TcpStream` when no encryption is used.
     ///
+    /// When using Rustls, the client's certificate chain, if any, is inserted into the connection
+    /// data as [`PeerCertificates`](crate::web::PeerCertificates) before this function is called.
+    ///
     /// See the `on_connect` example for additional details.
     pub fn on_connect<CB>(self, f: CB) -> HttpServer<F, I, S, B>
     where
@@ -255,6 +419,8 @@ where
             sockets: self.sockets,
             builder: self.builder,
             on_connect_fn: Some(Arc::new(f)),
+            shutdown_timeout: self.shutdown_timeout,
+            on_shutdown: self.on_shutdown,
             _phantom: PhantomData,
         }
     }
@@ -292,6 +458,47 @@ where
     /// By default shutdown timeout sets to 30 seconds.
     pub fn shutdown_timeout(mut self, sec: u64) -> Self {
         self.builder = self.builder.shutdown_timeout(sec);
+        self.shutdown_timeout = Duration::from_secs(sec);
+        self
+    }
+
+    /// Adds an async function to run when the server starts a graceful shutdown.
+    ///
+    /// Shutdown hooks run once the server starts draining, however the shutdown was started: by a
+    /// stop signal or using its [`ServerHandle`](crate::dev::ServerHandle). They run on the runtime
+    /// the server was started in, one at a time and in the order they were added, while workers
+    /// finish serving open connections. This makes hooks suitable for draining application state,
+    /// such as flushing buffered metrics or closing connection pools, that is not owned by any
+    /// single worker.
+    ///
+    /// Each hook is called at most once. Hooks are given, overall, as long as the
+    /// [shutdown timeout](Self::shutdown_timeout) to complete; hooks still running after the
+    /// timeout are dropped. The server's future does not wait for hooks, so the runtime must be
+    /// kept running until they have completed. Hooks are not run on forced shutdowns, such as
+    /// those started by `SIGINT`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{web, App, HttpServer};
+    ///
+    /// # async fn flush_metrics() {}
+    /// #[actix_web::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     HttpServer::new(|| App::new().route("/", web::get().to(|| async { "hello world" })))
+    ///         .on_shutdown(|| async {
+    ///             flush_metrics().await;
+    ///         })
+    ///         .bind(("127.0.0.1", 8080))?
+    ///         .run()
+    ///         .await
+    /// }
+    /// ```
+    pub fn on_shutdown<H, Fut>(mut self, hook: H) -> Self
+    where
+        H: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_shutdown.push(Box::new(move || Box::pin(hook())));
         self
     }
 
@@ -338,7 +545,7 @@ where
     /// # ; Ok(()) }
     /// ```
     pub fn bind<A: net::ToSocketAddrs>(mut self, addrs: A) -> io::Result<Self> {
-        let sockets = bind_addrs(addrs, self.backlog)?;
+        let sockets = bind_addrs(addrs, self.backlog, false)?;
 
         for lst in sockets {
             self = self.listen(lst)?;
@@ -347,6 +554,118 @@ where
         Ok(self)
     }
 
+    /// Resolves socket address(es), binds server to created listener(s), and returns the addresses
+    /// that were bound.
+    ///
+    /// Behaves like [`bind`](Self::bind) but also returns the local address of each listener
+    /// created by this call. This is most useful when binding to port 0, to discover which port
+    /// the OS assigned. Note that when `addrs` resolves to multiple addresses, such as
+    /// `localhost:0`, each listener may be assigned a different port.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` in the same cases as [`bind`](Self::bind).
+    ///
+    /// # Example
+    /// ```
+    /// # use actix_web::{App, HttpServer};
+    /// # fn inner() -> std::io::Result<()> {
+    /// let (server, addrs) = HttpServer::new(|| App::new()).bind_auto_port(("127.0.0.1", 0))?;
+    /// assert_ne!(addrs[0].port(), 0);
+    /// # Ok(()) }
+    /// # inner().unwrap();
+    /// ```
+    pub fn bind_auto_port<A: net::ToSocketAddrs>(
+        mut self,
+        addrs: A,
+    ) -> io::Result<(Self, Vec<net::SocketAddr>)> {
+        let sockets = bind_addrs(addrs, self.backlog, false)?;
+        let mut bound = Vec::with_capacity(sockets.len());
+
+        for lst in sockets {
+            bound.push(lst.local_addr()?);
+            self = self.listen(lst)?;
+        }
+
+        Ok((self, bound))
+    }
+
+    /// Resolves socket address(es) and binds server to created listener(s) with the
+    /// `SO_REUSEPORT` socket option set.
+    ///
+    /// Behaves like [`bind`](Self::bind), except that other sockets with `SO_REUSEPORT` set, such
+    /// as those of other processes, may be bound to the same address. The kernel then distributes
+    /// incoming connections across all of them. This can be used for zero-downtime restarts, where
+    /// a new server process binds to the address before the old one is [stopped
+    /// gracefully](crate::dev::ServerHandle::stop), or to run multiple server processes.
+    ///
+    /// Within each server, connections are accepted on a single thread and distributed across its
+    /// [workers](Self::workers).
+    ///
+    /// # Errors
+    /// Returns an `io::Error` in the same cases as [`bind`](Self::bind), which includes the
+    /// address being bound by a socket without `SO_REUSEPORT` set.
+    ///
+    /// # Example
+    /// ```
+    /// # use actix_web::{App, HttpServer};
+    /// # fn inner() -> std::io::Result<()> {
+    /// HttpServer::new(|| App::new())
+    ///     .bind_reuseport(("127.0.0.1", 8080))?
+    /// # ; Ok(()) }
+    /// ```
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    pub fn bind_reuseport<A: net::ToSocketAddrs>(mut self, addrs: A) -> io::Result<Self> {
+        let sockets = bind_addrs(addrs, self.backlog, true)?;
+
+        for lst in sockets {
+            self = self.listen(lst)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Binds to existing listener, with the `SO_REUSEPORT` socket option set, for accepting
+    /// incoming connection requests.
+    ///
+    /// Behaves like [`listen`](Self::listen), but first checks that `SO_REUSEPORT` is set on
+    /// `lst`. Use this for sockets that are meant to share their address with other sockets, such
+    /// as those passed down by a supervisor to each of several server processes; unlike
+    /// [`bind_reuseport`](Self::bind_reuseport), `lst` is not bound again. The kernel distributes
+    /// incoming connections across all sockets bound to the address, and each server distributes
+    /// the connections it accepts across its [workers](Self::workers).
+    ///
+    /// # Errors
+    /// Returns an `io::Error` with kind [`InvalidInput`](io::ErrorKind::InvalidInput) if
+    /// `SO_REUSEPORT` is not set on `lst`, or in the same cases as [`listen`](Self::listen).
+    ///
+    /// # Example
+    /// ```
+    /// # use actix_web::{App, HttpServer};
+    /// # fn inner() -> std::io::Result<()> {
+    /// use socket2::{Domain, Socket, Type};
+    ///
+    /// let addr = "127.0.0.1:8080".parse::<std::net::SocketAddr>().unwrap();
+    /// let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    /// socket.set_reuse_port(true)?;
+    /// socket.bind(&addr.into())?;
+    /// socket.listen(1024)?;
+    ///
+    /// HttpServer::new(|| App::new())
+    ///     .listen_reuseport(socket.into())?
+    /// # ; Ok(()) }
+    /// ```
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    pub fn listen_reuseport(self, lst: net::TcpListener) -> io::Result<Self> {
+        if !socket2::SockRef::from(&lst).reuse_port()? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "listener does not have SO_REUSEPORT set",
+            ));
+        }
+
+        self.listen(lst)
+    }
+
     /// Resolves socket address(es) and binds server to created listener(s) for TLS connections
     /// using Rustls.
     ///
@@ -359,7 +678,7 @@ where
         addrs: A,
         config: RustlsServerConfig,
     ) -> io::Result<Self> {
-        let sockets = bind_addrs(addrs, self.backlog)?;
+        let sockets = bind_addrs(addrs, self.backlog, false)?;
         for lst in sockets {
             self = self.listen_rustls_inner(lst, config.clone())?;
         }
@@ -377,7 +696,7 @@ where
     where
         A: net::ToSocketAddrs,
     {
-        let sockets = bind_addrs(addrs, self.backlog)?;
+        let sockets = bind_addrs(addrs, self.backlog, false)?;
         let acceptor = openssl_acceptor(builder)?;
 
         for lst in sockets {
@@ -389,8 +708,16 @@ where
 
     /// Binds to existing listener for accepting incoming connection requests.
     ///
-    /// No changes are made to `lst`'s configuration. Ensure it is configured properly before
-    /// passing ownership to `listen()`.
+    /// Apart from being switched to non-blocking mode, no changes are made to `lst`'s
+    /// configuration. Ensure it is configured properly before passing ownership to `listen()`.
+    /// This allows serving sockets that were bound by another process, such as listening sockets
+    /// passed down by a supervisor for socket activation; actix-web does not bind them again.
+    ///
+    /// Connections are accepted on a single thread and distributed across the server's
+    /// [workers](Self::workers). When multiple processes serve sockets bound to the same address
+    /// with `SO_REUSEPORT` set, the kernel distributes connections across those sockets; see
+    /// [`bind_reuseport`](Self::bind_reuseport) for binding such sockets and
+    /// [`listen_reuseport`](Self::listen_reuseport) for serving them.
     pub fn listen(mut self, lst: net::TcpListener) -> io::Result<Self> {
         let cfg = self.config.clone();
         let factory = self.factory.clone();
@@ -408,11 +735,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let trusted_proxies = c.trusted_proxies.clone();
 
                     let mut svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_max_header_count(c.max_header_count)
+                        .h1_max_header_size(c.max_header_size)
+                        .h1_max_request_line_size(c.max_request_line_size)
+                        .expect(expect_handler(c.on_expect_continue.clone()))
                         .local_addr(addr);
 
                     if let Some(handler) = on_connect_fn.clone() {
@@ -427,6 +759,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
+                            .with_trusted_proxies(trusted_proxies.clone())
                     }))
                     .tcp()
                 })?;
@@ -468,17 +801,27 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let trusted_proxies = c.trusted_proxies.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
-
-                    let svc = if let Some(handler) = on_connect_fn.clone() {
-                        svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
-                    } else {
-                        svc
-                    };
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_max_header_count(c.max_header_count)
+                        .h1_max_header_size(c.max_header_size)
+                        .h1_max_request_line_size(c.max_request_line_size)
+                        .expect(expect_handler(c.on_expect_continue.clone()));
+
+                    let on_connect_fn = on_connect_fn.clone();
+                    let svc = svc.on_connect_ext(
+                        move |io: &RustlsStream<actix_rt::net::TcpStream>, ext: _| {
+                            insert_peer_certificates(io, ext);
+
+                            if let Some(ref handler) = on_connect_fn {
+                                (handler)(io as &dyn Any, ext)
+                            }
+                        },
+                    );
 
                     let fac = factory()
                         .into_factory()
@@ -491,6 +834,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_trusted_proxies(trusted_proxies.clone())
                     }))
                     .rustls_with_config(config.clone(), acceptor_config)
                 })?;
@@ -533,11 +877,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let trusted_proxies = c.trusted_proxies.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_max_header_count(c.max_header_count)
+                        .h1_max_header_size(c.max_header_size)
+                        .h1_max_request_line_size(c.max_request_line_size)
+                        .expect(expect_handler(c.on_expect_continue.clone()))
                         .local_addr(addr);
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
@@ -559,6 +908,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_trusted_proxies(trusted_proxies.clone())
                     }))
                     .openssl_with_config(acceptor.clone(), acceptor_config)
                 })?;
@@ -595,7 +945,8 @@ where
                     false,
                     c.host.clone().unwrap_or_else(|| format!("{}", socket_addr)),
                     socket_addr,
-                );
+                )
+                .with_trusted_proxies(c.trusted_proxies.clone());
 
                 let fac = factory()
                     .into_factory()
@@ -606,6 +957,10 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_max_header_count(c.max_header_count)
+                        .h1_max_header_size(c.max_header_size)
+                        .h1_max_request_line_size(c.max_request_line_size)
+                        .expect(expect_handler(c.on_expect_continue.clone()))
                         .finish(map_config(fac, move |_| config.clone())),
                 )
             },
@@ -640,13 +995,18 @@ where
                 false,
                 c.host.clone().unwrap_or_else(|| format!("{}", socket_addr)),
                 socket_addr,
-            );
+            )
+            .with_trusted_proxies(c.trusted_proxies.clone());
 
             fn_service(|io: UnixStream| async { Ok((io, Protocol::Http1, None)) }).and_then({
                 let mut svc = HttpService::build()
                     .keep_alive(c.keep_alive)
                     .client_request_timeout(c.client_request_timeout)
-                    .client_disconnect_timeout(c.client_disconnect_timeout);
+                    .client_disconnect_timeout(c.client_disconnect_timeout)
+                    .h1_max_header_count(c.max_header_count)
+                    .h1_max_header_size(c.max_header_size)
+                    .h1_max_request_line_size(c.max_request_line_size)
+                    .expect(expect_handler(c.on_expect_continue.clone()));
 
                 if let Some(handler) = on_connect_fn.clone() {
                     svc = svc
@@ -688,21 +1048,49 @@ where
     /// This methods panics if no socket addresses were successfully bound or if no Tokio runtime
     /// is set up.
     pub fn run(self) -> Server {
+        if !self.on_shutdown.is_empty() {
+            let shutdown = self.builder.graceful_shutdown_signal();
+            let shutdown_timeout = self.shutdown_timeout;
+            let hooks = self.on_shutdown;
+
+            tokio::spawn(async move {
+                shutdown.notified().await;
+
+                log::info!("Running shutdown hooks");
+
+                let hooks = async move {
+                    for hook in hooks {
+                        hook().await;
+                    }
+                };
+
+                if actix_rt::time::timeout(shutdown_timeout, hooks)
+                    .await
+                    .is_err()
+                {
+                    log::warn!("Shutdown hooks timed out");
+                }
+            });
+        }
+
         self.builder.run()
     }
 }
 
+type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
 /// Bind TCP listeners to socket addresses resolved from `addrs` with options.
 fn bind_addrs(
     addrs: impl net::ToSocketAddrs,
     backlog: u32,
+    reuse_port: bool,
 ) -> io::Result<Vec<net::TcpListener>> {
     let mut err = None;
     let mut success = false;
     let mut sockets = Vec::new();
 
     for addr in addrs.to_socket_addrs()? {
-        match create_tcp_listener(addr, backlog) {
+        match create_tcp_listener(addr, backlog, reuse_port) {
             Ok(lst) => {
                 success = true;
                 sockets.push(lst);
@@ -723,12 +1111,52 @@ fn bind_addrs(
     }
 }
 
+/// Creates expect service that consults the `on_expect_continue` function, if any.
+fn expect_handler(
+    on_expect_continue: Option<ExpectFn>,
+) -> impl ServiceFactory<
+    Request,
+    Config = (),
+    Response = Request,
+    Error = Response<BoxBody>,
+    InitError = (),
+> {
+    fn_service(move |req: Request| {
+        let action = match on_expect_continue {
+            Some(ref f) => f(req.head()),
+            None => ExpectAction::Continue,
+        };
+
+        std::future::ready(match action {
+            ExpectAction::Continue => Ok(req),
+            ExpectAction::Reject(status) => {
+                let mut res = Response::new(status);
+                res.head_mut().set_connection_type(ConnectionType::Close);
+                Err(res)
+            }
+        })
+    })
+}
+
 /// Creates a TCP listener from socket address and options.
-fn create_tcp_listener(addr: net::SocketAddr, backlog: u32) -> io::Result<net::TcpListener> {
+fn create_tcp_listener(
+    addr: net::SocketAddr,
+    backlog: u32,
+    reuse_port: bool,
+) -> io::Result<net::TcpListener> {
     use socket2::{Domain, Protocol, Socket, Type};
     let domain = Domain::for_address(addr);
     let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
     socket.set_reuse_address(true)?;
+
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    if reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+
+    #[cfg(not(all(unix, not(any(target_os = "solaris", target_os = "illumos")))))]
+    let _ = reuse_port;
+
     socket.bind(&addr.into())?;
     // clamp backlog to max u32 that fits in i32 range
     let backlog = cmp::min(backlog, i32::MAX as u32) as i32;
@@ -756,3 +1184,18 @@ fn openssl_acceptor(mut builder: SslAcceptorBuilder) -> io::Result<SslAcceptor>
 
     Ok(builder.build())
 }
+
+/// Inserts the certificate chain presented by the client, if any, into connection data.
+#[cfg(feature = "rustls")]
+fn insert_peer_certificates(io: &RustlsStream<actix_rt::net::TcpStream>, ext: &mut Extensions) {
+    let (_, conn) = io.get_ref();
+
+    if let Some(certs) = conn.peer_certificates().filter(|certs| !certs.is_empty()) {
+        let chain = certs
+            .iter()
+            .map(|cert| bytes::Bytes::copy_from_slice(&cert.0))
+            .collect();
+
+        ext.insert(PeerCertificates::new(chain));
+    }
+}
@@ -2,10 +2,11 @@ use std::{
     cell::{Ref, RefMut},
     fmt, net,
     rc::Rc,
+    sync::Arc,
 };
 
 use actix_http::{
-    body::{BoxBody, EitherBody, MessageBody},
+    body::{BoxBody, EitherBody, EitherBody3, MessageBody},
     header::HeaderMap,
     BoxedPayloadStream, Extensions, HttpMessage, Method, Payload, RequestHead, Response,
     ResponseHead, StatusCode, Uri, Version,
@@ -112,6 +113,12 @@ impl ServiceRequest {
     /// Returns the `T` extractor's `Future` type which can be `await`ed. This is particularly handy
     /// when you want to use an extractor in a middleware implementation.
     ///
+    /// Extractors that only read the request head, such as [`Path`](crate::web::Path) or
+    /// [`Header`](crate::web::Header), leave the payload in place for the wrapped service. Extractors
+    /// that read the body, such as [`Bytes`](crate::web::Bytes) or [`Json`](crate::web::Json),
+    /// take the payload, leaving it empty; use [`set_payload`](Self::set_payload) to put the body
+    /// back if the wrapped service also needs it.
+    ///
     /// # Examples
     /// ```
     /// use actix_web::{
@@ -262,6 +269,12 @@ impl ServiceRequest {
         self.req.match_pattern()
     }
 
+    /// Counterpart to [`HttpRequest::match_pattern_str`].
+    #[inline]
+    pub fn match_pattern_str(&self) -> Option<&str> {
+        self.req.match_pattern_str()
+    }
+
     /// Returns a reference to the application's resource map.
     /// Counterpart to [`HttpRequest::resource_map`].
     #[inline]
@@ -307,12 +320,47 @@ impl ServiceRequest {
         self.req.cookie(name)
     }
 
+    /// Returns the value of the named request cookie, parsed as `T`.
+    ///
+    /// Counterpart to [`HttpRequest::cookie_typed`].
+    #[cfg(feature = "cookies")]
+    #[inline]
+    pub fn cookie_typed<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.req.cookie_typed(name)
+    }
+
     /// Set request payload.
     #[inline]
     pub fn set_payload(&mut self, payload: Payload) {
         self.payload = payload;
     }
 
+    /// Inserts request-local data, returning any previous value of the same type.
+    ///
+    /// Handlers can extract the data using [`ReqData<T>`](crate::web::ReqData). Also see
+    /// [`req_data`](Self::req_data) for reading it back in middleware.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::test::TestRequest;
+    /// let mut req = TestRequest::default().to_srv_request();
+    ///
+    /// assert_eq!(req.insert_req_data(42u32), None);
+    /// assert_eq!(req.insert_req_data(43u32), Some(42));
+    /// assert_eq!(req.req_data::<u32>(), Some(43));
+    /// ```
+    pub fn insert_req_data<T: 'static>(&mut self, data: T) -> Option<T> {
+        self.extensions_mut().insert(data)
+    }
+
+    /// Returns a clone of the request-local data of type `T`, if present.
+    ///
+    /// The request extensions are not borrowed beyond this call, so the request can be passed on
+    /// to the next service while holding the returned value.
+    pub fn req_data<T: Clone + 'static>(&self) -> Option<T> {
+        self.extensions().get::<T>().cloned()
+    }
+
     /// Add data container to request's resolution set.
     ///
     /// In middleware, prefer [`extensions_mut`](ServiceRequest::extensions_mut) for request-local
@@ -324,6 +372,11 @@ impl ServiceRequest {
             .push(extensions);
     }
 
+    /// Records the full pattern of the resource handling this request.
+    pub(crate) fn set_matched_pattern(&mut self, pattern: Option<Arc<str>>) {
+        Rc::get_mut(&mut (self.req).inner).unwrap().matched_pattern = pattern;
+    }
+
     /// Creates a context object for use with a routing [guard](crate::guard).
     #[inline]
     pub fn guard_ctx(&self) -> GuardContext<'_> {
@@ -487,6 +540,21 @@ impl<B> ServiceResponse<B> {
         self.map_body(|_, body| EitherBody::right(body))
     }
 
+    #[inline]
+    pub fn map_into_first_body<B2, B3>(self) -> ServiceResponse<EitherBody3<B, B2, B3>> {
+        self.map_body(|_, body| EitherBody3::first(body))
+    }
+
+    #[inline]
+    pub fn map_into_second_body<B1, B3>(self) -> ServiceResponse<EitherBody3<B1, B, B3>> {
+        self.map_body(|_, body| EitherBody3::second(body))
+    }
+
+    #[inline]
+    pub fn map_into_third_body<B1, B2>(self) -> ServiceResponse<EitherBody3<B1, B2, B>> {
+        self.map_body(|_, body| EitherBody3::third(body))
+    }
+
     #[inline]
     pub fn map_into_boxed_body(self) -> ServiceResponse<BoxBody>
     where
@@ -702,6 +770,28 @@ mod tests {
     use actix_service::Service;
     use actix_utils::future::ok;
 
+    #[actix_rt::test]
+    async fn test_extract() {
+        let mut req = TestRequest::default()
+            .insert_header((http::header::CONTENT_TYPE, "text/plain"))
+            .set_payload("hello")
+            .to_srv_request();
+
+        let ct = req
+            .extract::<web::Header<http::header::ContentType>>()
+            .await
+            .unwrap();
+        assert_eq!(ct.into_inner().0, mime::TEXT_PLAIN);
+
+        // head-only extractors leave the payload in place
+        let body = req.extract::<web::Bytes>().await.unwrap();
+        assert_eq!(body, "hello");
+
+        // body extractors take the payload
+        let body = req.extract::<web::Bytes>().await.unwrap();
+        assert!(body.is_empty());
+    }
+
     #[actix_rt::test]
     async fn test_service() {
         let srv = init_service(
@@ -724,7 +814,8 @@ mod tests {
             .method(http::Method::PUT)
             .to_request();
         let resp = srv.call(req).await.unwrap();
-        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(http::header::ALLOW).unwrap(), "GET");
     }
 
     // allow deprecated App::data
@@ -870,4 +961,41 @@ mod tests {
         let req = test::TestRequest::default().to_request();
         let _res = test::call_service(&app, req).await;
     }
+
+    #[actix_rt::test]
+    async fn test_map_into_either_body3() {
+        let app = init_service(
+            App::new()
+                .wrap_fn(|req, srv| {
+                    let path = req.path().to_owned();
+                    let fut = srv.call(req);
+
+                    async move {
+                        let res = fut.await?;
+
+                        Ok(match path.as_str() {
+                            "/" => res.map_into_first_body(),
+                            "/rewrite" => res
+                                .map_body(|_, _| "rewritten".to_owned())
+                                .map_into_second_body(),
+                            _ => res
+                                .into_response(HttpResponse::BadRequest().finish())
+                                .map_into_third_body(),
+                        })
+                    }
+                })
+                .default_service(web::to(|| async { "original" })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        assert_eq!(test::call_and_read_body(&app, req).await, "original");
+
+        let req = TestRequest::with_uri("/rewrite").to_request();
+        assert_eq!(test::call_and_read_body(&app, req).await, "rewritten");
+
+        let req = TestRequest::with_uri("/error").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), http::StatusCode::BAD_REQUEST);
+    }
 }
@@ -0,0 +1,353 @@
+//! See [`Sse`] for responder documentation.
+
+use std::{
+    convert::Infallible,
+    fmt::Write as _,
+    future::Future as _,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_rt::time::{sleep, Instant, Sleep};
+use bytes::{BufMut as _, Bytes, BytesMut};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{
+    body::{BodySize, MessageBody},
+    http::header::{self, ContentEncoding, ContentType, HeaderName, HeaderValue},
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// Comment frame sent when no message has been sent for the keep-alive interval.
+const KEEP_ALIVE_FRAME: &[u8] = b": keep-alive\n\n";
+
+/// A single Server-Sent Event.
+///
+/// Messages are constructed with either [`SseMessage::new`], for events carrying data, or
+/// [`SseMessage::comment`], for comments that are ignored by clients. The other fields of the
+/// event are set using the builder methods.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::web::SseMessage;
+///
+/// let msg = SseMessage::new("hello\nworld")
+///     .event("greeting")
+///     .id("42")
+///     .retry(Duration::from_secs(5));
+///
+/// assert_eq!(
+///     msg.to_bytes(),
+///     "retry: 5000\nid: 42\nevent: greeting\ndata: hello\ndata: world\n\n",
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseMessage {
+    comment: Option<String>,
+    retry: Option<Duration>,
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+}
+
+impl SseMessage {
+    /// Constructs new message carrying the given data.
+    ///
+    /// Multi-line data is sent as multiple `data` fields, which clients join back together
+    /// using newlines.
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: Some(data.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Constructs new comment message.
+    ///
+    /// Comments are ignored by clients but can be useful for debugging or keeping connections
+    /// alive through proxies.
+    pub fn comment(text: impl Into<String>) -> Self {
+        Self {
+            comment: Some(text.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the event type, which determines the client event listener that receives the message.
+    ///
+    /// Line breaks are removed from the event type.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(strip_line_breaks(event.into()));
+        self
+    }
+
+    /// Sets the event ID, which clients send back in the `Last-Event-ID` header on reconnection.
+    ///
+    /// Line breaks are removed from the ID.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(strip_line_breaks(id.into()));
+        self
+    }
+
+    /// Sets the time clients should wait before reconnecting if the connection is lost.
+    ///
+    /// The wire format has millisecond precision.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Serializes the message to the `text/event-stream` wire format, including the terminating
+    /// blank line.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+
+        if let Some(ref comment) = self.comment {
+            write_lines(&mut buf, "", comment);
+        }
+
+        if let Some(retry) = self.retry {
+            let _ = writeln!(buf, "retry: {}", retry.as_millis());
+        }
+
+        if let Some(ref id) = self.id {
+            let _ = writeln!(buf, "id: {}", id);
+        }
+
+        if let Some(ref event) = self.event {
+            let _ = writeln!(buf, "event: {}", event);
+        }
+
+        if let Some(ref data) = self.data {
+            write_lines(&mut buf, "data", data);
+        }
+
+        buf.put_u8(b'\n');
+        buf.freeze()
+    }
+}
+
+fn strip_line_breaks(mut text: String) -> String {
+    text.retain(|c| c != '\r' && c != '\n');
+    text
+}
+
+/// Writes one `field: line` per line of `text`, accepting any of the line break styles that
+/// clients accept.
+fn write_lines(buf: &mut BytesMut, field: &str, text: &str) {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        for line in line.split('\r') {
+            let _ = writeln!(buf, "{}: {}", field, line);
+        }
+    }
+}
+
+pin_project! {
+    /// Server-Sent Events (`text/event-stream`) responder.
+    ///
+    /// Wraps a stream of [`SseMessage`]s, sending each one to the client as it is produced. The
+    /// response is kept open until the stream ends or the client disconnects.
+    ///
+    /// When used as a responder, the following headers are set:
+    /// - `Content-Type: text/event-stream`;
+    /// - `Cache-Control: no-cache`;
+    /// - `Content-Encoding: identity`, so that the [`Compress`] middleware does not buffer events;
+    /// - `X-Accel-Buffering: no`, so that reverse proxies such as Nginx do not buffer events.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use actix_web::{web::{Sse, SseMessage}, Responder};
+    /// use futures_util::stream;
+    ///
+    /// async fn handler() -> impl Responder {
+    ///     let messages = (1..=3).map(|n| SseMessage::new(n.to_string()).event("count"));
+    ///
+    ///     Sse::from_stream(stream::iter(messages)).keep_alive(Duration::from_secs(15))
+    /// }
+    /// # actix_web::web::to(handler);
+    /// ```
+    ///
+    /// [`Compress`]: crate::middleware::Compress
+    pub struct Sse<S> {
+        #[pin]
+        stream: S,
+        keep_alive: Option<Duration>,
+        keep_alive_timer: Option<Pin<Box<Sleep>>>,
+    }
+}
+
+impl<S> Sse<S>
+where
+    S: Stream<Item = SseMessage>,
+{
+    /// Constructs new SSE responder from a stream of messages.
+    pub fn from_stream(stream: S) -> Self {
+        Self {
+            stream,
+            keep_alive: None,
+            keep_alive_timer: None,
+        }
+    }
+
+    /// Sends a comment to the client whenever no message has been sent for the given duration.
+    ///
+    /// Keep-alive comments prevent proxies and clients from closing connections that are idle
+    /// for long periods.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+}
+
+impl<S> MessageBody for Sse<S>
+where
+    S: Stream<Item = SseMessage>,
+{
+    type Error = Infallible;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(msg)) => {
+                if let (Some(interval), Some(timer)) = (this.keep_alive, this.keep_alive_timer)
+                {
+                    timer.as_mut().reset(Instant::now() + *interval);
+                }
+
+                Poll::Ready(Some(Ok(msg.to_bytes())))
+            }
+
+            Poll::Ready(None) => Poll::Ready(None),
+
+            Poll::Pending => {
+                let interval = match *this.keep_alive {
+                    Some(interval) => interval,
+                    None => return Poll::Pending,
+                };
+
+                let timer = this
+                    .keep_alive_timer
+                    .get_or_insert_with(|| Box::pin(sleep(interval)));
+
+                match timer.as_mut().poll(cx) {
+                    Poll::Ready(()) => {
+                        timer.as_mut().reset(Instant::now() + interval);
+                        Poll::Ready(Some(Ok(Bytes::from_static(KEEP_ALIVE_FRAME))))
+                    }
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+        }
+    }
+}
+
+impl<S> Responder for Sse<S>
+where
+    S: Stream<Item = SseMessage> + 'static,
+{
+    type Body = Self;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok()
+            .insert_header(ContentType(mime::TEXT_EVENT_STREAM))
+            .insert_header((header::CACHE_CONTROL, HeaderValue::from_static("no-cache")))
+            .insert_header(ContentEncoding::Identity)
+            .insert_header((
+                HeaderName::from_static("x-accel-buffering"),
+                HeaderValue::from_static("no"),
+            ))
+            .message_body(self)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_utils::future::poll_fn;
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{body, test::TestRequest};
+
+    #[test]
+    fn message_format() {
+        assert_eq!(SseMessage::new("foo").to_bytes(), "data: foo\n\n");
+        assert_eq!(SseMessage::new("").to_bytes(), "data: \n\n");
+        assert_eq!(
+            SseMessage::new("a\r\nb\rc\nd\n").to_bytes(),
+            "data: a\ndata: b\ndata: c\ndata: d\n\n"
+        );
+        assert_eq!(
+            SseMessage::comment("ping\npong").to_bytes(),
+            ": ping\n: pong\n\n"
+        );
+        assert_eq!(
+            SseMessage::new("foo")
+                .event("up\ndate")
+                .id("1\r")
+                .retry(Duration::from_millis(1500))
+                .to_bytes(),
+            "retry: 1500\nid: 1\nevent: update\ndata: foo\n\n"
+        );
+        assert_eq!(SseMessage::default().to_bytes(), "\n");
+    }
+
+    #[actix_rt::test]
+    async fn responder() {
+        let messages = stream::iter([SseMessage::new("one"), SseMessage::new("two").id("2")]);
+        let res =
+            Sse::from_stream(messages).respond_to(&TestRequest::default().to_http_request());
+
+        let headers = res.headers();
+        assert_eq!(
+            headers.get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+        assert_eq!(headers.get(header::CACHE_CONTROL).unwrap(), "no-cache");
+        assert_eq!(headers.get(header::CONTENT_ENCODING).unwrap(), "identity");
+        assert_eq!(headers.get("x-accel-buffering").unwrap(), "no");
+
+        let body = res.into_body();
+        assert_eq!(body.size(), BodySize::Stream);
+        assert_eq!(
+            body::to_bytes(body).await.unwrap(),
+            "data: one\n\nid: 2\ndata: two\n\n"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn keep_alive() {
+        let body = Sse::from_stream(stream::pending()).keep_alive(Duration::from_millis(1));
+        actix_rt::pin!(body);
+
+        for _ in 0..2 {
+            let chunk = poll_fn(|cx| body.as_mut().poll_next(cx)).await;
+            assert_eq!(chunk.unwrap().unwrap(), KEEP_ALIVE_FRAME);
+        }
+
+        let body = Sse::from_stream(stream::pending::<SseMessage>());
+        actix_rt::pin!(body);
+
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(body.as_mut().poll_next(&mut cx).is_pending());
+    }
+}
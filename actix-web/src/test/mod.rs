@@ -9,6 +9,7 @@
 //!
 //! # Calling Test Service
 //! - [`TestRequest`]
+//! - [`MultipartPart`]
 //! - [`call_service`]
 //! - [`try_call_service`]
 //! - [`call_and_read_body`]
@@ -29,14 +30,15 @@ mod test_request;
 mod test_services;
 mod test_utils;
 
-pub use self::test_request::TestRequest;
+pub use self::test_request::{MultipartPart, TestRequest};
 #[allow(deprecated)]
 pub use self::test_services::{default_service, ok_service, simple_service, status_service};
 #[allow(deprecated)]
 pub use self::test_utils::{
     call_and_read_body, call_and_read_body_json, call_service, init_service, read_body,
-    read_body_json, read_response, read_response_json, try_call_and_read_body_json,
-    try_call_service, try_read_body, try_read_body_json,
+    read_body_json, read_body_json_limited, read_body_json_timeout, read_response,
+    read_response_json, try_call_and_read_body_json, try_call_service, try_read_body,
+    try_read_body_json, try_read_body_json_limited, try_read_body_json_timeout,
 };
 
 #[cfg(test)]
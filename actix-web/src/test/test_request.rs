@@ -1,6 +1,7 @@
 use std::{borrow::Cow, net::SocketAddr, rc::Rc};
 
 use actix_http::{test::TestRequest as HttpTestRequest, Request};
+use bytes::{BufMut as _, BytesMut};
 use serde::Serialize;
 
 use crate::{
@@ -64,6 +65,7 @@ pub struct TestRequest {
     path: Path<Url>,
     peer_addr: Option<SocketAddr>,
     app_data: Extensions,
+    conn_data: Option<Extensions>,
     #[cfg(feature = "cookies")]
     cookies: CookieJar,
 }
@@ -77,6 +79,7 @@ impl Default for TestRequest {
             path: Path::new(Url::new(Uri::default())),
             peer_addr: None,
             app_data: Extensions::new(),
+            conn_data: None,
             #[cfg(feature = "cookies")]
             cookies: CookieJar::new(),
         }
@@ -204,6 +207,66 @@ impl TestRequest {
         self
     }
 
+    /// Build a `multipart/form-data` body from `parts` and set it as the request payload.
+    ///
+    /// A boundary that does not occur in any of the parts is generated and the `Content-Type`
+    /// header is set to `multipart/form-data` with that boundary.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::test::{MultipartPart, TestRequest};
+    ///
+    /// let req = TestRequest::post()
+    ///     .set_multipart([
+    ///         MultipartPart::new("name", "actix"),
+    ///         MultipartPart::new("avatar", &b"\x89PNG"[..])
+    ///             .filename("avatar.png")
+    ///             .content_type(mime::IMAGE_PNG),
+    ///     ])
+    ///     .to_request();
+    /// ```
+    pub fn set_multipart(mut self, parts: impl IntoIterator<Item = MultipartPart>) -> Self {
+        let parts = parts.into_iter().collect::<Vec<_>>();
+        let boundary = multipart_boundary(&parts);
+
+        let mut body = BytesMut::new();
+
+        for part in &parts {
+            body.put_slice(b"--");
+            body.put_slice(boundary.as_bytes());
+            body.put_slice(b"\r\nContent-Disposition: form-data; name=\"");
+            body.put_slice(escape_disposition_param(&part.name).as_bytes());
+            body.put_u8(b'"');
+
+            if let Some(ref filename) = part.filename {
+                body.put_slice(b"; filename=\"");
+                body.put_slice(escape_disposition_param(filename).as_bytes());
+                body.put_u8(b'"');
+            }
+
+            if let Some(ref content_type) = part.content_type {
+                body.put_slice(b"\r\nContent-Type: ");
+                body.put_slice(content_type.as_ref().as_bytes());
+            }
+
+            body.put_slice(b"\r\n\r\n");
+            body.put_slice(&part.data);
+            body.put_slice(b"\r\n");
+        }
+
+        body.put_slice(b"--");
+        body.put_slice(boundary.as_bytes());
+        body.put_slice(b"--\r\n");
+
+        let content_type = format!("multipart/form-data; boundary={}", boundary)
+            .parse()
+            .unwrap();
+
+        self.req.set_payload(body.freeze());
+        self.req.insert_header(ContentType(content_type));
+        self
+    }
+
     /// Set application data. This is equivalent of `App::data()` method
     /// for testing purpose.
     pub fn data<T: 'static>(mut self, data: T) -> Self {
@@ -218,6 +281,17 @@ impl TestRequest {
         self
     }
 
+    /// Inserts connection data.
+    ///
+    /// Connection data is normally set by the server's [`on_connect`](crate::HttpServer::on_connect)
+    /// callback and is read using [`HttpRequest::conn_data`].
+    pub fn insert_conn_data<T: 'static>(mut self, data: T) -> Self {
+        self.conn_data
+            .get_or_insert_with(Extensions::new)
+            .insert(data);
+        self
+    }
+
     #[cfg(test)]
     /// Set request config
     pub(crate) fn rmap(mut self, rmap: ResourceMap) -> Self {
@@ -272,7 +346,7 @@ impl TestRequest {
                 head,
                 app_state,
                 Rc::new(self.app_data),
-                None,
+                self.conn_data.map(Rc::new),
                 Default::default(),
             ),
             payload,
@@ -297,7 +371,7 @@ impl TestRequest {
             head,
             app_state,
             Rc::new(self.app_data),
-            None,
+            self.conn_data.map(Rc::new),
             Default::default(),
         )
     }
@@ -315,7 +389,7 @@ impl TestRequest {
             head,
             app_state,
             Rc::new(self.app_data),
-            None,
+            self.conn_data.map(Rc::new),
             Default::default(),
         );
 
@@ -338,12 +412,81 @@ impl TestRequest {
     }
 }
 
+/// A part of a `multipart/form-data` test request body.
+///
+/// See [`TestRequest::set_multipart`].
+#[derive(Debug, Clone)]
+pub struct MultipartPart {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<mime::Mime>,
+    data: Bytes,
+}
+
+impl MultipartPart {
+    /// Constructs new part with a field name and contents.
+    pub fn new(name: impl Into<String>, data: impl Into<Bytes>) -> Self {
+        Self {
+            name: name.into(),
+            filename: None,
+            content_type: None,
+            data: data.into(),
+        }
+    }
+
+    /// Sets the part's file name, making it a file upload.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Sets the part's `Content-Type` header.
+    ///
+    /// If not set, the part is sent without one, which receivers treat as `text/plain`.
+    pub fn content_type(mut self, content_type: mime::Mime) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+}
+
+/// Generates a boundary that does not occur in any of the given parts.
+fn multipart_boundary(parts: &[MultipartPart]) -> String {
+    let occurs_in = |boundary: &str, part: &MultipartPart| {
+        part.data
+            .windows(boundary.len())
+            .any(|window| window == boundary.as_bytes())
+    };
+
+    (0..)
+        .map(|n| format!("------------------------actix-test-{:08x}", n))
+        .find(|boundary| !parts.iter().any(|part| occurs_in(boundary, part)))
+        .unwrap()
+}
+
+/// Percent-encodes the characters that can not appear in a quoted `Content-Disposition`
+/// parameter, as browsers do.
+fn escape_disposition_param(value: &str) -> Cow<'_, str> {
+    if !value.contains(['"', '\r', '\n']) {
+        return Cow::Borrowed(value);
+    }
+
+    Cow::Owned(
+        value
+            .replace('"', "%22")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A"),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::SystemTime;
 
     use super::*;
-    use crate::{http::header, test::init_service, web, App, Error, HttpResponse, Responder};
+    use crate::{
+        http::header, test::init_service, web, App, Error, FromRequest as _, HttpResponse,
+        Responder,
+    };
 
     #[actix_rt::test]
     async fn test_basics() {
@@ -373,6 +516,49 @@ mod tests {
         assert_eq!(*data, 20);
     }
 
+    #[actix_rt::test]
+    async fn test_multipart() {
+        let (req, mut payload) = TestRequest::post()
+            .set_multipart([
+                MultipartPart::new("text", "hello"),
+                MultipartPart::new("file\"", "a\r\nb")
+                    .filename("a.txt")
+                    .content_type(mime::TEXT_PLAIN),
+            ])
+            .to_http_parts();
+
+        let boundary = "------------------------actix-test-00000000";
+        assert_eq!(
+            req.headers().get(header::CONTENT_TYPE).unwrap(),
+            &format!("multipart/form-data; boundary={}", boundary)
+        );
+
+        let body = Bytes::from_request(&req, &mut payload).await.unwrap();
+        assert_eq!(
+            body,
+            format!(
+                "--{b}\r\n\
+                 Content-Disposition: form-data; name=\"text\"\r\n\r\n\
+                 hello\r\n\
+                 --{b}\r\n\
+                 Content-Disposition: form-data; name=\"file%22\"; filename=\"a.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\n\
+                 a\r\nb\r\n\
+                 --{b}--\r\n",
+                b = boundary
+            )
+        );
+
+        // boundary is changed when it would occur in a part
+        let req = TestRequest::post()
+            .set_multipart([MultipartPart::new("text", boundary)])
+            .to_http_request();
+        assert_eq!(
+            req.headers().get(header::CONTENT_TYPE).unwrap(),
+            "multipart/form-data; boundary=------------------------actix-test-00000001"
+        );
+    }
+
     #[actix_rt::test]
     async fn test_send_request() {
         let app = init_service(
@@ -1,7 +1,11 @@
-use std::error::Error as StdError;
+use std::{error::Error as StdError, task::Poll, time::Duration};
 
 use actix_http::Request;
 use actix_service::IntoServiceFactory;
+use bytes::BytesMut;
+use derive_more::Display;
+use futures_core::ready;
+use futures_util::future::poll_fn;
 use serde::de::DeserializeOwned;
 
 use crate::{
@@ -280,6 +284,212 @@ where
     serde_json::from_slice(&body).map_err(Into::<Box<dyn StdError>>::into)
 }
 
+/// Helper function that returns a deserialized response body of a ServiceResponse, reading at
+/// most `limit` bytes of it.
+///
+/// Use this instead of [`read_body_json`] to fail fast when a handler produces a huge or
+/// never-ending body. See also [`read_body_json_timeout`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{test, web, App};
+///
+/// #[actix_web::test]
+/// async fn test_numbers() {
+///     let app = test::init_service(
+///         App::new().route("/", web::get().to(|| async { web::Json(vec![1, 2, 3]) })),
+///     )
+///     .await;
+///
+///     let res = test::TestRequest::get().send_request(&app).await;
+///     let numbers: Vec<u32> = test::read_body_json_limited(res, 1024).await;
+///     assert_eq!(numbers, [1, 2, 3]);
+/// }
+/// ```
+///
+/// # Panics
+/// Panics if:
+/// - body yields an error while it is being read;
+/// - body is larger than `limit` bytes;
+/// - received body is not a valid JSON representation of `T`.
+pub async fn read_body_json_limited<T, B>(res: ServiceResponse<B>, limit: usize) -> T
+where
+    B: MessageBody,
+    T: DeserializeOwned,
+{
+    read_body_json_within(res, limit, None)
+        .await
+        .unwrap_or_else(|err| panic_read_body_json::<T>(err))
+}
+
+/// Fallible version of [`read_body_json_limited`] that allows testing response deserialization
+/// errors.
+pub async fn try_read_body_json_limited<T, B>(
+    res: ServiceResponse<B>,
+    limit: usize,
+) -> Result<T, Box<dyn StdError>>
+where
+    B: MessageBody,
+    T: DeserializeOwned,
+{
+    Ok(read_body_json_within(res, limit, None).await?)
+}
+
+/// Helper function that returns a deserialized response body of a ServiceResponse, reading at
+/// most `limit` bytes of it within `timeout`.
+///
+/// Use this instead of [`read_body_json`] to fail fast when a handler produces a huge,
+/// never-ending, or stalled body.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use actix_web::{test, web, App};
+///
+/// #[actix_web::test]
+/// async fn test_numbers() {
+///     let app = test::init_service(
+///         App::new().route("/", web::get().to(|| async { web::Json(vec![1, 2, 3]) })),
+///     )
+///     .await;
+///
+///     let res = test::TestRequest::get().send_request(&app).await;
+///     let numbers: Vec<u32> =
+///         test::read_body_json_timeout(res, 1024, Duration::from_secs(5)).await;
+///     assert_eq!(numbers, [1, 2, 3]);
+/// }
+/// ```
+///
+/// # Panics
+/// Panics if:
+/// - body yields an error while it is being read;
+/// - body is larger than `limit` bytes;
+/// - body is not completely read within `timeout`;
+/// - received body is not a valid JSON representation of `T`.
+pub async fn read_body_json_timeout<T, B>(
+    res: ServiceResponse<B>,
+    limit: usize,
+    timeout: Duration,
+) -> T
+where
+    B: MessageBody,
+    T: DeserializeOwned,
+{
+    read_body_json_within(res, limit, Some(timeout))
+        .await
+        .unwrap_or_else(|err| panic_read_body_json::<T>(err))
+}
+
+/// Fallible version of [`read_body_json_timeout`] that allows testing response deserialization
+/// errors.
+pub async fn try_read_body_json_timeout<T, B>(
+    res: ServiceResponse<B>,
+    limit: usize,
+    timeout: Duration,
+) -> Result<T, Box<dyn StdError>>
+where
+    B: MessageBody,
+    T: DeserializeOwned,
+{
+    Ok(read_body_json_within(res, limit, Some(timeout)).await?)
+}
+
+/// Error returned when reading a JSON body with a size limit and, optionally, a timeout.
+#[derive(Debug, Display)]
+enum ReadBodyJsonError {
+    #[display(fmt = "body exceeded limit of {} bytes; read {} bytes", limit, read)]
+    Limit { limit: usize, read: usize },
+
+    #[display(
+        fmt = "timed out reading body after {:?}; read {} bytes",
+        timeout,
+        read
+    )]
+    Timeout { timeout: Duration, read: usize },
+
+    #[display(fmt = "error reading body after {} bytes: {}", read, err)]
+    Body { read: usize, err: Box<dyn StdError> },
+
+    #[display(fmt = "{}", _0)]
+    Deserialize(serde_json::Error),
+}
+
+impl ReadBodyJsonError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Limit { .. } => "limit",
+            Self::Timeout { .. } => "timeout",
+            Self::Body { .. } => "body",
+            Self::Deserialize(_) => "deserialize",
+        }
+    }
+}
+
+impl StdError for ReadBodyJsonError {}
+
+fn panic_read_body_json<T>(err: ReadBodyJsonError) -> ! {
+    panic!(
+        "could not read body into a {} ({} error)\nerr: {}",
+        std::any::type_name::<T>(),
+        err.kind(),
+        err,
+    )
+}
+
+/// Reads at most `limit` bytes of a response body, within `timeout` if one is given, and
+/// deserializes it as JSON.
+///
+/// Errors mention how many bytes were read before failing.
+async fn read_body_json_within<T, B>(
+    res: ServiceResponse<B>,
+    limit: usize,
+    timeout: Option<Duration>,
+) -> Result<T, ReadBodyJsonError>
+where
+    B: MessageBody,
+    T: DeserializeOwned,
+{
+    let body = res.into_body();
+    actix_rt::pin!(body);
+
+    let mut buf = BytesMut::new();
+
+    let read = poll_fn(|cx| loop {
+        match ready!(body.as_mut().poll_next(cx)) {
+            Some(Ok(chunk)) if buf.len() + chunk.len() > limit => {
+                return Poll::Ready(Err(ReadBodyJsonError::Limit {
+                    limit,
+                    read: buf.len() + chunk.len(),
+                }));
+            }
+            Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+            Some(Err(err)) => {
+                return Poll::Ready(Err(ReadBodyJsonError::Body {
+                    read: buf.len(),
+                    err: err.into(),
+                }))
+            }
+            None => return Poll::Ready(Ok(())),
+        }
+    });
+
+    match timeout {
+        Some(timeout) => match actix_rt::time::timeout(timeout, read).await {
+            Ok(res) => res?,
+            Err(_) => {
+                return Err(ReadBodyJsonError::Timeout {
+                    timeout,
+                    read: buf.len(),
+                })
+            }
+        },
+        None => read.await?,
+    };
+
+    serde_json::from_slice(&buf).map_err(ReadBodyJsonError::Deserialize)
+}
+
 /// Helper function that returns a deserialized response body of a TestRequest
 ///
 /// # Examples
@@ -487,6 +697,71 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[actix_rt::test]
+    async fn test_body_json_limited() {
+        use futures_util::{stream, StreamExt as _};
+
+        let app = init_service(
+            App::new()
+                .route(
+                    "/people",
+                    web::get().to(|| {
+                        HttpResponse::Ok().json(Person {
+                            id: "12345".to_owned(),
+                            name: "User name".to_owned(),
+                        })
+                    }),
+                )
+                .route(
+                    "/endless",
+                    web::get().to(|| {
+                        let chunks = stream::repeat(Bytes::from_static(b"[1,"));
+                        HttpResponse::Ok().streaming(chunks.map(Ok::<_, Error>))
+                    }),
+                )
+                .route(
+                    "/stalled",
+                    web::get().to(|| {
+                        HttpResponse::Ok().streaming(stream::pending::<Result<Bytes, Error>>())
+                    }),
+                ),
+        )
+        .await;
+
+        let res = TestRequest::with_uri("/people").send_request(&app).await;
+        let result: Person = read_body_json_limited(res, 1024).await;
+        assert_eq!(&result.name, "User name");
+
+        let res = TestRequest::with_uri("/people").send_request(&app).await;
+        let result: Person = read_body_json_timeout(res, 1024, Duration::from_secs(5)).await;
+        assert_eq!(&result.id, "12345");
+
+        let res = TestRequest::with_uri("/endless").send_request(&app).await;
+        let err = try_read_body_json_limited::<Vec<u32>, _>(res, 10)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "body exceeded limit of 10 bytes; read 12 bytes"
+        );
+
+        let res = TestRequest::with_uri("/stalled").send_request(&app).await;
+        let err = try_read_body_json_timeout::<Vec<u32>, _>(res, 10, Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "timed out reading body after 10ms; read 0 bytes"
+        );
+    }
+
+    #[actix_rt::test]
+    #[should_panic = "(limit error)"]
+    async fn test_read_body_json_limited_panic() {
+        let res = TestRequest::default().to_srv_response(HttpResponse::Ok().body("[1, 2, 3]"));
+        let _: Vec<u32> = read_body_json_limited(res, 4).await;
+    }
+
     #[actix_rt::test]
     async fn test_request_response_form() {
         let app = init_service(App::new().service(web::resource("/people").route(
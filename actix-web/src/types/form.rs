@@ -54,6 +54,29 @@ use crate::{
 /// }
 /// ```
 ///
+/// ## Repeated Fields
+/// Structs and maps keep only one value for each key. To receive every `key=value` pair, in the
+/// order they were sent and including repeated keys (eg. from a group of checkboxes), extract a
+/// `Form<Vec<(String, String)>>` instead. Keys and values are percent-decoded, and `+` is decoded
+/// as a space, as browsers encode them.
+///
+/// ```
+/// use actix_web::{post, web};
+///
+/// // for a body of `topping=cheese&topping=ham&size=large`, the pairs are
+/// // `[("topping", "cheese"), ("topping", "ham"), ("size", "large")]`
+/// #[post("/order")]
+/// async fn order(web::Form(pairs): web::Form<Vec<(String, String)>>) -> String {
+///     let toppings = pairs
+///         .iter()
+///         .filter(|(key, _)| key == "topping")
+///         .map(|(_, val)| val.as_str())
+///         .collect::<Vec<_>>();
+///
+///     format!("Toppings: {}", toppings.join(", "))
+/// }
+/// ```
+///
 /// # Responder
 /// The `Form` type also allows you to create URL encoded responses by returning a value of type
 /// `Form<T>` where `T` is the type to be URL encoded, as long as `T` implements [`Serialize`].
@@ -450,6 +473,30 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_form_pairs() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_header((CONTENT_TYPE, "application/x-www-form-urlencoded"))
+            .set_payload(Bytes::from_static(
+                b"b=1&a=hello+world&b=%E2%9C%93&c%20d=%2B&empty=",
+            ))
+            .to_http_parts();
+
+        let Form(pairs) = Form::<Vec<(String, String)>>::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("b".to_owned(), "1".to_owned()),
+                ("a".to_owned(), "hello world".to_owned()),
+                ("b".to_owned(), "\u{2713}".to_owned()),
+                ("c d".to_owned(), "+".to_owned()),
+                ("empty".to_owned(), String::new()),
+            ]
+        );
+    }
+
     fn eq(err: UrlencodedError, other: UrlencodedError) -> bool {
         match err {
             UrlencodedError::Overflow { .. } => {
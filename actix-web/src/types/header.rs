@@ -1,12 +1,12 @@
 //! For header extractor helper documentation, see [`Header`](crate::types::Header).
 
-use std::{fmt, ops};
+use std::{fmt, ops, sync::Arc};
 
 use actix_utils::future::{err, ok, Ready};
 
 use crate::{
-    dev::Payload, error::ParseError, extract::FromRequest, http::header::Header as ParseHeader,
-    HttpRequest,
+    dev::Payload, error::HeaderError, extract::FromRequest,
+    http::header::Header as ParseHeader, Error, HttpRequest,
 };
 
 /// Extract typed headers from the request.
@@ -14,6 +14,10 @@ use crate::{
 /// To extract a header, the inner type `T` must implement the
 /// [`Header`](crate::http::header::Header) trait.
 ///
+/// If the header is missing or can not be parsed, a [`ParseError`](crate::error::ParseError) is
+/// returned which results in a
+/// `400 Bad Request` response. Use [`HeaderConfig`] to customize this response.
+///
 /// # Examples
 /// ```
 /// use actix_web::{get, web, http::header};
@@ -60,23 +64,99 @@ impl<T> FromRequest for Header<T>
 where
     T: ParseHeader,
 {
-    type Error = ParseError;
+    type Error = Error;
     type Future = Ready<Result<Self, Self::Error>>;
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
         match ParseHeader::parse(req) {
             Ok(header) => ok(Header(header)),
-            Err(e) => err(e),
+            Err(e) => {
+                let name = T::name();
+
+                log::debug!(
+                    "Failed during Header extractor parsing of `{}`. \
+                     Request path: {:?}",
+                    name,
+                    req.path()
+                );
+
+                let error_handler = req
+                    .app_data::<HeaderConfig>()
+                    .and_then(|cfg| cfg.err_handler.as_ref());
+
+                let e = if let Some(error_handler) = error_handler {
+                    let header_err = if req.headers().contains_key(&name) {
+                        HeaderError::Invalid(name)
+                    } else {
+                        HeaderError::Missing(name)
+                    };
+
+                    (error_handler)(header_err, req)
+                } else {
+                    e.into()
+                };
+
+                err(e)
+            }
         }
     }
 }
 
+/// Header extractor configuration.
+///
+/// The error handler receives a [`HeaderError`], identifying the missing or invalid header, and
+/// its result is returned as the `Header` extractor's error. Without an error handler, the
+/// extractor fails with a [`ParseError`](crate::error::ParseError).
+///
+/// # Examples
+/// ```
+/// use actix_web::{error, get, http::header, web, App, HttpResponse};
+///
+/// #[get("/")]
+/// async fn index(date: web::Header<header::Date>) -> String {
+///     format!("Request was sent at {}", date.to_string())
+/// }
+///
+/// // custom `Header` extractor configuration
+/// let header_cfg = web::HeaderConfig::default()
+///     // use custom error handler
+///     .error_handler(|err, req| {
+///         let res = HttpResponse::UnprocessableEntity()
+///             .body(format!("bad `{}` header", err.header_name()));
+///         error::InternalError::from_response(err, res).into()
+///     });
+///
+/// App::new()
+///     .app_data(header_cfg)
+///     .service(index);
+/// ```
+#[derive(Clone, Default)]
+pub struct HeaderConfig {
+    #[allow(clippy::type_complexity)]
+    err_handler: Option<Arc<dyn Fn(HeaderError, &HttpRequest) -> Error + Send + Sync>>,
+}
+
+impl HeaderConfig {
+    /// Set custom error handler.
+    pub fn error_handler<F>(mut self, f: F) -> Self
+    where
+        F: Fn(HeaderError, &HttpRequest) -> Error + Send + Sync + 'static,
+    {
+        self.err_handler = Some(Arc::new(f));
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::http::{header, Method};
-    use crate::test::TestRequest;
+    use crate::{
+        error,
+        http::{header, Method, StatusCode},
+        test::{call_service, init_service, read_body, TestRequest},
+        web, App, HttpResponse,
+    };
 
     #[actix_rt::test]
     async fn test_header_extract() {
@@ -99,4 +179,75 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[actix_rt::test]
+    async fn test_custom_err_handler() {
+        let srv = init_service(
+            App::new()
+                .app_data(HeaderConfig::default().error_handler(|err, _| {
+                    let res = HttpResponse::UnprocessableEntity().body(err.to_string());
+                    error::InternalError::from_response(err, res).into()
+                }))
+                .route("/", web::to(|_: Header<header::Date>| async { "ok" })),
+        )
+        .await;
+
+        let res = call_service(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(read_body(res).await, "Header `date` is missing");
+
+        let req = TestRequest::default()
+            .insert_header((header::DATE, "not a date"))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(read_body(res).await, "Header `date` could not be parsed");
+    }
+
+    #[actix_rt::test]
+    async fn test_custom_err_handler_extract() {
+        let req = TestRequest::default()
+            .app_data(HeaderConfig::default().error_handler(|err, _| {
+                error::InternalError::new(err, StatusCode::UNPROCESSABLE_ENTITY).into()
+            }))
+            .to_http_request();
+
+        let err = Header::<header::Date>::extract(&req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_custom_err_handler_optional() {
+        let srv = init_service(
+            App::new()
+                .app_data(HeaderConfig::default().error_handler(|err, _| {
+                    error::InternalError::new(err, StatusCode::UNPROCESSABLE_ENTITY).into()
+                }))
+                .route(
+                    "/",
+                    web::to(
+                        |_: Option<Header<header::Date>>, _: web::Query<u32>| async { "ok" },
+                    ),
+                ),
+        )
+        .await;
+
+        // failed optional header extraction does not affect errors of other extractors
+        let res = call_service(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_default_err_response() {
+        let srv = init_service(
+            App::new().route("/", web::to(|_: Header<header::Date>| async { "ok" })),
+        )
+        .await;
+
+        let res = call_service(&srv, TestRequest::default().to_request()).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
 }
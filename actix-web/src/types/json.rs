@@ -146,13 +146,17 @@ impl<T: DeserializeOwned> FromRequest for Json<T> {
         let config = JsonConfig::from_req(req);
 
         let limit = config.limit;
-        let ctype_required = config.content_type_required;
-        let ctype_fn = config.content_type.as_deref();
         let err_handler = config.err_handler.clone();
 
         JsonExtractFut {
             req: Some(req.clone()),
-            fut: JsonBody::new(req, payload, ctype_fn, ctype_required).limit(limit),
+            fut: if config.any_content_type {
+                JsonBody::new(req, payload, Some(&|_| true), false)
+            } else {
+                let ctype_fn = config.content_type.as_deref();
+                JsonBody::new(req, payload, ctype_fn, config.content_type_required)
+            }
+            .limit(limit),
             err_handler,
         }
     }
@@ -236,6 +240,7 @@ pub struct JsonConfig {
     err_handler: JsonErrorHandler,
     content_type: Option<Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>>,
     content_type_required: bool,
+    any_content_type: bool,
 }
 
 impl JsonConfig {
@@ -255,6 +260,9 @@ impl JsonConfig {
     }
 
     /// Set predicate for allowed content types.
+    ///
+    /// By default, `application/json` and any type with a `+json` structured syntax suffix, such
+    /// as `application/problem+json`, are allowed.
     pub fn content_type<F>(mut self, predicate: F) -> Self
     where
         F: Fn(mime::Mime) -> bool + Send + Sync + 'static,
@@ -264,11 +272,25 @@ impl JsonConfig {
     }
 
     /// Sets whether or not the request must have a `Content-Type` header to be parsed.
+    ///
+    /// Defaults to `true`. When set to `false`, requests with no `Content-Type` header are parsed
+    /// as JSON and any deserialization error is returned as normal. Requests with a `Content-Type`
+    /// header are still subject to the [`content_type`](Self::content_type) check.
     pub fn content_type_required(mut self, content_type_required: bool) -> Self {
         self.content_type_required = content_type_required;
         self
     }
 
+    /// Sets whether to skip the `Content-Type` check entirely and parse any payload as JSON.
+    ///
+    /// When enabled, the [`content_type`](Self::content_type) predicate and
+    /// [`content_type_required`](Self::content_type_required) setting are ignored. Disabled by
+    /// default.
+    pub fn accept_any_content_type(mut self, any_content_type: bool) -> Self {
+        self.any_content_type = any_content_type;
+        self
+    }
+
     /// Extract payload config from app data. Check both `T` and `Data<T>`, in that order, and fall
     /// back to the default payload config.
     fn from_req(req: &HttpRequest) -> &Self {
@@ -286,6 +308,7 @@ const DEFAULT_CONFIG: JsonConfig = JsonConfig {
     err_handler: None,
     content_type: None,
     content_type_required: true,
+    any_content_type: false,
 };
 
 impl Default for JsonConfig {
@@ -299,8 +322,8 @@ impl Default for JsonConfig {
 /// Can deserialize any type `T` that implements [`Deserialize`][serde::Deserialize].
 ///
 /// Returns error if:
-/// - `Content-Type` is not `application/json` when `ctype_required` (passed to [`new`][Self::new])
-///   is `true`.
+/// - `Content-Type` is not `application/json`, or another type with a `+json` suffix, when
+///   `ctype_required` (passed to [`new`][Self::new]) is `true`.
 /// - `Content-Length` is greater than [limit](JsonBody::limit()).
 /// - The payload, when consumed, is not valid JSON.
 pub enum JsonBody<T> {
@@ -719,6 +742,49 @@ mod tests {
         assert!(s.is_err())
     }
 
+    #[actix_rt::test]
+    async fn test_with_json_suffix_content_type() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_header((
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("application/problem+json"),
+            ))
+            .insert_header((
+                header::CONTENT_LENGTH,
+                header::HeaderValue::from_static("16"),
+            ))
+            .set_payload(Bytes::from_static(b"{\"name\": \"test\"}"))
+            .to_http_parts();
+
+        let s = Json::<MyObject>::from_request(&req, &mut pl).await;
+        assert_eq!(s.unwrap().name, "test");
+    }
+
+    #[actix_rt::test]
+    async fn test_with_json_and_any_content_type() {
+        let config = || JsonConfig::default().accept_any_content_type(true);
+
+        let (req, mut pl) = TestRequest::default()
+            .insert_header((
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/html"),
+            ))
+            .set_payload(Bytes::from_static(b"{\"name\": \"test\"}"))
+            .app_data(config().content_type(|_| false))
+            .to_http_parts();
+
+        let s = Json::<MyObject>::from_request(&req, &mut pl).await;
+        assert_eq!(s.unwrap().name, "test");
+
+        let (req, mut pl) = TestRequest::default()
+            .set_payload(Bytes::from_static(b"{\"name\": \"test\"}"))
+            .app_data(config())
+            .to_http_parts();
+
+        let s = Json::<MyObject>::from_request(&req, &mut pl).await;
+        assert_eq!(s.unwrap().name, "test");
+    }
+
     #[actix_rt::test]
     async fn test_json_with_no_content_type() {
         let (req, mut pl) = TestRequest::default()
@@ -734,6 +800,25 @@ mod tests {
         assert!(s.is_ok())
     }
 
+    #[actix_rt::test]
+    async fn test_json_with_no_content_type_errors() {
+        // strict by default
+        let (req, mut pl) = TestRequest::default()
+            .set_payload(Bytes::from_static(b"{\"name\": \"test\"}"))
+            .to_http_parts();
+
+        let s = JsonBody::<MyObject>::new(&req, &mut pl, None, true).await;
+        assert!(json_eq(s.unwrap_err(), JsonPayloadError::ContentType));
+
+        // falls back to normal deserialization errors
+        let (req, mut pl) = TestRequest::default()
+            .set_payload(Bytes::from_static(b"{\"name\": 42}"))
+            .to_http_parts();
+
+        let s = JsonBody::<MyObject>::new(&req, &mut pl, None, false).await;
+        assert!(matches!(s.unwrap_err(), JsonPayloadError::Deserialize(_)));
+    }
+
     #[actix_rt::test]
     async fn test_with_config_in_data_wrapper() {
         let (req, mut pl) = TestRequest::default()
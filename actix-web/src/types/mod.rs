@@ -4,16 +4,28 @@ mod either;
 mod form;
 mod header;
 mod json;
+mod ndjson;
 mod path;
 mod payload;
+mod peer_certificates;
 mod query;
 mod readlines;
+#[cfg(feature = "secure-cookies")]
+mod secure_cookies;
+#[cfg(feature = "validation")]
+mod valid;
 
 pub use self::either::Either;
 pub use self::form::{Form, FormConfig, UrlEncoded};
-pub use self::header::Header;
+pub use self::header::{Header, HeaderConfig};
 pub use self::json::{Json, JsonBody, JsonConfig};
+pub use self::ndjson::{NdJson, NdJsonConfig, NdJsonResponse};
 pub use self::path::{Path, PathConfig};
 pub use self::payload::{Payload, PayloadConfig};
+pub use self::peer_certificates::PeerCertificates;
 pub use self::query::{Query, QueryConfig};
 pub use self::readlines::Readlines;
+#[cfg(feature = "secure-cookies")]
+pub use self::secure_cookies::{PrivateCookies, SignedCookies};
+#[cfg(feature = "validation")]
+pub use self::valid::{Valid, ValidConfig};
@@ -0,0 +1,511 @@
+//! For newline-delimited JSON extractor and responder documentation, see [`NdJson`] and
+//! [`NdJsonResponse`].
+
+use std::{
+    future::{ready, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    body::{BodySize, MessageBody},
+    dev::Payload,
+    error::{Error, NdJsonError},
+    extract::FromRequest,
+    http::header::ContentType,
+    request::HttpRequest,
+    web, HttpMessage as _, HttpResponse, Responder,
+};
+
+const DEFAULT_LINE_LIMIT: usize = 262_144; // 256kB
+
+/// Newline-delimited JSON (NDJSON) extractor.
+///
+/// Streams the items of an `application/x-ndjson` request body, deserializing one line at a time
+/// as it is received, so that the body is never buffered as a whole. Blank lines are skipped.
+///
+/// # Errors
+/// Extraction fails if the request's `Content-Type` is not `application/x-ndjson`, unless this
+/// check is disabled using [`NdJsonConfig`].
+///
+/// Once extracted, the stream yields an [`NdJsonError`]:
+/// - for lines that fail to deserialize, after which the following lines are still read;
+/// - for lines longer than the [limit](NdJsonConfig::line_limit), which ends the stream;
+/// - if the payload ends in the middle of a line, e.g., because the client disconnected, which
+///   ends the stream;
+/// - for errors reading the payload, which end the stream.
+///
+/// # Examples
+/// ```
+/// use actix_web::{post, web};
+/// use futures_util::StreamExt as _;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct LogRecord {
+///     level: String,
+///     message: String,
+/// }
+///
+/// #[post("/logs")]
+/// async fn ingest(mut records: web::NdJson<LogRecord>) -> actix_web::Result<String> {
+///     let mut count = 0;
+///
+///     while let Some(record) = records.next().await {
+///         let record = record?;
+///         log::info!("[{}] {}", record.level, record.message);
+///         count += 1;
+///     }
+///
+///     Ok(format!("Ingested {} records", count))
+/// }
+/// ```
+pub struct NdJson<T> {
+    payload: Payload,
+    buf: BytesMut,
+    /// Length of the start of `buf` that is known not to contain a newline.
+    scanned: usize,
+    /// Number of the line currently being read.
+    line: usize,
+    line_limit: usize,
+    done: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> NdJson<T> {
+    /// Splits the next complete line, without its newline, off the buffer, if there is one.
+    fn next_line(&mut self) -> Option<Result<Bytes, NdJsonError>> {
+        match self.buf[self.scanned..].iter().position(|&b| b == b'\n') {
+            Some(pos) => {
+                let len = self.scanned + pos;
+                self.scanned = 0;
+                self.line += 1;
+
+                if len > self.line_limit {
+                    return Some(Err(self.overflow()));
+                }
+
+                let line = self.buf.split_to(len + 1).freeze();
+                Some(Ok(line.slice(..len)))
+            }
+
+            None => {
+                self.scanned = self.buf.len();
+                None
+            }
+        }
+    }
+
+    fn overflow(&mut self) -> NdJsonError {
+        self.done = true;
+
+        NdJsonError::LineOverflow {
+            line: self.line,
+            limit: self.line_limit,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> NdJson<T> {
+    /// Deserializes a line, skipping it if it is blank.
+    fn parse(&self, line: &[u8]) -> Option<Result<T, NdJsonError>> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return None;
+        }
+
+        Some(
+            serde_json::from_slice(line).map_err(|source| NdJsonError::Deserialize {
+                line: self.line,
+                source,
+            }),
+        )
+    }
+}
+
+impl<T: DeserializeOwned> Stream for NdJson<T> {
+    type Item = Result<T, NdJsonError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match this.next_line() {
+                Some(Ok(line)) => match this.parse(&line) {
+                    Some(item) => return Poll::Ready(Some(item)),
+                    None => continue,
+                },
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => {}
+            }
+
+            if this.buf.len() > this.line_limit {
+                this.line += 1;
+                return Poll::Ready(Some(Err(this.overflow())));
+            }
+
+            match ready!(Pin::new(&mut this.payload).poll_next(cx)) {
+                Some(Ok(chunk)) => this.buf.extend_from_slice(&chunk),
+
+                Some(Err(err)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(NdJsonError::Payload(err))));
+                }
+
+                // last line does not need to end with a newline but must be complete
+                None => {
+                    this.done = true;
+                    this.line += 1;
+
+                    let line = this.buf.split().freeze();
+
+                    let item = this.parse(&line).map(|item| {
+                        item.map_err(|err| match err {
+                            NdJsonError::Deserialize { line, source } if source.is_eof() => {
+                                NdJsonError::Incomplete { line }
+                            }
+                            err => err,
+                        })
+                    });
+
+                    return Poll::Ready(item);
+                }
+            }
+        }
+    }
+}
+
+/// See [here](#examples) for example of usage as an extractor.
+impl<T: DeserializeOwned> FromRequest for NdJson<T> {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let config = NdJsonConfig::from_req(req);
+
+        if config.content_type_required {
+            let is_ndjson = matches!(
+                req.mime_type(),
+                Ok(Some(mime)) if mime.essence_str() == APPLICATION_NDJSON
+            );
+
+            if !is_ndjson {
+                log::debug!(
+                    "Failed to extract NDJSON stream from payload. \
+                     Request path: {}",
+                    req.path()
+                );
+
+                return ready(Err(NdJsonError::ContentType.into()));
+            }
+        }
+
+        ready(Ok(NdJson {
+            payload: payload.take(),
+            buf: BytesMut::new(),
+            scanned: 0,
+            line: 0,
+            line_limit: config.line_limit,
+            done: false,
+            _item: PhantomData,
+        }))
+    }
+}
+
+/// `NdJson` extractor configuration.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// let app = App::new().app_data(
+///     web::NdJsonConfig::default()
+///         // accept lines of up to 1MB
+///         .line_limit(1_048_576)
+///         // do not check the Content-Type header
+///         .content_type_required(false),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct NdJsonConfig {
+    line_limit: usize,
+    content_type_required: bool,
+}
+
+impl NdJsonConfig {
+    /// Sets the maximum length of a line, in bytes. By default, the limit is 256kB.
+    pub fn line_limit(mut self, limit: usize) -> Self {
+        self.line_limit = limit;
+        self
+    }
+
+    /// Sets whether the `Content-Type` header must be `application/x-ndjson`. Defaults to `true`.
+    pub fn content_type_required(mut self, content_type_required: bool) -> Self {
+        self.content_type_required = content_type_required;
+        self
+    }
+
+    /// Extract NDJSON config from app data. Check both `T` and `Data<T>`, in that order, and fall
+    /// back to the default config.
+    fn from_req(req: &HttpRequest) -> &Self {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|d| d.as_ref()))
+            .unwrap_or(&DEFAULT_CONFIG)
+    }
+}
+
+/// Allow shared refs used as default.
+const DEFAULT_CONFIG: NdJsonConfig = NdJsonConfig {
+    line_limit: DEFAULT_LINE_LIMIT,
+    content_type_required: true,
+};
+
+impl Default for NdJsonConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+const APPLICATION_NDJSON: &str = "application/x-ndjson";
+
+pin_project! {
+    /// Newline-delimited JSON (NDJSON) responder.
+    ///
+    /// Wraps a stream of serializable items, sending each one to the client as a line of JSON as
+    /// it is produced. The `Content-Type` of the response is set to `application/x-ndjson`.
+    ///
+    /// If an item fails to serialize, the response body ends with an error, which closes the
+    /// connection.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{get, web, Responder};
+    /// use futures_util::stream;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Record {
+    ///     id: u32,
+    /// }
+    ///
+    /// #[get("/records")]
+    /// async fn records() -> impl Responder {
+    ///     let records = (1..=3).map(|id| Record { id });
+    ///
+    ///     web::NdJsonResponse::from_stream(stream::iter(records))
+    /// }
+    /// ```
+    pub struct NdJsonResponse<S> {
+        #[pin]
+        stream: S,
+    }
+}
+
+impl<S> NdJsonResponse<S>
+where
+    S: Stream,
+    S::Item: Serialize,
+{
+    /// Constructs new NDJSON responder from a stream of items.
+    pub fn from_stream(stream: S) -> Self {
+        Self { stream }
+    }
+}
+
+impl<S> MessageBody for NdJsonResponse<S>
+where
+    S: Stream,
+    S::Item: Serialize,
+{
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let item = match ready!(self.project().stream.poll_next(cx)) {
+            Some(item) => item,
+            None => return Poll::Ready(None),
+        };
+
+        let mut line = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+
+        Poll::Ready(Some(Ok(Bytes::from(line))))
+    }
+}
+
+impl<S> Responder for NdJsonResponse<S>
+where
+    S: Stream + 'static,
+    S::Item: Serialize,
+{
+    type Body = Self;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok()
+            .insert_header(ContentType(APPLICATION_NDJSON.parse().unwrap()))
+            .message_body(self)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures_util::{stream, StreamExt as _};
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        body,
+        http::{
+            header::{self, HeaderValue},
+            StatusCode,
+        },
+        test::TestRequest,
+        ResponseError as _,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        id: u32,
+    }
+
+    fn chunked(chunks: &'static [&'static str]) -> Payload {
+        let stream = stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes()))),
+        );
+        Payload::Stream {
+            payload: Box::pin(stream),
+        }
+    }
+
+    async fn extract(config: NdJsonConfig, chunks: &'static [&'static str]) -> NdJson<Record> {
+        let (req, _) = TestRequest::default()
+            .insert_header((header::CONTENT_TYPE, APPLICATION_NDJSON))
+            .app_data(config)
+            .to_http_parts();
+
+        NdJson::from_request(&req, &mut chunked(chunks))
+            .await
+            .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn lines() {
+        let chunks = &[
+            "{\"id\":1}\n{\"i",
+            "d\":2}\r\n\n  \n{\"id\":3}\n",
+            "{\"id\":4}",
+        ];
+        let records = extract(NdJsonConfig::default(), chunks).await;
+
+        let records = records.map(Result::unwrap).collect::<Vec<_>>().await;
+        assert_eq!(records, [1, 2, 3, 4].map(|id| Record { id }),);
+    }
+
+    #[actix_rt::test]
+    async fn errors() {
+        let chunks = &["{\"id\":1}\n{\"id\":\"two\"}\n{\"id\":3}\n{\"id\":"];
+        let mut records = extract(NdJsonConfig::default(), chunks).await;
+
+        assert_eq!(records.next().await.unwrap().unwrap(), Record { id: 1 });
+        assert!(matches!(
+            records.next().await.unwrap(),
+            Err(NdJsonError::Deserialize { line: 2, .. })
+        ));
+        assert_eq!(records.next().await.unwrap().unwrap(), Record { id: 3 });
+
+        let err = records.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, NdJsonError::Incomplete { line: 4 }));
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert!(records.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn line_limit() {
+        let config = NdJsonConfig::default().line_limit(10);
+
+        // line with newline
+        let mut records = extract(config.clone(), &["{\"id\":1}\n{\"id\":  10}\n"]).await;
+        assert_eq!(records.next().await.unwrap().unwrap(), Record { id: 1 });
+        let err = records.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            NdJsonError::LineOverflow { line: 2, limit: 10 }
+        ));
+        assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(records.next().await.is_none());
+
+        // line still being read
+        let mut records = extract(config, &["{\"id\":", "     ", "1}\n"]).await;
+        assert!(matches!(
+            records.next().await.unwrap(),
+            Err(NdJsonError::LineOverflow { line: 1, .. })
+        ));
+        assert!(records.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn content_type() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .to_http_parts();
+        let err = NdJson::<Record>::from_request(&req, &mut pl)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.as_error::<NdJsonError>(),
+            Some(NdJsonError::ContentType)
+        ));
+
+        let (req, _) = TestRequest::default()
+            .app_data(NdJsonConfig::default().content_type_required(false))
+            .to_http_parts();
+        let records = NdJson::<Record>::from_request(&req, &mut chunked(&["{\"id\":1}"]))
+            .await
+            .unwrap();
+        assert_eq!(records.collect::<Vec<_>>().await.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn responder() {
+        let req = TestRequest::default().to_http_request();
+
+        let items = stream::iter(vec![vec![1], vec![2, 3]]);
+        let res = NdJsonResponse::from_stream(items).respond_to(&req);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            HeaderValue::from_static("application/x-ndjson")
+        );
+        assert_eq!(
+            body::to_bytes(res.into_body()).await.unwrap(),
+            "[1]\n[2,3]\n"
+        );
+
+        // maps with non-string keys can not be serialized
+        let items = stream::iter(vec![HashMap::from([((), ())])]);
+        let res = NdJsonResponse::from_stream(items).respond_to(&req);
+        assert!(body::to_bytes(res.into_body()).await.is_err());
+    }
+}
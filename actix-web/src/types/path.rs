@@ -1,10 +1,10 @@
 //! For path segment extractor documentation, see [`Path`].
 
-use std::sync::Arc;
+use std::{cmp, fmt, sync::Arc};
 
 use actix_router::PathDeserializer;
 use actix_utils::future::{ready, Ready};
-use derive_more::{AsRef, Deref, DerefMut, Display, From};
+use derive_more::{AsRef, Deref, DerefMut, Display};
 use serde::de;
 
 use crate::{
@@ -53,14 +53,67 @@ use crate::{
 ///     format!("Welcome {}!", info.name)
 /// }
 /// ```
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, AsRef, Display, From)]
-pub struct Path<T>(T);
+#[derive(Deref, DerefMut, AsRef, Display)]
+#[display(fmt = "{}", _0)]
+pub struct Path<T>(
+    #[deref]
+    #[deref_mut]
+    #[as_ref]
+    T,
+    Option<Arc<str>>,
+);
 
 impl<T> Path<T> {
     /// Unwrap into inner `T` value.
     pub fn into_inner(self) -> T {
         self.0
     }
+
+    /// Returns the full resource pattern that was matched to extract this path.
+    ///
+    /// For example, when a handler for the resource `/users/{id}/posts/{post_id}` extracts a path
+    /// from a call to `/users/123/posts/456`, this returns `Some("/users/{id}/posts/{post_id}")`.
+    /// The pattern is recorded by the resource while routing the request and is shared between
+    /// requests, so extracting and obtaining it does not allocate, making it suitable for use as a
+    /// low-cardinality metrics label.
+    ///
+    /// Returns `None` when the path was not extracted within a [`Resource`](crate::Resource),
+    /// including in default services. See also [`HttpRequest::match_pattern_str`].
+    pub fn pattern(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
+}
+
+impl<T> From<T> for Path<T> {
+    fn from(inner: T) -> Self {
+        Self(inner, None)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Path<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Path").field(&self.0).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Path<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq> Eq for Path<T> {}
+
+impl<T: PartialOrd> PartialOrd for Path<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T: Ord> Ord for Path<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
 }
 
 /// See [here](#Examples) for example of usage as an extractor.
@@ -80,7 +133,7 @@ where
 
         ready(
             de::Deserialize::deserialize(PathDeserializer::new(req.match_info()))
-                .map(Path)
+                .map(|inner| Path(inner, req.matched_pattern().cloned()))
                 .map_err(move |err| {
                     log::debug!(
                         "Failed during Path extractor deserialization. \
@@ -193,13 +246,13 @@ mod tests {
         resource.capture_match_info(req.match_info_mut());
 
         let (req, mut pl) = req.into_parts();
-        let (Path(res),) = <(Path<(String, String)>,)>::from_request(&req, &mut pl)
+        let (Path(res, _),) = <(Path<(String, String)>,)>::from_request(&req, &mut pl)
             .await
             .unwrap();
         assert_eq!(res.0, "name");
         assert_eq!(res.1, "user1");
 
-        let (Path(a), Path(b)) =
+        let (Path(a, _), Path(b, _)) =
             <(Path<(String, String)>, Path<(String, String)>)>::from_request(&req, &mut pl)
                 .await
                 .unwrap();
@@ -231,7 +284,7 @@ mod tests {
         let s = s.into_inner();
         assert_eq!(s.value, "user2");
 
-        let Path(s) = Path::<(String, String)>::from_request(&req, &mut pl)
+        let Path(s, _) = Path::<(String, String)>::from_request(&req, &mut pl)
             .await
             .unwrap();
         assert_eq!(s.0, "name");
@@ -246,7 +299,7 @@ mod tests {
         assert_eq!(s.as_ref().key, "name");
         assert_eq!(s.value, 32);
 
-        let Path(s) = Path::<(String, u8)>::from_request(&req, &mut pl)
+        let Path(s, _) = Path::<(String, u8)>::from_request(&req, &mut pl)
             .await
             .unwrap();
         assert_eq!(s.0, "name");
@@ -288,4 +341,60 @@ mod tests {
 
         assert_eq!(res.status(), http::StatusCode::CONFLICT);
     }
+
+    #[actix_rt::test]
+    async fn matched_pattern() {
+        use crate::{
+            test::{call_service, init_service},
+            web, App,
+        };
+
+        async fn post(path: Path<(u32, u32)>) -> HttpResponse {
+            assert_eq!(path.pattern(), Some("/users/{id}/posts/{post_id}"));
+            HttpResponse::Ok().finish()
+        }
+
+        async fn fallback(path: Path<(u32,)>) -> HttpResponse {
+            assert!(path.pattern().is_none());
+            HttpResponse::Ok().finish()
+        }
+
+        async fn me(path: Path<()>) -> HttpResponse {
+            assert_eq!(path.pattern(), Some("/me/{x}"));
+            HttpResponse::Ok().finish()
+        }
+
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/me/{id}")
+                        .guard(crate::guard::Header("x-admin", "1"))
+                        .to(HttpResponse::Forbidden),
+                )
+                .service(web::resource("/me/{x}").to(me))
+                .service(
+                    web::scope("/users/{id}")
+                        .service(web::resource("/posts/{post_id}").to(post))
+                        .default_service(web::to(fallback)),
+                ),
+        )
+        .await;
+
+        // pattern of the resource that handled the request, not the first one matching the path
+        for _ in 0..2 {
+            let req = TestRequest::with_uri("/me/1").to_request();
+            let res = call_service(&srv, req).await;
+            assert_eq!(res.status(), http::StatusCode::OK);
+        }
+
+        let req = TestRequest::with_uri("/users/123/posts/456").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), http::StatusCode::OK);
+
+        let req = TestRequest::with_uri("/users/123/other").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), http::StatusCode::OK);
+
+        assert!(Path::from(42).pattern().is_none());
+    }
 }
@@ -6,9 +6,11 @@ use std::{
     pin::Pin,
     str,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use actix_http::error::PayloadError;
+use actix_rt::time::{sleep, Instant, Sleep};
 use actix_utils::future::{ready, Either, Ready};
 use bytes::{Bytes, BytesMut};
 use encoding_rs::{Encoding, UTF_8};
@@ -22,7 +24,8 @@ use crate::{
 
 /// Extract a request's raw payload stream.
 ///
-/// See [`PayloadConfig`] for important notes when using this advanced extractor.
+/// See [`PayloadConfig`] for important notes when using this advanced extractor. Only its
+/// timeouts apply to this extractor.
 ///
 /// # Examples
 /// ```
@@ -68,8 +71,9 @@ impl FromRequest for Payload {
     type Future = Ready<Result<Payload, Error>>;
 
     #[inline]
-    fn from_request(_: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
-        ready(Ok(Payload(payload.take())))
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let cfg = PayloadConfig::from_req(req);
+        ready(Ok(Payload(cfg.take_payload(payload))))
     }
 }
 
@@ -102,8 +106,10 @@ impl FromRequest for Bytes {
             return Either::right(ready(Err(err)));
         }
 
+        let mut payload = cfg.take_payload(payload);
+
         Either::left(BytesExtractFut {
-            body_fut: HttpMessageBody::new(req, payload).limit(cfg.limit),
+            body_fut: HttpMessageBody::new(req, &mut payload).limit(cfg.limit),
         })
     }
 }
@@ -155,7 +161,8 @@ impl FromRequest for String {
             Err(err) => return Either::right(ready(Err(err.into()))),
         };
         let limit = cfg.limit;
-        let body_fut = HttpMessageBody::new(req, payload).limit(limit);
+        let mut payload = cfg.take_payload(payload);
+        let body_fut = HttpMessageBody::new(req, &mut payload).limit(limit);
 
         Either::left(StringExtractFut { body_fut, encoding })
     }
@@ -202,12 +209,32 @@ fn bytes_to_string(body: Bytes, encoding: &'static Encoding) -> Result<String, E
 ///
 /// By default, the payload size limit is 256kB and there is no mime type condition.
 ///
+/// Reading the payload can also be bounded in time using [`read_timeout`](Self::read_timeout)
+/// and [`idle_timeout`](Self::idle_timeout), which stops slow clients from tying up workers. The
+/// timeouts apply to the [`Payload`] extractor too. There are no timeouts by default.
+///
 /// To use this, add an instance of it to your [`app`](crate::App), [`scope`](crate::Scope)
 /// or [`resource`](crate::Resource) through the associated `.app_data()` method.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{web, App};
+///
+/// let payload_cfg = web::PayloadConfig::new(64 * 1024)
+///     // the whole payload must be read within 30 seconds...
+///     .read_timeout(Duration::from_secs(30))
+///     // ...and no more than 5 seconds may pass between chunks
+///     .idle_timeout(Duration::from_secs(5));
+///
+/// App::new().app_data(payload_cfg);
+/// ```
 #[derive(Clone)]
 pub struct PayloadConfig {
     limit: usize,
     mimetype: Option<Mime>,
+    read_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
 }
 
 impl PayloadConfig {
@@ -231,6 +258,43 @@ impl PayloadConfig {
         self
     }
 
+    /// Set maximum duration to spend reading the whole payload.
+    ///
+    /// The timer starts when extraction starts. If it expires before the payload has been read,
+    /// the payload stream yields a [`PayloadError::Timeout`] error, which responds with
+    /// `408 Request Timeout`.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set maximum duration to wait for each chunk of the payload.
+    ///
+    /// The timer restarts whenever a chunk is received. If it expires, the payload stream yields a
+    /// [`PayloadError::Timeout`] error, which responds with `408 Request Timeout`.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Takes payload from `payload`, wrapping it to enforce the configured timeouts, if any.
+    fn take_payload(&self, payload: &mut dev::Payload) -> dev::Payload {
+        if self.read_timeout.is_none() && self.idle_timeout.is_none() {
+            return payload.take();
+        }
+
+        dev::Payload::Stream {
+            payload: Box::pin(TimeoutPayload {
+                payload: payload.take(),
+                read_timer: self.read_timeout.map(|timeout| Box::pin(sleep(timeout))),
+                idle_timer: self
+                    .idle_timeout
+                    .map(|timeout| (timeout, Box::pin(sleep(timeout)))),
+                timed_out: false,
+            }),
+        }
+    }
+
     fn check_mimetype(&self, req: &HttpRequest) -> Result<(), Error> {
         // check content-type
         if let Some(ref mt) = self.mimetype {
@@ -267,6 +331,8 @@ const DEFAULT_CONFIG_LIMIT: usize = 262_144; // 2^18 bytes (~256kB)
 const DEFAULT_CONFIG: PayloadConfig = PayloadConfig {
     limit: DEFAULT_CONFIG_LIMIT,
     mimetype: None,
+    read_timeout: None,
+    idle_timeout: None,
 };
 
 impl Default for PayloadConfig {
@@ -275,6 +341,51 @@ impl Default for PayloadConfig {
     }
 }
 
+/// Payload stream that errors when the configured timeouts expire.
+struct TimeoutPayload {
+    payload: dev::Payload,
+    read_timer: Option<Pin<Box<Sleep>>>,
+    idle_timer: Option<(Duration, Pin<Box<Sleep>>)>,
+    timed_out: bool,
+}
+
+impl Stream for TimeoutPayload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.timed_out {
+            return Poll::Ready(None);
+        }
+
+        if let Poll::Ready(item) = Pin::new(&mut this.payload).poll_next(cx) {
+            if let Some((timeout, ref mut timer)) = this.idle_timer {
+                timer.as_mut().reset(Instant::now() + timeout);
+            }
+
+            return Poll::Ready(item);
+        }
+
+        let read_expired = this
+            .read_timer
+            .as_mut()
+            .map_or(false, |timer| timer.as_mut().poll(cx).is_ready());
+
+        let idle_expired = this
+            .idle_timer
+            .as_mut()
+            .map_or(false, |(_, timer)| timer.as_mut().poll(cx).is_ready());
+
+        if read_expired || idle_expired {
+            this.timed_out = true;
+            return Poll::Ready(Some(Err(PayloadError::Timeout)));
+        }
+
+        Poll::Pending
+    }
+}
+
 /// Future that resolves to a complete HTTP body payload.
 ///
 /// By default only 256kB payload is accepted before `PayloadError::Overflow` is returned.
@@ -375,6 +486,7 @@ impl Future for HttpMessageBody {
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
+    use futures_util::StreamExt as _;
 
     use super::*;
     use crate::http::{header, StatusCode};
@@ -535,4 +647,68 @@ mod tests {
             _ => unreachable!("error"),
         }
     }
+
+    fn pending_after(chunks: &'static [&'static [u8]]) -> dev::Payload {
+        let chunks = chunks.iter().map(|chunk| Ok(Bytes::from_static(chunk)));
+        let stream = futures_util::stream::iter(chunks).chain(futures_util::stream::pending());
+
+        dev::Payload::Stream {
+            payload: Box::pin(stream),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_read_timeout() {
+        let (req, _) = TestRequest::default()
+            .app_data(PayloadConfig::default().read_timeout(Duration::from_millis(10)))
+            .to_http_parts();
+
+        let mut pl = pending_after(&[b"hello"]);
+        let err = Bytes::from_request(&req, &mut pl).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::REQUEST_TIMEOUT
+        );
+
+        let mut pl = pending_after(&[]);
+        let err = String::from_request(&req, &mut pl).await.unwrap_err();
+        assert!(matches!(
+            err.as_error::<PayloadError>(),
+            Some(PayloadError::Timeout)
+        ));
+
+        let mut pl = pending_after(&[b"hello"]);
+        let mut payload = Payload::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(payload.next().await.unwrap().unwrap(), "hello");
+        assert!(matches!(
+            payload.next().await.unwrap(),
+            Err(PayloadError::Timeout)
+        ));
+        assert!(payload.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_idle_timeout() {
+        let (req, _) = TestRequest::default()
+            .app_data(PayloadConfig::default().idle_timeout(Duration::from_millis(50)))
+            .to_http_parts();
+
+        // chunks arriving more often than the idle timeout are accepted
+        let stream = futures_util::stream::iter([b"a", b"b", b"c"]).then(|chunk| async move {
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, PayloadError>(Bytes::from_static(chunk))
+        });
+        let mut pl: dev::Payload = dev::Payload::Stream {
+            payload: Box::pin(stream),
+        };
+        let body = Bytes::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(body, "abc");
+
+        let mut pl = pending_after(&[b"a", b"b"]);
+        let err = Bytes::from_request(&req, &mut pl).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::REQUEST_TIMEOUT
+        );
+    }
 }
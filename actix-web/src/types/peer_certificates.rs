@@ -0,0 +1,132 @@
+//! For peer certificates extractor documentation, see [`PeerCertificates`].
+
+use std::future::{ready, Ready};
+
+use bytes::Bytes;
+
+use crate::{dev::Payload, error, Error, FromRequest, HttpRequest};
+
+/// Certificate chain presented by the client of a mutual TLS connection.
+///
+/// Certificates are DER-encoded and ordered as sent by the client, starting with the client's own
+/// (end-entity) certificate.
+///
+/// When using [`HttpServer::bind_rustls`] or [`HttpServer::listen_rustls`], the chain is stored in
+/// connection data for any client that presents a certificate. Configure the Rustls `ServerConfig`
+/// with a client certificate verifier so that only verified chains are accepted. For other
+/// listeners, it can be inserted manually using [`HttpServer::on_connect`].
+///
+/// # Extractor
+/// Extracting `PeerCertificates` fails with a `401 Unauthorized` error if the client did not
+/// present a certificate. Use `Option<PeerCertificates>` if client certificates are optional.
+///
+/// # Examples
+/// ```
+/// use actix_web::{get, web, HttpResponse};
+///
+/// #[get("/")]
+/// async fn index(certs: web::PeerCertificates) -> HttpResponse {
+///     // parse with an X.509 library to read the client's subject or SANs
+///     let der = certs.end_entity();
+///     HttpResponse::Ok().body(format!("client certificate is {} bytes", der.len()))
+/// }
+/// ```
+///
+/// [`HttpServer::bind_rustls`]: crate::HttpServer::bind_rustls
+/// [`HttpServer::listen_rustls`]: crate::HttpServer::listen_rustls
+/// [`HttpServer::on_connect`]: crate::HttpServer::on_connect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerCertificates {
+    chain: Vec<Bytes>,
+}
+
+impl PeerCertificates {
+    /// Constructs new peer certificate chain from DER-encoded certificates, end-entity first.
+    ///
+    /// # Panics
+    /// Panics if `chain` is empty.
+    pub fn new(chain: Vec<Bytes>) -> Self {
+        assert!(!chain.is_empty(), "peer certificate chain can not be empty");
+        Self { chain }
+    }
+
+    /// Returns the DER-encoded end-entity certificate; i.e., the client's own certificate.
+    pub fn end_entity(&self) -> &[u8] {
+        &self.chain[0]
+    }
+
+    /// Returns the full DER-encoded certificate chain, starting with the end-entity certificate.
+    pub fn chain(&self) -> &[Bytes] {
+        &self.chain
+    }
+
+    /// Unwraps into the DER-encoded certificate chain.
+    pub fn into_inner(self) -> Vec<Bytes> {
+        self.chain
+    }
+}
+
+impl FromRequest for PeerCertificates {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        match req.conn_data::<PeerCertificates>() {
+            Some(certs) => ready(Ok(certs.clone())),
+            None => {
+                log::debug!(
+                    "Failed to extract `PeerCertificates` for {:?} handler. \
+                     Client did not present a TLS certificate.",
+                    req.match_name().unwrap_or_else(|| req.path())
+                );
+
+                ready(Err(error::ErrorUnauthorized(
+                    "client certificate is required",
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http::StatusCode, test::TestRequest};
+
+    #[actix_rt::test]
+    async fn extract() {
+        let certs = PeerCertificates::new(vec![
+            Bytes::from_static(b"leaf"),
+            Bytes::from_static(b"intermediate"),
+        ]);
+
+        let (req, mut pl) = TestRequest::default()
+            .insert_conn_data(certs.clone())
+            .to_http_parts();
+        let extracted = PeerCertificates::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(extracted, certs);
+        assert_eq!(extracted.end_entity(), b"leaf");
+        assert_eq!(extracted.chain().len(), 2);
+
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+        let err = PeerCertificates::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let opt = Option::<PeerCertificates>::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert!(opt.is_none());
+    }
+
+    #[test]
+    #[should_panic = "can not be empty"]
+    fn empty_chain() {
+        PeerCertificates::new(vec![]);
+    }
+}
@@ -3,7 +3,7 @@
 use std::{fmt, ops, sync::Arc};
 
 use actix_utils::future::{err, ok, Ready};
-use serde::de::DeserializeOwned;
+use serde::de::{self, DeserializeOwned};
 
 use crate::{dev::Payload, error::QueryPayloadError, Error, FromRequest, HttpRequest};
 
@@ -112,29 +112,33 @@ impl<T: DeserializeOwned> FromRequest for Query<T> {
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let error_handler = req
-            .app_data::<QueryConfig>()
-            .and_then(|c| c.err_handler.clone());
+        let config = req.app_data::<QueryConfig>();
 
-        serde_urlencoded::from_str::<T>(req.query_string())
-            .map(|val| ok(Query(val)))
-            .unwrap_or_else(move |e| {
-                let e = QueryPayloadError::Deserialize(e);
+        let error_handler = config.and_then(|c| c.err_handler.clone());
 
-                log::debug!(
-                    "Failed during Query extractor deserialization. \
+        let res = match config.and_then(|c| c.deserializer.as_ref()) {
+            Some(deserializer) => (deserializer)(req.query_string())
+                .and_then(|pairs| T::deserialize(PairsDeserializer::new(pairs))),
+            None => serde_urlencoded::from_str::<T>(req.query_string()),
+        };
+
+        res.map(|val| ok(Query(val))).unwrap_or_else(move |e| {
+            let e = QueryPayloadError::Deserialize(e);
+
+            log::debug!(
+                "Failed during Query extractor deserialization. \
                      Request path: {:?}",
-                    req.path()
-                );
+                req.path()
+            );
 
-                let e = if let Some(error_handler) = error_handler {
-                    (error_handler)(e, req)
-                } else {
-                    e.into()
-                };
+            let e = if let Some(error_handler) = error_handler {
+                (error_handler)(e, req)
+            } else {
+                e.into()
+            };
 
-                err(e)
-            })
+            err(e)
+        })
     }
 }
 
@@ -171,8 +175,13 @@ impl<T: DeserializeOwned> FromRequest for Query<T> {
 pub struct QueryConfig {
     #[allow(clippy::type_complexity)]
     err_handler: Option<Arc<dyn Fn(QueryPayloadError, &HttpRequest) -> Error + Send + Sync>>,
+
+    deserializer: Option<Arc<QueryDeserializer>>,
 }
 
+type QueryDeserializer =
+    dyn Fn(&str) -> Result<Vec<(String, String)>, de::value::Error> + Send + Sync;
+
 impl QueryConfig {
     /// Set custom error handler
     pub fn error_handler<F>(mut self, f: F) -> Self
@@ -182,6 +191,222 @@ impl QueryConfig {
         self.err_handler = Some(Arc::new(f));
         self
     }
+
+    /// Set custom deserializer for extracting `Query<T>`s.
+    ///
+    /// The deserializer is given the raw (still percent-encoded) query string and returns the
+    /// decoded key/value pairs that `T` is deserialized from, in place of the default
+    /// [`serde_urlencoded`] based deserialization. This can be used to support query formats that
+    /// are not handled by default, such as comma-separated lists.
+    ///
+    /// The values of keys that occur more than once are collected into sequences, so that, e.g.,
+    /// `?tag=a&tag=b` can be deserialized into a `Vec<String>` field. Values of other fields are
+    /// parsed from their string representation, as they are by default. Any error, returned by the
+    /// deserializer or raised while deserializing `T` from the pairs, is converted to a
+    /// [`QueryPayloadError::Deserialize`] and passed to the [error handler](Self::error_handler),
+    /// if one is set.
+    ///
+    /// The deserializer is used for all `Query` extractors that this config applies to.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{get, web, App};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Filter {
+    ///     tag: Vec<String>,
+    ///     page: u32,
+    /// }
+    ///
+    /// #[get("/")]
+    /// async fn index(filter: web::Query<Filter>) -> String {
+    ///     format!("page {} of {}", filter.page, filter.tag.join(", "))
+    /// }
+    ///
+    /// // also splits comma-separated values, e.g. `?tag=a,b&tag=c&page=2`
+    /// let query_cfg = web::QueryConfig::default().deserializer(|query| {
+    ///     let pairs = url::form_urlencoded::parse(query.as_bytes())
+    ///         .flat_map(|(key, val)| {
+    ///             val.split(',')
+    ///                 .map(|val| (key.clone().into_owned(), val.to_owned()))
+    ///                 .collect::<Vec<_>>()
+    ///         })
+    ///         .collect();
+    ///
+    ///     Ok::<_, std::convert::Infallible>(pairs)
+    /// });
+    ///
+    /// App::new()
+    ///     .app_data(query_cfg)
+    ///     .service(index);
+    /// ```
+    pub fn deserializer<E, F>(mut self, f: F) -> Self
+    where
+        E: fmt::Display,
+        F: Fn(&str) -> Result<Vec<(String, String)>, E> + Send + Sync + 'static,
+    {
+        self.deserializer = Some(Arc::new(move |query| f(query).map_err(de::Error::custom)));
+        self
+    }
+}
+
+/// Deserializes a map from key/value pairs, collecting the values of repeated keys.
+struct PairsDeserializer {
+    pairs: Vec<(String, Values)>,
+}
+
+impl PairsDeserializer {
+    fn new(pairs: Vec<(String, String)>) -> Self {
+        let mut grouped: Vec<(String, Values)> = Vec::new();
+
+        for (key, val) in pairs {
+            match grouped.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, vals)) => vals.0.push(val),
+                None => grouped.push((key, Values(vec![val]))),
+            }
+        }
+
+        Self { pairs: grouped }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for PairsDeserializer {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(de::value::MapDeserializer::new(self.pairs.into_iter()))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+/// Values of a key, deserialized as a sequence or, if there is only one, as a single value.
+struct Values(Vec<String>);
+
+impl Values {
+    fn single(mut self) -> Result<String, de::value::Error> {
+        match self.0.len() {
+            1 => Ok(self.0.remove(0)),
+            len => Err(de::Error::invalid_length(len, &"a single value")),
+        }
+    }
+}
+
+impl<'de> de::IntoDeserializer<'de, de::value::Error> for Values {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($deserialize:ident => $visit:ident,)*) => {$(
+        fn $deserialize<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            let val = self.single()?;
+
+            match val.parse() {
+                Ok(parsed) => visitor.$visit(parsed),
+                Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(&val), &visitor)),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Values {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.len() == 1 {
+            visitor.visit_string(self.single()?)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let vals = self.0.into_iter().map(|val| Values(vec![val]));
+        visitor.visit_seq(de::value::SeqDeserializer::new(vals))
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(de::IntoDeserializer::into_deserializer(self.single()?))
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit_struct map struct identifier
+    }
 }
 
 #[cfg(test)]
@@ -249,6 +474,131 @@ mod tests {
             .unwrap();
     }
 
+    #[actix_rt::test]
+    async fn test_custom_deserializer() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Tags {
+            tag: Vec<String>,
+        }
+
+        let query_cfg = QueryConfig::default()
+            .deserializer(|query| {
+                let pairs = url::form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect::<Vec<_>>();
+
+                if pairs.is_empty() {
+                    Err("empty query")
+                } else {
+                    Ok(pairs)
+                }
+            })
+            .error_handler(|e, _| {
+                let resp = HttpResponse::UnprocessableEntity().finish();
+                InternalError::from_response(e, resp).into()
+            });
+
+        let req = TestRequest::with_uri("/?tag=a&tag=b%20c")
+            .app_data(query_cfg.clone())
+            .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let tags = Query::<Tags>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(tags.into_inner().tag, ["a", "b c"]);
+
+        let req = TestRequest::with_uri("/?tag=a")
+            .app_data(query_cfg.clone())
+            .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let tags = Query::<Tags>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(tags.into_inner().tag, ["a"]);
+
+        // deserializer errors are passed to error handler
+        let req = TestRequest::with_uri("/")
+            .app_data(query_cfg.clone())
+            .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let err = Query::<Tags>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().error_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(err.to_string(), "Query deserialize error: empty query");
+
+        // as are errors deserializing the returned pairs
+        let req = TestRequest::with_uri("/?id=test")
+            .app_data(query_cfg)
+            .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let err = Query::<Tags>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().error_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_custom_deserializer_values() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Order {
+            Asc,
+            Desc,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Filter {
+            page: u32,
+            ratio: f64,
+            ids: Vec<i64>,
+            active: Option<bool>,
+            order: Order,
+            #[serde(default)]
+            tags: Vec<String>,
+        }
+
+        let query_cfg = QueryConfig::default().deserializer(|query| {
+            Ok::<_, std::convert::Infallible>(
+                url::form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect(),
+            )
+        });
+
+        let req =
+            TestRequest::with_uri("/?page=2&ratio=0.5&ids=1&ids=-2&active=true&order=desc")
+                .app_data(query_cfg.clone())
+                .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let filter = Query::<Filter>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(
+            filter.into_inner(),
+            Filter {
+                page: 2,
+                ratio: 0.5,
+                ids: vec![1, -2],
+                active: Some(true),
+                order: Order::Desc,
+                tags: vec![],
+            }
+        );
+
+        // invalid numbers and repeated keys of single value fields are errors
+        for query in [
+            "page=two&ratio=1&ids=1&order=asc",
+            "page=1&page=2&ratio=1&ids=1&order=asc",
+        ] {
+            let req = TestRequest::with_uri(&format!("/?{}", query))
+                .app_data(query_cfg.clone())
+                .to_srv_request();
+            let (req, mut pl) = req.into_parts();
+            assert!(Query::<Filter>::from_request(&req, &mut pl).await.is_err());
+        }
+    }
+
     #[actix_rt::test]
     async fn test_custom_error_responder() {
         let req = TestRequest::with_uri("/name/user1/")
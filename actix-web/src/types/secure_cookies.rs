@@ -0,0 +1,270 @@
+//! For signed and private cookie extractor documentation, see [`SignedCookies`] and
+//! [`PrivateCookies`].
+
+use std::{
+    future::{ready, Ready},
+    ops,
+};
+
+use actix_http::Payload;
+use cookie::{Cookie, CookieJar, Key};
+
+use crate::{error, web, Error, FromRequest, HttpRequest};
+
+/// Extractor for the request cookies that were signed with the application's cookie [`Key`].
+///
+/// The extracted [`CookieJar`] only contains cookies whose signature could be verified, with their
+/// plain values; cookies that were tampered with, signed with a different key, or never signed are
+/// dropped. Cookies from all of the request's `Cookie` headers are considered. If multiple
+/// authentic cookies have the same name, the first one sent by the client is used.
+///
+/// Signed cookies are readable, but not modifiable, by clients. Use [`PrivateCookies`] for cookies
+/// that should also be confidential. Cookies can be signed using [`CookieJar::signed_mut`].
+///
+/// Requires the `secure-cookies` feature.
+///
+/// # Configuration
+/// The key is read from app data, set with either `app_data(key)` or
+/// `app_data(Data::new(key))`. Extraction fails with a `500 Internal Server Error` if no key is
+/// configured, and a `400 Bad Request` if a `Cookie` header is malformed.
+///
+/// # Expiration
+/// Clients do not send cookie attributes, such as `Expires` or `Max-Age`, in the `Cookie` header,
+/// so they are not checked here. If a cookie must not be accepted after some point in time, include
+/// a timestamp in its (signed) value and check it in the handler.
+///
+/// # Examples
+/// ```
+/// use actix_web::{cookie::Key, get, web, App, HttpResponse};
+///
+/// #[get("/")]
+/// async fn index(jar: web::SignedCookies) -> HttpResponse {
+///     match jar.get("session") {
+///         Some(session) => HttpResponse::Ok().body(format!("session: {}", session.value())),
+///         None => HttpResponse::Unauthorized().finish(),
+///     }
+/// }
+///
+/// let app = App::new()
+///     .app_data(Key::from(&[0; 64]))
+///     .service(index);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SignedCookies(CookieJar);
+
+/// Extractor for the request cookies that were encrypted with the application's cookie [`Key`].
+///
+/// The extracted [`CookieJar`] only contains cookies that could be decrypted and authenticated,
+/// with their plain values; cookies that were tampered with, encrypted with a different key, or
+/// never encrypted are dropped. Cookies from all of the request's `Cookie` headers are considered.
+/// If multiple authentic cookies have the same name, the first one sent by the client is used.
+///
+/// Private cookies are neither readable nor modifiable by clients. Cookies can be encrypted using
+/// [`CookieJar::private_mut`].
+///
+/// Requires the `secure-cookies` feature.
+///
+/// # Configuration
+/// The key is read from app data, the same as for [`SignedCookies`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{cookie::Key, get, web, App, HttpResponse};
+///
+/// #[get("/")]
+/// async fn index(jar: web::PrivateCookies) -> HttpResponse {
+///     match jar.get("session") {
+///         Some(session) => HttpResponse::Ok().body(format!("session: {}", session.value())),
+///         None => HttpResponse::Unauthorized().finish(),
+///     }
+/// }
+///
+/// let app = App::new()
+///     .app_data(Key::from(&[0; 64]))
+///     .service(index);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrivateCookies(CookieJar);
+
+macro_rules! verified_jar {
+    ($name:ident, $jar:ident, $verify:ident) => {
+        impl $name {
+            /// Unwraps into the inner jar of verified cookies.
+            pub fn into_inner(self) -> CookieJar {
+                self.0
+            }
+        }
+
+        impl ops::Deref for $name {
+            type Target = CookieJar;
+
+            fn deref(&self) -> &CookieJar {
+                &self.0
+            }
+        }
+
+        /// See [here](#examples) for example of usage as an extractor.
+        impl FromRequest for $name {
+            type Error = Error;
+            type Future = Ready<Result<Self, Error>>;
+
+            #[inline]
+            fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+                ready(
+                    verified_cookies(req, stringify!($name), |key, cookie| {
+                        CookieJar::new().$jar(key).$verify(cookie)
+                    })
+                    .map($name),
+                )
+            }
+        }
+    };
+}
+
+verified_jar!(SignedCookies, signed, verify);
+verified_jar!(PrivateCookies, private, decrypt);
+
+/// Collects the request cookies for which `verify` returns a cookie with the plain value.
+fn verified_cookies(
+    req: &HttpRequest,
+    extractor: &str,
+    verify: impl Fn(&Key, Cookie<'static>) -> Option<Cookie<'static>>,
+) -> Result<CookieJar, Error> {
+    let key = req
+        .app_data::<Key>()
+        .or_else(|| req.app_data::<web::Data<Key>>().map(|d| d.as_ref()))
+        .ok_or_else(|| {
+            log::debug!(
+                "Failed to extract cookies for `{}` handler. For the `{}` extractor to work \
+                correctly, pass a `cookie::Key` to `App::app_data()`.",
+                req.match_name().unwrap_or_else(|| req.path()),
+                extractor,
+            );
+
+            error::ErrorInternalServerError(
+                "Cookie key is not configured correctly. View/enable debug logs for more details.",
+            )
+        })?;
+
+    let cookies = req.cookies().map_err(error::ErrorBadRequest)?;
+
+    let mut jar = CookieJar::new();
+
+    for cookie in cookies.iter() {
+        if jar.get(cookie.name()).is_some() {
+            continue;
+        }
+
+        if let Some(cookie) = verify(key, cookie.clone()) {
+            jar.add_original(cookie);
+        }
+    }
+
+    Ok(jar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http::header, http::StatusCode, test::TestRequest};
+
+    fn key() -> Key {
+        Key::from(&[1; 64])
+    }
+
+    /// Returns the `name=value` pair of `cookie` after adding it to a jar using `add`.
+    fn secure(
+        cookie: Cookie<'static>,
+        add: impl FnOnce(&mut CookieJar, Cookie<'static>),
+    ) -> String {
+        let mut jar = CookieJar::new();
+        add(&mut jar, cookie.clone());
+        jar.get(cookie.name()).unwrap().stripped().to_string()
+    }
+
+    fn signed(name: &'static str, value: &'static str, key: &Key) -> String {
+        secure(Cookie::new(name, value), |jar, c| {
+            jar.signed_mut(key).add(c)
+        })
+    }
+
+    fn private(name: &'static str, value: &'static str, key: &Key) -> String {
+        secure(Cookie::new(name, value), |jar, c| {
+            jar.private_mut(key).add(c)
+        })
+    }
+
+    #[actix_rt::test]
+    async fn signed_cookies() {
+        let session = signed("session", "alice", &key());
+        let tampered = session.replace("alice", "admin");
+        let other_key = signed("theme", "dark", &Key::from(&[2; 64]));
+
+        let (req, mut pl) = TestRequest::default()
+            .app_data(key())
+            .insert_header((header::COOKIE, format!("{}; plain=value", session)))
+            .append_header((header::COOKIE, other_key))
+            .to_http_parts();
+        let jar = SignedCookies::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(jar.get("session").unwrap().value(), "alice");
+        assert!(jar.get("plain").is_none());
+        assert!(jar.get("theme").is_none());
+
+        let (req, mut pl) = TestRequest::default()
+            .app_data(web::Data::new(key()))
+            .insert_header((header::COOKIE, tampered))
+            .to_http_parts();
+        let jar = SignedCookies::from_request(&req, &mut pl).await.unwrap();
+        assert!(jar.get("session").is_none());
+
+        // private cookies are not signed cookies
+        let (req, mut pl) = TestRequest::default()
+            .app_data(key())
+            .insert_header((header::COOKIE, private("session", "alice", &key())))
+            .to_http_parts();
+        let jar = SignedCookies::from_request(&req, &mut pl).await.unwrap();
+        assert!(jar.get("session").is_none());
+    }
+
+    #[actix_rt::test]
+    async fn private_cookies() {
+        let first = private("session", "alice", &key());
+        let second = private("session", "bob", &key());
+        let forged = signed("session", "mallory", &key());
+
+        let (req, mut pl) = TestRequest::default()
+            .app_data(key())
+            .insert_header((header::COOKIE, forged))
+            .append_header((header::COOKIE, format!("{}; {}", first, second)))
+            .to_http_parts();
+        let jar = PrivateCookies::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(jar.get("session").unwrap().value(), "alice");
+        assert_eq!(jar.into_inner().iter().count(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn errors() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_header((header::COOKIE, signed("session", "alice", &key())))
+            .to_http_parts();
+        let err = SignedCookies::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let (req, mut pl) = TestRequest::default()
+            .app_data(key())
+            .insert_header((header::COOKIE, "=invalid"))
+            .to_http_parts();
+        let err = PrivateCookies::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! For validating extractor documentation, see [`Valid`].
+
+use std::{
+    fmt,
+    future::Future,
+    ops,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use actix_http::Payload;
+use futures_core::ready;
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationErrors};
+
+use super::{form::FormExtractFut, json::JsonExtractFut};
+use crate::{
+    error::Error,
+    extract::FromRequest,
+    request::HttpRequest,
+    web::{self, Form, Json},
+    HttpMessage as _,
+};
+
+/// Validating extractor for JSON and URL encoded form payloads.
+///
+/// The payload is deserialized like [`Form<T>`](Form), if the request's `Content-Type` is
+/// `application/x-www-form-urlencoded`, and like [`Json<T>`](Json) otherwise. The extracted value
+/// is then checked using its [`Validate`] implementation.
+///
+/// Requires the `validation` feature.
+///
+/// # Configuration
+/// Deserialization is configured by [`JsonConfig`](web::JsonConfig) and
+/// [`FormConfig`](web::FormConfig), including their limits and error handlers. Validation errors
+/// respond with `400 Bad Request` and a JSON body listing the fields that failed validation; use
+/// [`ValidConfig`] to change this.
+///
+/// # Examples
+/// ```
+/// use actix_web::{post, web};
+/// use serde::Deserialize;
+/// use validator::Validate;
+///
+/// #[derive(Deserialize, Validate)]
+/// struct Signup {
+///     #[validate(email)]
+///     email: String,
+///     #[validate(length(min = 8))]
+///     password: String,
+/// }
+///
+/// // accepts both JSON and URL encoded bodies
+/// #[post("/signup")]
+/// async fn signup(form: web::Valid<Signup>) -> String {
+///     format!("Welcome {}!", form.email)
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    /// Unwrap into inner `T` value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Valid<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Valid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// See [here](#examples) for example of usage as an extractor.
+impl<T> FromRequest for Valid<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = Error;
+    type Future = ValidExtractFut<T>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let is_form = matches!(
+            req.mime_type(),
+            Ok(Some(mime)) if mime.essence_str() == mime::APPLICATION_WWW_FORM_URLENCODED
+        );
+
+        let fut = if is_form {
+            ValidBody::Form(Form::from_request(req, payload))
+        } else {
+            ValidBody::Json(Json::from_request(req, payload))
+        };
+
+        ValidExtractFut {
+            fut,
+            req: req.clone(),
+            err_handler: ValidConfig::from_req(req).err_handler.clone(),
+        }
+    }
+}
+
+type ValidErrHandler =
+    Option<Arc<dyn Fn(ValidationErrors, &HttpRequest) -> Error + Send + Sync>>;
+
+enum ValidBody<T> {
+    Json(JsonExtractFut<T>),
+    Form(FormExtractFut<T>),
+}
+
+pub struct ValidExtractFut<T> {
+    fut: ValidBody<T>,
+    err_handler: ValidErrHandler,
+    req: HttpRequest,
+}
+
+impl<T> Future for ValidExtractFut<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Output = Result<Valid<T>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let item = match this.fut {
+            ValidBody::Json(ref mut fut) => ready!(Pin::new(fut).poll(cx))?.into_inner(),
+            ValidBody::Form(ref mut fut) => ready!(Pin::new(fut).poll(cx))?.into_inner(),
+        };
+
+        let res = match item.validate() {
+            Ok(()) => Ok(Valid(item)),
+            Err(err) => {
+                log::debug!(
+                    "Failed to validate extracted payload. \
+                     Request path: {}",
+                    this.req.path()
+                );
+
+                match &this.err_handler {
+                    Some(err_handler) => Err((err_handler)(err, &this.req)),
+                    None => Err(err.into()),
+                }
+            }
+        };
+
+        Poll::Ready(res)
+    }
+}
+
+/// [`Valid`] extractor configuration.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error, web, App, HttpResponse};
+///
+/// let valid_cfg = web::ValidConfig::default()
+///     // use custom error handler
+///     .error_handler(|err, req| {
+///         error::InternalError::from_response(err, HttpResponse::UnprocessableEntity().into())
+///             .into()
+///     });
+///
+/// App::new().app_data(valid_cfg);
+/// ```
+#[derive(Clone)]
+pub struct ValidConfig {
+    err_handler: ValidErrHandler,
+}
+
+impl ValidConfig {
+    /// Set custom error handler for validation errors.
+    pub fn error_handler<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ValidationErrors, &HttpRequest) -> Error + Send + Sync + 'static,
+    {
+        self.err_handler = Some(Arc::new(f));
+        self
+    }
+
+    /// Extract validation config from app data. Check both `T` and `Data<T>`, in that order, and
+    /// fall back to the default config.
+    fn from_req(req: &HttpRequest) -> &Self {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|d| d.as_ref()))
+            .unwrap_or(&DEFAULT_CONFIG)
+    }
+}
+
+/// Allow shared refs used as default.
+const DEFAULT_CONFIG: ValidConfig = ValidConfig { err_handler: None };
+
+impl Default for ValidConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        error::{InternalError, JsonPayloadError},
+        http::{
+            header::{self, CONTENT_LENGTH, CONTENT_TYPE},
+            StatusCode,
+        },
+        test::TestRequest,
+        HttpResponse,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize, Validate)]
+    struct Signup {
+        #[validate(length(min = 3))]
+        name: String,
+        #[validate(range(min = 18))]
+        age: u32,
+    }
+
+    fn request(content_type: &'static str, body: &'static str) -> TestRequest {
+        TestRequest::default()
+            .insert_header((CONTENT_TYPE, content_type))
+            .insert_header((CONTENT_LENGTH, body.len()))
+            .set_payload(Bytes::from_static(body.as_bytes()))
+    }
+
+    #[actix_rt::test]
+    async fn json_and_form() {
+        let expected = Signup {
+            name: "alice".to_owned(),
+            age: 30,
+        };
+
+        let (req, mut pl) =
+            request("application/json", r#"{"name":"alice","age":30}"#).to_http_parts();
+        let s = Valid::<Signup>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(s.into_inner(), expected);
+
+        let (req, mut pl) =
+            request("application/x-www-form-urlencoded", "name=alice&age=30").to_http_parts();
+        let s = Valid::<Signup>::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(s.into_inner(), expected);
+    }
+
+    #[actix_rt::test]
+    async fn validation_error() {
+        let (req, mut pl) =
+            request("application/json", r#"{"name":"al","age":30}"#).to_http_parts();
+        let err = Valid::<Signup>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+
+        let res = err.error_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+
+        let errors = err.as_error::<ValidationErrors>().unwrap();
+        assert!(errors.field_errors().contains_key("name"));
+        assert!(!errors.field_errors().contains_key("age"));
+    }
+
+    #[actix_rt::test]
+    async fn deserialize_config_is_used() {
+        let (req, mut pl) = request("application/json", r#"{"name":"alice","age":30}"#)
+            .app_data(web::JsonConfig::default().limit(10))
+            .to_http_parts();
+        let err = Valid::<Signup>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        let (req, mut pl) = request("text/plain", "name=alice&age=30").to_http_parts();
+        let err = Valid::<Signup>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.as_error::<JsonPayloadError>(),
+            Some(JsonPayloadError::ContentType)
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn custom_error_handler() {
+        let (req, mut pl) = request("application/x-www-form-urlencoded", "name=alice&age=3")
+            .app_data(ValidConfig::default().error_handler(|err, _req| {
+                InternalError::from_response(err, HttpResponse::UnprocessableEntity().finish())
+                    .into()
+            }))
+            .to_http_parts();
+        let err = Valid::<Signup>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
@@ -2,19 +2,30 @@
 //!
 //! # Request Extractors
 //! - [`Data`]: Application data item
+//! - [`ThreadData`]: Per-worker application data item
 //! - [`ReqData`]: Request-local data item
+//! - [`ConnData`]: Connection data item
 //! - [`Path`]: URL path parameters / dynamic segments
 //! - [`Query`]: URL query parameters
 //! - [`Header`]: Typed header
 //! - [`Json`]: JSON payload
+//! - [`NdJson`]: Streaming newline-delimited JSON payload
 //! - [`Form`]: URL-encoded payload
+//! - [`Valid`]: Validated JSON or URL-encoded payload (requires the `validation` feature)
 //! - [`Bytes`]: Raw payload
+//! - [`SignedCookies`] and [`PrivateCookies`]: Verified cookies (requires the `secure-cookies`
+//!   feature)
+//! - [`PeerCertificates`]: Client certificate chain of a mutual TLS connection
 //!
 //! # Responders
 //! - [`Json`]: JSON response
+//! - [`NdJsonResponse`]: Streaming newline-delimited JSON response
 //! - [`Form`]: URL-encoded response
 //! - [`Bytes`]: Raw bytes response
 //! - [`Redirect`](Redirect::to): Convenient redirect responses
+//! - [`Sse`]: Server-Sent Events responses
+//! - [`Negotiate`]: JSON or HTML responses, depending on the `Accept` header
+//! - [`RangeBody`]: Partial content responses for range requests
 
 use std::{borrow::Cow, future::Future};
 
@@ -26,10 +37,17 @@ use crate::{
     Responder, Route, Scope,
 };
 
+pub use crate::block::{block_cancellable, CancelFlag};
+pub use crate::conditional::{conditional, Conditional};
 pub use crate::config::ServiceConfig;
-pub use crate::data::Data;
+pub use crate::conn_data::ConnData;
+pub use crate::data::{Data, ThreadData};
+pub use crate::negotiate::Negotiate;
+pub use crate::range_body::RangeBody;
 pub use crate::redirect::Redirect;
 pub use crate::request_data::ReqData;
+pub use crate::request_id::RequestId;
+pub use crate::sse::{Sse, SseMessage};
 pub use crate::types::*;
 
 /// Creates a new resource for a specific path.
@@ -209,6 +227,14 @@ pub fn redirect(
 
 /// Executes blocking function on a thread pool, returns future that resolves to result of the
 /// function execution.
+///
+/// Each worker has its own blocking thread pool. Its size can be set, independently of the number
+/// of workers, with [`HttpServer::worker_max_blocking_threads`].
+///
+/// The function keeps running if the returned future is dropped. See [`block_cancellable`] for a
+/// variant that lets the function know when its result is no longer awaited.
+///
+/// [`HttpServer::worker_max_blocking_threads`]: crate::HttpServer::worker_max_blocking_threads
 pub fn block<F, R>(f: F) -> impl Future<Output = Result<R, BlockingError>>
 where
     F: FnOnce() -> R + Send + 'static,
@@ -4,8 +4,9 @@
 extern crate tls_openssl as openssl;
 
 #[cfg(any(unix, feature = "openssl"))]
+use actix_web::HttpResponse;
 use {
-    actix_web::{web, App, HttpResponse, HttpServer},
+    actix_web::{web, App, HttpServer},
     std::{sync::mpsc, thread, time::Duration},
 };
 
@@ -58,6 +59,342 @@ async fn test_start() {
     srv.stop(false).await;
 }
 
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_bind_auto_port() {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let (srv, addrs) = HttpServer::new(|| {
+                    App::new().route("/", web::to(|| async { HttpResponse::Ok().body("test") }))
+                })
+                .workers(1)
+                .disable_signals()
+                .bind_auto_port("127.0.0.1:0")
+                .unwrap();
+
+                let srv = srv.run();
+                tx.send((srv.handle(), addrs)).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    let (srv, addrs) = rx.recv().unwrap();
+    assert_eq!(addrs.len(), 1);
+    assert_ne!(addrs[0].port(), 0);
+
+    let host = format!("http://{}", addrs[0]);
+    let response = awc::Client::new().get(host).send().await.unwrap();
+    assert!(response.status().is_success());
+
+    srv.stop(false).await;
+}
+
+#[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+#[actix_rt::test]
+async fn test_reuseport() {
+    use std::{
+        collections::HashSet,
+        io::{Read as _, Write as _},
+        net,
+    };
+
+    use socket2::{Domain, Socket, Type};
+
+    let (tx, rx) = mpsc::channel();
+
+    // the first server binds the address itself
+    let first_tx = tx.clone();
+    thread::spawn(move || {
+        actix_rt::System::new().block_on(async move {
+            let srv = HttpServer::new(|| App::new().route("/", web::to(|| async { "first" })))
+                .workers(1)
+                .bind_reuseport("127.0.0.1:0")
+                .unwrap();
+            let addr = srv.addrs()[0];
+            let srv = srv.run();
+
+            first_tx.send((srv.handle(), addr)).unwrap();
+            srv.await
+        })
+    });
+
+    let (first, addr) = rx.recv().unwrap();
+
+    // the second server is given a listener bound to the same address
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, None).unwrap();
+    socket.set_reuse_port(true).unwrap();
+    socket.bind(&addr.into()).unwrap();
+    socket.listen(1024).unwrap();
+    let lst = net::TcpListener::from(socket);
+
+    thread::spawn(move || {
+        actix_rt::System::new().block_on(async move {
+            let srv = HttpServer::new(|| App::new().route("/", web::to(|| async { "second" })))
+                .workers(1)
+                .listen_reuseport(lst)
+                .unwrap()
+                .run();
+
+            tx.send((srv.handle(), addr)).unwrap();
+            srv.await
+        })
+    });
+
+    let (second, _) = rx.recv().unwrap();
+
+    // sockets without `SO_REUSEPORT` set may not share the address
+    assert!(HttpServer::new(App::new).bind(addr).is_err());
+
+    let lst = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let err = HttpServer::new(App::new)
+        .listen_reuseport(lst)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    // new connections are distributed across both servers
+    let mut served = HashSet::new();
+
+    for _ in 0..100 {
+        let mut stream = net::TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .unwrap();
+
+        let mut res = String::new();
+        stream.read_to_string(&mut res).unwrap();
+        served.insert(res.rsplit("\r\n\r\n").next().unwrap().to_owned());
+
+        if served.len() == 2 {
+            break;
+        }
+    }
+
+    assert_eq!(
+        served,
+        HashSet::from(["first".to_owned(), "second".to_owned()])
+    );
+
+    first.stop(false).await;
+    second.stop(false).await;
+}
+
+#[test]
+fn test_trusted_proxies() {
+    assert!(HttpServer::new(App::new)
+        .trusted_proxies(["10.0.0.0/8", "::1"])
+        .is_ok());
+
+    for invalid in ["10.0.0.0/33", "localhost", ""] {
+        let err = HttpServer::new(App::new)
+            .trusted_proxies(["127.0.0.1", invalid])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_on_shutdown() {
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+    let (event_tx, event_rx) = mpsc::channel();
+
+    let server = thread::spawn(move || {
+        actix_rt::System::new().block_on(async {
+            let handler_tx = event_tx.clone();
+            let hook_tx = event_tx.clone();
+
+            let srv = HttpServer::new(move || {
+                let handler_tx = handler_tx.clone();
+
+                App::new().route(
+                    "/",
+                    web::to(move || {
+                        let handler_tx = handler_tx.clone();
+
+                        async move {
+                            actix_rt::time::sleep(Duration::from_millis(500)).await;
+                            handler_tx.send("handled").unwrap();
+                            HttpResponse::Ok().body("test")
+                        }
+                    }),
+                )
+            })
+            .workers(1)
+            .on_shutdown(move || async move {
+                hook_tx.send("shutdown").unwrap();
+            })
+            .bind(addr)
+            .unwrap()
+            .run();
+
+            tx.send(srv.handle()).unwrap();
+
+            srv.await.unwrap();
+            event_tx.send("stopped").unwrap();
+        });
+    });
+
+    let srv = rx.recv().unwrap();
+
+    let req = actix_rt::spawn(async move {
+        let host = format!("http://{}", addr);
+        awc::Client::new().get(host).send().await.unwrap().status()
+    });
+
+    actix_rt::time::sleep(Duration::from_millis(100)).await;
+    assert!(event_rx.try_recv().is_err());
+
+    srv.stop(true).await;
+
+    // hooks run as soon as draining starts, while open connections are served
+    assert_eq!(
+        event_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "shutdown"
+    );
+    assert_eq!(
+        event_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "handled"
+    );
+    assert_eq!(
+        event_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "stopped"
+    );
+    assert!(req.await.unwrap().is_success());
+    server.join().unwrap();
+}
+
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_on_shutdown_timeout() {
+    struct DropGuard(mpsc::Sender<&'static str>);
+
+    impl Drop for DropGuard {
+        fn drop(&mut self) {
+            self.0.send("dropped").unwrap();
+        }
+    }
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+    let (event_tx, event_rx) = mpsc::channel();
+
+    let server = thread::spawn(move || {
+        actix_rt::System::new().block_on(async {
+            let guard = DropGuard(event_tx.clone());
+
+            let srv = HttpServer::new(App::new)
+                .workers(1)
+                .shutdown_timeout(1)
+                .on_shutdown(move || async move {
+                    let _guard = guard;
+                    std::future::pending::<()>().await;
+                })
+                .bind(addr)
+                .unwrap()
+                .run();
+
+            tx.send(srv.handle()).unwrap();
+
+            srv.await.unwrap();
+
+            // keep the runtime running for longer than the shutdown timeout
+            actix_rt::time::sleep(Duration::from_secs(2)).await;
+            event_tx.send("stopped").unwrap();
+        });
+    });
+
+    let srv = rx.recv().unwrap();
+    srv.stop(true).await;
+
+    // hooks still running after the shutdown timeout are dropped
+    assert_eq!(
+        event_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "dropped"
+    );
+    assert_eq!(
+        event_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "stopped"
+    );
+    server.join().unwrap();
+}
+
+#[actix_rt::test]
+async fn test_on_expect_continue() {
+    use std::io::{Read as _, Write as _};
+
+    use actix_web::{dev::ExpectAction, http::StatusCode};
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(|| {
+                    App::new().route("/", web::post().to(|body: String| async move { body }))
+                })
+                .workers(1)
+                .disable_signals()
+                .on_expect_continue(|head| {
+                    if head.headers().contains_key("authorization") {
+                        ExpectAction::Continue
+                    } else {
+                        ExpectAction::Reject(StatusCode::UNAUTHORIZED)
+                    }
+                })
+                .bind(addr)
+                .unwrap()
+                .run();
+
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    let srv = rx.recv().unwrap();
+
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\ncontent-length: 4\r\n\r\n")
+        .unwrap();
+    let mut data = Vec::new();
+    stream.read_to_end(&mut data).unwrap();
+    assert!(data.starts_with(b"HTTP/1.1 401 Unauthorized\r\n"));
+
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nauthorization: token\r\ncontent-length: 4\r\n\r\n")
+        .unwrap();
+    let mut data = [0; 25];
+    stream.read_exact(&mut data).unwrap();
+    assert_eq!(&data, b"HTTP/1.1 100 Continue\r\n\r\n");
+
+    stream.write_all(b"test").unwrap();
+    let mut data = [0; 1024];
+    let n = stream.read(&mut data).unwrap();
+    let res = String::from_utf8_lossy(&data[..n]);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(res.ends_with("test"));
+
+    srv.stop(false).await;
+}
+
 #[cfg(feature = "openssl")]
 fn ssl_acceptor() -> openssl::ssl::SslAcceptorBuilder {
     use openssl::{
@@ -135,3 +472,106 @@ async fn test_start_ssl() {
 
     srv.stop(false).await;
 }
+
+#[actix_rt::test]
+#[cfg(all(feature = "rustls", feature = "openssl"))]
+async fn test_start_rustls_peer_certificates() {
+    use actix_web::web::PeerCertificates;
+    use openssl::{
+        pkey::PKey,
+        ssl::{SslConnector, SslMethod, SslVerifyMode},
+        x509::X509,
+    };
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa};
+    use tls_rustls::{
+        server::AllowAnyAnonymousOrAuthenticatedClient, Certificate as RustlsCert, PrivateKey,
+        RootCertStore, ServerConfig,
+    };
+
+    let mut ca_params = CertificateParams::new(vec![]);
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca = Certificate::from_params(ca_params).unwrap();
+
+    let server_cert = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let client_cert =
+        Certificate::from_params(CertificateParams::new(vec!["client.localhost".to_owned()]))
+            .unwrap();
+    let client_der = client_cert.serialize_der_with_signer(&ca).unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(&RustlsCert(ca.serialize_der().unwrap())).unwrap();
+
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(AllowAnyAnonymousOrAuthenticatedClient::new(roots))
+        .with_single_cert(
+            vec![RustlsCert(server_cert.serialize_der().unwrap())],
+            PrivateKey(server_cert.serialize_private_key_der()),
+        )
+        .unwrap();
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(|| {
+                    App::new().service(web::resource("/").route(web::to(
+                        |certs: Option<PeerCertificates>| async move {
+                            match certs {
+                                Some(certs) => {
+                                    HttpResponse::Ok().body(certs.end_entity().to_vec())
+                                }
+                                None => HttpResponse::NoContent().finish(),
+                            }
+                        },
+                    )))
+                })
+                .workers(1)
+                .shutdown_timeout(1)
+                .system_exit()
+                .disable_signals()
+                .bind_rustls(format!("{}", addr), config)
+                .unwrap();
+
+                let srv = srv.run();
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap()
+    });
+    let srv = rx.recv().unwrap();
+
+    let host = format!("https://{}", addr);
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_verify(SslVerifyMode::NONE);
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().openssl(builder.build()))
+        .finish();
+
+    let response = client.get(host.clone()).send().await.unwrap();
+    assert_eq!(response.status(), actix_web::http::StatusCode::NO_CONTENT);
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_verify(SslVerifyMode::NONE);
+    builder
+        .set_certificate(&X509::from_der(&client_der).unwrap())
+        .unwrap();
+    builder
+        .set_private_key(
+            &PKey::private_key_from_der(&client_cert.serialize_private_key_der()).unwrap(),
+        )
+        .unwrap();
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().openssl(builder.build()))
+        .finish();
+
+    let mut response = client.get(host).send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.body().await.unwrap(), client_der);
+
+    srv.stop(false).await;
+}
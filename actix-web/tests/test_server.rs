@@ -857,6 +857,47 @@ async fn test_slow_request() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn test_response_trailers() {
+    use std::{cell::Cell, net, rc::Rc};
+
+    use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+    use futures_util::StreamExt as _;
+
+    let srv = actix_test::start(|| {
+        App::new().service(web::resource("/").route(web::to(|| async {
+            let len = Rc::new(Cell::new(0));
+            let len2 = Rc::clone(&len);
+
+            let body = futures_util::stream::iter(["hello ", "world"]).map(move |chunk| {
+                len.set(len.get() + chunk.len());
+                Ok::<_, Error>(Bytes::from_static(chunk.as_bytes()))
+            });
+
+            HttpResponse::Ok()
+                .insert_header((header::TRAILER, "x-length"))
+                .trailers(move || {
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert(
+                        HeaderName::from_static("x-length"),
+                        HeaderValue::from(len2.get()),
+                    );
+                    trailers
+                })
+                .streaming(body)
+        })))
+    });
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.ends_with("\r\n6\r\nhello \r\n5\r\nworld\r\n0\r\nx-length: 11\r\n\r\n"));
+
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn test_normalize() {
     let srv = actix_test::start_with(actix_test::config().h1(), || {
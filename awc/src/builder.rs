@@ -16,7 +16,7 @@ use crate::{
     },
     connect::DefaultConnector,
     error::SendRequestError,
-    middleware::{NestTransform, Redirect, Transform},
+    middleware::{NestTransform, Redirect, RetryPolicy, Transform},
     Client, ConnectRequest, ConnectResponse,
 };
 
@@ -249,6 +249,28 @@ where
         }
     }
 
+    /// Retry idempotent requests that fail because of connection-level errors.
+    ///
+    /// See [`RetryPolicy`] for which requests are retried, and when.
+    ///
+    /// Requests that cannot be retried are passed through unchanged, without any indication that
+    /// the policy was skipped. This applies to requests with a non-idempotent method (e.g., `POST`
+    /// or `PATCH`), requests with a streaming body (e.g., sent using
+    /// [`send_stream`](crate::ClientRequest::send_stream)), and WebSocket requests. Those requests
+    /// are sent exactly once and their error, if any, is returned as is.
+    ///
+    /// Retries are attempted for each request made while following redirects.
+    pub fn retry<S1>(
+        self,
+        policy: RetryPolicy,
+    ) -> ClientBuilder<S, NestTransform<M, RetryPolicy, S1, ConnectRequest>>
+    where
+        M: Transform<S1, ConnectRequest>,
+        RetryPolicy: Transform<M::Transform, ConnectRequest>,
+    {
+        self.wrap(policy)
+    }
+
     /// Finish build process and create `Client` instance.
     pub fn finish(self) -> Client
     where
@@ -287,13 +309,17 @@ where
             connector = connector.local_address(val);
         }
 
-        let connector = DefaultConnector::new(connector.finish());
+        let connector = connector.finish();
+        let pool_stats = connector.pool_stats_fn();
+
+        let connector = DefaultConnector::new(connector);
         let connector = boxed::rc_service(self.middleware.new_transform(connector));
 
         Client(ClientConfig {
             default_headers: Rc::new(self.default_headers),
             timeout: self.timeout,
             connector,
+            pool_stats,
         })
     }
 }
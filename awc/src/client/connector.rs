@@ -26,7 +26,7 @@ use super::{
     config::ConnectorConfig,
     connection::{Connection, ConnectionIo},
     error::ConnectError,
-    pool::ConnectionPool,
+    pool::{ConnectionPool, PoolStats},
     Connect,
 };
 
@@ -707,6 +707,32 @@ where
     tls_pool: Option<ConnectionPool<S2, Io2>>,
 }
 
+impl<S1, S2, Io1, Io2> ConnectorServicePriv<S1, S2, Io1, Io2>
+where
+    S1: Service<Connect, Response = (Io1, Protocol), Error = ConnectError>,
+    S2: Service<Connect, Response = (Io2, Protocol), Error = ConnectError>,
+    Io1: ConnectionIo,
+    Io2: ConnectionIo,
+{
+    /// Returns a function that takes a snapshot of the plain and TLS connection pools.
+    pub(crate) fn pool_stats_fn(&self) -> Rc<dyn Fn() -> PoolStats> {
+        let tcp_stats = self.tcp_pool.stats_fn();
+        let tls_stats = self.tls_pool.as_ref().map(ConnectionPool::stats_fn);
+
+        Rc::new(move || {
+            let mut stats = PoolStats::default();
+
+            tcp_stats(&mut stats);
+
+            if let Some(ref tls_stats) = tls_stats {
+                tls_stats(&mut stats);
+            }
+
+            stats
+        })
+    }
+}
+
 impl<S1, S2, Io1, Io2> Service<Connect> for ConnectorServicePriv<S1, S2, Io1, Io2>
 where
     S1: Service<Connect, Response = (Io1, Protocol), Error = ConnectError> + Clone + 'static,
@@ -22,6 +22,7 @@ mod pool;
 pub use self::connection::{Connection, ConnectionIo};
 pub use self::connector::{Connector, ConnectorService};
 pub use self::error::{ConnectError, FreezeRequestError, InvalidUrl, SendRequestError};
+pub use self::pool::{HostPoolStats, PoolStats};
 
 #[derive(Clone)]
 pub struct Connect {
@@ -58,6 +59,7 @@ pub(crate) struct ClientConfig {
     pub(crate) connector: BoxConnectorService,
     pub(crate) default_headers: Rc<HeaderMap>,
     pub(crate) timeout: Option<Duration>,
+    pub(crate) pool_stats: Rc<dyn Fn() -> PoolStats>,
 }
 
 impl Default for Client {
@@ -200,4 +202,24 @@ impl Client {
     pub fn headers(&mut self) -> Option<&mut HeaderMap> {
         Rc::get_mut(&mut self.0.default_headers)
     }
+
+    /// Returns a snapshot of the state of this client's connection pools.
+    ///
+    /// Connections to a host are counted from the time a request starts waiting for a new or idle
+    /// connection to it until that connection is released back to the pool or closed. Pools are
+    /// shared by clones of a client.
+    ///
+    /// # Examples
+    /// ```
+    /// use awc::Client;
+    ///
+    /// let client = Client::default();
+    ///
+    /// let stats = client.pool_stats();
+    /// assert_eq!(stats.active, 0);
+    /// assert_eq!(stats.idle, 0);
+    /// ```
+    pub fn pool_stats(&self) -> PoolStats {
+        (self.0.pool_stats)()
+    }
 }
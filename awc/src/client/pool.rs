@@ -1,13 +1,13 @@
 //! Client connection pooling keyed on the authority part of the connection URI.
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::{HashMap, VecDeque},
     future::Future,
     io,
     ops::Deref,
     pin::Pin,
-    rc::Rc,
+    rc::{Rc, Weak},
     sync::Arc,
     task::{Context, Poll},
     time::{Duration, Instant},
@@ -40,6 +40,37 @@ impl From<Authority> for Key {
     }
 }
 
+/// Snapshot of the state of a client's connection pools.
+///
+/// Returned by [`Client::pool_stats()`](crate::Client::pool_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolStats {
+    /// Number of idle connections kept open for reuse.
+    pub idle: usize,
+
+    /// Number of connections that are in use or being established.
+    pub active: usize,
+
+    /// Number of requests waiting for a connection because the connector's
+    /// [limit](crate::Connector::limit) has been reached.
+    pub waiters: usize,
+
+    /// Idle and active connection counts for each host with open connections.
+    pub per_host: HashMap<Authority, HostPoolStats>,
+}
+
+/// Connection counts for a single host in a [`PoolStats`] snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HostPoolStats {
+    /// Number of idle connections kept open for reuse.
+    pub idle: usize,
+
+    /// Number of connections that are in use or being established.
+    pub active: usize,
+}
+
 #[doc(hidden)]
 /// Connections pool for reuse Io type for certain [`http::uri::Authority`] as key.
 pub struct ConnectionPool<S, Io>
@@ -67,6 +98,8 @@ where
             config,
             available,
             permits,
+            acquired: RefCell::new(HashMap::default()),
+            waiters: Cell::new(0),
         }))
     }
 
@@ -125,6 +158,40 @@ where
     config: ConnectorConfig,
     available: RefCell<HashMap<Key, VecDeque<PooledConnection<Io>>>>,
     permits: Arc<Semaphore>,
+    /// number of live `Acquired`s for each key.
+    acquired: RefCell<HashMap<Key, usize>>,
+    /// number of requests waiting for a permit.
+    waiters: Cell<usize>,
+}
+
+impl<Io> ConnectionPoolInnerPriv<Io>
+where
+    Io: AsyncWrite + Unpin + 'static,
+{
+    /// Add counts of this pool's connections to `stats`.
+    fn add_stats(&self, stats: &mut PoolStats) {
+        let available = self.available.borrow();
+
+        for (key, conns) in available.iter().filter(|(_, conns)| !conns.is_empty()) {
+            stats.idle += conns.len();
+            stats
+                .per_host
+                .entry(key.authority.clone())
+                .or_default()
+                .idle += conns.len();
+        }
+
+        for (key, &count) in self.acquired.borrow().iter() {
+            stats.active += count;
+            stats
+                .per_host
+                .entry(key.authority.clone())
+                .or_default()
+                .active += count;
+        }
+
+        stats.waiters += self.waiters.get();
+    }
 }
 
 impl<S, Io> ConnectionPool<S, Io>
@@ -146,6 +213,19 @@ where
 
         Self { connector, inner }
     }
+
+    /// Returns a function that adds counts of this pool's connections to a [`PoolStats`].
+    ///
+    /// The function does not keep the pool alive and adds nothing once it has been dropped.
+    pub(crate) fn stats_fn(&self) -> impl Fn(&mut PoolStats) + 'static {
+        let inner = Rc::downgrade(&self.inner.0);
+
+        move |stats| {
+            if let Some(inner) = Weak::upgrade(&inner) {
+                inner.add_stats(stats);
+            }
+        }
+    }
 }
 
 impl<S, Io> Service<Connect> for ConnectionPool<S, Io>
@@ -171,12 +251,14 @@ where
             };
 
             // acquire an owned permit and carry it with connection
+            let waiting = Waiting::new(&inner.waiters);
             let permit = inner.permits.clone().acquire_owned().await.map_err(|_| {
                 ConnectError::Io(io::Error::new(
                     io::ErrorKind::Other,
                     "failed to acquire semaphore on client connection pool",
                 ))
             })?;
+            drop(waiting);
 
             let conn = {
                 let mut conn = None;
@@ -223,7 +305,7 @@ where
 
             // construct acquired. It's used to put Io type back to pool/ close the Io type.
             // permit is carried with the whole lifecycle of Acquired.
-            let acquired = Acquired { key, inner, permit };
+            let acquired = Acquired::new(key, inner, permit);
 
             // match the connection and spawn new one if did not get anything.
             match conn {
@@ -248,6 +330,22 @@ where
     }
 }
 
+/// Counts a request as waiting for a permit for as long as it is alive.
+struct Waiting<'a>(&'a Cell<usize>);
+
+impl<'a> Waiting<'a> {
+    fn new(waiters: &'a Cell<usize>) -> Self {
+        waiters.set(waiters.get() + 1);
+        Self(waiters)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
 /// Type for check the connection and determine if it's usable.
 struct ConnectionCheckFuture<'a, Io> {
     io: &'a mut Io,
@@ -343,6 +441,33 @@ where
     permit: OwnedSemaphorePermit,
 }
 
+impl<Io> Acquired<Io>
+where
+    Io: AsyncWrite + Unpin + 'static,
+{
+    fn new(key: Key, inner: ConnectionPoolInner<Io>, permit: OwnedSemaphorePermit) -> Self {
+        *inner.acquired.borrow_mut().entry(key.clone()).or_insert(0) += 1;
+        Self { key, inner, permit }
+    }
+}
+
+impl<Io> Drop for Acquired<Io>
+where
+    Io: AsyncWrite + Unpin + 'static,
+{
+    fn drop(&mut self) {
+        let mut acquired = self.inner.acquired.borrow_mut();
+
+        if let Some(count) = acquired.get_mut(&self.key) {
+            *count -= 1;
+
+            if *count == 0 {
+                acquired.remove(&self.key);
+            }
+        }
+    }
+}
+
 impl<Io: ConnectionIo> Acquired<Io> {
     /// Close the IO.
     pub(super) fn close(&self, conn: ConnectionInnerType<Io>) {
@@ -605,6 +730,85 @@ mod test {
         release(conn);
     }
 
+    #[actix_rt::test]
+    async fn test_pool_stats() {
+        let connector = TestPoolConnector {
+            generated: Rc::new(Cell::new(0)),
+        };
+
+        let config = ConnectorConfig {
+            limit: 2,
+            ..Default::default()
+        };
+
+        let pool = Rc::new(super::ConnectionPool::new(connector, config));
+        let stats_fn = pool.stats_fn();
+        let stats = || {
+            let mut stats = PoolStats::default();
+            stats_fn(&mut stats);
+            stats
+        };
+
+        let localhost = Connect {
+            uri: Uri::from_static("http://localhost"),
+            addr: None,
+        };
+        let crates = Connect {
+            uri: Uri::from_static("https://crates.io"),
+            addr: None,
+        };
+
+        assert_eq!(stats(), PoolStats::default());
+
+        let conn1 = pool.call(localhost.clone()).await.unwrap();
+        let conn2 = pool.call(crates).await.unwrap();
+
+        let stats1 = stats();
+        assert_eq!(stats1.active, 2);
+        assert_eq!(stats1.idle, 0);
+        assert_eq!(stats1.per_host.len(), 2);
+        assert_eq!(
+            stats1.per_host[&Authority::from_static("localhost")].active,
+            1
+        );
+        assert_eq!(
+            stats1.per_host[&Authority::from_static("crates.io")].active,
+            1
+        );
+
+        let pool_clone = Rc::clone(&pool);
+        let waiter = actix_rt::spawn(async move { pool_clone.call(localhost).await.unwrap() });
+        actix_rt::task::yield_now().await;
+        assert_eq!(stats().waiters, 1);
+
+        release(conn1);
+
+        let stats2 = stats();
+        assert_eq!(stats2.active, 1);
+        assert_eq!(stats2.idle, 1);
+        assert_eq!(stats2.waiters, 1);
+        assert_eq!(
+            stats2.per_host[&Authority::from_static("localhost")],
+            HostPoolStats { idle: 1, active: 0 }
+        );
+
+        // waiter reuses the idle connection
+        let conn3 = waiter.await.unwrap();
+
+        let stats3 = stats();
+        assert_eq!(stats3.active, 2);
+        assert_eq!(stats3.idle, 0);
+        assert_eq!(stats3.waiters, 0);
+
+        drop(conn2);
+        drop(conn3);
+        assert_eq!(stats().active, 0);
+        assert!(stats().per_host.is_empty());
+
+        drop(pool);
+        assert_eq!(stats(), PoolStats::default());
+    }
+
     #[actix_rt::test]
     async fn test_pool_drop() {
         let generated = Rc::new(Cell::new(0));
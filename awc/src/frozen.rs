@@ -96,6 +96,21 @@ impl FrozenClientRequest {
         )
     }
 
+    /// Send a streaming body of newline-delimited JSON.
+    pub fn send_json_stream<S>(&self, stream: S) -> SendClientRequest
+    where
+        S: Stream + 'static,
+        S::Item: Serialize,
+    {
+        RequestSender::Rc(self.head.clone(), None).send_json_stream(
+            self.addr,
+            self.response_decompress,
+            self.timeout,
+            &self.config,
+            stream,
+        )
+    }
+
     /// Send an empty body.
     pub fn send(&self) -> SendClientRequest {
         RequestSender::Rc(self.head.clone(), None).send(
@@ -210,6 +225,25 @@ impl FrozenSendBuilder {
         )
     }
 
+    /// Complete request construction and send a streaming body of newline-delimited JSON.
+    pub fn send_json_stream<S>(self, stream: S) -> SendClientRequest
+    where
+        S: Stream + 'static,
+        S::Item: Serialize,
+    {
+        if let Some(e) = self.err {
+            return e.into();
+        }
+
+        RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_json_stream(
+            self.req.addr,
+            self.req.response_decompress,
+            self.req.timeout,
+            &self.req.config,
+            stream,
+        )
+    }
+
     /// Complete request construction and send an empty body.
     pub fn send(self) -> SendClientRequest {
         if let Some(e) = self.err {
@@ -0,0 +1,341 @@
+use std::{cmp, rc::Rc, time::Duration};
+
+use actix_http::{error::ParseError, Method, RequestHeadType};
+use actix_rt::time::sleep;
+use actix_service::Service;
+use futures_core::future::LocalBoxFuture;
+
+use super::Transform;
+use crate::{
+    any_body::AnyBody,
+    client::SendRequestError,
+    connect::{ConnectRequest, ConnectResponse},
+};
+
+/// Middleware for retrying idempotent requests that fail.
+///
+/// Requests with an idempotent method (`GET`, `HEAD`, `PUT`, `DELETE`, `OPTIONS`, and `TRACE`)
+/// are sent again when they fail because of connection-level errors, such as a pooled connection
+/// that was closed by the server, and, optionally, when they receive a `5xx` response. The
+/// response to, or error of, the last attempt is returned.
+///
+/// Requests are only retried if their body can be sent again, i.e., if it is empty or was
+/// provided as a complete, in-memory body (e.g. using [`send_body`] with `Bytes` or a `String`, or
+/// [`send_json`]). Requests with streaming bodies, other methods, and WebSocket requests are
+/// never retried.
+///
+/// Retries are delayed by exponential backoff, starting with a delay of 100ms by default.
+///
+/// [`send_body`]: crate::ClientRequest::send_body
+/// [`send_json`]: crate::ClientRequest::send_json
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use awc::{middleware::RetryPolicy, Client};
+///
+/// let client = Client::builder()
+///     .retry(
+///         RetryPolicy::new(3)
+///             .backoff(Duration::from_millis(50))
+///             .retry_server_errors(true),
+///     )
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u8,
+    backoff: Duration,
+    max_backoff: Duration,
+    server_errors: bool,
+}
+
+impl RetryPolicy {
+    /// Constructs new retry policy that sends requests again up to `max_retries` times.
+    pub fn new(max_retries: u8) -> Self {
+        Self {
+            max_retries,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            server_errors: false,
+        }
+    }
+
+    /// Sets the delay before the first retry.
+    ///
+    /// The delay is doubled for each subsequent retry, up to the [maximum](Self::max_backoff).
+    /// Defaults to 100ms.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the maximum delay between retries.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets whether requests receiving a `5xx` response are retried.
+    ///
+    /// Defaults to `false`.
+    pub fn retry_server_errors(mut self, retry: bool) -> Self {
+        self.server_errors = retry;
+        self
+    }
+
+    /// Returns the delay before the given retry, starting from 0.
+    fn delay(&self, retry: u8) -> Duration {
+        let factor = 1u32.checked_shl(retry.into()).unwrap_or(u32::MAX);
+        cmp::min(self.backoff.saturating_mul(factor), self.max_backoff)
+    }
+}
+
+impl<S> Transform<S, ConnectRequest> for RetryPolicy
+where
+    S: Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError> + 'static,
+{
+    type Transform = RetryService<S>;
+
+    fn new_transform(self, service: S) -> Self::Transform {
+        RetryService {
+            policy: Rc::new(self),
+            connector: Rc::new(service),
+        }
+    }
+}
+
+pub struct RetryService<S> {
+    policy: Rc<RetryPolicy>,
+    connector: Rc<S>,
+}
+
+impl<S> Service<ConnectRequest> for RetryService<S>
+where
+    S: Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError> + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = LocalBoxFuture<'static, Result<ConnectResponse, SendRequestError>>;
+
+    actix_service::forward_ready!(connector);
+
+    fn call(&self, req: ConnectRequest) -> Self::Future {
+        // only bodies that are empty or complete can be sent again
+        let (head, body, addr) = match req {
+            ConnectRequest::Client(head, body, addr)
+                if self.policy.max_retries > 0 && is_idempotent(&head) =>
+            {
+                match body {
+                    AnyBody::None => (head, None, addr),
+                    AnyBody::Bytes { body } => (head, Some(body), addr),
+                    body => {
+                        return Box::pin(
+                            self.connector
+                                .call(ConnectRequest::Client(head, body, addr)),
+                        )
+                    }
+                }
+            }
+            req => return Box::pin(self.connector.call(req)),
+        };
+
+        let policy = Rc::clone(&self.policy);
+        let connector = Rc::clone(&self.connector);
+
+        Box::pin(async move {
+            let mut retry = 0;
+
+            loop {
+                let body = body
+                    .clone()
+                    .map_or(AnyBody::None, |body| AnyBody::Bytes { body });
+                let req = ConnectRequest::Client(clone_head(&head), body, addr);
+                let res = connector.call(req).await;
+
+                let should_retry = match res {
+                    Ok(ConnectResponse::Client(ref res)) => {
+                        policy.server_errors && res.status().is_server_error()
+                    }
+                    Ok(ConnectResponse::Tunnel(..)) => false,
+                    Err(ref err) => is_connection_error(err),
+                };
+
+                if !should_retry || retry == policy.max_retries {
+                    return res;
+                }
+
+                // drop response, and its connection, before waiting
+                drop(res);
+
+                sleep(policy.delay(retry)).await;
+                retry += 1;
+            }
+        })
+    }
+}
+
+fn is_idempotent(head: &RequestHeadType) -> bool {
+    matches!(
+        head.as_ref().method,
+        Method::GET
+            | Method::HEAD
+            | Method::PUT
+            | Method::DELETE
+            | Method::OPTIONS
+            | Method::TRACE
+    )
+}
+
+/// Returns true for errors that occur before the response head is received from the server.
+fn is_connection_error(err: &SendRequestError) -> bool {
+    matches!(
+        err,
+        SendRequestError::Connect(_)
+            | SendRequestError::Send(_)
+            | SendRequestError::H2(_)
+            | SendRequestError::Response(ParseError::Incomplete | ParseError::Io(_))
+    )
+}
+
+fn clone_head(head: &RequestHeadType) -> RequestHeadType {
+    match head {
+        RequestHeadType::Owned(head) => RequestHeadType::Owned(head.clone()),
+        RequestHeadType::Rc(head, extra_headers) => {
+            RequestHeadType::Rc(Rc::clone(head), extra_headers.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, io};
+
+    use actix_http::{body::BodyStream, Payload, RequestHead, ResponseHead, StatusCode};
+    use bytes::Bytes;
+    use futures_util::stream;
+
+    use super::*;
+    use crate::ClientResponse;
+
+    /// Fails with the given results, in order, then responds with `200 OK`.
+    struct TestConnector {
+        calls: Rc<Cell<usize>>,
+        results: Vec<Result<StatusCode, ()>>,
+    }
+
+    impl Service<ConnectRequest> for TestConnector {
+        type Response = ConnectResponse;
+        type Error = SendRequestError;
+        type Future = LocalBoxFuture<'static, Result<ConnectResponse, SendRequestError>>;
+
+        actix_service::always_ready!();
+
+        fn call(&self, _: ConnectRequest) -> Self::Future {
+            let call = self.calls.get();
+            self.calls.set(call + 1);
+
+            let res = match self.results.get(call) {
+                Some(Ok(status)) => Ok(*status),
+                Some(Err(())) => Err(SendRequestError::Send(io::Error::new(
+                    io::ErrorKind::ConnectionReset,
+                    "connection reset",
+                ))),
+                None => Ok(StatusCode::OK),
+            };
+
+            Box::pin(async move {
+                let status = res?;
+                let head = ResponseHead::new(status);
+                Ok(ConnectResponse::Client(ClientResponse::new(
+                    head,
+                    Payload::None,
+                )))
+            })
+        }
+    }
+
+    fn service(
+        policy: RetryPolicy,
+        results: Vec<Result<StatusCode, ()>>,
+    ) -> (Rc<Cell<usize>>, RetryService<TestConnector>) {
+        let calls = Rc::new(Cell::new(0));
+        let connector = TestConnector {
+            calls: Rc::clone(&calls),
+            results,
+        };
+        (
+            calls,
+            policy.backoff(Duration::ZERO).new_transform(connector),
+        )
+    }
+
+    fn request(method: Method, body: AnyBody) -> ConnectRequest {
+        let mut head = RequestHead::default();
+        head.method = method;
+        ConnectRequest::Client(RequestHeadType::Owned(head), body, None)
+    }
+
+    #[actix_rt::test]
+    async fn connection_errors() {
+        let (calls, srv) = service(RetryPolicy::new(2), vec![Err(()), Err(())]);
+        let res = srv.call(request(Method::GET, AnyBody::None)).await.unwrap();
+        assert_eq!(res.into_client_response().status(), StatusCode::OK);
+        assert_eq!(calls.get(), 3);
+
+        let (calls, srv) = service(RetryPolicy::new(2), vec![Err(()), Err(()), Err(())]);
+        let res = srv
+            .call(request(Method::PUT, AnyBody::copy_from_slice(b"data")))
+            .await;
+        assert!(matches!(res, Err(SendRequestError::Send(_))));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[actix_rt::test]
+    async fn server_errors() {
+        let results = vec![Ok(StatusCode::SERVICE_UNAVAILABLE)];
+
+        let (calls, srv) = service(RetryPolicy::new(2), results.clone());
+        let res = srv.call(request(Method::GET, AnyBody::None)).await.unwrap();
+        assert_eq!(
+            res.into_client_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(calls.get(), 1);
+
+        let (calls, srv) = service(RetryPolicy::new(2).retry_server_errors(true), results);
+        let res = srv.call(request(Method::GET, AnyBody::None)).await.unwrap();
+        assert_eq!(res.into_client_response().status(), StatusCode::OK);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn not_retryable() {
+        let (calls, srv) = service(RetryPolicy::new(2), vec![Err(())]);
+        let res = srv.call(request(Method::POST, AnyBody::None)).await;
+        assert!(res.is_err());
+        assert_eq!(calls.get(), 1);
+
+        let chunks = stream::iter([Ok::<_, io::Error>(Bytes::from_static(b"data"))]);
+        let body = AnyBody::new_boxed(BodyStream::new(chunks));
+        let (calls, srv) = service(RetryPolicy::new(2), vec![Err(())]);
+        let res = srv.call(request(Method::PUT, body)).await;
+        assert!(res.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::new(10)
+            .backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1));
+
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(800));
+        assert_eq!(policy.delay(4), Duration::from_secs(1));
+        assert_eq!(policy.delay(u8::MAX), Duration::from_secs(1));
+    }
+}
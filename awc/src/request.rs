@@ -403,6 +403,51 @@ impl ClientRequest {
         )
     }
 
+    /// Set a streaming body of newline-delimited JSON and generate `ClientRequest`.
+    ///
+    /// Each item of the stream is serialized to a single line as it is sent. Unless already set,
+    /// the `Content-Type` header is set to `application/x-ndjson`. If an item fails to serialize,
+    /// the body ends with an error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[actix_rt::main]
+    /// # async fn main() {
+    /// use futures_util::stream;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Record {
+    ///     id: u32,
+    /// }
+    ///
+    /// let records = stream::iter((0..1000).map(|id| Record { id }));
+    ///
+    /// let res = awc::Client::new()
+    ///     .post("http://www.rust-lang.org")
+    ///     .send_json_stream(records)
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn send_json_stream<S>(self, stream: S) -> SendClientRequest
+    where
+        S: Stream + 'static,
+        S::Item: Serialize,
+    {
+        let slf = match self.prep_for_sending() {
+            Ok(slf) => slf,
+            Err(e) => return e.into(),
+        };
+
+        RequestSender::Owned(slf.head).send_json_stream(
+            slf.addr,
+            slf.response_decompress,
+            slf.timeout,
+            &slf.config,
+            stream,
+        )
+    }
+
     /// Set an empty body and generate `ClientRequest`.
     pub fn send(self) -> SendClientRequest {
         let slf = match self.prep_for_sending() {
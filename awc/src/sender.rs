@@ -17,6 +17,7 @@ use actix_rt::time::{sleep, Sleep};
 use bytes::Bytes;
 use derive_more::From;
 use futures_core::Stream;
+use futures_util::StreamExt as _;
 use serde::Serialize;
 
 #[cfg(feature = "__compress")]
@@ -271,6 +272,32 @@ impl RequestSender {
         )
     }
 
+    pub(crate) fn send_json_stream<S>(
+        mut self,
+        addr: Option<net::SocketAddr>,
+        response_decompress: bool,
+        timeout: Option<Duration>,
+        config: &ClientConfig,
+        stream: S,
+    ) -> SendClientRequest
+    where
+        S: Stream + 'static,
+        S::Item: Serialize,
+    {
+        if let Err(err) = self.set_header_if_none(header::CONTENT_TYPE, "application/x-ndjson")
+        {
+            return err.into();
+        }
+
+        let lines = stream.map(|item| {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok::<_, serde_json::Error>(Bytes::from(line))
+        });
+
+        self.send_stream(addr, response_decompress, timeout, config, lines)
+    }
+
     pub(crate) fn send(
         self,
         addr: Option<net::SocketAddr>,
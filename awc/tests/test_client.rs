@@ -74,6 +74,34 @@ async fn json() {
     assert!(response.status().is_success());
 }
 
+#[actix_rt::test]
+async fn json_stream() {
+    let srv = actix_test::start(|| {
+        App::new().default_service(web::to(|req: HttpRequest, body: Bytes| async move {
+            let content_type = req.headers().get(header::CONTENT_TYPE).unwrap().clone();
+            HttpResponse::Ok()
+                .insert_header((header::CONTENT_TYPE, content_type))
+                .body(body)
+        }))
+    });
+
+    let records = stream::iter(vec![vec![1], vec![2, 3], vec![]]);
+    let mut res = srv.post("/").send_json_stream(records).await.unwrap();
+    assert!(res.status().is_success());
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/x-ndjson"
+    );
+
+    let bytes = res.body().await.unwrap();
+    assert_eq!(bytes, "[1]\n[2,3]\n[]\n");
+
+    // maps with non-string keys can not be serialized
+    let records = stream::iter(vec![HashMap::from([((), ())])]);
+    let res = srv.post("/").send_json_stream(records).await;
+    assert!(matches!(res, Err(SendRequestError::Body(_))));
+}
+
 #[actix_rt::test]
 async fn form() {
     let srv = actix_test::start(|| {